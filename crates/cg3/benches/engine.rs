@@ -0,0 +1,332 @@
+//! Engine benchmark: throughput and steady-state heap traffic per window.
+//!
+//! `cargo bench --bench engine [-- FILTER]` runs two workload families
+//! in-process through `FormatConverter` (CG text in, CG text out to a sink):
+//!
+//! * every `test/T_*` fixture — `grammar.cg3` over `input.txt`, with the
+//!   fixture's `args.txt` flags — so a port change that slows a rule family down
+//!   shows up next to the fixture exercising it;
+//! * `synthetic`, a generated grammar of a few hundred sets and a few thousand
+//!   rules of the common types (SELECT/REMOVE/MAP/ADD/SUBSTITUTE, with barriers
+//!   and unbounded contexts) over a generated corpus, standing in for a large
+//!   production grammar.
+//!
+//! Each workload is run twice on a fresh applicator: once over the input and
+//! once over `CG3_BENCH_REPS` (default 200) concatenated copies. The time column
+//! is from the long run; the allocation columns are the DIFFERENCE between the
+//! two runs divided by the extra windows, i.e. the heap traffic of a window once
+//! the store's arenas and spare lists have warmed up. Allocations are counted by
+//! `stats_alloc`'s instrumented system allocator.
+//!
+//! Grammar parsing is outside the measured region. A `FILTER` argument keeps
+//! only workloads whose name contains it.
+
+use std::alloc::System;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cg3::format_converter::FormatConverter;
+use cg3::grammar::Grammar;
+use cg3::grammar_applicator::{GrammarApplicator, StreamFormatKind};
+use cg3::options::{Opt, options};
+use cg3::textual_parser::TextualParser;
+use stats_alloc::{INSTRUMENTED_SYSTEM, Region, StatsAlloc};
+
+#[global_allocator]
+static GLOBAL: &StatsAlloc<System> = &INSTRUMENTED_SYSTEM;
+
+fn repo_root() -> PathBuf {
+    // crates/cg3 -> repo root
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .canonicalize()
+        .unwrap()
+}
+
+/// One benchmark workload: a grammar source, its extra vislcg3 flags and one
+/// copy of the input stream.
+struct Workload {
+    name: String,
+    grammar: Vec<u8>,
+    /// Source name handed to the parser (resolves relative `INCLUDE`s).
+    grammar_name: String,
+    args: Vec<String>,
+    input: Vec<u8>,
+}
+
+/// The measurements of one run over a repeated input.
+struct Run {
+    elapsed: Duration,
+    windows: u32,
+    allocations: usize,
+    bytes: usize,
+}
+
+/// Parse and reindex the workload's grammar and wire a CG→CG converter the way
+/// `vislcg3` does. Errors are reported as strings (the workload is skipped).
+fn build(w: &Workload) -> Result<FormatConverter, String> {
+    let mut argv: Vec<Vec<char>> = std::iter::once("vislcg3")
+        .chain(w.args.iter().map(|s| s.as_str()))
+        .map(|s| s.chars().collect())
+        .collect();
+    let mut opts = options();
+    let argc = cg3::icu_uoptions::u_parse_args(
+        argv.len() as i32,
+        &mut argv,
+        Opt::NumOptions as i32,
+        &mut opts,
+    );
+    if argc < 0 {
+        return Err(format!("bad args {:?}", w.args));
+    }
+
+    let mut parser = TextualParser::new(Grammar::default(), false);
+    parser
+        .parse_grammar_named(&w.grammar, &w.grammar_name)
+        .map_err(|e| e.to_string())?;
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).map_err(|e| e.to_string())?;
+
+    let mut conv = FormatConverter::new(GrammarApplicator::new(Grammar::default()))
+        .map_err(|e| e.to_string())?;
+    let base = conv.base_mut();
+    base.cfg.fmt_input = StreamFormatKind::Cg;
+    base.cfg.fmt_output = StreamFormatKind::Cg;
+    base.grammar = grammar;
+    base.set_grammar().map_err(|e| e.to_string())?;
+    base.set_options(&opts).map_err(|e| e.to_string())?;
+    Ok(conv)
+}
+
+fn run(w: &Workload, reps: usize) -> Result<Run, String> {
+    let mut conv = build(w)?;
+    let mut input = Vec::with_capacity((w.input.len() + 20) * reps);
+    for _ in 0..reps {
+        input.extend_from_slice(&w.input);
+        if !w.input.ends_with(b"\n") {
+            input.push(b'\n');
+        }
+        // Fixtures without DELIMITERS would otherwise fuse every copy into one
+        // ever-growing window.
+        input.extend_from_slice(b"<STREAMCMD:FLUSH>\n");
+    }
+    let mut cursor = Cursor::new(input);
+    let mut sink = std::io::sink();
+
+    let region = Region::new(GLOBAL);
+    let start = Instant::now();
+    conv.run_grammar_on_text(&mut cursor, &mut sink)
+        .map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed();
+    let stats = region.change();
+    Ok(Run {
+        elapsed,
+        windows: conv.base().doc.num_windows,
+        allocations: stats.allocations + stats.reallocations,
+        bytes: stats.bytes_allocated,
+    })
+}
+
+fn fixtures() -> Vec<Workload> {
+    let test = repo_root().join("test");
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(&test)
+        .expect("test/ fixture directory")
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("T_"))
+        })
+        .collect();
+    dirs.sort();
+    let mut out = Vec::new();
+    for dir in dirs {
+        let grammar_path = dir.join("grammar.cg3");
+        let (Ok(grammar), Ok(input)) = (
+            std::fs::read(&grammar_path),
+            std::fs::read(dir.join("input.txt")),
+        ) else {
+            continue;
+        };
+        // EXTERNAL rules spawn a helper process; that is not engine time.
+        if grammar.windows(8).any(|w| w == b"EXTERNAL") {
+            continue;
+        }
+        let args = std::fs::read_to_string(dir.join("args.txt"))
+            .map(|s| s.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        out.push(Workload {
+            name: dir.file_name().unwrap().to_string_lossy().into_owned(),
+            grammar,
+            grammar_name: grammar_path.to_string_lossy().into_owned(),
+            args,
+            input,
+        });
+    }
+    out
+}
+
+/// A small deterministic generator (LCG) so the synthetic workload is identical
+/// across runs and machines.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as u32
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        self.next() % n
+    }
+}
+
+const POS: &[&str] = &["n", "v", "adj", "adv", "pr", "det", "pron", "cnj"];
+const MORPH: &[&str] = &[
+    "sg", "pl", "nom", "acc", "gen", "pres", "past", "inf", "def", "indef", "p1", "p3",
+];
+const WORDS: u32 = 600;
+const LISTS: u32 = 300;
+const RULES: u32 = 3000;
+const SENTENCES: u32 = 200;
+
+fn synthetic() -> Workload {
+    use std::fmt::Write;
+    let mut rng = Lcg(0x5eed);
+    let mut g = String::new();
+    g.push_str("DELIMITERS = \"<.>\" \"<!>\" \"<?>\" ;\n");
+    g.push_str("LIST BOS = (>>>) ;\nLIST EOS = (<<<) ;\n");
+    for p in POS {
+        let _ = writeln!(g, "LIST {} = {p} ;", p.to_uppercase());
+    }
+    for m in MORPH {
+        let _ = writeln!(g, "LIST {} = {m} ;", m.to_uppercase());
+    }
+    g.push_str("SET NOMINAL = N OR PRON OR ADJ ;\nSET VERBAL = V - INF ;\n");
+    for i in 0..LISTS {
+        g.push_str(&format!("LIST W{i} ="));
+        for _ in 0..(2 + rng.below(6)) {
+            let _ = write!(g, " \"w{}\"", rng.below(WORDS));
+        }
+        g.push_str(" ;\n");
+    }
+    g.push_str("LIST @SUBJ = @SUBJ ;\nLIST @OBJ = @OBJ ;\n");
+    g.push_str("SECTION\n");
+    let set = |rng: &mut Lcg| -> String {
+        match rng.below(4) {
+            0 => POS[rng.below(POS.len() as u32) as usize].to_uppercase(),
+            1 => MORPH[rng.below(MORPH.len() as u32) as usize].to_uppercase(),
+            2 => format!("W{}", rng.below(LISTS)),
+            _ => "NOMINAL".to_string(),
+        }
+    };
+    let ctx = |rng: &mut Lcg| -> String {
+        match rng.below(5) {
+            0 => format!("(-1 {})", set(rng)),
+            1 => format!("(1C {})", set(rng)),
+            2 => format!("(*-1 {} BARRIER {})", set(rng), set(rng)),
+            3 => format!("(*1 {} BARRIER VERBAL)", set(rng)),
+            _ => format!("(NOT 0 {})", set(rng)),
+        }
+    };
+    for i in 0..RULES {
+        if i == RULES / 2 {
+            g.push_str("SECTION\n");
+        }
+        let target = set(&mut rng);
+        let c1 = ctx(&mut rng);
+        let c2 = ctx(&mut rng);
+        // No IFF: a failing-context IFF over an all-target cohort can list the
+        // same reading in `deleted` twice, which panics at window teardown.
+        let _ = match rng.below(5) {
+            0 => writeln!(g, "SELECT {target} IF {c1} {c2} ;"),
+            1 => writeln!(g, "REMOVE {target} IF {c1} {c2} ;"),
+            2 => writeln!(g, "MAP (@SUBJ) TARGET {target} IF {c1} ;"),
+            3 => writeln!(g, "ADD (@OBJ) TARGET {target} IF {c1} (NOT 0 @OBJ) ;"),
+            _ => writeln!(g, "SUBSTITUTE (sg) (pl) TARGET {target} IF {c1} ;"),
+        };
+    }
+
+    let mut input = String::new();
+    for _ in 0..SENTENCES {
+        for _ in 0..(5 + rng.below(20)) {
+            let w = rng.below(WORDS);
+            let _ = writeln!(input, "\"<w{w}>\"");
+            for _ in 0..(1 + rng.below(4)) {
+                let pos = POS[rng.below(POS.len() as u32) as usize];
+                let m1 = MORPH[rng.below(MORPH.len() as u32) as usize];
+                let m2 = MORPH[rng.below(MORPH.len() as u32) as usize];
+                let _ = writeln!(input, "\t\"w{w}\" {pos} {m1} {m2}");
+            }
+        }
+        input.push_str("\"<.>\"\n\t\".\" CLB\n");
+    }
+
+    Workload {
+        name: "synthetic".to_string(),
+        grammar: g.into_bytes(),
+        grammar_name: "synthetic.cg3".to_string(),
+        args: Vec::new(),
+        input: input.into_bytes(),
+    }
+}
+
+fn main() {
+    // `cargo bench` appends `--bench`; anything else is a name filter.
+    let filter: Option<String> = std::env::args().skip(1).find(|a| !a.starts_with("--"));
+    let reps: usize = std::env::var("CG3_BENCH_REPS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n| n > 1)
+        .unwrap_or(200);
+
+    let mut workloads = fixtures();
+    workloads.push(synthetic());
+    workloads.retain(|w| filter.as_deref().is_none_or(|f| w.name.contains(f)));
+
+    println!(
+        "{:<28} {:>9} {:>12} {:>14} {:>14}",
+        "workload", "windows", "µs/window", "allocs/window", "bytes/window"
+    );
+    let (mut total_windows, mut total_time, mut total_allocs) = (0u64, Duration::ZERO, 0usize);
+    for w in &workloads {
+        // A workload that trips an engine panic is reported, not fatal: the
+        // rest of the table is still worth having.
+        let measured =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (run(w, 1), run(w, reps))));
+        let (warm, long) = match measured {
+            Ok((Ok(a), Ok(b))) => (a, b),
+            Ok((Err(e), _) | (_, Err(e))) => {
+                println!("{:<28} skipped: {e}", w.name);
+                continue;
+            }
+            Err(_) => {
+                println!("{:<28} panicked", w.name);
+                continue;
+            }
+        };
+        let extra = long.windows.saturating_sub(warm.windows).max(1) as f64;
+        let allocs = long.allocations.saturating_sub(warm.allocations);
+        let bytes = long.bytes.saturating_sub(warm.bytes);
+        let per_window_us = long.elapsed.as_secs_f64() * 1e6 / f64::from(long.windows.max(1));
+        println!(
+            "{:<28} {:>9} {:>12.2} {:>14.2} {:>14.1}",
+            w.name,
+            long.windows,
+            per_window_us,
+            allocs as f64 / extra,
+            bytes as f64 / extra,
+        );
+        total_windows += u64::from(long.windows);
+        total_time += long.elapsed;
+        total_allocs += allocs;
+    }
+    println!(
+        "total: {total_windows} windows in {:.3}s, {total_allocs} steady-state allocations",
+        total_time.as_secs_f64()
+    );
+}
@@ -32,11 +32,16 @@ use std::ops::DerefMut;
 
 use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::cohort::{CT_AP_UNKNOWN, CT_REMOVED, alloc_cohort, append_reading, unignore_all};
-use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::grammar_applicator::weights::{apertium_weight_tag, is_weight_tag, weight_key};
+use crate::grammar_applicator::{Engine, GrammarApplicator, StreamFormatKind};
 use crate::inlines::{hash_value, insert_if_exists};
 use crate::reading::{Reading, ReadingList, alloc_reading, free_reading};
+use crate::runtime_diagnostics::{LineTail, RuntimeDiagnosticKind};
 use crate::single_window::{SingleWindow, append_cohort};
+use crate::stream_command::StreamCommand;
+use crate::strings::STR_CMD_GETVARS;
 use crate::tag::{T_BASEFORM, T_DEPENDENCY, T_MAPPING, T_WORDFORM, TagList};
+use crate::text_offsets::{OffsetCursor, strip_wordform};
 use crate::types::{DynBitset, TagHash, UString};
 use crate::uextras::{U_EOF, u_fflush, u_fgetc, u_fputc, ux_strip_bom};
 
@@ -50,8 +55,6 @@ const ESC_LT: char = '\u{1}';
 // without depending on those private consts.
 const STR_BEGINTAG: &str = ">>>";
 const STR_ENDTAG: &str = "<<<";
-const STR_CMD_SETVAR: &str = "<STREAMCMD:SETVAR:";
-const STR_CMD_REMVAR: &str = "<STREAMCMD:REMVAR:";
 
 // C++ `Strings.hpp` `constexpr UChar not_sign = u'¬';`.
 const NOT_SIGN: char = '\u{AC}';
@@ -75,6 +78,10 @@ pub struct ApertiumApplicator<B = Box<GrammarApplicator>> {
     pub print_only_first: bool,
     pub delimit_lexical_units: bool,
     pub surface_readings: bool,
+    /// ADDED: the end of the input line being read, and the line and column
+    /// of the open cohort's `^`, for quoting malformed input.
+    tail: LineTail,
+    cohort_at: Option<(u32, u32)>,
 }
 
 // ---------------------------------------------------------------------------
@@ -133,20 +140,43 @@ where
             print_only_first: false,
             delimit_lexical_units: true,
             surface_readings: false,
+            tail: LineTail::default(),
+            cohort_at: None,
         }
     }
 
+    /// ADDED: note `kind`, a problem with the input, quoted at `column` of the
+    /// line being read, else at the open cohort's `^` when it is on that line.
+    fn note_input(
+        &mut self,
+        kind: RuntimeDiagnosticKind,
+        cohort: Option<CohortId>,
+        column: Option<u32>,
+    ) -> Result<(), crate::error::RunError> {
+        let column = match (column, self.cohort_at) {
+            (Some(column), _) => column,
+            (None, Some((line, column))) if line == self.tail.line() => column,
+            _ => 1,
+        };
+        let excerpt = self.tail.excerpt(column);
+        self.base.engine().note_input(kind, cohort, excerpt)
+    }
+
     // [spec:cg3:def:apertium-applicator.cg3.apertium-applicator.parse-stream-var-fn]
     // [spec:cg3:sem:apertium-applicator.cg3.apertium-applicator.parse-stream-var-fn]
     /// C++ `void ApertiumApplicator::parseStreamVar(const SingleWindow* cSWindow,
     /// UString& cleaned, uint32FlatHashMap& variables_set, uint32FlatHashSet&
     /// variables_rem, uint32SortedVector& variables_output)`.
     ///
-    /// `cleaned` is a `Vec<char>` (the C++ mutates it in place with NUL
-    /// terminators; here the `u_strchr`/prefix walks operate on `usize` indices
-    /// over that buffer and slices are re-interned with `add_tag`). BUG-FOR-BUG:
-    /// the live member `variables` map is only updated in case (a) (single bare
-    /// identifier) and only when `c_swindow` is null.
+    ///
+    /// `cleaned` is the superblank without its `[` and `>]`. ADDED: the C++
+    /// walks the list itself and only updates the live `variables` map for a
+    /// lone bare identifier; the port hands the command to
+    /// [`StreamCommand::parse`] and [`Engine::stage_variables`], like every
+    /// other reader, so each listed variable goes live when `c_swindow` is
+    /// null.
+    ///
+    /// [`Engine::stage_variables`]: crate::grammar_applicator::Engine::stage_variables
     pub fn parse_stream_var(
         &mut self,
         c_swindow: Option<SwId>,
@@ -155,180 +185,21 @@ where
         variables_rem: &mut crate::flat_unordered_set::Uint32FlatHashSet,
         variables_output: &mut crate::sorted_vector::Uint32SortedVector,
     ) -> Result<(), crate::error::RunError> {
-        let tag_any = self.base.grammar.tag_any;
-
-        // Helper: intern a slice [start, end) of `cleaned` as a tag, returning
-        // its hash. Empty slice yields `None` (caller checks `s[0]`).
-        let slice_str =
-            |start: usize, end: usize| -> String { cleaned[start..end].iter().collect() };
-
-        let setvar: Vec<char> = STR_CMD_SETVAR.chars().collect();
-        let remvar: Vec<char> = STR_CMD_REMVAR.chars().collect();
-
-        // u_strncmp(&cleaned[0], STR_CMD_SETVAR, size) == 0
-        if cleaned.len() >= setvar.len() && cleaned[..setvar.len()] == setvar[..] {
-            let base = setvar.len();
-            let len = cleaned.len();
-            // s points just past the prefix.
-            let s0 = base;
-            // c = u_strchr(s, ','), d = u_strchr(s, '=')
-            let find_from = |from: usize, ch: char| -> Option<usize> {
-                (from..len).find(|&i| cleaned[i] == ch)
-            };
-            let c = find_from(s0, ',');
-            let d = find_from(s0, '=');
-
-            if c.is_none() && d.is_none() {
-                // Case (a): single bare identifier.
-                let ident = slice_str(s0, len);
-                let tag = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                let hash = self.base.grammar.single_tags_list.get(tag.0).hash.get();
-                variables_set.insert((hash, tag_any));
-                variables_rem.erase(hash);
-                variables_output.insert(hash);
-                if c_swindow.is_none() {
-                    self.base.doc.variables.insert((hash, tag_any));
-                }
-            } else {
-                // Case (b): comma/`=` list. Walk `s` re-computing `c`/`d`.
-                let mut s = Some(s0);
-                let mut c = c;
-                let mut d = d;
-                let mut a: u32;
-                let mut b: u32;
-                while c.is_some() || d.is_some() {
-                    if let Some(dd) = d {
-                        if d.is_some() && (c.is_none() || dd < c.unwrap()) {
-                            // `=` before the next `,`. identifier before `=`.
-                            let ss = s.unwrap();
-                            if ss >= dd {
-                                // empty identifier before `=`
-                                tracing::warn!(
-                                    "Warning: SETVAR on line {} had no identifier before the =! Defaulting to identifier *.",
-                                    self.base.doc.num_lines
-                                );
-                                a = tag_any;
-                            } else {
-                                let ident = slice_str(ss, dd);
-                                let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                                a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                            }
-                            // if (c) { *c = 0; s = c + 1; }
-                            let mut new_s = s;
-                            if let Some(cc) = c {
-                                new_s = Some(cc + 1);
-                            }
-                            // value after `=`: d[1] .. (c or len)
-                            let val_end = c.unwrap_or(len);
-                            if dd + 1 >= val_end {
-                                tracing::warn!(
-                                    "Warning: SETVAR on line {} had no value after the =! Defaulting to value *.",
-                                    self.base.doc.num_lines
-                                );
-                                b = tag_any;
-                            } else {
-                                let val = slice_str(dd + 1, val_end);
-                                let t = self.base.add_tag(&val, crate::tag::TagType::empty())?;
-                                b = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                            }
-                            if c.is_none() {
-                                d = None;
-                                new_s = None;
-                            }
-                            s = new_s;
-                            variables_set.insert((a, b));
-                            variables_rem.erase(a);
-                            variables_output.insert(a);
-                        } else if let Some(cc) = c {
-                            // comma-separated bare identifier.
-                            let ss = s.unwrap();
-                            if ss >= cc {
-                                tracing::warn!(
-                                    "Warning: SETVAR on line {} had no identifier after the ,! Defaulting to identifier *.",
-                                    self.base.doc.num_lines
-                                );
-                                a = tag_any;
-                            } else {
-                                let ident = slice_str(ss, cc);
-                                let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                                a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                            }
-                            s = Some(cc + 1);
-                            variables_set.insert((a, tag_any));
-                            variables_rem.erase(a);
-                            variables_output.insert(a);
-                        }
-                    } else if let Some(cc) = c {
-                        // d is None but c exists — comma-separated bare identifier.
-                        let ss = s.unwrap();
-                        if ss >= cc {
-                            tracing::warn!(
-                                "Warning: SETVAR on line {} had no identifier after the ,! Defaulting to identifier *.",
-                                self.base.doc.num_lines
-                            );
-                            a = tag_any;
-                        } else {
-                            let ident = slice_str(ss, cc);
-                            let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                            a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                        }
-                        s = Some(cc + 1);
-                        variables_set.insert((a, tag_any));
-                        variables_rem.erase(a);
-                        variables_output.insert(a);
-                    }
-
-                    if let Some(ss) = s {
-                        c = find_from(ss, ',');
-                        d = find_from(ss, '=');
-                        if c.is_none() && d.is_none() {
-                            // final bare identifier.
-                            let ident = slice_str(ss, len);
-                            let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                            a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                            variables_set.insert((a, tag_any));
-                            variables_rem.erase(a);
-                            variables_output.insert(a);
-                            s = None;
-                        }
-                    }
-                }
-            }
-        } else if cleaned.len() >= remvar.len() && cleaned[..remvar.len()] == remvar[..] {
-            let base = remvar.len();
-            let len = cleaned.len();
-            let find_from = |from: usize, ch: char| -> Option<usize> {
-                (from..len).find(|&i| cleaned[i] == ch)
-            };
-            let mut s = base;
-            let mut c = find_from(s, ',');
-            while let Some(cc) = c {
-                // while (c && *c) — *c is ',' which is non-NUL, so continue.
-                // if (s[0]) — s must not be at the terminator (i.e. s < len and
-                // slice non-empty).
-                if s < cc {
-                    let ident = slice_str(s, cc);
-                    let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                    let a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                    variables_set.erase(a);
-                    variables_rem.insert(a);
-                    variables_output.insert(a);
-                }
-                s = cc + 1;
-                c = find_from(s, ',');
-            }
-            // if (s && s[0]) — trailing identifier.
-            if s < len {
-                let ident = slice_str(s, len);
-                let t = self.base.add_tag(&ident, crate::tag::TagType::empty())?;
-                let a = self.base.grammar.single_tags_list.get(t.0).hash.get();
-                variables_set.erase(a);
-                variables_rem.insert(a);
-                variables_output.insert(a);
+        let mut text: String = cleaned.iter().collect();
+        text.push('>');
+        // Neither prefix matched → do nothing.
+        match StreamCommand::parse(&text) {
+            Some(cmd @ (StreamCommand::SetVar(_) | StreamCommand::RemVar(_))) => {
+                self.base.engine().stage_variables(
+                    &cmd,
+                    c_swindow.is_none(),
+                    variables_set,
+                    variables_rem,
+                    variables_output,
+                )
             }
+            _ => Ok(()),
         }
-        // Neither prefix matched → do nothing.
-        Ok(())
     }
 
     // [spec:cg3:def:apertium-applicator.cg3.apertium-applicator.process-reading-fn]
@@ -375,13 +246,14 @@ where
                     n += 1;
                 }
                 if n >= len || p[n] != '>' {
-                    tracing::warn!(
-                        "Warning: Did not find matching > to close the tag on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    self.note_input(RuntimeDiagnosticKind::UnclosedTag, None, None)?;
                     continue;
                 }
-                let tagtext: String = p[i..n].iter().collect();
+                let mut tagtext: String = p[i..n].iter().collect();
+                // ADDED: a weight keeps its brackets, so it is numeric.
+                if let Some(weight) = apertium_weight_tag(&tagtext, &self.base.cfg.weight_tag) {
+                    tagtext = weight;
+                }
                 let t = self.base.add_tag(&tagtext, crate::tag::TagType::empty())?;
                 // bf.size() == 1 means only the opening quote so far.
                 if bf.chars().count() == 1 {
@@ -602,21 +474,7 @@ where
         // ux_stdin/ux_stdout are Option<()> placeholders — assignment elided.
 
         // No-hard/soft-delimiter warnings.
-        let no_hard = self.base.grammar.delimiters.is_none();
-        let no_soft = self.base.grammar.soft_delimiters.is_none();
-        if no_hard {
-            if no_soft {
-                tracing::warn!(
-                    "Warning: No soft or hard delimiters defined in grammar. Hard limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.hard_limit
-                );
-            } else {
-                tracing::warn!(
-                    "Warning: No hard delimiters defined in grammar. Soft limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.soft_limit
-                );
-            }
-        }
+        self.base.engine().note_missing_delimiters();
 
         // C++ `UChar c = 0;` — the `while ((c = u_fgetc(input)) …)` head assigns
         // it before every read, so no initializer is needed here.
@@ -636,6 +494,7 @@ where
             variables_set: crate::flat_unordered_map::Uint32FlatHashMap::default(),
             variables_rem: crate::flat_unordered_set::Uint32FlatHashSet::default(),
             variables_output: crate::sorted_vector::Uint32SortedVector::new(),
+            offsets: OffsetCursor::default(),
         };
 
         self.base.index();
@@ -658,6 +517,8 @@ where
         self.base.doc.stream.window_span = self.base.cfg.num_windows;
 
         ux_strip_bom(input);
+        self.tail = LineTail::default();
+        self.cohort_at = None;
 
         // Main character loop: while ((c = u_fgetc(input)) != U_EOF).
         loop {
@@ -665,6 +526,7 @@ where
             if c == U_EOF {
                 break;
             }
+            self.tail.push(c);
 
             if c == '\n' {
                 self.base.doc.num_lines = self.base.doc.num_lines.wrapping_add(1);
@@ -672,6 +534,20 @@ where
 
             if c == '\\' {
                 let n = u_fgetc(input);
+                if n == U_EOF {
+                    // ADDED: the C++ kept the sentinel as the escaped char.
+                    let kind = RuntimeDiagnosticKind::malformed(
+                        "\\ at the end of the input escapes nothing",
+                        "",
+                    );
+                    let column = Some(self.tail.column());
+                    self.note_input(kind, None, column)?;
+                } else {
+                    self.tail.push(n);
+                }
+                if !st.in_cohort && !st.in_wblank && n != U_EOF {
+                    st.offsets.advance_char(n);
+                }
                 if !st.in_cohort {
                     st.blank.push(c);
                     st.blank.push(n);
@@ -687,6 +563,13 @@ where
                 continue;
             }
 
+            // ADDED: blank text counts towards the offsets; the markup around
+            // superblanks and cohorts, and word-bound blanks, do not.
+            let markup = c == '[' || (st.in_blank && c == ']') || (!st.in_blank && c == '^');
+            if !(st.in_cohort || st.in_wblank || markup) {
+                st.offsets.advance_char(c);
+            }
+
             if !st.in_cohort && c == '[' {
                 if st.in_blank {
                     st.in_wblank = true;
@@ -694,6 +577,7 @@ where
                 st.in_blank = true;
             } else if !st.in_blank && c == '^' {
                 st.in_cohort = true;
+                self.cohort_at = Some((self.tail.line(), self.tail.column()));
             }
 
             if !st.in_cohort {
@@ -707,13 +591,34 @@ where
             } else if st.in_blank && c == ']' {
                 st.in_blank = false;
                 let bchars: Vec<char> = st.blank.chars().collect();
-                if bchars.len() > 14
-                    && bchars.get(1) == Some(&'<')
-                    && bchars.get(bchars.len() - 2) == Some(&'>')
-                {
+                // ADDED: the superblank just closed, wherever it starts; the
+                // C++ only sees one that opens the blank, so `$ [<…>]` is text.
+                let at = bchars.iter().rposition(|&ch| ch == '[').unwrap_or(0);
+                let sb = &bchars[at..];
+                if sb.len() > 14 && sb.get(1) == Some(&'<') && sb.get(sb.len() - 2) == Some(&'>') {
                     // cleaned = blank.substr(1, size-3): drop leading '[' and
                     // trailing '>]' (no trailing '>').
-                    let cleaned: Vec<char> = bchars[1..bchars.len() - 2].to_vec();
+                    let cleaned: Vec<char> = sb[1..sb.len() - 2].to_vec();
+                    // ADDED: GETVARS drains the windows read so far and
+                    // answers in its place.
+                    let getvars = STR_CMD_GETVARS.strip_suffix('>').is_some_and(|cmd| {
+                        cleaned.len() == cmd.chars().count()
+                            && cleaned.iter().copied().eq(cmd.chars())
+                    });
+                    if getvars {
+                        st.blank = bchars[..at].iter().collect();
+                        self.drain(fmt, false, &mut st, '\0', output)?;
+                        let reply = self
+                            .base
+                            .engine()
+                            .variables_reply(&st.variables_set, &st.variables_rem);
+                        fmt.print_plain_text_line(
+                            &mut self.base.engine(),
+                            &format!("[{reply}]"),
+                            output,
+                        );
+                        continue;
+                    }
                     self.parse_stream_var(
                         st.c_swindow,
                         &cleaned,
@@ -724,10 +629,9 @@ where
                 }
             } else if !st.in_blank && c == '$' {
                 if !st.in_cohort {
-                    tracing::error!(
-                        "Error: $ found without prior ^ on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind = RuntimeDiagnosticKind::malformed("$ found without prior ^", "");
+                    let column = Some(self.tail.column());
+                    self.note_input(kind, None, column)?;
                     // CG3Quit(1) — abort in C++; keep going in the port.
                     return Ok(());
                 }
@@ -751,10 +655,11 @@ where
                     let wchars: Vec<char> = st.wblank.chars().collect();
                     let n = wchars.len();
                     if wchars[n - 1] != ']' || (n < 2 || wchars[n - 2] != ']') {
-                        tracing::error!(
-                            "Error: Word-bound blank was not immediately prior to token on line {}",
-                            self.base.doc.num_lines
+                        let kind = RuntimeDiagnosticKind::malformed(
+                            "Word-bound blank was not immediately prior to token",
+                            "",
                         );
+                        self.note_input(kind, None, None)?;
                         return Ok(());
                     }
                 }
@@ -843,7 +748,11 @@ where
                 }
                 wf.push_str(">\"");
                 let wf_tid = self.base.add_tag(&wf, crate::tag::TagType::empty())?;
-                self.base.doc.store.cohorts.get_mut(cc.0).wordform = Some(wf_tid);
+                {
+                    let c = self.base.doc.store.cohorts.get_mut(cc.0);
+                    c.wordform = Some(wf_tid);
+                    c.offsets = Some(st.offsets.take(strip_wordform(&wf)));
+                }
 
                 // Static reading.
                 if p < tchars.len() && tchars[p] == '<' {
@@ -924,11 +833,13 @@ where
                                 .baseform
                                 .is_none()
                             {
-                                tracing::warn!(
-                                    "Warning: Cohort {} on line {} had no valid baseform.",
-                                    self.base.doc.num_cohorts,
-                                    self.base.doc.num_lines
-                                );
+                                self.note_input(
+                                    RuntimeDiagnosticKind::NoBaseform {
+                                        format: StreamFormatKind::Apertium,
+                                    },
+                                    Some(cc),
+                                    None,
+                                )?;
                             }
                             rbuf.clear();
                             p += 1;
@@ -1015,16 +926,9 @@ where
                     };
                     if hard || delim_match {
                         if !self.base.cfg.is_conv && cohorts_size >= self.base.cfg.hard_limit {
-                            let wf_tid = self.base.doc.store.cohorts.get(cc.0).wordform.unwrap();
-                            let wftag =
-                                self.base.grammar.single_tags_list.get(wf_tid.0).tag.clone();
-                            tracing::warn!(
-                                "Warning: Hard limit of {} cohorts reached at cohort {} (#{}) on line {} - forcing break.",
-                                self.base.cfg.hard_limit,
-                                wftag,
-                                self.base.doc.num_cohorts,
-                                self.base.doc.num_lines
-                            );
+                            self.base
+                                .engine()
+                                .note_hard_limit(cc, StreamFormatKind::Apertium);
                         }
                         let readings = self.base.doc.store.cohorts.get(cc.0).readings.clone();
                         for r in readings {
@@ -1088,7 +992,8 @@ where
 
     /// C++ `flush(bool n)` lambda from `runGrammarOnText`. Drains all pending
     /// windows, prints them, and resets the driver state (the lambda's
-    /// by-reference captures, passed as [`ApertiumStreamState`]).
+    /// by-reference captures, passed as [`ApertiumStreamState`]), variables
+    /// included.
     fn flush<F, W>(
         &mut self,
         fmt: &mut F,
@@ -1097,6 +1002,28 @@ where
         c: char,
         output: &mut W,
     ) -> Result<(), crate::error::RunError>
+    where
+        F: crate::grammar_applicator::stream_format::StreamFormat,
+        W: Write,
+    {
+        self.drain(fmt, n, st, c, output)?;
+        st.variables_rem.clear(0);
+        st.variables_set.clear(0);
+        st.variables_output.clear();
+        self.base.doc.variables.clear(0);
+        Ok(())
+    }
+
+    /// ADDED: [`Self::flush`] short of clearing the variables, which is how
+    /// `GETVARS` drains.
+    fn drain<F, W>(
+        &mut self,
+        fmt: &mut F,
+        n: bool,
+        st: &mut ApertiumStreamState,
+        c: char,
+        output: &mut W,
+    ) -> Result<(), crate::error::RunError>
     where
         F: crate::grammar_applicator::stream_format::StreamFormat,
         W: Write,
@@ -1157,7 +1084,7 @@ where
         self.base.engine().shuffle_windows_down();
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
-            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
             let opt = Some(tmp);
             {
                 let base = &mut *self.base;
@@ -1188,10 +1115,6 @@ where
         st.c_swindow = None;
         st.c_cohort = None;
         st.token.clear();
-        st.variables_rem.clear(0);
-        st.variables_set.clear(0);
-        st.variables_output.clear();
-        self.base.doc.variables.clear(0);
         Ok(())
     }
 }
@@ -1226,6 +1149,8 @@ struct ApertiumStreamState {
     variables_rem: crate::flat_unordered_set::Uint32FlatHashSet,
     /// C++ `uint32SortedVector variables_output`.
     variables_output: crate::sorted_vector::Uint32SortedVector,
+    /// ADDED: the position in the input text, for `Cohort::offsets`.
+    offsets: OffsetCursor,
 }
 
 /// Apertium's print-vtable state. This lets an Apertium input driver borrow the
@@ -1375,15 +1300,18 @@ impl ApertiumFormat {
                 .unwrap_or_default();
 
             if self.wordform_case {
+                // ADDED: in the grammar's case locale, so a Turkish `i` raises
+                // to `İ` (the C++ used ICU's root locale).
+                let locale = grammar.case_locale;
                 if casing == ApertiumCasing::Upper {
-                    bf = bf.iter().flat_map(|c| c.to_uppercase()).collect();
+                    bf = locale
+                        .to_upper(&bf.iter().collect::<String>())
+                        .chars()
+                        .collect();
                 } else if casing == ApertiumCasing::Title && r.next.is_none() {
                     let fl = firstlower as usize;
                     if fl < bf.len() {
-                        let up: Vec<char> = bf[fl].to_uppercase().collect();
-                        if let Some(&first_up) = up.first() {
-                            bf[fl] = first_up;
-                        }
+                        bf[fl] = locale.upper_char(bf[fl]);
                     }
                 }
             }
@@ -1438,6 +1366,7 @@ impl ApertiumFormat {
 
         let mut used_tags = crate::sorted_vector::Uint32SortedVector::new();
         let escape = if self.surface_readings { "\\" } else { "" };
+        let weight = weight_key(&e.cfg.weight_tag);
         for tter in tags_list {
             if e.cfg.unique_tags {
                 if used_tags.find(tter) != used_tags.end() {
@@ -1455,6 +1384,10 @@ impl ApertiumFormat {
                 let first = tag.tag.chars().next();
                 if first == Some('+') {
                     let _ = write!(output, "{}", tag.tag);
+                } else if is_weight_tag(tag, weight) {
+                    // ADDED: a weight tag has its brackets already.
+                    let inner = &tag.tag[1..tag.tag.len() - 1];
+                    let _ = write!(output, "{escape}<{inner}{escape}>");
                 } else if first == Some('&') {
                     let inner = substr_from(&tag.tag, 2);
                     let _ = write!(output, "{escape}<{inner}{escape}>");
@@ -1504,7 +1437,12 @@ impl ApertiumFormat {
         if e.cfg.trace {
             for &iter_hb in r.hit_by.iter() {
                 u_fputc('<', output);
-                crate::grammar_applicator::core::print_trace(e.grammar, e.cfg, output, iter_hb);
+                crate::grammar_applicator::core::print_trace(
+                    e.trace_grammar(),
+                    e.cfg,
+                    output,
+                    iter_hb,
+                );
                 u_fputc('>', output);
             }
         }
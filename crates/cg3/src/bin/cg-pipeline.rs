@@ -0,0 +1,9 @@
+//! `cg-pipeline` — run a CG pipeline described in a JSON file (no C++ analog).
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cg3::tools::handle_divvun_version(&args, "Pipeline", &[]) {
+        return;
+    }
+    cg3::tools::init_diagnostics();
+    std::process::exit(cg3::tools::cg_pipeline::main_pipeline(&args));
+}
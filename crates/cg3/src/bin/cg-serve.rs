@@ -0,0 +1,9 @@
+//! `cg-serve` — serve preloaded grammars over HTTP (no C++ analog).
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cg3::tools::handle_divvun_version(&args, "Server", &[]) {
+        return;
+    }
+    cg3::tools::init_diagnostics();
+    std::process::exit(cg3::tools::cg_serve::main_serve(&args));
+}
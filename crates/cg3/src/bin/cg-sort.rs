@@ -0,0 +1,14 @@
+//! `cg-sort` — ADDED, no C++ analog: `cg-conv --sort` under its own
+//! name, replacing `scripts/cg-sort` (see `cg3::stream_filters`).
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cg3::tools::handle_divvun_version(&args, "Sort", &[]) {
+        return;
+    }
+    cg3::tools::init_diagnostics();
+    std::process::exit(cg3::tools::cg_conv::main_conv_as(
+        "cg-sort",
+        &args,
+        Some(cg3::options_conv::Opt::Sort),
+    ));
+}
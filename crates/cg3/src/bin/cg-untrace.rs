@@ -0,0 +1,14 @@
+//! `cg-untrace` — ADDED, no C++ analog: `cg-conv --untrace` under its own
+//! name, replacing `scripts/cg-untrace` (see `cg3::stream_filters`).
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cg3::tools::handle_divvun_version(&args, "Untrace", &[]) {
+        return;
+    }
+    cg3::tools::init_diagnostics();
+    std::process::exit(cg3::tools::cg_conv::main_conv_as(
+        "cg-untrace",
+        &args,
+        Some(cg3::options_conv::Opt::Untrace),
+    ));
+}
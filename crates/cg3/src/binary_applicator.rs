@@ -67,7 +67,7 @@ fn tag_by_hash(grammar: &Grammar, hash: TagHash) -> TagId {
 
 // C++ `Strings.hpp` stream-command name strings — now sourced from the fully
 // ported `crate::strings` module.
-use crate::strings::{STR_CMD_EXIT, STR_CMD_FLUSH, STR_CMD_IGNORE, STR_CMD_RESUME};
+use crate::strings::{STR_CMD_EXIT, STR_CMD_FLUSH, STR_CMD_IGNORE, STR_CMD_RESUME, STR_CMD_VARS};
 
 // [spec:cg3:def:binary-applicator.cg3.binary-format-flags]
 // C++ `enum BinaryFormatFlags` — OR-combinable, so `u32` bit constants.
@@ -576,6 +576,11 @@ impl BinaryFormat {
     /// then the mapped command byte. QUIRK (faithful): an unrecognised `cmd`
     /// writes ONLY the type byte (malformed packet). No flush.
     pub fn bin_print_stream_command<W: Write>(&mut self, cmd: &str, output: &mut W) {
+        // ADDED: the `GETVARS` answer has no command byte; it goes out as text.
+        if cmd.starts_with(STR_CMD_VARS) {
+            self.bin_print_plain_text_line(cmd, output);
+            return;
+        }
         self.bin_write_header(output);
         write_le(output, ui8(BinaryPacketType::BfpCommand as u32));
         if cmd == STR_CMD_FLUSH {
@@ -1137,7 +1142,7 @@ impl<'x> BinaryApplicator<'x> {
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
             // C++ virtual printSingleWindow — the most-derived format decides.
-            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
             let t = Some(tmp);
             crate::single_window::free_swindow(
                 &mut self.base.doc.store,
@@ -60,6 +60,17 @@
 //! * **context record count** is `grammar.contexts.size()` — every context
 //!   reachable via `tmpl`/`ors`/`linked` MUST also be a distinct `contexts` map
 //!   entry, else more records emit than the count and the stream desyncs on read.
+//! * **`BINF_NORMALIZE_NFC` / `BINF_NORMALIZE_NFD`** (bits 18–19, ADDED) record
+//!   the grammar's `normalize-nfc` / `normalize-nfd` option in the feature word
+//!   the C++ shares. The C++ reader ignores bits it does not know, so a file
+//!   that sets one is marked with `BIN_REV_PORT` over the revision, which the
+//!   C++ refuses as newer than it knows rather than running the grammar
+//!   without normalising. A grammar without the option writes the same bytes
+//!   as before.
+//! * **`BINF_CASEFOLD_TURKIC` / `BINF_CASEFOLD_LITHUANIAN`** (bits 20–21,
+//!   ADDED) record a `casefold-tr`/`-az`/`-lt` option the same way, and mark
+//!   the revision likewise, rather than let the C++ run the file with its
+//!   root-locale folding.
 //!
 //! ## Legacy reader OUT OF SCOPE
 //! The C++ `readBinaryGrammar_10043` / `readContextualTest_10043` methods (the
@@ -73,11 +84,13 @@ use std::collections::HashMap;
 use std::io::{Read, Write};
 
 use crate::arena::{CtxId, RuleId, SetId, TagId};
+use crate::case_folding::CaseLocale;
 use crate::contextual_test::POS_64BIT;
 use crate::flat_unordered_set::Uint32FlatHashSet;
 use crate::grammar::{Grammar, trie_unserialize};
 use crate::igrammar_parser::IGrammarParser;
 use crate::inlines::{is_cg3b, read_be, read_be_f64, ui16, ui32, write_be, write_be_f64};
+use crate::normalization::Normalization;
 use crate::rule::Rule;
 use crate::set::Set;
 use crate::strings::Keywords;
@@ -106,6 +119,17 @@ const BINF_BAG: u32 = 1 << 14;
 const BINF_ORDERED: u32 = 1 << 15;
 const BINF_TEXT_DELIMS: u32 = 1 << 16;
 const BINF_ADDCOHORT_ATTACH: u32 = 1 << 17;
+// ADDED — no C++ analog: the grammar's `normalize-nfc` / `normalize-nfd`. The
+// C++ reader ignores both bits (see the module doc).
+const BINF_NORMALIZE_NFC: u32 = 1 << 18;
+const BINF_NORMALIZE_NFD: u32 = 1 << 19;
+// ADDED — no C++ analog: or-ed into the revision of a file whose feature word
+// sets a bit only this port reads. The layout is that of the revision under it.
+const BIN_REV_PORT: u32 = 1 << 31;
+// ADDED — no C++ analog: the grammar's `casefold-*` locale. The C++ reader
+// ignores both bits (see the module doc).
+const BINF_CASEFOLD_TURKIC: u32 = 1 << 20;
+const BINF_CASEFOLD_LITHUANIAN: u32 = 1 << 21;
 
 // C++ `BinaryGrammar.hpp` `constexpr uint32_t BIN_REV_ANCIENT / BIN_REV_CMDARGS`.
 const BIN_REV_ANCIENT: u32 = 10297;
@@ -203,11 +227,12 @@ impl BinaryGrammar {
         })?;
         self.grammar.grammar_size = meta.len() as usize;
 
-        let data =
-            std::fs::read(filename).map_err(|source| crate::error::GrammarError::Unreadable {
+        let data = crate::compression::read_file(filename).map_err(|source| {
+            crate::error::GrammarError::Unreadable {
                 path: filename.to_string(),
                 source,
-            })?;
+            }
+        })?;
         let mut cur = std::io::Cursor::new(data);
         let rv = self.parse_grammar_reader(&mut cur);
         // [spec:cg3:req:diagnostics.source-lazy]
@@ -264,6 +289,12 @@ impl BinaryGrammar {
         }
 
         let bin_revision = read_be::<u32, _>(input);
+        // ADDED: a file marked as needing this port reads as the current one.
+        let bin_revision = if bin_revision == CG3_FEATURE_REV | BIN_REV_PORT {
+            CG3_FEATURE_REV
+        } else {
+            bin_revision
+        };
         if bin_revision <= BIN_REV_ANCIENT {
             if self.verbosity >= 1 {
                 tracing::warn!(
@@ -294,6 +325,20 @@ impl BinaryGrammar {
         self.grammar.has_bag_of_tags = (fields & BINF_BAG) != 0;
         self.grammar.ordered = (fields & BINF_ORDERED) != 0;
         self.grammar.addcohort_attach = (fields & BINF_ADDCOHORT_ATTACH) != 0;
+        self.grammar.normalization = if fields & BINF_NORMALIZE_NFC != 0 {
+            Normalization::Nfc
+        } else if fields & BINF_NORMALIZE_NFD != 0 {
+            Normalization::Nfd
+        } else {
+            Normalization::None
+        };
+        self.grammar.case_locale = if fields & BINF_CASEFOLD_TURKIC != 0 {
+            CaseLocale::Turkic
+        } else if fields & BINF_CASEFOLD_LITHUANIAN != 0 {
+            CaseLocale::Lithuanian
+        } else {
+            CaseLocale::Root
+        };
 
         if fields & BINF_PREFIX != 0 {
             let len = read_be::<u32, _>(input);
@@ -898,9 +943,6 @@ impl BinaryGrammar {
         // the BINF_SETS bit + the set section agree.
         let used_sets = self.used_set_ids();
 
-        let _ = output.write_all(b"CG3B");
-        write_be(output, CG3_FEATURE_REV);
-
         let mut fields = 0u32;
         if self.grammar.has_dep {
             fields |= BINF_DEP;
@@ -956,7 +998,28 @@ impl BinaryGrammar {
         if self.grammar.addcohort_attach {
             fields |= BINF_ADDCOHORT_ATTACH;
         }
+        match self.grammar.normalization {
+            Normalization::None => {}
+            Normalization::Nfc => fields |= BINF_NORMALIZE_NFC,
+            Normalization::Nfd => fields |= BINF_NORMALIZE_NFD,
+        }
+        match self.grammar.case_locale {
+            CaseLocale::Root => {}
+            CaseLocale::Turkic => fields |= BINF_CASEFOLD_TURKIC,
+            CaseLocale::Lithuanian => fields |= BINF_CASEFOLD_LITHUANIAN,
+        }
 
+        // ADDED: a file the C++ would misread is marked so it refuses it.
+        let port_only = BINF_NORMALIZE_NFC
+            | BINF_NORMALIZE_NFD
+            | BINF_CASEFOLD_TURKIC
+            | BINF_CASEFOLD_LITHUANIAN;
+        let _ = output.write_all(b"CG3B");
+        if fields & port_only != 0 {
+            write_be(output, CG3_FEATURE_REV | BIN_REV_PORT);
+        } else {
+            write_be(output, CG3_FEATURE_REV);
+        }
         write_be(output, fields);
 
         if self.grammar.mapping_prefix != '\0' {
@@ -0,0 +1,679 @@
+//! ADDED — no C++ analog. A packet-by-packet decoder for binary streams
+//! (`cg-conv --inspect-binary`), for finding out what went wrong between two
+//! processes talking `--out-binary` / `--in-binary`.
+//!
+//! [`inspect`] decodes a stream without a grammar or an engine, keeping the
+//! byte offset of every packet, cohort and reading, and notes each place the
+//! stream breaks the protocol instead of stopping there: a field cut short, a
+//! tag index past the window's tag table, an unknown packet, command or
+//! variable code, a string that is not UTF-8, bytes a window declares but
+//! never uses. [`Inspection::write_report`] prints it all as text. A window's
+//! length prefix lets decoding carry on past a broken window; once a packet's
+//! own framing is lost (a cut or an unknown packet type), nothing after it can
+//! be trusted, and decoding stops.
+//!
+//! ## Wire format
+//! The reader is [`crate::binary_applicator::BinaryApplicator`], the writer
+//! [`crate::binary_applicator::BinaryFormat`]. Integers are little-endian;
+//! `str` is a `u16` byte length and that many bytes of UTF-8; `tag` is a
+//! `u16` index into the current window's tag table.
+//!
+//! | Part | Layout |
+//! |------|--------|
+//! | header | `"CGBF"`, `u32` version (1) |
+//! | packet | `u8` type: 1 window, 2 command, 3 text; then its body |
+//! | command | `u8` code: 1 FLUSH, 2 EXIT, 3 IGNORE, 4 RESUME |
+//! | text | `str` |
+//! | window | `u32` length of what follows; `u16` flags (1: dependency spans); `u16` tag count, each a `str`; `u16` variable count, each `u8` op (1 set, 2 set to any, 3 remove), `tag` key, `tag` value; `str` text; `str` text after; `u16` cohort count, then the cohorts |
+//! | cohort | `u16` flags (1: related); `tag` wordform; `u16` static tag count, each a `tag`; `u32` own dependency number; `u32` parent (`0xFFFFFFFF` for none); `u16` relation count, each `tag` name, `u32` target; `str` text; `str` wordform blank; `u16` reading count, then the readings |
+//! | reading | `u16` flags (1: sub-reading of the one before, 2: deleted); `tag` baseform; `u16` tag count, each a `tag` |
+
+use std::io::{self, Write};
+
+use crate::binary_applicator::{
+    BFC_EXIT, BFC_FLUSH, BFC_IGNORE, BFC_RELATED, BFC_RESUME, BFR_DELETED, BFR_SUBREADING,
+    BFV_REMVAR, BFV_SETVAR, BFV_SETVAR_ANY, BFW_DEP_SPAN, BinaryPacketType, CG3_BINARY_STREAM,
+};
+use crate::cohort::DEP_NO_PARENT;
+
+/// A place the stream breaks the protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Byte offset into the stream.
+    pub offset: usize,
+    pub problem: String,
+}
+
+/// A decoded stream.
+#[derive(Debug, Default)]
+pub struct Inspection {
+    /// The version the header names, if it could be read.
+    pub version: Option<u32>,
+    pub packets: Vec<Packet>,
+    pub problems: Vec<Problem>,
+    /// How many bytes were decoded before the stream ended or decoding
+    /// stopped.
+    pub decoded: usize,
+}
+
+/// One packet, at the offset of its type byte.
+#[derive(Debug)]
+pub struct Packet {
+    pub offset: usize,
+    pub kind: PacketKind,
+}
+
+#[derive(Debug)]
+pub enum PacketKind {
+    Window(Box<WindowDump>),
+    /// The command code, `None` when the stream ended before it.
+    Command(Option<u8>),
+    Text(String),
+}
+
+#[derive(Debug, Default)]
+pub struct WindowDump {
+    /// The length the window declares, after its length field.
+    pub length: u32,
+    pub flags: u16,
+    pub tags: Vec<String>,
+    pub variables: Vec<Variable>,
+    pub text: String,
+    pub text_post: String,
+    pub cohorts: Vec<CohortDump>,
+}
+
+#[derive(Debug)]
+pub struct Variable {
+    pub op: u8,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default)]
+pub struct CohortDump {
+    pub offset: usize,
+    pub flags: u16,
+    pub wordform: String,
+    pub static_tags: Vec<String>,
+    pub dep_self: u32,
+    pub dep_parent: u32,
+    pub relations: Vec<(String, u32)>,
+    pub text: String,
+    pub wblank: String,
+    pub readings: Vec<ReadingDump>,
+}
+
+#[derive(Debug, Default)]
+pub struct ReadingDump {
+    pub offset: usize,
+    pub flags: u16,
+    pub baseform: String,
+    pub tags: Vec<String>,
+}
+
+/// The stream ended, or a window's body did, in the middle of `field`.
+struct Cut {
+    offset: usize,
+    field: &'static str,
+}
+
+/// A read position over `bytes[..end]`, reporting offsets into the stream.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize, field: &'static str) -> Result<&[u8], Cut> {
+        if self.end - self.pos < n {
+            return Err(Cut {
+                offset: self.pos,
+                field,
+            });
+        }
+        let start = self.pos;
+        self.pos += n;
+        Ok(&self.bytes[start..self.pos])
+    }
+
+    fn u8(&mut self, field: &'static str) -> Result<u8, Cut> {
+        Ok(self.take(1, field)?[0])
+    }
+
+    fn u16(&mut self, field: &'static str) -> Result<u16, Cut> {
+        let b = self.take(2, field)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self, field: &'static str) -> Result<u32, Cut> {
+        let b = self.take(4, field)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn str(&mut self, field: &'static str, problems: &mut Vec<Problem>) -> Result<String, Cut> {
+        let len = self.u16(field)?;
+        let at = self.pos;
+        let bytes = self.take(len.into(), field)?;
+        let s = String::from_utf8_lossy(bytes).into_owned();
+        if std::str::from_utf8(bytes).is_err() {
+            problems.push(Problem {
+                offset: at,
+                problem: format!("{field} is not valid UTF-8"),
+            });
+        }
+        Ok(s)
+    }
+
+    /// A tag index, resolved against the window's tag table.
+    fn tag(
+        &mut self,
+        field: &'static str,
+        tags: &[String],
+        problems: &mut Vec<Problem>,
+    ) -> Result<String, Cut> {
+        let at = self.pos;
+        let index = self.u16(field)?;
+        match tags.get(usize::from(index)) {
+            Some(tag) => Ok(tag.clone()),
+            None => {
+                problems.push(Problem {
+                    offset: at,
+                    problem: format!(
+                        "{field} is tag {index}, but the window has only {} tags",
+                        tags.len()
+                    ),
+                });
+                Ok(format!("<missing tag {index}>"))
+            }
+        }
+    }
+}
+
+/// Decode the binary stream `bytes`, packet by packet.
+pub fn inspect(bytes: &[u8]) -> Inspection {
+    let mut report = Inspection::default();
+    let mut r = Reader {
+        bytes,
+        pos: 0,
+        end: bytes.len(),
+    };
+    match r.take(4, "stream magic") {
+        Ok(magic) if crate::inlines::is_cg3bsf(magic) => {}
+        Ok(magic) => {
+            report.problems.push(Problem {
+                offset: 0,
+                problem: format!(
+                    "stream does not start with \"CGBF\" but {:02x?}{}",
+                    magic,
+                    if crate::inlines::is_cg3b(bytes) {
+                        ", the magic of a binary grammar"
+                    } else {
+                        ""
+                    }
+                ),
+            });
+            return report;
+        }
+        Err(cut) => return report.cut(cut),
+    }
+    match r.u32("stream version") {
+        Ok(version) => {
+            report.version = Some(version);
+            if version != CG3_BINARY_STREAM {
+                report.problems.push(Problem {
+                    offset: 4,
+                    problem: format!(
+                        "stream is version {version}, but only version {CG3_BINARY_STREAM} is read"
+                    ),
+                });
+            }
+        }
+        Err(cut) => return report.cut(cut),
+    }
+
+    while r.pos < r.end {
+        let offset = r.pos;
+        let kind = match r.u8("packet type").map(u32::from) {
+            Ok(ty) if ty == BinaryPacketType::BfpWindow as u32 => {
+                let mut w = Box::<WindowDump>::default();
+                let result = window(&mut r, &mut w, &mut report.problems);
+                let kind = PacketKind::Window(w);
+                if let Err(cut) = result {
+                    report.packets.push(Packet { offset, kind });
+                    return report.cut(cut);
+                }
+                kind
+            }
+            Ok(ty) if ty == BinaryPacketType::BfpCommand as u32 => {
+                let at = r.pos;
+                match r.u8("command code") {
+                    Ok(code) => {
+                        if command_name(code).is_none() {
+                            report.problems.push(Problem {
+                                offset: at,
+                                problem: format!(
+                                    "unknown command code {code}; a writer given a command it \
+                                     has no code for leaves the code out, and the reader takes \
+                                     the next byte for it"
+                                ),
+                            });
+                        }
+                        PacketKind::Command(Some(code))
+                    }
+                    Err(cut) => {
+                        report.packets.push(Packet {
+                            offset,
+                            kind: PacketKind::Command(None),
+                        });
+                        return report.cut(cut);
+                    }
+                }
+            }
+            Ok(ty) if ty == BinaryPacketType::BfpText as u32 => {
+                match r.str("text packet", &mut report.problems) {
+                    Ok(text) => PacketKind::Text(text),
+                    Err(cut) => return report.cut(cut),
+                }
+            }
+            Ok(ty) => {
+                report.problems.push(Problem {
+                    offset,
+                    problem: format!(
+                        "unknown packet type {ty}; the packets after it cannot be found"
+                    ),
+                });
+                report.decoded = offset;
+                return report;
+            }
+            Err(cut) => return report.cut(cut),
+        };
+        report.packets.push(Packet { offset, kind });
+        report.decoded = r.pos;
+    }
+    report
+}
+
+/// A window packet after its type byte, into `w`; on a cut, `w` holds what
+/// was decoded before it.
+fn window(
+    outer: &mut Reader<'_>,
+    w: &mut WindowDump,
+    problems: &mut Vec<Problem>,
+) -> Result<(), Cut> {
+    w.length = outer.u32("window length")?;
+    let start = outer.pos;
+    let available = outer.end - start;
+    let declared = w.length as usize;
+    let mut r = Reader {
+        bytes: outer.bytes,
+        pos: start,
+        end: start + declared.min(available),
+    };
+    let body = window_body(&mut r, w, problems);
+    if declared > available {
+        // The stream ends inside the window: whatever the body decoded to, the
+        // cut is the stream's.
+        let field = body.err().map_or("window", |cut| cut.field);
+        return Err(Cut {
+            offset: outer.end,
+            field,
+        });
+    }
+    outer.pos = start + declared;
+    match body {
+        Ok(()) if r.pos < r.end => problems.push(Problem {
+            offset: r.pos,
+            problem: format!(
+                "window declares {declared} bytes but uses only {}",
+                r.pos - start
+            ),
+        }),
+        Ok(()) => {}
+        Err(cut) => problems.push(Problem {
+            offset: cut.offset,
+            problem: format!(
+                "window body of {declared} bytes ends inside the {}",
+                cut.field
+            ),
+        }),
+    }
+    Ok(())
+}
+
+fn window_body(
+    r: &mut Reader<'_>,
+    w: &mut WindowDump,
+    problems: &mut Vec<Problem>,
+) -> Result<(), Cut> {
+    w.flags = r.u16("window flags")?;
+    let tag_count = r.u16("tag count")?;
+    for _ in 0..tag_count {
+        let tag = r.str("tag table", problems)?;
+        w.tags.push(tag);
+    }
+    let var_count = r.u16("variable count")?;
+    for _ in 0..var_count {
+        let at = r.pos;
+        let op = r.u8("variable op")?;
+        let key = r.tag("variable key", &w.tags, problems)?;
+        // Only a set carries a value; the other ops write 0 in its place.
+        let value = if u32::from(op) == BFV_SETVAR {
+            r.tag("variable value", &w.tags, problems)?
+        } else {
+            r.u16("variable value")?;
+            String::new()
+        };
+        if ![BFV_SETVAR, BFV_SETVAR_ANY, BFV_REMVAR].contains(&u32::from(op)) {
+            problems.push(Problem {
+                offset: at,
+                problem: format!("unknown variable op {op}"),
+            });
+        }
+        w.variables.push(Variable { op, key, value });
+    }
+    w.text = r.str("window text", problems)?;
+    w.text_post = r.str("window text after", problems)?;
+    let cohort_count = r.u16("cohort count")?;
+    for _ in 0..cohort_count {
+        let mut c = CohortDump {
+            offset: r.pos,
+            ..Default::default()
+        };
+        let result = cohort(r, &w.tags, &mut c, problems);
+        w.cohorts.push(c);
+        result?;
+    }
+    Ok(())
+}
+
+fn cohort(
+    r: &mut Reader<'_>,
+    tags: &[String],
+    c: &mut CohortDump,
+    problems: &mut Vec<Problem>,
+) -> Result<(), Cut> {
+    c.flags = r.u16("cohort flags")?;
+    c.wordform = r.tag("wordform", tags, problems)?;
+    let static_count = r.u16("static tag count")?;
+    for _ in 0..static_count {
+        c.static_tags.push(r.tag("static tag", tags, problems)?);
+    }
+    c.dep_self = r.u32("dependency number")?;
+    c.dep_parent = r.u32("dependency parent")?;
+    let rel_count = r.u16("relation count")?;
+    for _ in 0..rel_count {
+        let name = r.tag("relation name", tags, problems)?;
+        let target = r.u32("relation target")?;
+        c.relations.push((name, target));
+    }
+    c.text = r.str("cohort text", problems)?;
+    c.wblank = r.str("wordform blank", problems)?;
+    let reading_count = r.u16("reading count")?;
+    for i in 0..reading_count {
+        let mut reading = ReadingDump {
+            offset: r.pos,
+            ..Default::default()
+        };
+        let result = reading_body(r, tags, &mut reading, problems);
+        if result.is_ok() && i == 0 && u32::from(reading.flags) & BFR_SUBREADING != 0 {
+            problems.push(Problem {
+                offset: reading.offset,
+                problem: "the first reading of a cohort is marked a sub-reading".to_string(),
+            });
+        }
+        c.readings.push(reading);
+        result?;
+    }
+    Ok(())
+}
+
+fn reading_body(
+    r: &mut Reader<'_>,
+    tags: &[String],
+    reading: &mut ReadingDump,
+    problems: &mut Vec<Problem>,
+) -> Result<(), Cut> {
+    reading.flags = r.u16("reading flags")?;
+    reading.baseform = r.tag("baseform", tags, problems)?;
+    let tag_count = r.u16("reading tag count")?;
+    for _ in 0..tag_count {
+        reading.tags.push(r.tag("reading tag", tags, problems)?);
+    }
+    Ok(())
+}
+
+fn command_name(code: u8) -> Option<&'static str> {
+    match code {
+        BFC_FLUSH => Some("FLUSH"),
+        BFC_EXIT => Some("EXIT"),
+        BFC_IGNORE => Some("IGNORE"),
+        BFC_RESUME => Some("RESUME"),
+        _ => None,
+    }
+}
+
+impl Inspection {
+    fn cut(mut self, cut: Cut) -> Inspection {
+        self.problems.push(Problem {
+            offset: cut.offset,
+            problem: format!("stream is truncated: it ends inside the {}", cut.field),
+        });
+        self.decoded = cut.offset;
+        self
+    }
+
+    /// Whether the stream follows the protocol throughout.
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Print every packet, cohort and reading with its byte offset, then the
+    /// problems found, each with its own.
+    pub fn write_report<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self.version {
+            Some(v) => writeln!(out, "{:08x}  header   CGBF, version {v}", 0)?,
+            None => writeln!(out, "{:08x}  header   unreadable", 0)?,
+        }
+        let mut windows = 0;
+        for packet in &self.packets {
+            let at = packet.offset;
+            match &packet.kind {
+                PacketKind::Window(w) => {
+                    windows += 1;
+                    write_window(out, at, windows, w)?;
+                }
+                PacketKind::Command(Some(code)) => writeln!(
+                    out,
+                    "{at:08x}  command  {}",
+                    command_name(*code).map_or_else(|| format!("unknown ({code})"), str::to_string)
+                )?,
+                PacketKind::Command(None) => writeln!(out, "{at:08x}  command  (cut off)")?,
+                PacketKind::Text(text) => writeln!(out, "{at:08x}  text     {text:?}")?,
+            }
+        }
+        writeln!(
+            out,
+            "packets: {}, windows: {windows}, bytes decoded: {}",
+            self.packets.len(),
+            self.decoded
+        )?;
+        for p in &self.problems {
+            writeln!(out, "{:08x}  problem: {}", p.offset, p.problem)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_window<W: Write>(out: &mut W, at: usize, n: usize, w: &WindowDump) -> io::Result<()> {
+    let span = if u32::from(w.flags) & BFW_DEP_SPAN != 0 {
+        ", dependency spans"
+    } else {
+        ""
+    };
+    writeln!(
+        out,
+        "{at:08x}  window   {n}: {} bytes, {} tags, {} cohorts, flags {:#06x}{span}",
+        w.length,
+        w.tags.len(),
+        w.cohorts.len(),
+        w.flags
+    )?;
+    for v in &w.variables {
+        let op = u32::from(v.op);
+        let line = if op == BFV_SETVAR {
+            format!("SETVAR {}={}", v.key, v.value)
+        } else if op == BFV_SETVAR_ANY {
+            format!("SETVAR {}", v.key)
+        } else if op == BFV_REMVAR {
+            format!("REMVAR {}", v.key)
+        } else {
+            format!("unknown op {} on {}", v.op, v.key)
+        };
+        writeln!(out, "{:10}variable {line}", "")?;
+    }
+    if !w.text.is_empty() {
+        writeln!(out, "{:10}text     {:?}", "", w.text)?;
+    }
+    for c in &w.cohorts {
+        let parent = if c.dep_parent == DEP_NO_PARENT {
+            "none".to_string()
+        } else {
+            c.dep_parent.to_string()
+        };
+        let related = if u32::from(c.flags) & BFC_RELATED != 0 {
+            ", related"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "{:08x}    cohort {}  dep {}->{parent}{related}",
+            c.offset, c.wordform, c.dep_self
+        )?;
+        if !c.static_tags.is_empty() {
+            writeln!(out, "{:14}static   {}", "", c.static_tags.join(" "))?;
+        }
+        for (name, target) in &c.relations {
+            writeln!(out, "{:14}relation {name} -> {target}", "")?;
+        }
+        for reading in &c.readings {
+            let flags = u32::from(reading.flags);
+            let indent = if flags & BFR_SUBREADING != 0 {
+                "  "
+            } else {
+                ""
+            };
+            let deleted = if flags & BFR_DELETED != 0 {
+                " (deleted)"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "{:08x}      {indent}{} {}{deleted}",
+                reading.offset,
+                reading.baseform,
+                reading.tags.join(" ")
+            )?;
+        }
+        if !c.text.is_empty() {
+            writeln!(out, "{:14}text     {:?}", "", c.text)?;
+        }
+    }
+    if !w.text_post.is_empty() {
+        writeln!(out, "{:10}text     {:?}", "", w.text_post)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn str(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&(s.len() as u16).to_le_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    /// A window of one cohort whose wordform points at tag `wordform`, and
+    /// whose body claims `extra` more bytes than it uses.
+    fn stream(wordform: u16, extra: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes());
+        str(&mut body, "\"<a>\"");
+        body.extend_from_slice(&0u16.to_le_bytes());
+        str(&mut body, "");
+        str(&mut body, "");
+        body.extend_from_slice(&1u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&wordform.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend_from_slice(&DEP_NO_PARENT.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        str(&mut body, "");
+        str(&mut body, "");
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.resize(body.len() + extra as usize, 0);
+
+        let mut out = b"CGBF".to_vec();
+        out.extend_from_slice(&CG3_BINARY_STREAM.to_le_bytes());
+        out.push(BinaryPacketType::BfpWindow as u8);
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(&body);
+        out.extend_from_slice(&[BinaryPacketType::BfpCommand as u8, BFC_FLUSH]);
+        out
+    }
+
+    #[test]
+    fn decodes_a_clean_stream() {
+        let report = inspect(&stream(0, 0));
+        assert!(report.is_clean(), "{:?}", report.problems);
+        assert_eq!(report.packets.len(), 2);
+        let PacketKind::Window(w) = &report.packets[0].kind else {
+            panic!("not a window");
+        };
+        assert_eq!(w.cohorts[0].wordform, "\"<a>\"");
+        assert_eq!(w.cohorts[0].offset, 32);
+        assert!(matches!(
+            report.packets[1].kind,
+            PacketKind::Command(Some(BFC_FLUSH))
+        ));
+    }
+
+    #[test]
+    fn flags_violations_at_their_offsets() {
+        // A tag index past the table, and unused bytes: decoding carries on
+        // to the FLUSH after the window.
+        let report = inspect(&stream(3, 2));
+        assert_eq!(
+            report.problems,
+            [
+                Problem {
+                    offset: 34,
+                    problem: "wordform is tag 3, but the window has only 1 tags".to_string(),
+                },
+                Problem {
+                    offset: 54,
+                    problem: "window declares 43 bytes but uses only 41".to_string(),
+                },
+            ]
+        );
+        assert_eq!(report.packets.len(), 2);
+
+        // Cut inside the window, then an unknown packet type.
+        let bytes = stream(0, 0);
+        let report = inspect(&bytes[..36]);
+        assert_eq!(report.problems[0].offset, 36);
+        assert!(report.problems[0].problem.contains("truncated"));
+        let mut bytes = stream(0, 0);
+        bytes.push(9);
+        let report = inspect(&bytes);
+        assert_eq!(report.problems[0].offset, bytes.len() - 1);
+        assert_eq!(report.packets.len(), 2);
+        assert!(!inspect(b"CG3B\0\0\0\0").is_clean());
+    }
+}
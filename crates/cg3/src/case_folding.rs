@@ -0,0 +1,276 @@
+//! ADDED — no C++ analog. Language-sensitive case mapping and folding.
+//!
+//! The C++ folds `"…"i` tags with ICU's `u_strCaseCompare(U_FOLD_CASE_DEFAULT)`
+//! and lowers and raises text with ICU's root locale, which is wrong for the
+//! languages whose `i` behaves differently:
+//!
+//! * Turkish and Azeri pair dotted `i`/`İ` and dotless `ı`/`I`, so `KIZ` is
+//!   `kız`, not `kiz`;
+//! * Lithuanian keeps the dot on an `i` that carries another accent, so `Ì`
+//!   lowers to `i̇̀` (`i`, U+0307, U+0300) and `i̇̀` raises back to `Ì`.
+//!
+//! A grammar picks its language with `OPTIONS += casefold-tr ;` (or `-az`,
+//! `-lt`), stored as [`Grammar::case_locale`](crate::grammar::Grammar::case_locale).
+//! That choice drives every case-insensitive comparison of a `"…"i` tag, the
+//! lowered baseform of plain-text input, the `%u`/`%U`/`%l`/`%L` markers of
+//! varstring tags, and the wordform case the Apertium writer restores. Folding
+//! is full folding in every locale, so `"<straße>"i` matches `STRASSE`.
+//!
+//! Regex tags with `i` are the exception: the regex engine folds one character
+//! at a time, in the root locale.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use caseless::{CaseFold, Caseless};
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::{Decompositions, UnicodeNormalization};
+
+/// U+0307 COMBINING DOT ABOVE.
+const DOT_ABOVE: char = '\u{307}';
+/// The canonical combining class of the marks drawn above their base.
+const CCC_ABOVE: u8 = 230;
+
+/// The language whose case rules apply.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaseLocale {
+    /// Unicode's default rules, as ICU's root locale.
+    #[default]
+    Root,
+    /// Turkish and Azeri: `i`/`İ` and `ı`/`I` are the case pairs.
+    Turkic,
+    /// Lithuanian: an accented `i` keeps its dot in lower case.
+    Lithuanian,
+}
+
+impl CaseLocale {
+    /// The grammar option that selects this locale; `None` for the default.
+    pub fn option(self) -> Option<&'static str> {
+        match self {
+            CaseLocale::Root => None,
+            CaseLocale::Turkic => Some("casefold-tr"),
+            CaseLocale::Lithuanian => Some("casefold-lt"),
+        }
+    }
+
+    /// `text` fully case-folded for comparison. Two strings are equal but for
+    /// case exactly when their foldings are equal.
+    pub fn fold(self, text: &str) -> String {
+        self.folded(text).collect()
+    }
+
+    /// Whether `a` and `b` are equal but for case.
+    pub fn caseless_eq(self, a: &str, b: &str) -> bool {
+        // Fold lazily; most comparisons fail on the first character.
+        self.folded(a).eq(self.folded(b))
+    }
+
+    /// The characters of [`fold`](Self::fold), produced as they are consumed.
+    fn folded(self, text: &str) -> Folded<'_> {
+        match self {
+            CaseLocale::Root => Folded::Root(text.chars().default_case_fold()),
+            CaseLocale::Turkic => Folded::Turkic(
+                TurkicI {
+                    chars: text.chars().peekable(),
+                }
+                .default_case_fold(),
+            ),
+            // Decomposed, a dotted accented `i` and the plain accented one
+            // differ only in the dot, which the fold then drops.
+            CaseLocale::Lithuanian => Folded::Lithuanian(SoftDots {
+                chars: text.chars().default_case_fold().nfd(),
+                after_soft_dotted: false,
+            }),
+        }
+    }
+
+    /// `text` in lower case.
+    pub fn to_lower(self, text: &str) -> String {
+        match self {
+            CaseLocale::Root => text.to_lowercase(),
+            CaseLocale::Turkic => {
+                let mut out = String::with_capacity(text.len());
+                let mut chars = text.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match c {
+                        'I' if chars.peek() == Some(&DOT_ABOVE) => {
+                            chars.next();
+                            out.push('i');
+                        }
+                        'I' => out.push('ı'),
+                        'İ' => out.push('i'),
+                        _ => out.extend(c.to_lowercase()),
+                    }
+                }
+                out
+            }
+            CaseLocale::Lithuanian => {
+                let chars: Vec<char> = text.chars().collect();
+                let mut out = String::with_capacity(text.len());
+                for (i, &c) in chars.iter().enumerate() {
+                    match c {
+                        'Ì' => out.push_str("i\u{307}\u{300}"),
+                        'Í' => out.push_str("i\u{307}\u{301}"),
+                        'Ĩ' => out.push_str("i\u{307}\u{303}"),
+                        'I' | 'J' | 'Į' if more_above(&chars[i + 1..]) => {
+                            out.extend(c.to_lowercase());
+                            out.push(DOT_ABOVE);
+                        }
+                        _ => out.extend(c.to_lowercase()),
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    /// `text` in upper case.
+    pub fn to_upper(self, text: &str) -> String {
+        match self {
+            CaseLocale::Root => text.to_uppercase(),
+            CaseLocale::Turkic => {
+                let mut out = String::with_capacity(text.len());
+                for c in text.chars() {
+                    match c {
+                        'i' => out.push('İ'),
+                        _ => out.extend(c.to_uppercase()),
+                    }
+                }
+                out
+            }
+            CaseLocale::Lithuanian => SoftDots {
+                chars: text.chars(),
+                after_soft_dotted: false,
+            }
+            .collect::<String>()
+            .to_uppercase(),
+        }
+    }
+
+    /// The first character of `c` in upper case, for title-casing a word one
+    /// character at a time.
+    pub fn upper_char(self, c: char) -> char {
+        match self {
+            CaseLocale::Turkic if c == 'i' => 'İ',
+            _ => c.to_uppercase().next().unwrap_or(c),
+        }
+    }
+
+    /// The first character of `c` in lower case; see [`Self::upper_char`].
+    pub fn lower_char(self, c: char) -> char {
+        match self {
+            CaseLocale::Turkic if c == 'I' => 'ı',
+            CaseLocale::Turkic if c == 'İ' => 'i',
+            _ => c.to_lowercase().next().unwrap_or(c),
+        }
+    }
+}
+
+/// Whether a mark drawn above follows before the next base character.
+fn more_above(rest: &[char]) -> bool {
+    for &c in rest {
+        match canonical_combining_class(c) {
+            0 => return false,
+            CCC_ABOVE => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// A case-folded string, one locale's way.
+enum Folded<'a> {
+    Root(CaseFold<Chars<'a>>),
+    Turkic(CaseFold<TurkicI<Chars<'a>>>),
+    Lithuanian(SoftDots<Decompositions<CaseFold<Chars<'a>>>>),
+}
+
+impl Iterator for Folded<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Folded::Root(chars) => chars.next(),
+            Folded::Turkic(chars) => chars.next(),
+            Folded::Lithuanian(chars) => chars.next(),
+        }
+    }
+}
+
+/// `chars` with the Turkic capital `I`s lowered: `I` to `ı`, and `İ` or `I`
+/// with U+0307 to `i`. The default fold leaves both lower-case letters alone.
+struct TurkicI<I: Iterator<Item = char>> {
+    chars: Peekable<I>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for TurkicI<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self.chars.next()? {
+            'I' if self.chars.peek() == Some(&DOT_ABOVE) => {
+                self.chars.next();
+                Some('i')
+            }
+            'I' => Some('ı'),
+            'İ' => Some('i'),
+            c => Some(c),
+        }
+    }
+}
+
+/// `chars` without the U+0307 that follows a soft-dotted letter (`i`, `j`,
+/// `į`), which in Lithuanian only marks the dot kept under another accent.
+struct SoftDots<I> {
+    chars: I,
+    after_soft_dotted: bool,
+}
+
+impl<I: Iterator<Item = char>> Iterator for SoftDots<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            let c = self.chars.next()?;
+            if c == DOT_ABOVE && self.after_soft_dotted {
+                continue;
+            }
+            match canonical_combining_class(c) {
+                0 => self.after_soft_dotted = matches!(c, 'i' | 'j' | 'į'),
+                CCC_ABOVE => self.after_soft_dotted = false,
+                _ => {}
+            }
+            return Some(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folding_is_full_and_language_sensitive() {
+        let root = CaseLocale::Root;
+        assert!(root.caseless_eq("straße", "STRASSE"));
+        assert!(root.caseless_eq("KIZ", "kiz"));
+        assert!(!root.caseless_eq("KIZ", "kız"));
+
+        let tr = CaseLocale::Turkic;
+        assert!(tr.caseless_eq("KIZ", "kız"));
+        assert!(!tr.caseless_eq("KIZ", "kiz"));
+        assert!(tr.caseless_eq("İstanbul", "istanbul"));
+        assert_eq!(tr.to_lower("DİYARBAKIR"), "diyarbakır");
+        assert_eq!(tr.to_upper("istanbul ılık"), "İSTANBUL ILIK");
+
+        let lt = CaseLocale::Lithuanian;
+        assert_eq!(lt.to_lower("Ì"), "i\u{307}\u{300}");
+        assert_eq!(lt.to_lower("I\u{301}"), "i\u{307}\u{301}");
+        assert_eq!(lt.to_lower("IS"), "is");
+        assert_eq!(lt.to_upper("i\u{307}\u{300}"), "I\u{300}");
+        assert!(lt.caseless_eq("Ì", "i\u{307}\u{300}"));
+        assert!(lt.caseless_eq("į\u{307}\u{301}", "Į\u{301}"));
+        assert_eq!(lt.fold("Į\u{307}\u{301}"), lt.fold("į\u{301}"));
+        assert_eq!(tr.fold("I\u{307}STANBUL"), "istanbul");
+    }
+}
@@ -28,6 +28,7 @@ use crate::inlines::{NUMERIC_MAX, NUMERIC_MIN, ui32};
 use crate::reading::{Reading, ReadingList, alloc_reading, alloc_reading_copy, free_reading};
 use crate::sorted_vector::{SortedVector, Uint32SortedVector};
 use crate::store::RuntimeStore;
+use crate::text_offsets::TextOffsets;
 use crate::types::{DynBitset, GlobalNumber, UString};
 use crate::window::{CohortRegistry, DepBookkeeping};
 
@@ -144,6 +145,9 @@ pub struct Cohort {
     pub relations: RelationCtn,
     pub relations_input: RelationCtn,
     pub line_number: u32,
+    /// ADDED — no C++ analog. Where the wordform sits in the input text, when
+    /// the reader knows (see [`crate::text_offsets`]).
+    pub offsets: Option<TextOffsets>,
 }
 
 impl Default for Cohort {
@@ -176,10 +180,46 @@ impl Default for Cohort {
             relations: RelationCtn::new(),
             relations_input: RelationCtn::new(),
             line_number: 0,
+            offsets: None,
         }
     }
 }
 
+/// Spare-list reset for `RuntimeStore::spare_cohorts`. Runs on a value that
+/// [`cohort_clear`] has already reset and whose slot is freed, so there are no
+/// readings or map entries left to release — it only finishes the job
+/// `cohort_clear` leaves undone (the `ignored` quirk) and guarantees the
+/// `Default` state while keeping every container's capacity.
+impl crate::pool::Poolable for Cohort {
+    fn clear(&mut self) {
+        self.r#type = CohortType::empty();
+        self.global_number = GlobalNumber(0);
+        self.local_number = 0;
+        self.enclosed = 0;
+        self.wordform = None;
+        self.dep_self = None;
+        self.dep_parent = None;
+        self.is_pleft = 0;
+        self.is_pright = 0;
+        self.parent = None;
+        self.text.clear();
+        self.wblank.clear();
+        self.prev = None;
+        self.next = None;
+        self.wread = None;
+        self.readings.clear();
+        self.deleted.clear();
+        self.delayed.clear();
+        self.ignored.clear();
+        self.dep_children.clear();
+        self.possible_sets.clear();
+        self.relations.clear();
+        self.relations_input.clear();
+        self.line_number = 0;
+        self.offsets = None;
+    }
+}
+
 // ===========================================================================
 // Ported method/function bodies (Cohort.cpp / Cohort.hpp).
 //
@@ -229,17 +269,16 @@ fn free_reading_list(store: &mut RuntimeStore, ids: &[ReadingId]) {
 /// `parent` is reassigned to `p`. BOTH branches yield a cohort with `parent = p`
 /// and every other field at its default, so — unlike `alloc_reading_copy` — there
 /// is NO pooled-vs-new divergence: a single fresh `Cohort { parent: p, ..default }`
-/// placed via `store.cohorts.alloc` is exact. (Consequence of the arena model:
-/// `alloc` overwrites the reused slot with this fresh value, so the `clear()`
-/// `ignored`-not-cleared quirk below cannot leak into a *reused* cohort here.)
+/// placed via `store.cohorts.alloc` is exact. The value comes from
+/// `store.spare_cohorts` when one is parked there — fully reset by the
+/// [`Poolable`] clear, containers keeping their capacity — so the `clear()`
+/// `ignored`-not-cleared quirk below cannot leak into a *reused* cohort here.
 ///
 /// `p` is `Option<SwId>` (not a bare `SwId`) to preserve the nullable
 /// `SingleWindow*`.
 pub fn alloc_cohort(store: &mut RuntimeStore, p: Option<SwId>) -> CohortId {
-    let c = Cohort {
-        parent: p,
-        ..Default::default()
-    };
+    let mut c = store.spare_cohorts.get();
+    c.parent = p;
     CohortId(store.cohorts.alloc(c))
 }
 
@@ -251,8 +290,9 @@ pub fn alloc_cohort(store: &mut RuntimeStore, p: Option<SwId>) -> CohortId {
 /// Otherwise mirrors `pool_cohorts.put(c)` — which invokes `c->clear()`
 /// ([`cohort_clear`], resetting the cohort, freeing its readings, unlinking it
 /// from the Window maps and sibling chain) — then returns the arena slot to
-/// the free-list. (The C++ `Cohort*&` null-out is ownership by value here —
-/// wave 4; a caller keeping a long-lived handle sets it `None` itself.)
+/// the free-list and parks the cleared value in `store.spare_cohorts` for the
+/// next [`alloc_cohort`]. (The C++ `Cohort*&` null-out is ownership by value
+/// here — wave 4; a caller keeping a long-lived handle sets it `None` itself.)
 pub fn free_cohort(
     store: &mut RuntimeStore,
     window: Option<(&mut CohortRegistry, &mut DepBookkeeping)>,
@@ -260,7 +300,9 @@ pub fn free_cohort(
 ) {
     let Some(id) = c else { return };
     cohort_clear(store, window, id);
-    store.cohorts.free_slot(id.0);
+    if let Some(spare) = store.cohorts.free_slot(id.0) {
+        store.spare_cohorts.put(spare);
+    }
 }
 
 // [spec:cg3:def:cohort.cg3.cohort.cohort-fn]
@@ -358,6 +400,7 @@ pub fn cohort_clear(
         c.is_pright = 0;
         c.parent = None;
         c.line_number = 0;
+        c.offsets = None;
 
         c.text.clear();
         c.wblank.clear();
@@ -367,28 +410,30 @@ pub fn cohort_clear(
         c.relations_input.clear();
     }
 
-    let (rd, del, dly, ign, wr) = {
-        let c = store.cohorts.get(this.0);
-        (
-            c.readings.clone(),
-            c.deleted.clone(),
-            c.delayed.clone(),
-            c.ignored.clone(),
-            c.wread,
-        )
-    };
+    // The lists are moved out (not cloned) while their readings are freed and
+    // moved back afterwards, so a recycled cohort keeps their capacity.
+    let c = store.cohorts.get_mut(this.0);
+    let mut rd = std::mem::take(&mut c.readings);
+    let mut del = std::mem::take(&mut c.deleted);
+    let mut dly = std::mem::take(&mut c.delayed);
+    let ign = std::mem::take(&mut c.ignored);
+    let wr = c.wread;
     free_reading_list(store, &rd);
     free_reading_list(store, &del);
     free_reading_list(store, &dly);
     free_reading_list(store, &ign);
     free_reading(store, wr);
 
+    rd.clear();
+    del.clear();
+    dly.clear();
     let c = store.cohorts.get_mut(this.0);
-    c.readings.clear();
-    c.deleted.clear();
-    c.delayed.clear();
-    c.wread = None;
+    c.readings = rd;
+    c.deleted = del;
+    c.delayed = dly;
     // QUIRK: `ignored` is deliberately NOT cleared here (bug-for-bug).
+    c.ignored = ign;
+    c.wread = None;
 }
 
 // [spec:cg3:def:cohort.cg3.cohort.detach-fn]
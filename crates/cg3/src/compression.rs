@@ -0,0 +1,350 @@
+//! ADDED — no C++ analog. Transparent gzip and zstd streams for the tools.
+//!
+//! Input is recognised by its magic bytes, whatever the file is called, so a
+//! compressed stream can be piped into `vislcg3`, `cg-conv`, `cg-proc` or
+//! `cg-mwesplit` as it is, and a compressed grammar handed to any tool that
+//! loads one. Concatenated members (`cat a.gz b.gz`) and frames are read as
+//! one stream, as `zcat` and `zstdcat` read them. Anything else passes through
+//! untouched.
+//!
+//! Output is compressed when asked for: by `--compress gzip|zstd|none`, or
+//! else by the extension of the file written (`.gz`, `.zst`). The flag wins,
+//! so `--compress none` writes a plain `out.gz`.
+//!
+//! Compression sits outside the applicators: input is decompressed into the
+//! `BufRead` the reader is handed, and output is compressed by the `Write` the
+//! printer writes to. A flush reaches the file or pipe, but not what the
+//! encoder is still holding; the stream is only complete once
+//! [`CompressedWriter::finish`] has run.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use flate2::Compression as GzLevel;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use ruzstd::decoding::FrameDecoder;
+use ruzstd::decoding::errors::{FrameDecoderError, ReadFrameHeaderError};
+use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// How much zstd output is buffered before it is written as a frame.
+const ZSTD_FRAME_SIZE: usize = 1 << 20;
+
+/// A stream compression format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Why a `--compress` value was refused.
+#[derive(Debug, thiserror::Error)]
+#[error("Error: --compress expects gzip, zstd or none, not `{0}`.")]
+pub struct CompressionNameError(pub String);
+
+impl Compression {
+    /// The format a stream starting with `head` is in.
+    pub fn sniff(head: &[u8]) -> Compression {
+        if head.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if head.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// The format a file name asks for, by its extension.
+    pub fn from_path(path: &Path) -> Compression {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// A `--compress` value.
+    pub fn from_name(name: &str) -> Result<Compression, CompressionNameError> {
+        match name.trim() {
+            "none" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(CompressionNameError(name.to_string())),
+        }
+    }
+
+    /// The output format: the `--compress` value if given, else what `path`
+    /// (the file written, if any) asks for.
+    pub fn for_output(
+        flag: Option<&str>,
+        path: Option<&Path>,
+    ) -> Result<Compression, CompressionNameError> {
+        match flag {
+            Some(name) => Compression::from_name(name),
+            None => Ok(path.map_or(Compression::None, Compression::from_path)),
+        }
+    }
+}
+
+/// `input`, decompressed if its magic bytes say it is compressed. Only as
+/// much is read up front as it takes to tell, so a stream that arrives a bit
+/// at a time is not held up.
+pub fn decompressed<'a, R: Read + 'a>(mut input: R) -> io::Result<Box<dyn BufRead + 'a>> {
+    let mut head = Vec::with_capacity(ZSTD_MAGIC.len());
+    let mut buf = [0; ZSTD_MAGIC.len()];
+    while head.len() < ZSTD_MAGIC.len() && could_be_magic(&head) {
+        let n = match input.read(&mut buf[..ZSTD_MAGIC.len() - head.len()]) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        head.extend_from_slice(&buf[..n]);
+    }
+    let compression = Compression::sniff(&head);
+    let whole = io::Cursor::new(head).chain(input);
+    Ok(match compression {
+        Compression::None => Box::new(BufReader::new(whole)),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(whole))),
+        Compression::Zstd => Box::new(BufReader::new(ZstdReader::new(BufReader::new(whole)))),
+    })
+}
+
+/// Whether a stream starting with `head` could still turn out compressed.
+fn could_be_magic(head: &[u8]) -> bool {
+    Compression::sniff(head) == Compression::None
+        && (GZIP_MAGIC.starts_with(head) || ZSTD_MAGIC.starts_with(head))
+}
+
+/// All of `input`, decompressed.
+pub fn read_all(input: impl Read) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    decompressed(input)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The contents of the file at `path`, decompressed.
+pub fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let bytes = std::fs::read(path)?;
+    match Compression::sniff(&bytes) {
+        Compression::None => Ok(bytes),
+        _ => read_all(&bytes[..]),
+    }
+}
+
+/// `bytes`, compressed as `compression`.
+pub fn compress(bytes: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+    let mut out = CompressedWriter::new(Vec::new(), compression);
+    out.write_all(bytes)?;
+    out.finish()
+}
+
+/// Every zstd frame of a stream, one after the other; skippable frames are
+/// skipped.
+struct ZstdReader<R: BufRead> {
+    source: R,
+    frame: FrameDecoder,
+    in_frame: bool,
+}
+
+impl<R: BufRead> ZstdReader<R> {
+    fn new(source: R) -> Self {
+        ZstdReader {
+            source,
+            frame: FrameDecoder::new(),
+            in_frame: false,
+        }
+    }
+}
+
+impl<R: BufRead> Read for ZstdReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.in_frame {
+                while self.frame.can_collect() < buf.len() && !self.frame.is_finished() {
+                    let wanted = buf.len() - self.frame.can_collect();
+                    self.frame
+                        .decode_blocks(
+                            &mut self.source,
+                            ruzstd::decoding::BlockDecodingStrategy::UptoBytes(wanted),
+                        )
+                        .map_err(io::Error::other)?;
+                }
+                let n = self.frame.read(buf)?;
+                if n > 0 {
+                    return Ok(n);
+                }
+                self.in_frame = false;
+            }
+            if self.source.fill_buf()?.is_empty() {
+                return Ok(0);
+            }
+            match self.frame.init(&mut self.source) {
+                Ok(()) => self.in_frame = true,
+                Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame {
+                    length,
+                    ..
+                })) => {
+                    io::copy(&mut (&mut self.source).take(length.into()), &mut io::sink())?;
+                }
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+    }
+}
+
+/// A writer compressing what it is given as the chosen format, or not at all.
+pub struct CompressedWriter<W: Write> {
+    inner: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(ZstdWriter<W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    pub fn new(inner: W, compression: Compression) -> Self {
+        let inner = match compression {
+            Compression::None => Encoder::Plain(inner),
+            Compression::Gzip => Encoder::Gzip(GzEncoder::new(inner, GzLevel::default())),
+            Compression::Zstd => Encoder::Zstd(ZstdWriter {
+                inner: Some(inner),
+                pending: Vec::new(),
+                frames: 0,
+            }),
+        };
+        CompressedWriter { inner }
+    }
+
+    /// Write out whatever is still held and end the stream, giving back the
+    /// writer underneath.
+    pub fn finish(self) -> io::Result<W> {
+        match self.inner {
+            Encoder::Plain(mut w) => {
+                w.flush()?;
+                Ok(w)
+            }
+            Encoder::Gzip(gz) => {
+                let mut w = gz.finish()?;
+                w.flush()?;
+                Ok(w)
+            }
+            Encoder::Zstd(mut zstd) => {
+                zstd.end()?;
+                let mut w = zstd.inner.take().expect("zstd writer finished twice");
+                w.flush()?;
+                Ok(w)
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Encoder::Plain(w) => w.write(buf),
+            Encoder::Gzip(gz) => gz.write(buf),
+            Encoder::Zstd(zstd) => zstd.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Not a sync flush: the printers flush after every window, and cutting
+        // the compressed stream that often would cost most of its ratio.
+        match &mut self.inner {
+            Encoder::Plain(w) => w.flush(),
+            Encoder::Gzip(gz) => gz.get_mut().flush(),
+            Encoder::Zstd(zstd) => zstd.inner.as_mut().map_or(Ok(()), W::flush),
+        }
+    }
+}
+
+/// zstd output, as one frame per [`ZSTD_FRAME_SIZE`] of input.
+struct ZstdWriter<W: Write> {
+    inner: Option<W>,
+    pending: Vec<u8>,
+    frames: usize,
+}
+
+impl<W: Write> ZstdWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if self.pending.len() >= ZSTD_FRAME_SIZE {
+            self.write_frame()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn write_frame(&mut self) -> io::Result<()> {
+        let Some(inner) = self.inner.as_mut() else {
+            return Ok(());
+        };
+        let frame = compress_to_vec(&self.pending[..], CompressionLevel::Fastest);
+        inner.write_all(&frame)?;
+        self.pending.clear();
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Write the last frame; an empty stream still gets one, so it is valid
+    /// zstd.
+    fn end(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() || self.frames == 0 {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for ZstdWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_concatenation() {
+        let text = "\"<word>\"\n\t\"word\" N <W:0.5>\n".repeat(100);
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let packed = compress(text.as_bytes(), compression).unwrap();
+            assert_eq!(Compression::sniff(&packed), compression);
+            let twice = [packed.clone(), packed].concat();
+            assert_eq!(read_all(&twice[..]).unwrap(), text.repeat(2).as_bytes());
+        }
+        let empty = compress(b"", Compression::Zstd).unwrap();
+        assert!(read_all(&empty[..]).unwrap().is_empty());
+        assert!(read_all(&b"ab"[..]).unwrap() == b"ab");
+    }
+
+    #[test]
+    fn output_choice() {
+        let gz = Path::new("out.cg.gz");
+        assert_eq!(
+            Compression::for_output(None, Some(gz)).unwrap(),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::for_output(Some("none"), Some(gz)).unwrap(),
+            Compression::None
+        );
+        assert_eq!(
+            Compression::for_output(None, Some(Path::new("o.zst"))).unwrap(),
+            Compression::Zstd
+        );
+        assert!(Compression::for_output(Some("lz4"), None).is_err());
+    }
+}
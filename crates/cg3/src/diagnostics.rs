@@ -25,6 +25,11 @@ use std::io::{IsTerminal, Write};
 use ariadne::{Config, IndexType, Label, Report, ReportKind};
 
 use crate::error::{ParseError, ParseSource};
+use crate::grammar::Grammar;
+use crate::grammar_sources::GrammarSources;
+use crate::runtime_diagnostics::{
+    InputExcerpt, RuntimeDiagnostic, RuntimeDiagnosticKind, Severity,
+};
 
 // [spec:cg3:req:diagnostics.rendered]
 /// Render every error of a failed parse to stderr, then flush.
@@ -100,6 +105,137 @@ pub fn render_parse_errors(
     Ok(())
 }
 
+/// ADDED — no C++ analog. Render a run's diagnostics to stderr, quoting the
+/// rule each one names when the grammar's sources can be had
+/// ([`crate::grammar_sources::resolve`]).
+pub fn report_runtime_diagnostics(diags: &[RuntimeDiagnostic], grammar: &Grammar, input: &str) {
+    if diags.is_empty() {
+        return;
+    }
+    let sources = crate::grammar_sources::resolve(grammar);
+    let mut out = Vec::new();
+    let colour = std::io::stderr().is_terminal();
+    let _ = render_runtime_diagnostics(diags, sources.as_ref(), input, &mut out, colour);
+    let mut stderr = std::io::stderr().lock();
+    let _ = stderr.write_all(&out);
+    let _ = stderr.flush();
+}
+
+/// ADDED — no C++ analog. Render a run's diagnostics into `out`, in the style
+/// of [`render_parse_errors`].
+///
+/// A diagnostic raised under a rule the sources describe quotes that rule,
+/// with the input line in the headline; one about malformed input quotes the
+/// line of `input` it was found on; any other prints as its one-line
+/// [`Display`](std::fmt::Display) text, which says the same minus the quote.
+pub fn render_runtime_diagnostics(
+    diags: &[RuntimeDiagnostic],
+    sources: Option<&GrammarSources>,
+    input: &str,
+    out: &mut impl Write,
+    colour: bool,
+) -> std::io::Result<()> {
+    let mut cache = sources
+        .map(|g| ariadne::sources(g.sources.iter().map(|s| (s.name.clone(), s.text.clone()))));
+    let config = Config::new()
+        .with_color(colour)
+        .with_index_type(IndexType::Char);
+
+    for diag in diags {
+        let placed = sources
+            .zip(diag.rule)
+            .and_then(|(g, rule)| place_span(&g.locate(rule)?, &g.sources));
+        let (kind, color) = match diag.severity() {
+            Severity::Info => (ReportKind::Advice, ariadne::Color::Cyan),
+            Severity::Warning => (ReportKind::Warning, ariadne::Color::Yellow),
+            Severity::Error => (ReportKind::Error, ariadne::Color::Red),
+        };
+        let (Some(placed), Some(cache)) = (placed, cache.as_mut()) else {
+            match &diag.excerpt {
+                Some(excerpt) => {
+                    let headline = diag.kind.to_string();
+                    render_excerpt(diag.severity(), &headline, input, excerpt, out, colour)?
+                }
+                None => writeln!(out, "{diag}")?,
+            }
+            continue;
+        };
+        let mut headline = diag.kind.to_string();
+        if diag.line != 0 {
+            headline.push_str(&format!(", on input line {}", diag.line));
+        }
+        Report::build(kind, placed.clone())
+            .with_config(config)
+            .with_message(headline)
+            .with_label(
+                Label::new(placed)
+                    .with_message("while running this rule")
+                    .with_color(color),
+            )
+            .finish()
+            .write(&mut *cache, &mut *out)?;
+    }
+    Ok(())
+}
+
+/// ADDED — no C++ analog. Render the input line a `--strict-input` run
+/// stopped on to stderr.
+pub fn report_input_failure(input: &str, excerpt: &InputExcerpt, problem: &RuntimeDiagnosticKind) {
+    let mut out = Vec::new();
+    let colour = std::io::stderr().is_terminal();
+    let headline = problem.to_string();
+    let _ = render_excerpt(Severity::Error, &headline, input, excerpt, &mut out, colour);
+    let mut stderr = std::io::stderr().lock();
+    let _ = stderr.write_all(&out);
+    let _ = stderr.flush();
+}
+
+/// Quote the one line an excerpt holds and mark its column.
+///
+/// Drawn here rather than by ariadne: the line is one of an input that was
+/// never kept, so there is no source to hand it, and ariadne 0.6 numbers a
+/// one-line source from 1 whatever line it was — the place goes in the header
+/// as `file:line:column`, the number in the gutter.
+fn render_excerpt(
+    severity: Severity,
+    headline: &str,
+    input: &str,
+    excerpt: &InputExcerpt,
+    out: &mut impl Write,
+    colour: bool,
+) -> std::io::Result<()> {
+    let (label, ansi) = match severity {
+        Severity::Info => ("Advice:", "36"),
+        Severity::Warning => ("Warning:", "33"),
+        Severity::Error => ("Error:", "31"),
+    };
+    let paint = |text: &str| {
+        if colour {
+            format!("\u{1b}[{ansi}m{text}\u{1b}[0m")
+        } else {
+            text.to_string()
+        }
+    };
+    let number = excerpt.line.to_string();
+    let gutter = " ".repeat(number.len());
+    // The text's own tabs stay tabs, so the caret lands under its column.
+    let pad: String = excerpt
+        .text
+        .chars()
+        .take(excerpt.text_column as usize - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    writeln!(out, "{} {headline}", paint(label))?;
+    writeln!(
+        out,
+        "{gutter} --> {input}:{}:{}",
+        excerpt.line, excerpt.column
+    )?;
+    writeln!(out, "{gutter} |")?;
+    writeln!(out, "{number} | {}", excerpt.text)?;
+    writeln!(out, "{gutter} | {pad}{}", paint("^"))
+}
+
 /// What the underlined text IS.
 ///
 /// Not a second diagnostic message — the report's headline already carries the
@@ -135,7 +271,14 @@ fn marked(kind: &crate::error::ParseErrorKind) -> &'static str {
 /// range, and a diagnostic renderer is the last place that should be able to
 /// bring down the process it is explaining a failure to.
 fn place(error: &ParseError, sources: &[ParseSource]) -> Option<(String, std::ops::Range<usize>)> {
-    let span = error.span.as_ref()?;
+    place_span(error.span.as_ref()?, sources)
+}
+
+/// [`place`] for a bare span.
+fn place_span(
+    span: &crate::error::ParseSpan,
+    sources: &[ParseSource],
+) -> Option<(String, std::ops::Range<usize>)> {
     let source = sources.get(span.source)?;
     let len = source.text.chars().count();
     if len == 0 {
@@ -244,4 +387,26 @@ mod tests {
         }]);
         assert!(rendered.contains("syntax error"), "{rendered}");
     }
+
+    /// The caret under a quoted input line keeps the line's tabs, so it marks
+    /// the same column however wide the terminal draws a tab.
+    #[test]
+    fn an_excerpt_caret_keeps_the_tabs() {
+        let excerpt = InputExcerpt::new(2, "\t\"a\" <n", 6);
+        let mut out = Vec::new();
+        render_excerpt(
+            Severity::Warning,
+            "Did not find matching > to close the tag",
+            "in.cg",
+            &excerpt,
+            &mut out,
+            false,
+        )
+        .expect("a Vec cannot fail");
+        let rendered = String::from_utf8(out).unwrap();
+        assert!(
+            rendered.contains("2 | \t\"a\" <n\n  | \t    ^\n"),
+            "{rendered}"
+        );
+    }
 }
@@ -0,0 +1,409 @@
+//! ADDED — no C++ analog. Diagnostics for a machine: the JSON and SARIF
+//! documents `--diagnostics-format` writes, for CI that annotates the lines a
+//! grammar change broke.
+//!
+//! [`crate::diagnostics`] renders for a person. This flattens the same values
+//! — every [`ParseError`] of a failed load, every [`RuntimeDiagnostic`] of a
+//! run, and any other failure that ended the process — into [`Record`]s that
+//! carry the file, the 1-based line and column, the char span where there is
+//! one, a stable `kind` code, the message and, for malformed input, the quoted
+//! line, and writes them as one document.
+//!
+//! The JSON document is this crate's own shape:
+//!
+//! ```json
+//! {"cg3diagnostics": 1, "tool": "cg-comp", "diagnostics": [
+//!   {"severity": "error", "kind": "syntax", "message": "syntax error",
+//!    "file": "nb.cg3", "line": 2, "column": 10, "end_line": 2, "end_column": 15,
+//!    "span": {"start": 30, "end": 35}}]}
+//! ```
+//!
+//! SARIF is version 2.1.0, one run per document, a result per record, with
+//! `kind` as the rule id.
+
+use std::io::Write;
+
+use serde_json::{Map, Value, json};
+
+use crate::error::{Cg3Error, GrammarError, ParseError, ParseSource, ParseSpan, RunError};
+use crate::grammar_sources::GrammarSources;
+use crate::runtime_diagnostics::{RuntimeDiagnostic, Severity};
+
+/// The version of the JSON document, bumped when a key changes meaning.
+pub const JSON_VERSION: u32 = 1;
+
+/// How a tool reports its diagnostics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    /// Rendered for a terminal, as without the flag.
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+/// A `--diagnostics-format` value that names no format.
+#[derive(Debug, thiserror::Error)]
+#[error("Error: --diagnostics-format must be human, json or sarif, not `{0}`")]
+pub struct UnknownFormat(pub String);
+
+impl std::str::FromStr for DiagnosticsFormat {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(DiagnosticsFormat::Human),
+            "json" => Ok(DiagnosticsFormat::Json),
+            "sarif" => Ok(DiagnosticsFormat::Sarif),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Where in a file a record points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// 1-based.
+    pub line: u32,
+    /// 1-based, in chars; `None` when only the line is known.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    /// Char offsets into the file.
+    pub span: Option<std::ops::Range<usize>>,
+}
+
+/// One diagnostic, flattened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub severity: Severity,
+    /// [`ParseErrorKind::code`](crate::error::ParseErrorKind::code),
+    /// [`RuntimeDiagnosticKind::code`](crate::runtime_diagnostics::RuntimeDiagnosticKind::code),
+    /// or `fatal` for a failure with neither.
+    pub kind: &'static str,
+    pub message: String,
+    /// The grammar or input file; `None` for a failure that is about no file.
+    pub file: Option<String>,
+    pub region: Option<Region>,
+    /// The extra facts a runtime diagnostic has, as `(key, value)` pairs:
+    /// `input_line`, `window`, `cohort`, `rule`.
+    pub properties: Vec<(&'static str, u32)>,
+    /// The quoted input line, for a problem with the input.
+    pub excerpt: Option<String>,
+}
+
+/// The line, column and end of `span` in `sources`.
+fn region_of(span: &ParseSpan, sources: &[ParseSource]) -> Option<(String, Region)> {
+    let source = sources.get(span.source)?;
+    let mut line = 1u32;
+    let mut column = 1u32;
+    let mut start = None;
+    let mut end = None;
+    for (i, c) in source.text.chars().enumerate() {
+        if i == span.range.start {
+            start = Some((line, column));
+        }
+        if i == span.range.end {
+            end = Some((line, column));
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    // A span that runs to the end of the text ends after its last char.
+    let (end_line, end_column) = end.unwrap_or((line, column));
+    let (line, column) = start.unwrap_or((line, column));
+    Some((
+        source.name.clone(),
+        Region {
+            line,
+            column: Some(column),
+            end_line: Some(end_line),
+            end_column: Some(end_column),
+            span: Some(span.range.clone()),
+        },
+    ))
+}
+
+/// The records of a failed parse.
+pub fn parse_records(errors: &[ParseError], sources: &[ParseSource]) -> Vec<Record> {
+    errors
+        .iter()
+        .map(|e| {
+            let placed = e.span.as_ref().and_then(|s| region_of(s, sources));
+            let (file, region) = match placed {
+                Some((file, region)) => (file, region),
+                None => (
+                    e.file.clone(),
+                    Region {
+                        line: e.line,
+                        column: None,
+                        end_line: None,
+                        end_column: None,
+                        span: None,
+                    },
+                ),
+            };
+            Record {
+                severity: Severity::Error,
+                kind: e.kind.code(),
+                message: e.kind.to_string(),
+                file: Some(file),
+                region: (region.line != 0).then_some(region),
+                properties: Vec::new(),
+                excerpt: None,
+            }
+        })
+        .collect()
+}
+
+/// The records of a run's diagnostics. One raised under a rule the sources
+/// describe points at that rule; any other points at its line of `input`.
+pub fn runtime_records(
+    diags: &[RuntimeDiagnostic],
+    sources: Option<&GrammarSources>,
+    input: &str,
+) -> Vec<Record> {
+    diags
+        .iter()
+        .map(|d| {
+            let placed = sources
+                .zip(d.rule)
+                .and_then(|(g, rule)| region_of(&g.locate(rule)?, &g.sources));
+            let (file, region) = match placed {
+                Some((file, region)) => (file, Some(region)),
+                None => (
+                    input.to_string(),
+                    (d.line != 0).then_some(Region {
+                        line: d.line,
+                        column: d.excerpt.as_ref().map(|e| e.column),
+                        end_line: None,
+                        end_column: None,
+                        span: None,
+                    }),
+                ),
+            };
+            let properties = [
+                ("input_line", (d.line != 0).then_some(d.line)),
+                ("window", d.window),
+                ("cohort", d.cohort),
+                ("rule", d.rule),
+                ("rule_line", d.rule_line),
+            ]
+            .into_iter()
+            .filter_map(|(k, v)| Some((k, v?)))
+            .collect();
+            Record {
+                severity: d.severity(),
+                kind: d.kind.code(),
+                message: d.kind.to_string(),
+                file: Some(file),
+                region,
+                properties,
+                excerpt: d.excerpt.as_ref().map(|e| e.text.clone()),
+            }
+        })
+        .collect()
+}
+
+/// The records of a failure that ends a tool: its parse errors when it has
+/// them, else the failure itself.
+pub fn failure_records(e: &Cg3Error) -> Vec<Record> {
+    match e {
+        Cg3Error::Grammar(GrammarError::Parse { errors, sources }) => {
+            parse_records(errors, sources)
+        }
+        Cg3Error::Run(RunError::TagConstruction {
+            source, sources, ..
+        }) => parse_records(std::slice::from_ref(source.as_ref()), sources),
+        Cg3Error::Run(RunError::MalformedInput {
+            input,
+            excerpt,
+            problem,
+        }) => vec![Record {
+            severity: Severity::Error,
+            kind: problem.code(),
+            message: problem.to_string(),
+            file: Some(input.clone()),
+            region: Some(Region {
+                line: excerpt.line,
+                column: Some(excerpt.column),
+                end_line: None,
+                end_column: None,
+                span: None,
+            }),
+            properties: Vec::new(),
+            excerpt: Some(excerpt.text.clone()),
+        }],
+        _ => vec![Record {
+            severity: Severity::Error,
+            kind: "fatal",
+            message: e.to_string(),
+            file: None,
+            region: None,
+            properties: Vec::new(),
+            excerpt: None,
+        }],
+    }
+}
+
+fn json_record(r: &Record) -> Value {
+    let mut o = Map::new();
+    o.insert("severity".into(), json!(r.severity.label().to_lowercase()));
+    o.insert("kind".into(), json!(r.kind));
+    o.insert("message".into(), json!(r.message));
+    if let Some(file) = &r.file {
+        o.insert("file".into(), json!(file));
+    }
+    if let Some(region) = &r.region {
+        o.insert("line".into(), json!(region.line));
+        let optional = [
+            ("column", region.column),
+            ("end_line", region.end_line),
+            ("end_column", region.end_column),
+        ];
+        for (k, v) in optional {
+            if let Some(v) = v {
+                o.insert(k.into(), json!(v));
+            }
+        }
+        if let Some(span) = &region.span {
+            o.insert("span".into(), json!({"start": span.start, "end": span.end}));
+        }
+    }
+    for (k, v) in &r.properties {
+        o.insert((*k).into(), json!(v));
+    }
+    if let Some(excerpt) = &r.excerpt {
+        o.insert("excerpt".into(), json!(excerpt));
+    }
+    Value::Object(o)
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn sarif_result(r: &Record) -> Value {
+    let mut result = json!({
+        "ruleId": r.kind,
+        "level": sarif_level(r.severity),
+        "message": {"text": r.message},
+    });
+    if let Some(file) = &r.file {
+        let mut location = json!({"artifactLocation": {"uri": file}});
+        if let Some(region) = &r.region {
+            let mut reg = json!({"startLine": region.line});
+            let optional = [
+                ("startColumn", region.column),
+                ("endLine", region.end_line),
+                ("endColumn", region.end_column),
+            ];
+            for (k, v) in optional {
+                if let Some(v) = v {
+                    reg[k] = json!(v);
+                }
+            }
+            if let Some(span) = &region.span {
+                reg["charOffset"] = json!(span.start);
+                reg["charLength"] = json!(span.end - span.start);
+            }
+            if let Some(excerpt) = &r.excerpt {
+                reg["snippet"] = json!({"text": excerpt});
+            }
+            location["region"] = reg;
+        }
+        result["locations"] = json!([{"physicalLocation": location}]);
+    }
+    if !r.properties.is_empty() {
+        let props: Map<String, Value> = r
+            .properties
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), json!(v)))
+            .collect();
+        result["properties"] = Value::Object(props);
+    }
+    result
+}
+
+/// The document for `records` in `format`; `None` for [`DiagnosticsFormat::Human`].
+pub fn document(format: DiagnosticsFormat, tool: &str, records: &[Record]) -> Option<Value> {
+    match format {
+        DiagnosticsFormat::Human => None,
+        DiagnosticsFormat::Json => Some(json!({
+            "cg3diagnostics": JSON_VERSION,
+            "tool": tool,
+            "diagnostics": records.iter().map(json_record).collect::<Vec<_>>(),
+        })),
+        DiagnosticsFormat::Sarif => {
+            let mut kinds: Vec<&str> = records.iter().map(|r| r.kind).collect();
+            kinds.sort_unstable();
+            kinds.dedup();
+            Some(json!({
+                "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                "version": "2.1.0",
+                "runs": [{
+                    "tool": {"driver": {
+                        "name": tool,
+                        "version": crate::tools::DIVVUN_VERSION,
+                        "informationUri": crate::tools::DIVVUN_REPOSITORY,
+                        "rules": kinds.iter().map(|k| json!({"id": k})).collect::<Vec<_>>(),
+                    }},
+                    "columnKind": "unicodeCodePoints",
+                    "results": records.iter().map(sarif_result).collect::<Vec<_>>(),
+                }],
+            }))
+        }
+    }
+}
+
+/// Write the document for `records` to `out`, followed by a newline. Nothing
+/// for [`DiagnosticsFormat::Human`].
+pub fn write_document(
+    format: DiagnosticsFormat,
+    tool: &str,
+    records: &[Record],
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    if let Some(doc) = document(format, tool, records) {
+        serde_json::to_writer(&mut *out, &doc)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_become_lines_and_columns() {
+        let sources = vec![ParseSource {
+            name: "nb.cg3".to_string(),
+            text: "LIST a = b ;\nLIST c = bogus ;\n".to_string(),
+        }];
+        let span = ParseSpan {
+            source: 0,
+            range: 22..27,
+        };
+        let (file, region) = region_of(&span, &sources).unwrap();
+        assert_eq!(file, "nb.cg3");
+        assert_eq!(
+            (
+                region.line,
+                region.column,
+                region.end_line,
+                region.end_column
+            ),
+            (2, Some(10), Some(2), Some(15))
+        );
+    }
+}
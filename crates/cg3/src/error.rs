@@ -153,6 +153,27 @@ impl ParseErrorKind {
             ParseErrorKind::IncludeUnreadable { .. } | ParseErrorKind::EmptyInput
         )
     }
+
+    /// ADDED — no C++ analog. A short stable name, for machine-readable
+    /// output ([`crate::diagnostics_export`]).
+    pub fn code(&self) -> &'static str {
+        use ParseErrorKind as K;
+        match self {
+            K::Syntax => "syntax",
+            K::TagRegex { .. } => "tag-regex",
+            K::UnknownTemplate { .. } => "unknown-template",
+            K::EmptyTag => "empty-tag",
+            K::TagStartsWithParen { .. } => "tag-starts-with-paren",
+            K::TemplateRedefined { .. } => "template-redefined",
+            K::AnchorRedefined { .. } => "anchor-redefined",
+            K::SetRedefined { .. } => "set-redefined",
+            K::SetContentCollision => "set-content-collision",
+            K::EmptyNumericBranch => "empty-numeric-branch",
+            K::IncludeUnreadable { .. } => "include-unreadable",
+            K::EmptyInput => "empty-input",
+            K::RuntimeTag { .. } => "runtime-tag",
+        }
+    }
 }
 
 /// Render each item on its own indented line, so a collection of failures
@@ -277,6 +298,36 @@ pub enum RunError {
     UnsupportedOutputFormat { format: String },
     #[error("input format {format} cannot be read here")]
     UnsupportedInputFormat { format: String },
+    /// ADDED — no C++ analog: Matxin XML input that is not a well-formed
+    /// `<corpus>` of `<SENTENCE>` and `<NODE>` trees.
+    #[error("malformed Matxin input on line {line}: {problem}")]
+    MalformedMatxin { line: u32, problem: String },
+    /// ADDED — no C++ analog: a JSONL line that breaks the schema, read in
+    /// strict mode. `pointer` is the JSON pointer of the offending value,
+    /// empty when the whole line is at fault.
+    #[error("invalid JSONL on line {line} at \"{pointer}\": {problem}")]
+    InvalidJsonl {
+        line: u32,
+        pointer: String,
+        problem: String,
+    },
+    /// ADDED — no C++ analog: input a reader could not use, read with
+    /// `--strict-input` (see [`crate::runtime_diagnostics::Engine::note_input`]).
+    #[error(
+        "{input}:{}:{}: malformed input: {problem} (`{}`)",
+        excerpt.line,
+        excerpt.column,
+        excerpt.text
+    )]
+    MalformedInput {
+        input: String,
+        excerpt: crate::runtime_diagnostics::InputExcerpt,
+        problem: Box<crate::runtime_diagnostics::RuntimeDiagnosticKind>,
+    },
+    /// ADDED — no C++ analog: a window handed from one grammar of a pipeline
+    /// to the next carries a tag that neither grammar has interned.
+    #[error("a window handed between pipeline grammars carries unknown tag hash {hash}")]
+    UnknownPipelineTag { hash: u32 },
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
@@ -338,6 +389,13 @@ pub fn report_cli(e: &Cg3Error) {
                 sources,
             );
         }
+        Cg3Error::Run(RunError::MalformedInput {
+            input,
+            excerpt,
+            problem,
+        }) => {
+            crate::diagnostics::report_input_failure(input, excerpt, problem);
+        }
         _ => {}
     }
     tracing::error!("{e}");
@@ -0,0 +1,319 @@
+//! ADDED — no C++ analog. Grammar-checker errors as JSON, an output-only
+//! [`FormatConverter`] stream format: `cg-conv -E` / `--out-errors`,
+//! `vislcg3 --out-errors`.
+//!
+//! Divvun-style grammar checkers mark errors in the stream itself: an error
+//! tag (a tag starting with `&`) on a reading, suggestion readings added with
+//! `COPY`/`ADD` and tagged `&SUGGEST` or `&SUGGESTWF`, and `ADDRELATION` links
+//! from the error cohort to the cohorts its error spans. This format reads
+//! those back out, so a checker does not have to re-parse CG text.
+//!
+//! ## Output
+//! One JSON object per window, on a line of its own:
+//! `{"errors":[...],"offset":O,"text":"...","window":N}`. `text` is the
+//! window's surface text and `offset` where it starts in the document's: the
+//! surface texts of all windows printed so far, one after the other. Each
+//! error is
+//! `{"beg":B,"cohort":C,"end":E,"form":"...","span":[F,L],"suggestions":[...],"tag":"..."}`:
+//! * `tag` — the error tag, without its `&`;
+//! * `cohort` — the position (from 1) of the cohort carrying it, and `span` the
+//!   first and last position covered by it and the cohorts of the window it
+//!   reaches through relations, directly or through other such cohorts;
+//! * `beg` / `end` — the span's offsets in the document's surface text, in
+//!   characters, and `form` the text between them;
+//! * `suggestions` — the span's text with the error cohort's form replaced by
+//!   each suggestion. A suggestion reading's form is its own wordform tag
+//!   (`"<...>"`), or else its baseform; it belongs to the error tags it
+//!   carries, or to every error of its cohort when it carries none.
+//!
+//! ## Surface text
+//! The text is rebuilt from the stream: each cohort's wordform, and around it
+//! the text the stream carries between cohorts. A `:` line is a blank as
+//! `hfst-tokenise` writes them — what follows the `:`, with `\n` and `\t`
+//! unescaped; any other line is taken as it stands. Word-bound blanks
+//! (`Cohort::wblank`) are markup and add no text. Removed cohorts keep their
+//! place in the text but report no errors.
+//!
+//! Stream commands and text outside windows have no place in the output and
+//! are dropped.
+//!
+//! [`FormatConverter`]: crate::format_converter::FormatConverter
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use serde_json::json;
+
+use crate::arena::{CohortId, SwId, TagId};
+use crate::cohort::{CT_REMOVED, unignore_all};
+use crate::error::RunError;
+use crate::grammar::Grammar;
+use crate::grammar_applicator::Engine;
+use crate::grammar_applicator::stream_format::StreamFormat;
+use crate::tag::T_WORDFORM;
+use crate::text_offsets::{push_cg_text, strip_wordform};
+use crate::types::{GlobalNumber, TagHash};
+
+/// The tags that mark a reading as a suggestion rather than an error.
+const SUGGEST_TAGS: [&str; 2] = ["&SUGGEST", "&SUGGESTWF"];
+
+fn tag_by_hash(grammar: &Grammar, hash: TagHash) -> TagId {
+    let it = grammar.single_tags.find(hash.get());
+    if it != grammar.single_tags.end() {
+        it.get().1
+    } else {
+        TagId(0)
+    }
+}
+
+/// `"base"` → `base`.
+fn strip_baseform(tag: &str) -> &str {
+    tag.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(tag)
+}
+
+/// Append the surface text of a stretch of stream text to `out`; the break
+/// ending the stretch belongs to the line markup, not the text.
+fn push_surface(segment: &str, out: &mut String) {
+    push_cg_text(segment.strip_suffix('\n').unwrap_or(segment), out);
+}
+
+/// A cohort's wordform and where it sits in the surface text.
+struct Word {
+    cohort: CohortId,
+    beg: usize,
+    end: usize,
+}
+
+/// The errors found on one cohort.
+#[derive(Default)]
+struct Marks {
+    /// Error tags, in the order first seen.
+    errors: Vec<TagHash>,
+    /// Suggested forms, with the error tags they belong to.
+    suggestions: Vec<(Vec<TagHash>, String)>,
+}
+
+/// The grammar-checker errors print vtable.
+#[derive(Default)]
+pub struct ErrorsFormat {
+    /// Characters of surface text printed in earlier windows.
+    offset: usize,
+}
+
+impl ErrorsFormat {
+    /// The error tags and suggestions on `cohort`'s readings, sub-readings
+    /// included.
+    fn marks_of(e: &Engine<'_>, cohort: CohortId) -> Marks {
+        let c = e.doc.store.cohorts.get(cohort.0);
+        let wordform = c.wordform.map(|wf| e.grammar.single_tags_list[wf.0].hash);
+        let mut marks = Marks::default();
+        for &reading in &c.readings {
+            let mut errors = Vec::new();
+            let mut suggest = false;
+            let mut form = None;
+            let mut baseform = None;
+            let mut cur = Some(reading);
+            while let Some(r) = cur {
+                let r = e.doc.store.readings.get(r.0);
+                baseform = baseform.or(r.baseform);
+                for &hash in &r.tags_list {
+                    let tag = &e.grammar.single_tags_list[tag_by_hash(e.grammar, TagHash(hash)).0];
+                    if SUGGEST_TAGS.contains(&tag.tag.as_str()) {
+                        suggest = true;
+                    } else if tag.tag.len() > 1 && tag.tag.starts_with('&') {
+                        if !errors.contains(&TagHash(hash)) {
+                            errors.push(TagHash(hash));
+                        }
+                    } else if form.is_none()
+                        && tag.r#type.intersects(T_WORDFORM)
+                        && Some(TagHash(hash)) != wordform
+                    {
+                        form = Some(strip_wordform(&tag.tag).to_string());
+                    }
+                }
+                cur = r.next;
+            }
+            for &error in &errors {
+                if !marks.errors.contains(&error) {
+                    marks.errors.push(error);
+                }
+            }
+            if suggest {
+                let form = form.or_else(|| {
+                    baseform.map(|bf| {
+                        let tag = &e.grammar.single_tags_list[tag_by_hash(e.grammar, bf).0];
+                        strip_baseform(&tag.tag).to_string()
+                    })
+                });
+                if let Some(form) = form {
+                    marks.suggestions.push((errors, form));
+                }
+            }
+        }
+        marks
+    }
+
+    pub(crate) fn print_single_window_e<W: Write>(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+        output: &mut W,
+        profiling: bool,
+    ) -> Result<(), RunError> {
+        let (all_cohorts, number) = {
+            let sw = e.doc.store.single_windows.get(window.0);
+            (sw.all_cohorts.clone(), sw.number)
+        };
+
+        let mut text = String::new();
+        push_surface(&e.doc.store.single_windows.get(window.0).text, &mut text);
+        let mut len = text.chars().count();
+        let mut words = Vec::with_capacity(all_cohorts.len());
+        for &cohort in &all_cohorts {
+            let c = e.doc.store.cohorts.get(cohort.0);
+            if let Some(wf) = c.wordform
+                && c.local_number != 0
+            {
+                let form = strip_wordform(&e.grammar.single_tags_list[wf.0].tag);
+                text.push_str(form);
+                let beg = len;
+                len += form.chars().count();
+                words.push(Word {
+                    cohort,
+                    beg,
+                    end: len,
+                });
+            }
+            let before = text.len();
+            push_surface(&c.text, &mut text);
+            len += text[before..].chars().count();
+        }
+        push_surface(
+            &e.doc.store.single_windows.get(window.0).text_post,
+            &mut text,
+        );
+
+        let chars: Vec<char> = text.chars().collect();
+        let positions: HashMap<CohortId, usize> = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.cohort, i))
+            .collect();
+        let mut errors = Vec::new();
+        for (at, word) in words.iter().enumerate() {
+            if e.doc
+                .store
+                .cohorts
+                .get(word.cohort.0)
+                .r#type
+                .intersects(CT_REMOVED)
+            {
+                continue;
+            }
+            if !profiling {
+                unignore_all(&mut e.doc.store, word.cohort);
+            }
+            let marks = Self::marks_of(e, word.cohort);
+            if marks.errors.is_empty() {
+                continue;
+            }
+
+            let (mut first, mut last) = (at, at);
+            let mut seen = vec![at];
+            let mut queue = vec![word.cohort];
+            while let Some(cohort) = queue.pop() {
+                for targets in e.doc.store.cohorts.get(cohort.0).relations.values() {
+                    for &target in targets.iter() {
+                        let Some(&related) = e.doc.cohorts.cohort_map.get(&GlobalNumber(target))
+                        else {
+                            continue;
+                        };
+                        let Some(&pos) = positions.get(&related) else {
+                            continue;
+                        };
+                        if !seen.contains(&pos) {
+                            seen.push(pos);
+                            queue.push(related);
+                            first = first.min(pos);
+                            last = last.max(pos);
+                        }
+                    }
+                }
+            }
+            let (beg, end) = (words[first].beg, words[last].end);
+            let span: String = chars[beg..end].iter().collect();
+            let before: String = chars[beg..word.beg].iter().collect();
+            let after: String = chars[word.end..end].iter().collect();
+
+            for &error in &marks.errors {
+                let mut suggestions: Vec<String> = Vec::new();
+                for (tags, form) in &marks.suggestions {
+                    if !tags.is_empty() && !tags.contains(&error) {
+                        continue;
+                    }
+                    let replacement = format!("{before}{form}{after}");
+                    if !suggestions.contains(&replacement) {
+                        suggestions.push(replacement);
+                    }
+                }
+                let tag = &e.grammar.single_tags_list[tag_by_hash(e.grammar, error).0].tag;
+                errors.push(json!({
+                    "tag": &tag[1..],
+                    "cohort": at + 1,
+                    "span": [first + 1, last + 1],
+                    "beg": self.offset + beg,
+                    "end": self.offset + end,
+                    "form": span,
+                    "suggestions": suggestions,
+                }));
+            }
+        }
+
+        let doc = json!({
+            "window": number,
+            "offset": self.offset,
+            "text": text,
+            "errors": errors,
+        });
+        self.offset += chars.len();
+        writeln!(output, "{doc}")?;
+        output.flush()?;
+        Ok(())
+    }
+}
+
+impl StreamFormat for ErrorsFormat {
+    /// Cohorts are only reported as part of their window.
+    fn print_cohort<W: Write>(
+        &mut self,
+        _e: &mut Engine<'_>,
+        _cohort: CohortId,
+        _output: &mut W,
+        _profiling: bool,
+    ) -> Result<(), RunError> {
+        Ok(())
+    }
+
+    fn print_single_window<W: Write>(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+        output: &mut W,
+        profiling: bool,
+    ) -> Result<(), RunError> {
+        self.print_single_window_e(e, window, output, profiling)
+    }
+
+    fn print_stream_command<W: Write>(&mut self, _e: &mut Engine<'_>, _cmd: &str, _output: &mut W) {
+    }
+
+    fn print_plain_text_line<W: Write>(
+        &mut self,
+        _e: &mut Engine<'_>,
+        _line: &str,
+        _output: &mut W,
+    ) {
+    }
+}
@@ -0,0 +1,120 @@
+//! `boost::container::flat_map` — an ordered map stored as a sorted
+//! `Vec<(K, V)>`.
+//!
+//! The port first mapped every `bc::flat_map` to [`BTreeMap`](std::collections::BTreeMap),
+//! which has the same key order but frees its nodes on `clear()`. The engine's
+//! per-cohort scratch maps (`unif_tags_rs`, `regexgrps_c`, `readings_plain`, …)
+//! are cleared and refilled for every (rule, cohort) pair, so that turned into
+//! a node allocation per insert. This is the flat layout the C++ actually used:
+//! `clear()` keeps the buffer, and after the first few cohorts the maps never
+//! touch the heap again. Lookups are a binary search; inserts shift the tail,
+//! which is cheap at the sizes involved (one entry per reading).
+//!
+//! Only the `BTreeMap` surface the scratch maps use is provided, with the same
+//! signatures, so the swap is a type change at the field.
+//!
+//! Not a manifest symbol — port infrastructure.
+
+/// Sorted-vector ordered map (C++ `bc::flat_map<K, V>`).
+#[derive(Clone, Debug)]
+pub struct FlatMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> Default for FlatMap<K, V> {
+    fn default() -> Self {
+        FlatMap {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<K: Ord, V> FlatMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn search(&self, k: &K) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(ek, _)| ek.cmp(k))
+    }
+
+    /// Insert or overwrite; returns the previous value, as `BTreeMap::insert`.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self.search(&k) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, v)),
+            Err(i) => {
+                self.entries.insert(i, (k, v));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.search(k).ok().map(|i| &self.entries[i].1)
+    }
+
+    pub fn get_mut(&mut self, k: &K) -> Option<&mut V> {
+        match self.search(k) {
+            Ok(i) => Some(&mut self.entries[i].1),
+            Err(_) => None,
+        }
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.search(k).is_ok()
+    }
+
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        self.search(k).ok().map(|i| self.entries.remove(i).1)
+    }
+
+    /// Empties the map, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Entries in ascending key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> crate::pool::Poolable for FlatMap<K, V> {
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ordered iteration, overwrite-returns-old, and clear() keeping capacity
+    // (the property the engine scratch maps rely on).
+    #[test]
+    fn ordered_insert_overwrite_clear() {
+        let mut m: FlatMap<u32, u8> = FlatMap::new();
+        assert_eq!(m.insert(7, 1), None);
+        assert_eq!(m.insert(3, 2), None);
+        assert_eq!(m.insert(9, 3), None);
+        assert_eq!(m.insert(3, 4), Some(2));
+        let keys: Vec<u32> = m.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, [3, 7, 9]);
+        assert_eq!(m.get(&3), Some(&4));
+        assert_eq!(m.remove(&7), Some(1));
+        assert!(!m.contains_key(&7));
+
+        let cap = m.entries.capacity();
+        m.clear();
+        assert!(m.is_empty());
+        assert_eq!(m.entries.capacity(), cap);
+    }
+}
@@ -15,35 +15,44 @@
 //! `fmt_output` that the drivers thread through every print.
 //!
 //! CG, Apertium, FST, Niceline, plaintext, JSONL, and binary are wired for both
-//! input and output. `CG3SF_MATXIN` still follows the C++ converter's default
-//! branch (`CG3Quit()`): upstream declares Matxin inheritance but supplies no
-//! converter switch arm for it.
+//! input and output, as in the C++. ADDED: `CG3SF_MATXIN` is too, through
+//! [`crate::matxin_format`]; upstream declares Matxin inheritance but supplies
+//! no converter switch arm for it, so it fell to the default branch
+//! (`CG3Quit()`). ADDED: the output-only grammar-checker errors format, through
+//! [`crate::errors_format`]. ADDED: a [`TagFilter`] drops and renames tags
+//! as each window is printed, ahead of every output format, and so do the
+//! [`StreamFilters`] the `cg-untrace` family of tools run.
 //!
 //! ## detectFormat regex mapping (ICU uregex → `regex` crate)
 //! See [`detect_format`]; every pattern's flag set and anchoring is reproduced
 //! per the spec's parity notes.
 
 use std::io::{Read, Seek, Write};
+use std::sync::Arc;
 
 use crate::apertium_applicator::{ApertiumApplicator, ApertiumFormat};
 use crate::arena::{CohortId, SwId};
+use crate::errors_format::ErrorsFormat;
 use crate::fst_applicator::{FSTApplicator, FstFormat};
 use crate::grammar::Grammar;
 use crate::grammar_applicator::stream_format::StreamFormat;
 use crate::grammar_applicator::{Engine, GrammarApplicator, StreamFormatKind};
 use crate::jsonl_applicator::{JsonlApplicator, JsonlFormat};
+use crate::matxin_format::{MatxinFormat, MatxinReader};
 use crate::niceline_applicator::{NicelineApplicator, NicelineFormat};
 use crate::plaintext_applicator::{PlaintextApplicator, PlaintextFormat};
+use crate::stream_filters::StreamFilters;
 use crate::streambuf::BStreamBuf;
 use crate::strings::STR_DUMMY;
+use crate::tag_filter::TagFilter;
+use crate::tokenizer::Tokenizer;
 use crate::types::UStringView;
 
-const BUF_SIZE: usize = 1000;
+pub(crate) const BUF_SIZE: usize = 1000;
 
 /// The C++ default-branch `CG3Quit()` for an output format this converter has
-/// no arm for — MATXIN, which never had one, and the invalid sentinel. It
-/// aborted with no diagnostic at all; naming the format is the whole point of
-/// replacing it.
+/// no arm for — now only the invalid sentinel. It aborted with no diagnostic
+/// at all; naming the format is the whole point of replacing it.
 fn unsupported_output(kind: StreamFormatKind) -> crate::error::RunError {
     crate::error::RunError::UnsupportedOutputFormat {
         format: format!("{kind:?}"),
@@ -65,7 +74,9 @@ fn unsupported_output(kind: StreamFormatKind) -> crate::error::RunError {
 /// * `\^`/`\$` are literal `^`/`$`.
 ///   The C++ converts to UTF-16 and caps the scan at [`BUF_SIZE`] (1000) UChars;
 ///   this port scans the (already UTF-8) prefix directly — equivalent for the
-///   anchoring the patterns rely on. NEVER returns `CG3SF_MATXIN`.
+///   anchoring the patterns rely on.
+/// * ADDED: a stream that opens with `<corpus>`, after at most an XML
+///   declaration, is `CG3SF_MATXIN`, which the C++ never detected.
 pub fn detect_format(buf8: &str) -> StreamFormatKind {
     use StreamFormatKind::*;
 
@@ -116,6 +127,11 @@ static SNIFF_PATTERNS: std::sync::LazyLock<Vec<(regex::Regex, StreamFormatKind)>
 /// Pattern sources for [`SNIFF_PATTERNS`], kept separate so a test can compile
 /// them without tripping the `LazyLock`'s panic first.
 const SNIFF_SOURCES: &[(&str, StreamFormatKind)] = &[
+    // ADDED: `\A\s*(<\?xml[^>]*\?>\s*)?<corpus[\s/>]` → MATXIN (no C++ analog).
+    (
+        r"\A\s*(?:<\?xml[^>]*\?>\s*)?<corpus[\s/>]",
+        StreamFormatKind::Matxin,
+    ),
     // `^"<[^>]+>".*?^\s+"[^"]+"` DOTALL|MULTILINE → CG
     (r#"(?sm)^"<[^>]+>".*?^\s+"[^"]+""#, StreamFormatKind::Cg),
     // `^\S+ *\t *\[\S+\]` DOTALL|MULTILINE → NICELINE
@@ -220,6 +236,39 @@ impl FormatConverter {
         self.fmt.plaintext_add_tags = add_tags;
     }
 
+    /// ADDED: configure how plaintext input is tokenized.
+    pub fn set_plaintext_tokenizer(&mut self, tokenizer: Arc<dyn Tokenizer>) {
+        self.fmt.plaintext_tokenizer = Some(tokenizer);
+    }
+
+    /// ADDED: configure whether plaintext input ends a window at each sentence.
+    pub fn set_plaintext_split_sentences(&mut self, split_sentences: bool) {
+        self.fmt.plaintext_split_sentences = split_sentences;
+    }
+
+    /// ADDED: configure whether JSONL input is held to its schema.
+    pub fn set_jsonl_strict(&mut self, strict: bool) {
+        self.fmt.jsonl_strict = strict;
+    }
+
+    /// ADDED: configure whether JSONL output starts with the schema version
+    /// marker.
+    pub fn set_jsonl_header(&mut self, header: bool) {
+        self.fmt.jsonl.header = header;
+    }
+
+    /// ADDED: configure which tags and readings are dropped or renamed as
+    /// each window is printed, whatever the output format.
+    pub fn set_tag_filter(&mut self, filter: TagFilter) {
+        self.fmt.tag_filter = filter;
+    }
+
+    /// ADDED: configure the untrace, sort and relation-stabilizing filters
+    /// run as each window is printed.
+    pub fn set_stream_filters(&mut self, filters: StreamFilters) {
+        self.fmt.stream_filters = filters;
+    }
+
     /// Configure the separator set used to parse and print FST subreadings.
     pub fn set_fst_sub_delims(&mut self, sub_delims: String) {
         self.fmt.fst.sub_delims = sub_delims;
@@ -261,7 +310,10 @@ impl FormatConverter {
     /// std::ostream& output)`. Dispatches input PARSING to the applicator matching
     /// `fmt_input`; the overridden `print*` methods emit `fmt_output`, so the two
     /// together convert. Sets `has_relations` when either side is binary.
-    /// `CG3SF_MATXIN`/invalid values → `CG3Quit()`.
+    /// Invalid values → `CG3Quit()`. ADDED: `CG3SF_MATXIN` input is read by
+    /// [`MatxinReader`], and a Matxin document written is closed at the end,
+    /// also when the run fails. Errors output sets `has_relations` too, its
+    /// spans following relations; as input it is unsupported.
     pub fn run_grammar_on_text<R, W>(
         &mut self,
         input: &mut R,
@@ -276,12 +328,24 @@ impl FormatConverter {
             let b = self.base();
             (b.cfg.fmt_input, b.cfg.fmt_output)
         };
-        if fmt_output == StreamFormatKind::Binary || fmt_input == StreamFormatKind::Binary {
+        if fmt_output == StreamFormatKind::Binary
+            || fmt_input == StreamFormatKind::Binary
+            || fmt_output == StreamFormatKind::Errors
+        {
             self.base_mut().grammar.has_relations = true;
         }
+        // ADDED: error offsets count from the start of each run's input.
+        self.fmt.errors = ErrorsFormat::default();
+        // ADDED: the JSONL schema version marker opens the output.
+        if fmt_output == StreamFormatKind::Jsonl {
+            self.fmt
+                .jsonl
+                .write_header(output)
+                .map_err(|e| crate::error::Cg3Error::from(crate::error::RunError::from(e)))?;
+        }
 
         use StreamFormatKind::*;
-        match fmt_input {
+        let rv = match fmt_input {
             Cg => {
                 // GrammarApplicator::runGrammarOnText(input, output) — the base CG
                 // stream driver, printing through the ConvFormat vtable.
@@ -302,11 +366,11 @@ impl FormatConverter {
                 input,
                 output,
             ),
-            Jsonl => JsonlApplicator::new(&mut self.base).run_grammar_on_text(
-                &mut self.fmt,
-                input,
-                output,
-            ),
+            Jsonl => {
+                let mut app = JsonlApplicator::new(&mut self.base);
+                app.strict = self.fmt.jsonl_strict;
+                app.run_grammar_on_text(&mut self.fmt, input, output)
+            }
             // BinaryApplicator::runGrammarOnText(input, output).
             Binary => crate::binary_applicator::BinaryApplicator::new(&mut self.base)
                 .run_grammar_on_text(&mut self.fmt, input, output),
@@ -321,23 +385,33 @@ impl FormatConverter {
             Plain => {
                 let mut app = PlaintextApplicator::borrowing(&mut self.base);
                 app.add_tags = self.fmt.plaintext_add_tags;
+                if let Some(tokenizer) = &self.fmt.plaintext_tokenizer {
+                    app.tokenizer = Arc::clone(tokenizer);
+                }
+                app.split_sentences = self.fmt.plaintext_split_sentences;
                 app.run_grammar_on_text_with(&mut self.fmt, input, output)
             }
-            // MATXIN has no C++ converter case; invalid values share its
-            // default branch.
-            other => Err(crate::error::RunError::UnsupportedInputFormat {
-                format: format!("{other:?}"),
+            // ADDED: MATXIN has no C++ converter case.
+            Matxin => {
+                MatxinReader::new(&mut self.base).run_grammar_on_text(&mut self.fmt, input, output)
+            }
+            Invalid | Errors => Err(crate::error::RunError::UnsupportedInputFormat {
+                format: format!("{fmt_input:?}"),
             }
             .into()),
+        };
+        if fmt_output == Matxin {
+            self.fmt.matxin.finish(output);
         }
+        rv
     }
 
     // [spec:cg3:def:format-converter.cg3.format-converter.print-cohort-fn]
     // [spec:cg3:sem:format-converter.cg3.format-converter.print-cohort-fn]
     /// C++ `void FormatConverter::printCohort(Cohort* cohort, std::ostream&
-    /// output, bool profiling)`. Dispatches on `fmt_output`. `CG3SF_BINARY` is a
-    /// no-op (binary emits whole windows elsewhere); `CG3SF_MATXIN`/`default` →
-    /// `CG3Quit()`.
+    /// output, bool profiling)`. Dispatches on `fmt_output`. `CG3SF_BINARY` and
+    /// the ADDED Matxin and errors formats are no-ops (both emit whole windows elsewhere);
+    /// `default` → `CG3Quit()`.
     pub fn print_cohort<W: Write>(
         &mut self,
         cohort: CohortId,
@@ -352,7 +426,8 @@ impl FormatConverter {
     // [spec:cg3:sem:format-converter.cg3.format-converter.print-single-window-fn]
     /// C++ `void FormatConverter::printSingleWindow(SingleWindow* window,
     /// std::ostream& output, bool profiling)`. Dispatches on `fmt_output`.
-    /// `CG3SF_BINARY` emits a whole-window packet; `CG3SF_MATXIN`/`default` →
+    /// `CG3SF_BINARY` emits a whole-window packet, the ADDED `CG3SF_MATXIN` a
+    /// `<SENTENCE>` and the ADDED errors format a JSON object; `default` →
     /// `CG3Quit()`.
     pub fn print_single_window<W: Write>(
         &mut self,
@@ -400,13 +475,28 @@ pub struct ConvFormat {
     plaintext: PlaintextFormat,
     /// Plaintext's input-only `add_tags` flag.
     plaintext_add_tags: bool,
+    /// ADDED: plaintext's tokenizer, when not the default.
+    plaintext_tokenizer: Option<Arc<dyn Tokenizer>>,
+    /// ADDED: plaintext's input-only `split_sentences` flag.
+    plaintext_split_sentences: bool,
     /// The binary print vtable (C++ `BinaryApplicator::header_done` et al).
     binary: crate::binary_applicator::BinaryFormat,
     /// The Niceline print vtable, owning the one-shot warn latches
     /// (`did_warn_statictags`/`did_warn_subreadings`) that persist across prints.
     niceline: NicelineFormat,
-    /// The JSONL print vtable (stateless).
+    /// The JSONL print vtable, owning the schema-marker switch.
     jsonl: JsonlFormat,
+    /// ADDED: JSONL's input-only strict flag.
+    jsonl_strict: bool,
+    /// ADDED: the tags and readings dropped or renamed before printing.
+    tag_filter: TagFilter,
+    /// ADDED: the `cg-untrace` family of filters, run after `tag_filter`.
+    stream_filters: StreamFilters,
+    /// ADDED: the Matxin print vtable, owning the open-`<corpus>` latch.
+    matxin: MatxinFormat,
+    /// ADDED: the grammar-checker errors print vtable, owning the running
+    /// text offset.
+    errors: ErrorsFormat,
 }
 
 impl StreamFormat for ConvFormat {
@@ -418,6 +508,9 @@ impl StreamFormat for ConvFormat {
         profiling: bool,
     ) -> Result<(), crate::error::RunError> {
         use StreamFormatKind::*;
+        // ADDED: drop and rename tags before any format prints them.
+        self.tag_filter.apply_cohort(e, cohort)?;
+        self.stream_filters.apply_cohort(e, cohort)?;
         match e.cfg.fmt_output {
             Cg => {
                 let trace = e.cfg.trace;
@@ -428,7 +521,7 @@ impl StreamFormat for ConvFormat {
             Niceline => self.niceline.print_cohort_e(e, cohort, output, profiling),
             Plain => self.plaintext.print_cohort_e(e, cohort, output, profiling),
             Jsonl => self.jsonl.print_cohort_e(e, cohort, output, profiling),
-            Binary => {}
+            Binary | Matxin | Errors => {}
             other => return Err(unsupported_output(other)),
         }
         Ok(())
@@ -442,6 +535,8 @@ impl StreamFormat for ConvFormat {
         profiling: bool,
     ) -> Result<(), crate::error::RunError> {
         use StreamFormatKind::*;
+        self.tag_filter.apply_window(e, window)?;
+        self.stream_filters.apply_window(e, window)?;
         match e.cfg.fmt_output {
             Cg => {
                 let trace = e.cfg.trace;
@@ -464,8 +559,14 @@ impl StreamFormat for ConvFormat {
             Binary => self
                 .binary
                 .bin_print_single_window(e, window, output, profiling),
-            // MATXIN has no C++ converter case; invalid values share its
-            // default branch.
+            // ADDED: MATXIN has no C++ converter case.
+            Matxin => self
+                .matxin
+                .print_single_window_e(e, window, output, profiling)?,
+            // ADDED: grammar-checker errors, one JSON object per window.
+            Errors => self
+                .errors
+                .print_single_window_e(e, window, output, profiling)?,
             other => return Err(unsupported_output(other)),
         }
         Ok(())
@@ -477,6 +578,9 @@ impl StreamFormat for ConvFormat {
             Jsonl => self.jsonl.print_stream_command_e(cmd, output),
             // BinaryApplicator::printStreamCommand.
             Binary => self.binary.bin_print_stream_command(cmd, output),
+            // ADDED: neither a Matxin document nor the errors have a place
+            // for them.
+            Matxin | Errors => {}
             // CG / APERTIUM / FST / NICELINE / PLAIN / default → base.
             _ => e.print_stream_command(cmd, output),
         }
@@ -488,6 +592,9 @@ impl StreamFormat for ConvFormat {
             Jsonl => self.jsonl.print_plain_text_line_e(line, output),
             // BinaryApplicator::printPlainTextLine.
             Binary => self.binary.bin_print_plain_text_line(line, output),
+            // ADDED: neither a Matxin document nor the errors have a place
+            // for them.
+            Matxin | Errors => {}
             // CG / APERTIUM / FST / NICELINE / PLAIN / default → base.
             _ => e.print_plain_text_line(line, output),
         }
@@ -31,15 +31,16 @@ use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::cohort::append_reading;
 use crate::cohort::{CT_REMOVED, alloc_cohort, free_cohort};
 use crate::grammar::Grammar;
-use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::grammar_applicator::{Engine, GrammarApplicator, StreamFormatKind};
 use crate::inlines::{
     NUMERIC_MAX, insert_if_exists, isnl, isspace, reversed, skipto_nospan_raw_chars,
 };
 use crate::reading::alloc_reading;
+use crate::runtime_diagnostics::{InputExcerpt, RuntimeDiagnosticKind};
 use crate::single_window::{append_cohort, free_swindow};
 use crate::tag::{T_DEPENDENCY, T_MAPPING, T_RELATION, TagList};
 use crate::types::{TagHash, UString};
-use crate::uextras::{get_line_clean_chars, u_fputc, ux_strip_bom};
+use crate::uextras::{get_line_clean_chars, raw_column, u_fputc, ux_strip_bom};
 
 /// C++ `grammar->single_tags[hash]` — resolves a tag hash to its `TagId`, else
 /// `TagId(0)`. Reproduces `grammar_applicator::core::tag_by_hash` (which is
@@ -150,6 +151,12 @@ where
             .map_err(crate::error::Cg3Error::from)
     }
 
+    /// ADDED: the line being read, quoted at `cleaned_at` in its cleaned copy.
+    fn excerpt(&self, line: &[char], cleaned_at: usize) -> InputExcerpt {
+        let column = raw_column(line.iter().copied(), cleaned_at);
+        InputExcerpt::from_chars(self.base.doc.num_lines + 1, line, column)
+    }
+
     fn run_grammar_on_text_impl<F, R, W>(
         &mut self,
         fmt: &mut F,
@@ -166,21 +173,7 @@ where
         // u_fprintf diagnostic) elided — the grammar is assumed present.
 
         // No-hard/soft-delimiter warnings (emitted to the discard sink).
-        let no_hard = self.base.grammar.delimiters.is_none();
-        let no_soft = self.base.grammar.soft_delimiters.is_none();
-        if no_hard {
-            if no_soft {
-                tracing::warn!(
-                    "Warning: No soft or hard delimiters defined in grammar. Hard limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.hard_limit
-                );
-            } else {
-                tracing::warn!(
-                    "Warning: No hard delimiters defined in grammar. Soft limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.soft_limit
-                );
-            }
-        }
+        self.base.engine().note_missing_delimiters();
 
         // UString line(1024, 0); UString cleaned(line.size(), 0);
         let mut line: Vec<char> = vec!['\0'; 1024];
@@ -235,11 +228,13 @@ where
                 if cleaned[space] != '\t' {
                     // If this line looks like markup, don't warn about it.
                     if cleaned[0] != '<' {
-                        tracing::warn!(
-                            "Warning: {} on line {} looked like a cohort but wasn't - treated as text.",
-                            cleaned[..space].iter().collect::<String>(),
-                            self.base.doc.num_lines
-                        );
+                        let text = cleaned[..space].iter().collect();
+                        let excerpt = self.excerpt(&line, 0);
+                        self.base.engine().note_input(
+                            RuntimeDiagnosticKind::NotACohort { text },
+                            None,
+                            excerpt,
+                        )?;
                     }
                     is_text = true;
                 } else {
@@ -331,6 +326,7 @@ where
                         // baseform reassignment (base = tag.data()) is tracked with
                         // `base_str = Some(...)`.
                         let mut base_idx = space;
+                        let reading_at = space;
                         let mut base_str: Option<String> = None;
                         let mut mappings = TagList::new();
 
@@ -483,10 +479,12 @@ where
                                 };
                                 if cur_first == '\0' {
                                     base_str = Some(String::from("_")); // notag {'_',0}
-                                    tracing::warn!(
-                                        "Warning: Line {} had empty tag.",
-                                        self.base.doc.num_lines
-                                    );
+                                    let excerpt = self.excerpt(&line, base_idx);
+                                    self.base.engine().note_input(
+                                        RuntimeDiagnosticKind::EmptyTag,
+                                        None,
+                                        excerpt,
+                                    )?;
                                 }
                                 // Tag* tag2 = addTag(base);
                                 let base_text = match &base_str {
@@ -593,10 +591,14 @@ where
                             let wf_hash = self.base.grammar.single_tags_list.get(wf.0).hash;
                             self.base.doc.store.readings.get_mut(c_reading.0).baseform =
                                 Some(wf_hash);
-                            tracing::warn!(
-                                "Warning: Line {} had no valid baseform.",
-                                self.base.doc.num_lines
-                            );
+                            let excerpt = self.excerpt(&line, reading_at);
+                            self.base.engine().note_input(
+                                RuntimeDiagnosticKind::NoBaseform {
+                                    format: StreamFormatKind::Fst,
+                                },
+                                Some(cc),
+                                excerpt,
+                            )?;
                         }
                         // if (single_tags[baseform]->tag.size() == 2) { ... }
                         let bf_hash = self
@@ -688,7 +690,7 @@ where
         self.base.engine().shuffle_windows_down();
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
-            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
             let t = Some(tmp);
             {
                 let base = &mut *self.base;
@@ -733,6 +735,8 @@ where
         if self.base.cfg.is_conv {
             if let Some(cc) = st.c_cohort {
                 self.base.doc.store.cohorts.get_mut(cc.0).local_number = 1;
+                // ADDED: no window will run, so prune by weight here.
+                self.base.engine().prune_cohort_by_weight(cc);
                 fmt.print_cohort(&mut self.base.engine(), cc, output, false)?;
                 let opt = Some(cc);
                 {
@@ -833,15 +837,9 @@ where
                 };
             if over_hard || delim_hit {
                 if !self.base.cfg.is_conv && over_hard {
-                    let wf = self.base.doc.store.cohorts.get(cc.0).wordform.unwrap();
-                    let wftag = self.base.grammar.single_tags_list.get(wf.0).tag.clone();
-                    tracing::warn!(
-                        "Warning: Hard limit of {} cohorts reached at cohort {} (#{}) on line {} - forcing break.",
-                        self.base.cfg.hard_limit,
-                        wftag,
-                        self.base.doc.num_cohorts,
-                        self.base.doc.num_lines
-                    );
+                    self.base
+                        .engine()
+                        .note_hard_limit(cc, StreamFormatKind::Fst);
                 }
                 let rs = self.base.doc.store.cohorts.get(cc.0).readings.clone();
                 for r in rs {
@@ -176,6 +176,14 @@ pub struct Grammar {
     pub sub_readings_ltr: bool,
     pub ordered: bool,
     pub addcohort_attach: bool,
+    /// ADDED — no C++ analog. The Unicode form tags are put into as they are
+    /// made (`OPTIONS += normalize-nfc ;`, `--normalize`); see
+    /// [`crate::normalization`].
+    pub normalization: crate::normalization::Normalization,
+    /// ADDED — no C++ analog. The language whose case rules case-insensitive
+    /// tags and case mapping follow (`OPTIONS += casefold-tr ;`); see
+    /// [`crate::case_folding`].
+    pub case_locale: crate::case_folding::CaseLocale,
 
     // --- sizes / counters ---
     pub grammar_size: usize,
@@ -250,6 +258,10 @@ pub struct Grammar {
     /// Owned contextual-test arena (ADDED — port infra) backing every `CtxId`.
     pub contexts_arena: Arena<ContextualTest>,
     pub templates: Contexts,
+    /// ADDED — no C++ analog. The name of each template, by the name hash
+    /// [`templates`](Self::templates) keys it by, for a textual parse; a
+    /// binary grammar keeps only the hashes.
+    pub template_names: BTreeMap<u32, String>,
     pub contexts: Contexts,
 
     // --- runtime indexes ---
@@ -312,6 +324,8 @@ impl Default for Grammar {
             sub_readings_ltr: false,
             ordered: false,
             addcohort_attach: false,
+            normalization: crate::normalization::Normalization::None,
+            case_locale: crate::case_folding::CaseLocale::Root,
             grammar_size: 0,
             num_tags: 0,
             mapping_prefix: '@',
@@ -337,6 +351,7 @@ impl Default for Grammar {
             icase_tags: SortedVector::new(),
             contexts_arena: Arena::new(),
             templates: Contexts::default(),
+            template_names: BTreeMap::default(),
             contexts: Contexts::default(),
             rules_by_set: RulesBySet::default(),
             rules_by_tag: RulesByTag::default(),
@@ -592,6 +607,8 @@ impl Grammar {
     }
 
     pub fn allocate_tag(&mut self, txt: &str) -> Result<TagId, crate::error::ParseError> {
+        // ADDED: put into the grammar's form first (see `crate::normalization`).
+        let txt = &*self.normalization.apply(txt);
         let first = txt.chars().next().unwrap_or('\0');
         if first == '\0' {
             return Err(self.error(crate::error::ParseErrorKind::EmptyTag));
@@ -796,6 +813,8 @@ impl Grammar {
             }));
         }
         self.templates.insert(cn, test);
+        // ADDED: the name, for the grammar graph.
+        self.template_names.insert(cn, name.to_string());
         Ok(())
     }
 
@@ -1686,7 +1705,7 @@ impl Grammar {
             }
             for iid in &icase_tag_ids {
                 let itext = &self.single_tags_list[iid.0].tag;
-                if ux_str_case_compare(&ttext, itext) {
+                if self.case_locale.caseless_eq(&ttext, itext) {
                     textual = true;
                 }
             }
@@ -2253,14 +2272,3 @@ pub fn trie_unserialize<R: Read>(
         }
     }
 }
-
-/// `uextras.hpp` `ux_strCaseCompare(a, b)` (ICU `u_strCaseCompare`,
-/// `U_FOLD_CASE_DEFAULT`) — true on full case-fold equality. Approximated with
-/// Unicode simple lowercase folding (same stand-in as `tag.rs`; ICU-vs-Rust
-/// parity risk for non-ASCII). Local because `uextras` is not yet ported and this
-/// pass edits only `grammar.rs`.
-fn ux_str_case_compare(a: &str, b: &str) -> bool {
-    a.chars()
-        .flat_map(char::to_lowercase)
-        .eq(b.chars().flat_map(char::to_lowercase))
-}
@@ -48,7 +48,7 @@ use crate::tag::{
 };
 use crate::tag_trie::trie_get_tag_list_append;
 use crate::types::{GlobalNumber, TagHash};
-use crate::uextras::{u_fflush, u_fputc, ux_str_case_compare};
+use crate::uextras::{u_fflush, u_fputc};
 
 use super::{Engine, Matcher, TmplContext};
 
@@ -671,7 +671,7 @@ impl Engine<'_> {
     /// wrapper delegating to the free [`print_trace`] (which reads only
     /// `grammar`/`cfg`, so it is also callable from `&self` non-Engine printers).
     pub fn print_trace<W: Write>(&self, output: &mut W, hit_by: u32) {
-        print_trace(self.grammar, self.cfg, output, hit_by);
+        print_trace(self.trace_grammar(), self.cfg, output, hit_by);
     }
 
     // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator.print-reading-fn]
@@ -724,10 +724,9 @@ impl Engine<'_> {
             let _ = write!(output, "{}", self.grammar.single_tags_list[tid.0].tag);
         }
 
-        let tags_list: Vec<u32> = self.doc.store.readings.get(reading.0).tags_list.clone();
         let mut unique: std::collections::BTreeSet<u32> = std::collections::BTreeSet::new();
-        let mut mappings: Vec<TagId> = Vec::new();
-        for tter in tags_list {
+        let mut mappings = self.scratch.ss_taglist.get();
+        for &tter in &self.doc.store.readings.get(reading.0).tags_list {
             let tter = TagHash(tter);
             if (!self.cfg.show_end_tags && tter == self.cfg.endtag) || tter == self.cfg.begintag {
                 continue;
@@ -755,9 +754,10 @@ impl Engine<'_> {
             }
             let _ = write!(output, " {}", self.grammar.single_tags_list[tid.0].tag);
         }
-        for tid in mappings {
+        for &tid in &mappings {
             let _ = write!(output, " {}", self.grammar.single_tags_list[tid.0].tag);
         }
+        self.scratch.ss_taglist.put(mappings);
 
         // --- dependency annotation ---
         let parent_removed = self
@@ -877,6 +877,22 @@ impl Engine<'_> {
         }
     }
 
+    /// Sorts one of `cohort`'s reading lists in place by `cmp_number` and
+    /// returns a copy in a recycled `ss_rids` buffer to print from (printing
+    /// needs `&mut self`); the caller `put`s it back.
+    fn sorted_reading_list(
+        &mut self,
+        cohort: CohortId,
+        list: fn(&mut crate::cohort::Cohort) -> &mut Vec<ReadingId>,
+    ) -> Vec<ReadingId> {
+        let mut sorted = std::mem::take(list(self.doc.store.cohorts.get_mut(cohort.0)));
+        sort_readings(&self.doc.store, &mut sorted);
+        let mut copy = self.scratch.ss_rids.get();
+        copy.extend_from_slice(&sorted);
+        *list(self.doc.store.cohorts.get_mut(cohort.0)) = sorted;
+        copy
+    }
+
     // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator.print-cohort-fn]
     // [spec:cg3:sem:grammar-applicator.cg3.grammar-applicator.print-cohort-fn]
     /// C++ `virtual void printCohort(Cohort* cohort, std::ostream& output,
@@ -925,7 +941,7 @@ impl Engine<'_> {
             }
 
             if !removed_goto {
-                let (wf_tag, wf_hash) = {
+                let wf_hash = {
                     let wf = self
                         .doc
                         .store
@@ -934,12 +950,11 @@ impl Engine<'_> {
                         .wordform
                         .expect("cohort wordform");
                     let t = &self.grammar.single_tags_list[wf.0];
-                    (t.tag.clone(), t.hash)
+                    let _ = write!(output, "{}", t.tag);
+                    t.hash
                 };
-                let _ = write!(output, "{wf_tag}");
                 if let Some(wr) = self.doc.store.cohorts.get(cohort.0).wread {
-                    let tags: Vec<u32> = self.doc.store.readings.get(wr.0).tags_list.clone();
-                    for tter in tags {
+                    for &tter in &self.doc.store.readings.get(wr.0).tags_list {
                         let tter = TagHash(tter);
                         if tter == wf_hash {
                             continue;
@@ -958,29 +973,23 @@ impl Engine<'_> {
                 }
 
                 // std::sort(readings, cmp_number)
-                let mut readings: Vec<ReadingId> =
-                    self.doc.store.cohorts.get(cohort.0).readings.clone();
-                sort_readings(&self.doc.store, &mut readings);
-                self.doc.store.cohorts.get_mut(cohort.0).readings = readings.clone();
-                for r in readings {
+                let readings = self.sorted_reading_list(cohort, |c| &mut c.readings);
+                for &r in &readings {
                     self.print_reading(r, output, 1, trace);
                 }
+                self.scratch.ss_rids.put(readings);
 
                 if trace && !self.cfg.trace_no_removed {
-                    let mut delayed: Vec<ReadingId> =
-                        self.doc.store.cohorts.get(cohort.0).delayed.clone();
-                    sort_readings(&self.doc.store, &mut delayed);
-                    self.doc.store.cohorts.get_mut(cohort.0).delayed = delayed.clone();
-                    for r in delayed {
+                    let delayed = self.sorted_reading_list(cohort, |c| &mut c.delayed);
+                    for &r in &delayed {
                         self.print_reading(r, output, 1, trace);
                     }
-                    let mut del: Vec<ReadingId> =
-                        self.doc.store.cohorts.get(cohort.0).deleted.clone();
-                    sort_readings(&self.doc.store, &mut del);
-                    self.doc.store.cohorts.get_mut(cohort.0).deleted = del.clone();
-                    for r in del {
+                    self.scratch.ss_rids.put(delayed);
+                    let del = self.sorted_reading_list(cohort, |c| &mut c.deleted);
+                    for &r in &del {
                         self.print_reading(r, output, 1, trace);
                     }
+                    self.scratch.ss_rids.put(del);
                 }
             }
         }
@@ -1031,11 +1040,11 @@ impl Engine<'_> {
         // (The C++ virtual dispatch to the MweSplit / FormatConverter
         // overrides is the StreamFormat strategy; this is the base CG
         // implementation.)
-        let (vars_output, all_cohorts, text, text_post, flush_after) = {
+        let (vars_output, n_cohorts, text, text_post, flush_after) = {
             let w = self.doc.store.single_windows.get(window.0);
             (
                 w.variables_output.iter().copied().collect::<Vec<u32>>(),
-                w.all_cohorts.clone(),
+                w.all_cohorts.len(),
                 w.text.clone(),
                 w.text_post.clone(),
                 w.flush_after,
@@ -1085,7 +1094,9 @@ impl Engine<'_> {
             self.print_plain_text_line(&text, output);
         }
 
-        for cohort in all_cohorts {
+        // By position: printing only sorts a cohort's own reading lists.
+        for i in 0..n_cohorts {
+            let cohort = self.doc.store.single_windows.get(window.0).all_cohorts[i];
             self.print_cohort(cohort, output, profiling, trace);
         }
 
@@ -1912,6 +1923,10 @@ impl Matcher<'_> {
         txt: &str,
         r#type: crate::tag::TagType,
     ) -> Result<TagId, crate::error::RunError> {
+        // ADDED: input in the grammar's form, whichever reader it came through
+        // (see `crate::normalization`).
+        let normal = self.grammar.normalization.apply(txt);
+        let txt = &*normal;
         // Fast path: an existing un-seeded slot whose text matches exactly.
         let thash = hash_value_ustring(txt, 0);
         {
@@ -2029,7 +2044,7 @@ impl Matcher<'_> {
                     let text = self.grammar.single_tags_list[titer.0].tag.clone();
                     for &iid in &icase_ids {
                         let itext = &self.grammar.single_tags_list[iid.0].tag;
-                        if ux_str_case_compare(&text, itext) {
+                        if self.grammar.case_locale.caseless_eq(&text, itext) {
                             self.grammar.single_tags_list[titer.0].r#type |= T_TEXTUAL;
                             reflow = true;
                         }
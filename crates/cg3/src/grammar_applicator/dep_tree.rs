@@ -0,0 +1,523 @@
+//! ADDED — no C++ analog. Checking and exporting each window's dependency tree.
+//!
+//! The C++ only guards the arcs a rule adds (`would_parent_child_loop`,
+//! `would_parent_child_cross`, and `--dep-allow-loops` / `--dep-no-crossing`
+//! to relax or tighten them); nothing looks at the tree a window ends up with.
+//! When a window is printed, and so no rule can change it any more, the engine
+//! can now take its tree as a [`DepTree`] and:
+//!
+//! * check it (`--dep-validate`, [`EngineConfig::dep_validate`]): each
+//!   [`DepIssue`] becomes a runtime diagnostic;
+//! * write it out ([`Diagnostics::dep_export`]) as a Graphviz `digraph`
+//!   (`--dep-dot FILE`) and as a JSON tree on a line of its own
+//!   (`--dep-json FILE`), one per window.
+//!
+//! Cohorts are numbered by their position in the window, as `#n->m` tags
+//! print them, with 0 the window's root. A cohort with no parent heads a tree
+//! of its own, so it counts as a root; the stream writes it as attached to
+//! itself (`#n->n`), which reads back as no parent too. A window is only
+//! looked at once something in the run has used dependencies.
+//!
+//! [`EngineConfig::dep_validate`]: super::EngineConfig::dep_validate
+//! [`Diagnostics::dep_export`]: super::Diagnostics::dep_export
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde_json::{Value, json};
+
+use crate::arena::{CohortId, SwId};
+use crate::cohort::CT_REMOVED;
+use crate::error::RunError;
+use crate::runtime_diagnostics::RuntimeDiagnosticKind;
+use crate::text_offsets::strip_wordform;
+use crate::types::GlobalNumber;
+
+use super::Engine;
+
+/// Where a cohort's parent is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepHead {
+    /// The window's root, 0.
+    Root,
+    /// No parent.
+    None,
+    /// The cohort at this position in the window.
+    Cohort(u32),
+    /// A cohort with this global number, outside the window or gone.
+    Outside(u32),
+}
+
+/// One cohort of a [`DepTree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepNode {
+    pub cohort: CohortId,
+    /// The position in the window, from 1.
+    pub position: u32,
+    pub global_number: u32,
+    /// The wordform, without its `"<…>"`.
+    pub form: String,
+    pub head: DepHead,
+}
+
+/// What is wrong with a window's dependency tree.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DepIssue {
+    /// Not exactly one cohort is attached to the root or to nothing.
+    #[error("Dependency tree should have one root, has {}", list_roots(.roots))]
+    Roots { roots: Vec<u32> },
+    /// The cohorts of a cycle, from the first in the window.
+    #[error("Dependency cycle {}", list_cycle(.cohorts))]
+    Cycle { cohorts: Vec<u32> },
+    /// Two arcs cross, each given as child and parent.
+    #[error("Dependency {}->{} crosses {}->{}", .arc.0, .arc.1, .crossed.0, .crossed.1)]
+    NonProjective {
+        arc: (u32, u32),
+        crossed: (u32, u32),
+    },
+    /// The parent, a global number, is not in the window.
+    #[error("Cohort {cohort} has its dependency parent {parent} outside the window")]
+    OutsideWindow { cohort: u32, parent: u32 },
+}
+
+impl DepIssue {
+    /// A short stable name, for machine-readable output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DepIssue::Roots { .. } => "dep-tree-roots",
+            DepIssue::Cycle { .. } => "dep-tree-cycle",
+            DepIssue::NonProjective { .. } => "dep-tree-non-projective",
+            DepIssue::OutsideWindow { .. } => "dep-tree-outside-window",
+        }
+    }
+}
+
+fn list_roots(roots: &[u32]) -> String {
+    if roots.is_empty() {
+        return "none".to_string();
+    }
+    let list: Vec<String> = roots.iter().map(u32::to_string).collect();
+    format!("{}: {}", roots.len(), list.join(", "))
+}
+
+fn list_cycle(cohorts: &[u32]) -> String {
+    let list: Vec<String> = cohorts
+        .iter()
+        .chain(cohorts.first())
+        .map(u32::to_string)
+        .collect();
+    list.join("->")
+}
+
+/// One window's dependency tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepTree {
+    /// The window's number.
+    pub window: u32,
+    /// The window's cohorts, in order.
+    pub nodes: Vec<DepNode>,
+}
+
+impl DepTree {
+    /// The node at `position`.
+    pub fn node(&self, position: u32) -> Option<&DepNode> {
+        self.index(position).map(|i| &self.nodes[i])
+    }
+
+    fn index(&self, position: u32) -> Option<usize> {
+        self.nodes
+            .binary_search_by_key(&position, |n| n.position)
+            .ok()
+    }
+
+    /// The positions of the cohorts whose parent is `head`, 0 for the root.
+    pub fn children(&self, head: u32) -> impl Iterator<Item = u32> + '_ {
+        self.nodes
+            .iter()
+            .filter(move |n| match n.head {
+                DepHead::Root => head == 0,
+                DepHead::Cohort(p) => p == head,
+                _ => false,
+            })
+            .map(|n| n.position)
+    }
+
+    /// Everything wrong with the tree: its roots, then its cycles, arcs that
+    /// leave the window, and crossing arcs. An empty window has no issues.
+    pub fn issues(&self) -> Vec<DepIssue> {
+        let mut issues = Vec::new();
+        if self.nodes.is_empty() {
+            return issues;
+        }
+        let roots: Vec<u32> = self
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.head, DepHead::Root | DepHead::None))
+            .map(|n| n.position)
+            .collect();
+        if roots.len() != 1 {
+            issues.push(DepIssue::Roots { roots });
+        }
+        for cohorts in self.cycles() {
+            issues.push(DepIssue::Cycle { cohorts });
+        }
+        for n in &self.nodes {
+            if let DepHead::Outside(parent) = n.head {
+                issues.push(DepIssue::OutsideWindow {
+                    cohort: n.position,
+                    parent,
+                });
+            }
+        }
+        for (arc, crossed) in self.crossings() {
+            issues.push(DepIssue::NonProjective { arc, crossed });
+        }
+        issues
+    }
+
+    /// The cycles, each as the positions along it from its first in the
+    /// window.
+    fn cycles(&self) -> Vec<Vec<u32>> {
+        // 0 unseen, 1 on the path being walked, 2 done.
+        let mut state = vec![0u8; self.nodes.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.nodes.len() {
+            let mut path: Vec<usize> = Vec::new();
+            let mut cur = Some(start);
+            while let Some(i) = cur {
+                if state[i] != 0 {
+                    if state[i] == 1 {
+                        let from = path.iter().position(|&p| p == i).unwrap_or(0);
+                        let mut cycle: Vec<u32> = path[from..]
+                            .iter()
+                            .map(|&p| self.nodes[p].position)
+                            .collect();
+                        let first = (0..cycle.len()).min_by_key(|&k| cycle[k]).unwrap_or(0);
+                        cycle.rotate_left(first);
+                        cycles.push(cycle);
+                    }
+                    break;
+                }
+                state[i] = 1;
+                path.push(i);
+                cur = match self.nodes[i].head {
+                    DepHead::Cohort(p) => self.index(p),
+                    _ => None,
+                };
+            }
+            for p in path {
+                state[p] = 2;
+            }
+        }
+        cycles.sort();
+        cycles
+    }
+
+    /// The pairs of crossing arcs, each arc as child and parent.
+    fn crossings(&self) -> Vec<((u32, u32), (u32, u32))> {
+        let arcs: Vec<(u32, u32)> = self
+            .nodes
+            .iter()
+            .filter_map(|n| match n.head {
+                DepHead::Root => Some((n.position, 0)),
+                DepHead::Cohort(p) => Some((n.position, p)),
+                _ => None,
+            })
+            .collect();
+        let span = |(c, p): (u32, u32)| (c.min(p), c.max(p));
+        let mut out = Vec::new();
+        for (i, &a) in arcs.iter().enumerate() {
+            let (a_lo, a_hi) = span(a);
+            for &b in &arcs[i + 1..] {
+                let (b_lo, b_hi) = span(b);
+                if (a_lo < b_lo && b_lo < a_hi && a_hi < b_hi)
+                    || (b_lo < a_lo && a_lo < b_hi && b_hi < a_hi)
+                {
+                    out.push((a, b));
+                }
+            }
+        }
+        out
+    }
+
+    /// The tree as a Graphviz `digraph`, arcs pointing from parent to child.
+    /// Arcs in a cycle or crossing another are red; a parent outside the
+    /// window is drawn by its global number, its arc dashed.
+    pub fn to_dot(&self) -> String {
+        let mut flagged = Vec::new();
+        for issue in self.issues() {
+            match issue {
+                DepIssue::Cycle { cohorts } => flagged.extend(cohorts),
+                DepIssue::NonProjective { arc, crossed } => {
+                    flagged.extend([arc.0, crossed.0]);
+                }
+                _ => {}
+            }
+        }
+        let mut out = format!("digraph window{} {{\n", self.window);
+        out += &format!("\tlabel=\"window {}\";\n", self.window);
+        out += "\tn0 [label=\"0\" shape=plaintext];\n";
+        for n in &self.nodes {
+            out += &format!(
+                "\tn{} [label=\"{}: {}\"];\n",
+                n.position,
+                n.position,
+                dot_escape(&n.form)
+            );
+        }
+        let mut outside = BTreeMap::new();
+        for n in &self.nodes {
+            let red = if flagged.contains(&n.position) {
+                " [color=red]"
+            } else {
+                ""
+            };
+            match n.head {
+                DepHead::Root => out += &format!("\tn0 -> n{}{red};\n", n.position),
+                DepHead::Cohort(p) => out += &format!("\tn{p} -> n{}{red};\n", n.position),
+                DepHead::Outside(g) => {
+                    outside.insert(g, ());
+                    out += &format!("\tg{g} -> n{} [style=dashed];\n", n.position);
+                }
+                DepHead::None => {}
+            }
+        }
+        for g in outside.keys() {
+            out += &format!("\tg{g} [label=\"{g}\" shape=plaintext];\n");
+        }
+        out += "}\n";
+        out
+    }
+
+    /// The tree as JSON: `{"window":N,"tree":[...],"detached":[...]}`. Each
+    /// node is `{"id":P,"form":"...","head":H,"children":[...]}`, `head` 0 for
+    /// the root and `null` for none. `tree` holds the roots with everything
+    /// under them; `detached` the rest — what hangs from a parent outside the
+    /// window (that node also has `"outside":G`, the parent's global number)
+    /// or from a cycle, which is cut where it closes.
+    pub fn to_json(&self) -> Value {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut tree = Vec::new();
+        for (i, n) in self.nodes.iter().enumerate() {
+            if matches!(n.head, DepHead::Root | DepHead::None) {
+                tree.push(self.json_node(i, &mut seen));
+            }
+        }
+        let mut detached = Vec::new();
+        for (i, n) in self.nodes.iter().enumerate() {
+            if matches!(n.head, DepHead::Outside(_)) && !seen[i] {
+                detached.push(self.json_node(i, &mut seen));
+            }
+        }
+        for cycle in self.cycles() {
+            if let Some(i) = self.index(cycle[0])
+                && !seen[i]
+            {
+                detached.push(self.json_node(i, &mut seen));
+            }
+        }
+        json!({"window": self.window, "tree": tree, "detached": detached})
+    }
+
+    fn json_node(&self, i: usize, seen: &mut [bool]) -> Value {
+        seen[i] = true;
+        let n = &self.nodes[i];
+        let mut children = Vec::new();
+        for c in self.children(n.position).collect::<Vec<_>>() {
+            if let Some(ci) = self.index(c)
+                && !seen[ci]
+            {
+                children.push(self.json_node(ci, seen));
+            }
+        }
+        let head = match n.head {
+            DepHead::Root => json!(0),
+            DepHead::Cohort(p) => json!(p),
+            DepHead::None | DepHead::Outside(_) => Value::Null,
+        };
+        let mut node = json!({
+            "id": n.position,
+            "form": n.form,
+            "head": head,
+            "children": children,
+        });
+        if let DepHead::Outside(g) = n.head {
+            node["outside"] = json!(g);
+        }
+        node
+    }
+}
+
+pub(crate) fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Where the trees go ([`Diagnostics::dep_export`](super::Diagnostics::dep_export)).
+#[derive(Default)]
+pub struct DepTreeExport {
+    /// One `digraph` per window.
+    pub dot: Option<Box<dyn Write + Send>>,
+    /// One JSON tree per window, each on a line of its own.
+    pub json: Option<Box<dyn Write + Send>>,
+}
+
+impl DepTreeExport {
+    /// Write `tree` to each output.
+    pub fn write(&mut self, tree: &DepTree) -> std::io::Result<()> {
+        if let Some(dot) = &mut self.dot {
+            dot.write_all(tree.to_dot().as_bytes())?;
+        }
+        if let Some(json) = &mut self.json {
+            serde_json::to_writer(&mut *json, &tree.to_json())?;
+            json.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(dot) = &mut self.dot {
+            dot.flush()?;
+        }
+        if let Some(json) = &mut self.json {
+            json.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Engine<'_> {
+    /// The dependency tree of `window` as it stands.
+    pub fn dep_tree(&self, window: SwId) -> DepTree {
+        let sw = self.doc.store.single_windows.get(window.0);
+        let mut nodes = Vec::new();
+        for &cid in &sw.cohorts {
+            let c = self.doc.store.cohorts.get(cid.0);
+            if c.local_number == 0 || c.r#type.intersects(CT_REMOVED) {
+                continue;
+            }
+            let head = match c.dep_parent {
+                None => DepHead::None,
+                Some(GlobalNumber(0)) => DepHead::Root,
+                Some(gn) if gn == c.global_number => DepHead::None,
+                Some(gn) => match self.doc.cohorts.cohort_map.get(&gn) {
+                    Some(&p) => {
+                        let pc = self.doc.store.cohorts.get(p.0);
+                        if pc.parent != Some(window) || pc.r#type.intersects(CT_REMOVED) {
+                            DepHead::Outside(gn.get())
+                        } else if pc.local_number == 0 {
+                            DepHead::Root
+                        } else {
+                            DepHead::Cohort(pc.local_number)
+                        }
+                    }
+                    None => DepHead::Outside(gn.get()),
+                },
+            };
+            let form = c
+                .wordform
+                .map(|wf| strip_wordform(&self.grammar.single_tags_list.get(wf.0).tag).to_string())
+                .unwrap_or_default();
+            nodes.push(DepNode {
+                cohort: cid,
+                position: c.local_number,
+                global_number: c.global_number.get(),
+                form,
+                head,
+            });
+        }
+        nodes.sort_by_key(|n| n.position);
+        DepTree {
+            window: sw.number,
+            nodes,
+        }
+    }
+
+    /// Check and export `window`'s tree as configured; see the module doc.
+    pub(crate) fn finish_dep_tree(&mut self, window: SwId) -> Result<(), RunError> {
+        if !self.doc.deps.has_dep || (!self.cfg.dep_validate && self.diag.dep_export.is_none()) {
+            return Ok(());
+        }
+        let tree = self.dep_tree(window);
+        if self.cfg.dep_validate {
+            for issue in tree.issues() {
+                let cohort = match &issue {
+                    DepIssue::Roots { roots } => roots.first(),
+                    DepIssue::Cycle { cohorts } => cohorts.first(),
+                    DepIssue::NonProjective { arc, .. } => Some(&arc.0),
+                    DepIssue::OutsideWindow { cohort, .. } => Some(cohort),
+                }
+                .and_then(|&p| tree.node(p))
+                .or(tree.nodes.first())
+                .map(|n| n.cohort);
+                let window = tree.window;
+                self.note_after_rules(
+                    RuntimeDiagnosticKind::DependencyTree { window, issue },
+                    cohort,
+                );
+            }
+        }
+        if let Some(export) = &mut self.diag.dep_export {
+            export.write(&tree)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(heads: &[DepHead]) -> DepTree {
+        DepTree {
+            window: 1,
+            nodes: heads
+                .iter()
+                .enumerate()
+                .map(|(i, &head)| DepNode {
+                    cohort: CohortId(i as u32),
+                    position: i as u32 + 1,
+                    global_number: i as u32 + 1,
+                    form: format!("w{}", i + 1),
+                    head,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn issues_cover_roots_cycles_crossings_and_outside_parents() {
+        use DepHead::*;
+        assert!(tree(&[Cohort(2), Root, Cohort(2)]).issues().is_empty());
+
+        assert_eq!(
+            tree(&[Root, None, Cohort(2)]).issues(),
+            vec![DepIssue::Roots { roots: vec![1, 2] }]
+        );
+        assert_eq!(
+            tree(&[Root, Cohort(3), Cohort(2), Outside(9)]).issues(),
+            vec![
+                DepIssue::Cycle {
+                    cohorts: vec![2, 3]
+                },
+                DepIssue::OutsideWindow {
+                    cohort: 4,
+                    parent: 9
+                },
+            ]
+        );
+        assert_eq!(
+            tree(&[Cohort(3), Root, Cohort(2)]).issues(),
+            vec![DepIssue::NonProjective {
+                arc: (1, 3),
+                crossed: (2, 0)
+            }]
+        );
+        assert_eq!(
+            DepIssue::Cycle {
+                cohorts: vec![2, 3]
+            }
+            .to_string(),
+            "Dependency cycle 2->3->2"
+        );
+    }
+}
@@ -64,6 +64,8 @@
 //!     C++ flat_map iterates by `Tag::hash`. Re-derived here by hash-sorting the
 //!     entries (stable), matching `tag_trie::ordered_entries`.
 
+use std::ops::Range;
+
 use crate::arena::GenArena;
 use crate::arena::{CohortId, ReadingId, TagId};
 use crate::cohort;
@@ -84,7 +86,7 @@ use crate::tag::{
     T_VARIABLE, T_VARSTRING, T_WORDFORM, Tag, TagList, TagSortedVector,
 };
 use crate::tag_trie::{TagTrie, TrieNode};
-use crate::types::{SetNumber, TagHash, UString};
+use crate::types::{SetNumber, TagHash, UString, Uint32Vector};
 
 use super::{CohortMatchContext, Matcher, RegexGroups, UnifKey};
 
@@ -287,17 +289,6 @@ pub fn test_tag_numerical(
     m
 }
 
-/// `uextras.hpp` `ux_strCaseCompare(a, b)` (ICU `u_strCaseCompare` with
-/// `U_FOLD_CASE_DEFAULT`): true on full case-fold equality. Approximated with
-/// Unicode lowercase folding (ICU-vs-Rust parity risk for non-ASCII), mirroring
-/// the `tag.rs` stand-in. Deliberately un-annotated (its spec id belongs to the
-/// `uextras` port).
-fn ux_str_case_compare(a: &str, b: &str) -> bool {
-    a.chars()
-        .flat_map(char::to_lowercase)
-        .eq(b.chars().flat_map(char::to_lowercase))
-}
-
 /// Collect a `Uint32FlatHashMap`'s live `(key, value)` entries in physical slot
 /// order (the C++ flat_unordered_map iteration order, which `find_if` walks).
 /// Not a manifest symbol — port infra so the variable branch can iterate while
@@ -783,6 +774,35 @@ impl Matcher<'_> {
         reading: ReadingId,
         set_number: u32,
         unif_mode: bool,
+    ) -> Result<bool, crate::error::RunError> {
+        let mut plain = self.scratch.ss_u32v.get();
+        let mut entries = self.scratch.ss_trie_keys.get();
+        let mut path = self.scratch.ss_taglist.get();
+        let retval = self.does_set_match_reading_tags_with(
+            reading,
+            set_number,
+            unif_mode,
+            &mut plain,
+            &mut entries,
+            &mut path,
+        );
+        self.scratch.ss_u32v.put(plain);
+        self.scratch.ss_trie_keys.put(entries);
+        self.scratch.ss_taglist.put(path);
+        retval
+    }
+
+    /// Body of [`Self::does_set_match_reading_tags`] over caller-lent scratch
+    /// buffers (the reading's plain tags, the hash-sorted first trie level, and
+    /// the walk path), so a cache miss does not allocate. Not a manifest symbol.
+    fn does_set_match_reading_tags_with(
+        &mut self,
+        reading: ReadingId,
+        set_number: u32,
+        unif_mode: bool,
+        plain: &mut Uint32Vector,
+        entries: &mut Vec<(TagId, u32)>,
+        path: &mut TagList,
     ) -> Result<bool, crate::error::RunError> {
         let mut retval = false;
 
@@ -806,33 +826,29 @@ impl Matcher<'_> {
         // first-level keys (both ascending by hash). `entries` is snapshotted with
         // a short borrow; node flags are re-read fresh per hit (never held across a
         // `&mut self` re-entry). `path` is the root-to-node key of the [`UnifKey`].
-        let plain: Vec<u32> = self.readings.get(reading.0).tags_plain.as_slice().to_vec();
-        let entries: Vec<(TagId, u32)> = {
-            match self.trie_level_at(set_number, false, &[]) {
-                Some(t) if !plain.is_empty() => {
-                    let mut e: Vec<(TagId, u32)> = t
-                        .keys()
-                        .map(|k| (*k, self.grammar.single_tags_list[k.0].hash.get()))
-                        .collect();
-                    e.sort_by_key(|x| x.1);
-                    e
-                }
-                _ => Vec::new(),
-            }
-        };
+        plain.extend_from_slice(self.readings.get(reading.0).tags_plain.as_slice());
+        if let Some(t) = self.trie_level_at(set_number, false, &[])
+            && !plain.is_empty()
+        {
+            entries.extend(
+                t.keys()
+                    .map(|k| (*k, self.grammar.single_tags_list[k.0].hash.get())),
+            );
+            // Keys arrive in TagId order; (hash, TagId) is the stable hash sort.
+            entries.sort_unstable_by_key(|x| (x.1, x.0));
+        }
         if !entries.is_empty() {
             let front_hash = plain[0]; // tags_plain.front() (smallest)
             let smallest_trie_hash = entries[0].1; // trie.begin()->first->hash
             let mut oi = plain.partition_point(|&x| x < smallest_trie_hash);
             let mut ii = entries.partition_point(|e| e.1 < front_hash);
-            let mut path: Vec<TagId> = Vec::new();
             while oi < plain.len() && ii < entries.len() {
                 if plain[oi] == entries[ii].1 {
                     let tid = entries[ii].0;
                     path.clear();
                     path.push(tid);
                     let (terminal, has_child) = {
-                        let n = self.trie_node_at(set_number, false, &path).unwrap();
+                        let n = self.trie_node_at(set_number, false, path).unwrap();
                         (n.terminal, n.trie.is_some())
                     };
                     if terminal {
@@ -851,7 +867,7 @@ impl Matcher<'_> {
                     }
                     if has_child
                         && self.does_set_match_reading_trie(
-                            reading, set_number, set_number, false, &mut path, unif_mode,
+                            reading, set_number, set_number, false, path, unif_mode,
                         )?
                     {
                         retval = true;
@@ -875,9 +891,9 @@ impl Matcher<'_> {
                 .trie_special
                 .is_empty();
             if !has_special {
-                let mut path: Vec<TagId> = Vec::new();
+                path.clear();
                 retval = self.does_set_match_reading_trie(
-                    reading, set_number, set_number, true, &mut path, unif_mode,
+                    reading, set_number, set_number, true, path, unif_mode,
                 )?;
             }
         }
@@ -978,25 +994,29 @@ impl Matcher<'_> {
             }
         } else {
             // (d) SET set: apply operators (non-OR binds tighter than OR)
-            let ssets = self.grammar.set_by_number(SetNumber(set)).sets.clone();
-            let sset_ops = self.grammar.set_by_number(SetNumber(set)).set_ops.clone();
-            let size = ssets.len();
+            // Members and operators are read by position rather than cloned:
+            // nothing below can change a compiled set.
+            let member =
+                |g: &crate::grammar::Grammar, k: usize| g.set_by_number(SetNumber(set)).sets[k];
+            let op =
+                |g: &crate::grammar::Grammar, k: usize| g.set_by_number(SetNumber(set)).set_ops[k];
+            let size = self.grammar.set_by_number(SetNumber(set)).sets.len();
             let mut i = 0usize;
             while i < size {
                 let mut m = self.does_set_match_reading(
                     reading,
-                    ssets[i],
+                    member(self.grammar, i),
                     bypass_index,
                     tagunif || unif_mode,
                 )?;
                 let mut failfast = false;
-                while i < size - 1 && sset_ops[i] != S_OR {
-                    match sset_ops[i] {
+                while i < size - 1 && op(self.grammar, i) != S_OR {
+                    match op(self.grammar, i) {
                         x if x == S_PLUS => {
                             if m {
                                 m = self.does_set_match_reading(
                                     reading,
-                                    ssets[i + 1],
+                                    member(self.grammar, i + 1),
                                     bypass_index,
                                     tagunif || unif_mode,
                                 )?;
@@ -1005,7 +1025,7 @@ impl Matcher<'_> {
                         x if x == S_FAILFAST => {
                             if self.does_set_match_reading(
                                 reading,
-                                ssets[i + 1],
+                                member(self.grammar, i + 1),
                                 bypass_index,
                                 tagunif || unif_mode,
                             )? {
@@ -1016,7 +1036,7 @@ impl Matcher<'_> {
                         x if x == S_MINUS => {
                             if m && self.does_set_match_reading(
                                 reading,
-                                ssets[i + 1],
+                                member(self.grammar, i + 1),
                                 bypass_index,
                                 tagunif || unif_mode,
                             )? {
@@ -1046,6 +1066,7 @@ impl Matcher<'_> {
                     .unwrap()
                     .unif_tags
                     .unwrap();
+                let ssets = &self.grammar.set_by_number(SetNumber(set)).sets;
                 let ut = &mut self.scratch.unif_tags_store[ut_idx];
                 let mut tag: Option<UnifKey> = None;
                 for &s in ssets.iter().take(size) {
@@ -1327,14 +1348,15 @@ impl Matcher<'_> {
         }
 
         // 4-slot list array (readings; plus deleted/delayed/ignored per options).
-        let lists = self.gather_lists(cohort, context.as_deref());
+        let (ids, lists) = self.gather_lists(cohort, context.as_deref());
 
-        for slot in lists.into_iter() {
+        let mut done = false;
+        'outer: for slot in lists.into_iter() {
             let list = match slot {
-                Some(l) => l,
+                Some(l) => &ids[l],
                 None => continue,
             };
-            for reading_head in list {
+            for &reading_head in list {
                 let mut reading = reading_head;
                 if let Some(ctx) = context.as_deref()
                     && let Some(test) = ctx.test
@@ -1385,10 +1407,15 @@ impl Matcher<'_> {
                 };
                 let did_test = context.as_deref().map(|c| c.did_test).unwrap_or(false);
                 if retval && (context.is_none() || !has_linked || did_test) {
-                    return Ok(retval);
+                    done = true;
+                    break 'outer;
                 }
             }
         }
+        self.scratch.ss_rids.put(ids);
+        if done {
+            return Ok(retval);
+        }
 
         // POS_NOT: run the linked test even though nothing matched.
         let do_tl = context
@@ -1436,14 +1463,14 @@ impl Matcher<'_> {
             }
         }
 
-        let lists = self.gather_lists(cohort, context.as_deref());
+        let (ids, lists) = self.gather_lists(cohort, context.as_deref());
 
         'outer: for slot in lists.into_iter() {
             let list = match slot {
-                Some(l) => l,
+                Some(l) => &ids[l],
                 None => continue,
             };
-            for reading0 in list {
+            for &reading0 in list {
                 let mut reading = reading0;
                 if let Some(ctx) = context.as_deref()
                     && let Some(test) = ctx.test
@@ -1477,6 +1504,7 @@ impl Matcher<'_> {
                 break 'outer;
             }
         }
+        self.scratch.ss_rids.put(ids);
 
         let do_tl = context
             .as_deref()
@@ -1492,28 +1520,36 @@ impl Matcher<'_> {
 
     /// Builds the C++ `ReadingList* lists[4]` array: slot 0 = `cohort.readings`;
     /// slots 1..3 = `deleted`/`delayed`/`ignored` only when the corresponding
-    /// POS_LOOK_* option is set (and a context is present). The id lists are cloned
-    /// so the `cohorts` arena is not borrowed across the matcher recursion. Not a
-    /// manifest symbol — shared setup for the two cohort matchers.
+    /// POS_LOOK_* option is set (and a context is present). The ids are copied
+    /// into one `ss_rids` buffer, each slot a range of it, so the `cohorts` arena
+    /// is not borrowed across the matcher recursion; callers `put` the buffer
+    /// back. Not a manifest symbol — shared setup for the two cohort matchers.
     fn gather_lists(
-        &self,
+        &mut self,
         cohort: CohortId,
         context: Option<&CohortMatchContext>,
-    ) -> [Option<Vec<ReadingId>>; 4] {
+    ) -> (Vec<ReadingId>, [Option<Range<usize>>; 4]) {
+        let mut ids = self.scratch.ss_rids.get();
         let c = self.cohorts.get(cohort.0);
-        let mut lists: [Option<Vec<ReadingId>>; 4] = [Some(c.readings.clone()), None, None, None];
+        let mut slots: [Option<Range<usize>>; 4] = [None, None, None, None];
+        let mut push = |i: usize, l: &[ReadingId]| {
+            let start = ids.len();
+            ids.extend_from_slice(l);
+            slots[i] = Some(start..ids.len());
+        };
+        push(0, &c.readings);
         if let Some(ctx) = context {
             if ctx.options.intersects(POS_LOOK_DELETED) {
-                lists[1] = Some(c.deleted.clone());
+                push(1, &c.deleted);
             }
             if ctx.options.intersects(POS_LOOK_DELAYED) {
-                lists[2] = Some(c.delayed.clone());
+                push(2, &c.delayed);
             }
             if ctx.options.intersects(POS_LOOK_IGNORED) {
-                lists[3] = Some(c.ignored.clone());
+                push(3, &c.ignored);
             }
         }
-        lists
+        (ids, slots)
     }
 
     // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator.does-tag-match-regexp-fn]
@@ -1597,7 +1633,7 @@ impl Matcher<'_> {
                 let t = &self.grammar.single_tags_list[tid.0];
                 (t.hash.get(), t.tag.clone())
             };
-            if ux_str_case_compare(&tag.tag, &itag_text) {
+            if self.grammar.case_locale.caseless_eq(&tag.tag, &itag_text) {
                 m = itag_hash;
             }
             if m != 0 {
@@ -16,6 +16,8 @@
 //! | [`match_set`]           | `GrammarApplicator_matchSet.cpp`        |
 //! | [`reflow`]              | `GrammarApplicator_reflow.cpp`          |
 //! | [`context`]             | `GrammarApplicator_context.cpp`         |
+//! | [`pipeline`]            | — (added: in-process grammar pipeline)   |
+//! | [`reload`]              | — (added: grammar swapped mid-stream)    |
 //!
 //! ARENA MODEL. C++ raw pointers become arena ids: `Tag*`→[`TagId`],
 //! `Set*`→[`SetId`], `Rule*`→[`RuleId`], `ContextualTest*`→[`CtxId`],
@@ -36,6 +38,7 @@ use crate::cohort_iterator::{
     CohortIterator, DepAncestorIter, DepDescendentIter, DepParentIter, TopologyLeftIter,
     TopologyRightIter,
 };
+use crate::flat_map::FlatMap;
 use crate::flat_unordered_map::Uint32FlatHashMap;
 use crate::flat_unordered_set::{Uint32FlatHashSet, Uint64FlatHashSet};
 use crate::interval_vector::Uint32IntervalVector;
@@ -47,12 +50,17 @@ use crate::types::{TagHash, UChar, UString, Uint32Vector};
 
 pub mod context;
 pub mod core;
+pub mod dep_tree;
 pub mod match_set;
+pub mod pipeline;
 pub mod reflow;
+pub mod relation_graph;
+pub mod reload;
 pub mod run_contextual_test;
 pub mod run_grammar;
 pub mod run_rules;
 pub mod stream_format;
+pub mod weights;
 
 /// C++ `cg3.h` `enum cg3_sformat` — the stream serialisation format tag used
 /// by `fmt_input` / `fmt_output`; the variants camel-case the C++ `CG3SF_*`
@@ -73,6 +81,9 @@ pub enum StreamFormatKind {
     Plain = 6,
     Jsonl = 7,
     Binary = 8,
+    /// ADDED — no C++ analog: grammar-checker errors as JSON, output only
+    /// ([`crate::errors_format`]).
+    Errors = 9,
 }
 
 // [spec:cg3:def:grammar-applicator.cg3.regexgrps-t]
@@ -179,7 +190,7 @@ pub struct ReadingSpec {
 /// `regexgrps` pointers alias into the applicator's `*_store` vectors; wave 4
 /// replaces the raw aliasing pointers with plain store INDICES (safe across
 /// `Vec` reallocation).
-#[derive(Default, Clone)]
+#[derive(Default)]
 pub struct RuleContext {
     pub target: ReadingSpec,
     /// C++ `std::vector<Cohort*> context` — positions may be null.
@@ -198,6 +209,29 @@ pub struct RuleContext {
     pub is_with: bool,
 }
 
+// Hand-written so `clone_from` reuses the destination's `context` buffers: the
+// rule loop snapshots a frame per matched reading into recycled frames.
+impl Clone for RuleContext {
+    fn clone(&self) -> Self {
+        let mut c = RuleContext::default();
+        c.clone_from(self);
+        c
+    }
+
+    fn clone_from(&mut self, o: &Self) {
+        self.target = o.target.clone();
+        self.context.clone_from(&o.context);
+        self.dep_context.clone_from(&o.dep_context);
+        self.attach_to = o.attach_to.clone();
+        self.mark = o.mark;
+        self.unif_tags = o.unif_tags;
+        self.unif_sets = o.unif_sets;
+        self.regexgrp_ct = o.regexgrp_ct;
+        self.regexgrps = o.regexgrps;
+        self.is_with = o.is_with;
+    }
+}
+
 // C++ `typedef std::function<void(void)> RuleCallback` (spec
 // `grammar-applicator.cg3.rule-callback`) — the reading/cohort callbacks handed
 // to `runSingleRule`. DISSOLVED in the port: the only two callbacks ever
@@ -223,7 +257,7 @@ pub type Externals = BTreeMap<u32, Process>;
 
 // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator.readings-plain-t]
 /// C++ `typedef bc::flat_map<uint32_t, Reading*> readings_plain_t`.
-pub type ReadingsPlain = BTreeMap<u32, ReadingId>;
+pub type ReadingsPlain = FlatMap<u32, ReadingId>;
 
 // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator.st-retvals]
 // C++ `enum ST_RETVALS { … }` — bit flags OR-ed into the `uint8_t& rvs`
@@ -235,12 +269,8 @@ pub const TRV_BREAK_DEFAULT: u8 = 1 << 2;
 
 // Port-infra: the `scoped_stack<C>` fields require `C: Poolable` to construct
 // (the proxy `clear()`s its slot on release). These concrete element types are
-// only ever pooled from this engine, so their `clear` impls live here.
-impl crate::pool::Poolable for TagList {
-    fn clear(&mut self) {
-        Vec::clear(self);
-    }
-}
+// only ever pooled from this engine, so their `clear` impls live here
+// (`TagList` and the other plain `Vec`s use the blanket impl in `pool.rs`).
 impl crate::pool::Poolable for UnifTags {
     fn clear(&mut self) {
         BTreeMap::clear(self);
@@ -256,6 +286,21 @@ impl crate::pool::Poolable for Uint32SortedVector {
         Uint32SortedVector::clear(self);
     }
 }
+// A blank frame with its `context`/`dep_context` buffers kept.
+impl crate::pool::Poolable for RuleContext {
+    fn clear(&mut self) {
+        self.target = ReadingSpec::default();
+        self.context.clear();
+        self.dep_context.clear();
+        self.attach_to = ReadingSpec::default();
+        self.mark = None;
+        self.unif_tags = None;
+        self.unif_sets = None;
+        self.regexgrp_ct = 0;
+        self.regexgrps = None;
+        self.is_with = false;
+    }
+}
 
 // [spec:cg3:def:grammar-applicator.cg3.grammar-applicator+4]
 /// The options-derived, setup-written, run-read-only configuration extracted
@@ -341,6 +386,20 @@ pub struct EngineConfig {
     /// Defaults to [`STDIN_SOURCE_NAME`], which is the truth for a stream with
     /// no file behind it.
     pub input_name: String,
+    /// ADDED — no C++ analog. Stop at the first input line a reader cannot
+    /// use, rather than note it and read on (`--strict-input`).
+    pub strict_input: bool,
+
+    /// ADDED — no C++ analog. The name of the numeric tag that holds a
+    /// reading's weight (`W`, for `<W:0.5>`); see [`weights`].
+    pub weight_tag: UString,
+    /// ADDED — no C++ analog. Which readings to prune by weight once the rules
+    /// are done with a window; nothing, by default.
+    pub weight_pruning: weights::WeightPruning,
+    /// ADDED — no C++ analog. Check each window's dependency tree once the
+    /// rules are done with it, and note what is wrong (`--dep-validate`); see
+    /// [`dep_tree`].
+    pub dep_validate: bool,
 }
 
 /// What a runtime diagnostic calls an input stream with no file behind it.
@@ -414,6 +473,11 @@ impl EngineConfig {
 
             text_delimiters: Default::default(),
             input_name: STDIN_SOURCE_NAME.to_string(),
+            strict_input: false,
+
+            weight_tag: "W".to_string(),
+            weight_pruning: Default::default(),
+            dep_validate: false,
         }
     }
 }
@@ -542,10 +606,10 @@ pub struct RuleScratch {
 
     pub regexgrps_store: Vec<RegexGroups>,
     /// C++ `bc::flat_map<uint32_t, uint8_t> regexgrps_z`.
-    pub regexgrps_z: BTreeMap<u32, u8>,
+    pub regexgrps_z: FlatMap<u32, u8>,
     /// C++ `bc::flat_map<uint32_t, regexgrps_t*> regexgrps_c` — values are
     /// indices into `regexgrps_store`.
-    pub regexgrps_c: BTreeMap<u32, usize>,
+    pub regexgrps_c: FlatMap<u32, usize>,
     pub same_basic: u32,
     pub rule_target: Option<CohortId>,
     pub merge_with: Option<CohortId>,
@@ -582,11 +646,11 @@ pub struct RuleScratch {
 
     /// C++ `bc::flat_map<uint32_t, unif_tags_t*> unif_tags_rs` — values are
     /// indices into `unif_tags_store`.
-    pub unif_tags_rs: BTreeMap<u32, usize>,
+    pub unif_tags_rs: FlatMap<u32, usize>,
     pub unif_tags_store: Vec<UnifTags>,
     /// C++ `bc::flat_map<uint32_t, unif_sets_t*> unif_sets_rs` — values are
     /// indices into `unif_sets_store`.
-    pub unif_sets_rs: BTreeMap<u32, usize>,
+    pub unif_sets_rs: FlatMap<u32, usize>,
     pub unif_sets_store: Vec<UnifSets>,
     pub unif_last_wordform: TagHash,
     pub unif_last_baseform: TagHash,
@@ -594,9 +658,34 @@ pub struct RuleScratch {
     /// C++ `bc::flat_map<uint32_t, uint32_t> rule_hits`.
     pub rule_hits: BTreeMap<u32, u32>,
 
+    pub ss_taglist: ScopedStack<TagList>,
     pub ss_utags: ScopedStack<UnifTags>,
     pub ss_usets: ScopedStack<UnifSets>,
     pub ss_u32sv: ScopedStack<Uint32SortedVector>,
+    /// Recycled member lists of `rules_by_tag`/`rules_by_set` intervals, walked
+    /// while `update_rule_to_cohorts` mutates the engine.
+    pub ss_u32v: ScopedStack<Uint32Vector>,
+    /// Recycled `(key, hash)` snapshots of a trie level, hash-sorted, for the
+    /// LIST-set matcher's merge walk.
+    pub ss_trie_keys: ScopedStack<Vec<(TagId, u32)>>,
+    /// `mergeReadings` working maps (`mapped`, and `mlist` flattened to
+    /// `(key, arrival, reading)` triples).
+    pub ss_merge_mapped: ScopedStack<FlatMap<u32, (u32, ReadingId)>>,
+    pub ss_merge_mlist: ScopedStack<Vec<(u32, usize, ReadingId)>>,
+    /// Recycled `context_stack` frames and per-reading frame snapshots (the
+    /// C++ kept both on the stack; here they are heap `Vec`s worth reusing).
+    pub ss_ctx: ScopedStack<RuleContext>,
+    /// Recycled `reading_contexts` lists of `run_single_rule_body`.
+    pub ss_ctx_list: ScopedStack<Vec<RuleContext>>,
+    /// Recycled copies of a rule's test list (the loop may reorder the rule's
+    /// own list while walking it).
+    pub ss_ctx_ids: ScopedStack<Vec<CtxId>>,
+    /// Recycled reading-id snapshots of a cohort's lists for the cohort
+    /// matchers (`gather_lists`).
+    pub ss_rids: ScopedStack<Vec<ReadingId>>,
+    /// Spare `runRulesOnSingleWindow` states, reused across windows and
+    /// sections (kept as a stack so a nested call never shares one).
+    pub(crate) spare_rr_states: Vec<run_rules::RRState>,
 
     pub index_regexp_yes: Uint64FlatHashSet,
     pub index_regexp_no: Uint64FlatHashSet,
@@ -669,9 +758,19 @@ impl RuleScratch {
             unif_last_textual: TagHash(0),
             rule_hits: Default::default(),
 
+            ss_taglist: ScopedStack::new(),
             ss_utags: ScopedStack::new(),
             ss_usets: ScopedStack::new(),
             ss_u32sv: ScopedStack::new(),
+            ss_u32v: ScopedStack::new(),
+            ss_trie_keys: ScopedStack::new(),
+            ss_merge_mapped: ScopedStack::new(),
+            ss_merge_mlist: ScopedStack::new(),
+            ss_ctx: ScopedStack::new(),
+            ss_ctx_list: ScopedStack::new(),
+            ss_ctx_ids: ScopedStack::new(),
+            ss_rids: ScopedStack::new(),
+            spare_rr_states: Vec::new(),
 
             index_regexp_yes: Default::default(),
             index_regexp_no: Default::default(),
@@ -690,6 +789,27 @@ impl RuleScratch {
         }
     }
 
+    /// Pops the innermost `context_stack` frame into the `ss_ctx` spares.
+    pub(crate) fn pop_context(&mut self) {
+        if let Some(ctx) = self.context_stack.pop() {
+            self.ss_ctx.put(ctx);
+        }
+    }
+
+    /// A copy of the innermost `context_stack` frame in a recycled frame.
+    pub(crate) fn snapshot_context(&mut self) -> RuleContext {
+        let mut ctx = self.ss_ctx.get();
+        ctx.clone_from(self.context_stack.last().unwrap());
+        ctx
+    }
+
+    /// Empties a list of frame snapshots into the `ss_ctx` spares.
+    pub(crate) fn recycle_contexts(&mut self, list: &mut Vec<RuleContext>) {
+        for ctx in list.drain(..) {
+            self.ss_ctx.put(ctx);
+        }
+    }
+
     /// The C++ `reading.matched_target = v` bitfield store: `true` inserts,
     /// `false` removes (so a stale membership is overwritten either way).
     pub(crate) fn set_matched_target(&mut self, id: ReadingId, v: bool) {
@@ -739,11 +859,28 @@ pub struct Diagnostics {
     /// OWNED `Option<Profiler>`: the driver (vislcg3) moves the profiler in
     /// before the run and takes it back out afterwards to write the database.
     pub profiler: Option<crate::profiler::Profiler>,
+    /// ADDED — no C++ analog: the warnings raised so far, as values (see
+    /// [`crate::runtime_diagnostics`]).
+    pub runtime: crate::runtime_diagnostics::RuntimeLog,
+    /// ADDED — no C++ analog: where each window's dependency tree is written
+    /// when it is printed (see [`dep_tree`]). As with the
+    /// profiler, the driver moves it in before the run and takes it back out
+    /// to flush it.
+    pub dep_export: Option<dep_tree::DepTreeExport>,
+    /// ADDED — no C++ analog: where each window's relations are written, or
+    /// kept, when it is printed (see [`relation_graph`]); moved in and out as
+    /// [`dep_export`](Self::dep_export) is.
+    pub relation_export: Option<relation_graph::RelationExport>,
 }
 
 impl Diagnostics {
     pub fn new() -> Self {
-        Diagnostics { profiler: None }
+        Diagnostics {
+            profiler: None,
+            runtime: Default::default(),
+            dep_export: None,
+            relation_export: None,
+        }
     }
 }
 
@@ -759,7 +896,9 @@ impl Default for Diagnostics {
 /// exactly five subsystems — the options-derived [`EngineConfig`] (`cfg`), the
 /// run-mutable document-lifetime [`Document`] (`doc`), the per-rule transient
 /// [`RuleScratch`] (`scratch`), the profiler/stats [`Diagnostics`] (`diag`), and
-/// the owned [`Grammar`](crate::grammar::Grammar) (`grammar`).
+/// the owned [`Grammar`](crate::grammar::Grammar) (`grammar`). The port-only
+/// `stages` list holds the grammars of an in-process pipeline that run after
+/// this one on every window (see [`pipeline`]).
 pub struct GrammarApplicator {
     /// The options-derived, setup-written configuration (Stage-B re-homing of the
     /// cfg-bucket members; see [`EngineConfig`]).
@@ -779,6 +918,17 @@ pub struct GrammarApplicator {
 
     /// C++ `const Grammar* grammar` — the applicator OWNS the loaded grammar.
     pub grammar: crate::grammar::Grammar,
+
+    /// ADDED — no C++ analog. The later grammars of an in-process pipeline,
+    /// applied in order to each window after `grammar` (see
+    /// [`add_pipeline_stage`](Self::add_pipeline_stage)). Empty for the
+    /// ordinary one-grammar run.
+    pub stages: Vec<pipeline::PipelineStage>,
+
+    /// ADDED — no C++ analog. Where a replacement for `grammar` is picked up
+    /// between windows, once [`attach_grammar_swap`](Self::attach_grammar_swap)
+    /// has been called (see [`reload`]).
+    pub hot: Option<reload::HotGrammar>,
 }
 
 impl GrammarApplicator {
@@ -797,6 +947,10 @@ impl GrammarApplicator {
             diag: Diagnostics::new(),
 
             grammar,
+
+            stages: Vec::new(),
+
+            hot: None,
         }
     }
 
@@ -814,6 +968,8 @@ impl GrammarApplicator {
             scratch: &mut self.scratch,
             diag: &mut self.diag,
             grammar: &mut self.grammar,
+            stages: &mut self.stages,
+            hot: self.hot.as_mut(),
         }
     }
 }
@@ -837,6 +993,12 @@ pub struct Engine<'a> {
     /// `generate_varstring_tag`. Held `&mut` so that single write path can
     /// intern into the tag arenas; every other peeled method only reads it.
     pub grammar: &'a mut crate::grammar::Grammar,
+    /// The pipeline grammars that run on each window after `grammar`
+    /// ([`GrammarApplicator::stages`]); empty in a stage's own view.
+    pub stages: &'a mut [pipeline::PipelineStage],
+    /// The grammar swapped in for `grammar` ([`GrammarApplicator::hot`]);
+    /// `None` in a stage's own view.
+    pub hot: Option<&'a mut reload::HotGrammar>,
 }
 
 /// Split-borrow sub-view of [`Engine`] for the predicate/test tree — the
@@ -891,6 +1053,8 @@ pub struct Matcher<'a> {
     /// Declared match state: captures, unification, memo indexes, the
     /// matched-flag sets, iterator pools, the context stack.
     pub scratch: &'a mut RuleScratch,
+    /// `diag.runtime` — where the tests record the warnings they raise.
+    pub runtime: &'a mut crate::runtime_diagnostics::RuntimeLog,
     /// Tag interning (append-only), per the [`Engine::grammar`] convention;
     /// also the `POS_TMPL_OVERRIDE` save/restore on `contexts_arena`.
     pub grammar: &'a mut crate::grammar::Grammar,
@@ -913,6 +1077,7 @@ impl Engine<'_> {
             cohorts,
             readings,
             single_windows,
+            ..
         } = store;
         Matcher {
             cfg: self.cfg,
@@ -924,6 +1089,7 @@ impl Engine<'_> {
             variables,
             num_lines,
             scratch: self.scratch,
+            runtime: &mut self.diag.runtime,
             grammar: self.grammar,
         }
     }
@@ -1033,6 +1199,15 @@ impl Engine<'_> {
         self.matcher().get_tag_list_of_set(set, unif_mode)
     }
 
+    /// [`Self::get_tag_list_of_set`] into a recycled `ss_taglist` list; the
+    /// caller `put`s it back.
+    pub(crate) fn get_scratch_tag_list_of_set(&mut self, set: SetId, unif_mode: bool) -> TagList {
+        let mut the_tags = self.scratch.ss_taglist.get();
+        let m = self.matcher();
+        m.get_tag_list(&m.grammar.sets_list[set.0], &mut the_tags, unif_mode);
+        the_tags
+    }
+
     pub(crate) fn get_tag_list_of_set_number(&mut self, number: u32, unif_mode: bool) -> TagList {
         self.matcher().get_tag_list_of_set_number(number, unif_mode)
     }
@@ -0,0 +1,302 @@
+//! ADDED — no C++ analog. The in-process grammar pipeline: several grammars
+//! applied in sequence to every window of one stream, as `vislcg3 -g a.cg3 -g
+//! b.cg3` or [`GrammarApplicator::add_pipeline_stage`].
+//!
+//! Upstream a pipeline is a chain of processes, each re-parsing the CG text
+//! the previous one printed. Here the first grammar's applicator reads the
+//! stream and owns the [`Document`](super::Document); once it has finished a
+//! window, each stage runs its own rule passes over that same window in the
+//! shared store, and only then does the window move on towards output.
+//!
+//! The store is shared but the grammars are not, and a few runtime fields name
+//! grammar-owned objects rather than hashes: `Cohort::wordform`,
+//! `Reading::mapping`, the `tags_numerical` values and `possible_sets`. Before a
+//! stage runs, the window it runs on is *adopted* into the stage's grammar:
+//! tags it has not seen are interned by text, exactly as the stream reader
+//! interns input tags, and each reading is reflowed so its derived indexes
+//! point into the new grammar. A stage whose tests can look past their window
+//! (a spanning or relation test, or `--always-span`) adopts every window in
+//! the stream instead, which costs a reflow of each per stage and window. Each
+//! window records the grammar it is in (`SingleWindow::pipeline_home`), and
+//! after the last stage those that left the first grammar are adopted back by
+//! it, which reads the rest of the input and prints.
+//!
+//! What follows the first grammar rather than each stage:
+//! * windowing — input delimiters, soft/hard limits and `--num-windows` (a
+//!   stage can still split its window with its own DELIMITERS);
+//! * the output, and so the grammar that `--trace` rule numbers would refer
+//!   to (the CLI refuses `--trace` and `--profile` with several grammars);
+//! * timing: a stage's contextual tests reach into the following windows
+//!   before earlier grammars have run on them, where a process pipeline would
+//!   have shown them already processed.
+
+use crate::arena::{CohortId, ReadingId, SwId, TagId};
+use crate::contextual_test::{POS_RELATION, POS_SPAN_BOTH, POS_SPAN_LEFT, POS_SPAN_RIGHT};
+use crate::error::RunError;
+use crate::grammar::Grammar;
+
+use super::{Engine, EngineConfig, GrammarApplicator, RuleScratch};
+
+/// One later grammar of a pipeline: the parts of a [`GrammarApplicator`] that
+/// belong to its grammar. The document, and with it the stream, stays with the
+/// applicator the stage was added to.
+pub struct PipelineStage {
+    pub cfg: EngineConfig,
+    pub scratch: RuleScratch,
+    pub grammar: Grammar,
+    /// Whether the grammar's tests can look past the window they run on, so
+    /// that the stage needs every window of the stream in its terms.
+    pub spans: bool,
+}
+
+impl GrammarApplicator {
+    /// Appends `stage`'s grammar to the pipeline: it will run on each window
+    /// after this applicator's grammar and every stage added before it.
+    ///
+    /// `stage` is set up like any applicator (`set_grammar`, then
+    /// `set_options`); it is indexed here, and its document is dropped. A
+    /// stage that parses dependencies turns dependency parsing on for the
+    /// stream, since only the first grammar reads the input.
+    pub fn add_pipeline_stage(&mut self, stage: GrammarApplicator) {
+        let stage = PipelineStage::from_applicator(stage);
+        self.cfg.parse_dep |= stage.cfg.parse_dep;
+        self.stages.push(stage);
+    }
+}
+
+impl PipelineStage {
+    /// Index `applicator` and keep the parts that belong to its grammar.
+    pub(crate) fn from_applicator(mut applicator: GrammarApplicator) -> Self {
+        applicator.index();
+        let spans = applicator.cfg.always_span || spans(&applicator.grammar);
+        PipelineStage {
+            cfg: std::mem::take(&mut applicator.cfg),
+            scratch: std::mem::take(&mut applicator.scratch),
+            grammar: std::mem::take(&mut applicator.grammar),
+            spans,
+        }
+    }
+}
+
+/// Whether any test of `grammar` may reach into another window: one that
+/// spans, or one that follows relations.
+fn spans(grammar: &Grammar) -> bool {
+    (0..grammar.contexts_arena.capacity())
+        .filter_map(|i| grammar.contexts_arena.try_get(i))
+        .any(|t| {
+            t.pos
+                .intersects(POS_SPAN_BOTH | POS_SPAN_LEFT | POS_SPAN_RIGHT | POS_RELATION)
+        })
+}
+
+/// The id `grammar` interned the tag with hash `hash` under, if any.
+fn tag_by_hash(grammar: &Grammar, hash: u32) -> Option<TagId> {
+    let it = grammar.single_tags.find(hash);
+    (it != grammar.single_tags.end()).then(|| it.get().1)
+}
+
+impl Engine<'_> {
+    /// Runs every pipeline stage on the current window, then hands the stream
+    /// back to this view's grammar.
+    pub(crate) fn run_pipeline_stages<F, W>(
+        &mut self,
+        fmt: &mut F,
+        output: &mut W,
+    ) -> Result<(), RunError>
+    where
+        F: super::stream_format::StreamFormat,
+        W: std::io::Write,
+    {
+        let stages = std::mem::take(&mut self.stages);
+        let rv = self.run_stages(stages, fmt, output);
+        self.stages = stages;
+        rv
+    }
+
+    pub(super) fn run_stages<F, W>(
+        &mut self,
+        stages: &mut [PipelineStage],
+        fmt: &mut F,
+        output: &mut W,
+    ) -> Result<(), RunError>
+    where
+        F: super::stream_format::StreamFormat,
+        W: std::io::Write,
+    {
+        for i in 0..stages.len() {
+            let (done, rest) = stages.split_at_mut(i);
+            let stage = &mut rest[0];
+            // Retired windows are printed by the first grammar only; a stage
+            // keeping fewer would print them mid-pipeline.
+            stage.cfg.num_windows = self.cfg.num_windows;
+            // The grammars a window may be in, by `pipeline_home`.
+            let homes: Vec<Option<&Grammar>> = std::iter::once(Some(&*self.grammar))
+                .chain(done.iter().map(|d| Some(&d.grammar)))
+                .chain([None])
+                .collect();
+            let mut e = Engine {
+                cfg: &stage.cfg,
+                doc: &mut *self.doc,
+                scratch: &mut stage.scratch,
+                diag: &mut *self.diag,
+                grammar: &mut stage.grammar,
+                stages: &mut [],
+                hot: None,
+            };
+            if stage.spans {
+                e.adopt_stream(&homes, i + 1)?;
+            } else if let Some(sw) = e.doc.stream.current {
+                e.adopt_single_window(&homes, sw, i + 1)?;
+            }
+            e.run_grammar_on_current_window(fmt, output)?;
+        }
+        if !stages.is_empty() {
+            let homes: Vec<Option<&Grammar>> = std::iter::once(None)
+                .chain(stages.iter().map(|s| Some(&s.grammar)))
+                .collect();
+            self.adopt_stream(&homes, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Re-homes every window of the stream that is not in grammar `to` yet,
+    /// in the `reflowTextuals` order (`previous`, `current`, `next`).
+    fn adopt_stream(&mut self, homes: &[Option<&Grammar>], to: usize) -> Result<(), RunError> {
+        for i in 0..self.doc.stream.previous.len() {
+            let sw = self.doc.stream.previous[i];
+            self.adopt_single_window(homes, sw, to)?;
+        }
+        if let Some(sw) = self.doc.stream.current {
+            self.adopt_single_window(homes, sw, to)?;
+        }
+        for i in 0..self.doc.stream.next.len() {
+            let sw = self.doc.stream.next[i];
+            self.adopt_single_window(homes, sw, to)?;
+        }
+        Ok(())
+    }
+
+    /// Re-homes `sw` from the grammar it is in to grammar `to`, this view's.
+    fn adopt_single_window(
+        &mut self,
+        homes: &[Option<&Grammar>],
+        sw: SwId,
+        to: usize,
+    ) -> Result<(), RunError> {
+        let home = self.doc.store.single_windows.get(sw.0).pipeline_home;
+        if home == to {
+            return Ok(());
+        }
+        let from = homes[home].expect("a window is only ever in a grammar of the pipeline");
+        self.adopt_window_from(from, sw)?;
+        self.doc.store.single_windows.get_mut(sw.0).pipeline_home = to;
+        Ok(())
+    }
+
+    fn adopt_window_from(&mut self, from: &Grammar, sw: SwId) -> Result<(), RunError> {
+        let mut i = 0;
+        while i < self.doc.store.single_windows.get(sw.0).all_cohorts.len() {
+            let c = self.doc.store.single_windows.get(sw.0).all_cohorts[i];
+            i += 1;
+            self.adopt_cohort(from, c)?;
+        }
+        // The window's bag of tags is never reflowed (it has no parent cohort);
+        // only its numeric tag ids name grammar objects.
+        let mut numerical = std::mem::take(
+            &mut self
+                .doc
+                .store
+                .single_windows
+                .get_mut(sw.0)
+                .bag_of_tags
+                .tags_numerical,
+        );
+        for (&hash, tag) in numerical.iter_mut() {
+            *tag = self.adopt_tag(from, hash)?;
+        }
+        self.doc
+            .store
+            .single_windows
+            .get_mut(sw.0)
+            .bag_of_tags
+            .tags_numerical = numerical;
+        Ok(())
+    }
+
+    fn adopt_cohort(&mut self, from: &Grammar, c: CohortId) -> Result<(), RunError> {
+        if let Some(wf) = self.doc.store.cohorts.get(c.0).wordform {
+            let wf = self.adopt_tag(from, from.single_tags_list[wf.0].hash.get())?;
+            self.doc.store.cohorts.get_mut(c.0).wordform = Some(wf);
+        }
+        // Rebuilt by the reflows below, against this grammar's sets.
+        self.doc.store.cohorts.get_mut(c.0).possible_sets.clear();
+        if let Some(wr) = self.doc.store.cohorts.get(c.0).wread {
+            self.adopt_reading(from, wr)?;
+        }
+        for list in 0..4 {
+            let mut k = 0;
+            loop {
+                let cohort = self.doc.store.cohorts.get(c.0);
+                let rs = match list {
+                    0 => &cohort.readings,
+                    1 => &cohort.deleted,
+                    2 => &cohort.delayed,
+                    _ => &cohort.ignored,
+                };
+                let Some(&r) = rs.get(k) else { break };
+                k += 1;
+                self.adopt_reading(from, r)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rewrites any hash this grammar interned differently (a seeded hash
+    /// collision), then reflows the reading and its sub-readings.
+    fn adopt_reading(&mut self, from: &Grammar, reading: ReadingId) -> Result<(), RunError> {
+        let mut k = 0;
+        while k < self.doc.store.readings.get(reading.0).tags_list.len() {
+            let hash = self.doc.store.readings.get(reading.0).tags_list[k];
+            let tag = self.adopt_tag(from, hash)?;
+            let own = self.grammar.single_tags_list[tag.0].hash.get();
+            if own != hash {
+                self.doc.store.readings.get_mut(reading.0).tags_list[k] = own;
+            }
+            k += 1;
+        }
+        if let Some(bf) = self.doc.store.readings.get(reading.0).baseform {
+            let tag = self.adopt_tag(from, bf.get())?;
+            let own = self.grammar.single_tags_list[tag.0].hash;
+            self.doc.store.readings.get_mut(reading.0).baseform = Some(own);
+        }
+        self.reflow_reading(reading)?;
+        // A reading that came off the stream with a mapping tag is mapped, and
+        // the next grammar of a process pipeline would have read it that way.
+        let r = self.doc.store.readings.get_mut(reading.0);
+        if r.mapping.is_some() {
+            r.mapped = true;
+        }
+        if let Some(next) = self.doc.store.readings.get(reading.0).next {
+            self.adopt_reading(from, next)?;
+        }
+        Ok(())
+    }
+
+    /// This grammar's tag for the tag `from` interned under `hash`, interning
+    /// its text the way the stream reader does when it is new here.
+    fn adopt_tag(&mut self, from: &Grammar, hash: u32) -> Result<TagId, RunError> {
+        let own = tag_by_hash(self.grammar, hash);
+        let Some(src) = tag_by_hash(from, hash) else {
+            // Interned by this grammar since `from` last held the window (the
+            // stream reader only ever interns into the first grammar).
+            return own.ok_or(RunError::UnknownPipelineTag { hash });
+        };
+        let text = &from.single_tags_list[src.0].tag;
+        if let Some(own) = own
+            && self.grammar.single_tags_list[own.0].tag == *text
+        {
+            return Ok(own);
+        }
+        self.add_tag(&text.clone(), crate::tag::TagType::empty())
+    }
+}
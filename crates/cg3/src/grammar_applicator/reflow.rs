@@ -56,7 +56,8 @@ use super::{Engine, Matcher};
 use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::cohort::{CT_DEP_DONE, CT_ENCLOSED, CT_IGNORED, CT_REMOVED};
 use crate::inlines::{erase, hash_value, insert_if_exists, ui32};
-use crate::reading::{Reading, ReadingList, alloc_reading_copy, free_reading, reading_rehash};
+use crate::reading::{Reading, ReadingList, alloc_reading_copy_of, free_reading, reading_rehash};
+use crate::runtime_diagnostics::RuntimeDiagnosticKind;
 use crate::tag::{
     T_BASEFORM, T_CASE_INSENSITIVE, T_DEPENDENCY, T_MAPPING, T_NUMERICAL, T_REGEXP, T_RELATION,
     T_SPECIAL, T_TEXTUAL, T_VARSTRING, T_WORDFORM, Tag,
@@ -94,17 +95,21 @@ const STR_VS: [&str; 9] = [
 /// `char` == the "one code unit" the C++ UChar ops assume). NOT a manifest
 /// symbol — port infra.
 fn find_and_replace(str: &mut Vec<char>, from: &str, to: &str) -> usize {
-    let from_v: Vec<char> = from.chars().collect();
-    let to_v: Vec<char> = to.chars().collect();
-    if from_v.is_empty() {
+    let from_len = from.chars().count();
+    let to_len = to.chars().count();
+    if from_len == 0 {
         return 0;
     }
     let mut rv = 0usize;
     let mut offset = 0usize;
-    while offset + from_v.len() <= str.len() {
-        if str[offset..offset + from_v.len()] == from_v[..] {
-            str.splice(offset..offset + from_v.len(), to_v.iter().copied());
-            offset += to_v.len();
+    while offset + from_len <= str.len() {
+        if str[offset..offset + from_len]
+            .iter()
+            .copied()
+            .eq(from.chars())
+        {
+            str.splice(offset..offset + from_len, to.chars());
+            offset += to_len;
             rv += 1;
         } else {
             offset += 1;
@@ -314,7 +319,12 @@ impl Engine<'_> {
         }
 
         if !allowloop && self.cfg.dep_block_loops && self.would_parent_child_loop(parent, child) {
-            // Loop warning — I/O deferred.
+            let (parent_gn, child_gn) = self.dep_pair(parent, child);
+            let kind = RuntimeDiagnosticKind::DependencyLoop {
+                parent: parent_gn,
+                child: child_gn,
+            };
+            self.note(kind, Some(child));
             return false;
         }
 
@@ -322,7 +332,12 @@ impl Engine<'_> {
             && self.cfg.dep_block_crossing
             && self.would_parent_child_cross(parent, child)
         {
-            // Crossing warning — I/O deferred.
+            let (parent_gn, child_gn) = self.dep_pair(parent, child);
+            let kind = RuntimeDiagnosticKind::DependencyCrossing {
+                parent: parent_gn,
+                child: child_gn,
+            };
+            self.note(kind, Some(child));
             return false;
         }
 
@@ -366,13 +381,25 @@ impl Engine<'_> {
             let cp = self.doc.store.cohorts.get(child.0).parent;
             let pp = self.doc.store.cohorts.get(parent.0).parent;
             if cp != pp {
-                // "Info: Dependency ... spans the window boundaries ..." — I/O deferred.
+                // "Info: Dependency ... spans the window boundaries ...".
+                let (parent_gn, child_gn) = self.dep_pair(parent, child);
+                let kind = RuntimeDiagnosticKind::DependencySpansWindows {
+                    parent: parent_gn,
+                    child: child_gn,
+                };
+                self.note(kind, Some(child));
                 self.doc.dep_has_spanned = true;
             }
         }
         true
     }
 
+    /// ADDED: the global numbers of a parent/child pair, for the diagnostics.
+    fn dep_pair(&self, parent: CohortId, child: CohortId) -> (u32, u32) {
+        let gn = |c: CohortId| self.doc.store.cohorts.get(c.0).global_number.get();
+        (gn(parent), gn(child))
+    }
+
     // =======================================================================
     // reflowDependencyWindow
     // =======================================================================
@@ -690,14 +717,20 @@ impl Engine<'_> {
             self.grammar.sets_any.as_ref(),
         );
 
-        // tlist.swap(reading.tags_list) — take the list, leaving it empty.
-        let tlist: Vec<u32> =
-            std::mem::take(&mut self.doc.store.readings.get_mut(reading.0).tags_list);
-        for tter in tlist {
+        // tlist.swap(reading.tags_list) — copied out and cleared instead, so
+        // the rebuilt list keeps the reading's own capacity.
+        let mut tlist = self.scratch.ss_u32v.get();
+        {
+            let r = self.doc.store.readings.get_mut(reading.0);
+            tlist.extend_from_slice(&r.tags_list);
+            r.tags_list.clear();
+        }
+        for &tter in &tlist {
             // addTagToReading(reading, tter, false) — the uint32_t/rehash form.
             let tid = self.grammar.single_tags.find(tter).get().1;
             self.add_tag_to_reading_rehash(reading, tid, false)?;
         }
+        self.scratch.ss_u32v.put(tlist);
 
         reading_rehash(&mut self.doc.store.readings, self.grammar, reading);
         Ok(())
@@ -1083,14 +1116,13 @@ impl Engine<'_> {
         let tag = mappings.pop().unwrap();
         let mut i = mappings.len();
 
-        let mappings_snapshot = mappings.clone();
-        for ttag in mappings_snapshot {
+        for &ttag in mappings.iter() {
             // Dedup against an existing cohort reading with the same hash_plain
             // and this mapping.
             let ttag_hash = self.grammar.single_tags_list[ttag.0].hash;
             let rp = self.doc.store.readings.get(reading.0).hash_plain;
             let mut found = false;
-            for &itr in &self.doc.store.cohorts.get(cohort.0).readings.clone() {
+            for &itr in &self.doc.store.cohorts.get(cohort.0).readings {
                 let (ihp, imap) = {
                     let r = self.doc.store.readings.get(itr.0);
                     (r.hash_plain, r.mapping)
@@ -1107,8 +1139,7 @@ impl Engine<'_> {
                 continue;
             }
             // nr = alloc_reading(reading); nr->mapped; nr->number = number - i--.
-            let rval = clone_reading_value(self.doc.store.readings.get(reading.0));
-            let nr = alloc_reading_copy(&mut self.doc.store, &rval);
+            let nr = alloc_reading_copy_of(&mut self.doc.store, reading);
             let reading_number = self.doc.store.readings.get(reading.0).number;
             {
                 let n = self.doc.store.readings.get_mut(nr.0);
@@ -1158,19 +1189,23 @@ impl Engine<'_> {
         if all_mappings.is_empty() {
             return Ok(());
         }
-        let readings: ReadingList = self.doc.store.cohorts.get(cohort.0).readings.clone();
-        for reading in readings {
+        // Snapshot: split_mappings appends the split-off copies to the cohort.
+        let mut readings = self.scratch.ss_rids.get();
+        readings.extend_from_slice(&self.doc.store.cohorts.get(cohort.0).readings);
+        for &reading in &readings {
             let mut mlist = match all_mappings.remove(&reading) {
                 Some(m) => m,
                 None => continue,
             };
             self.split_mappings(&mut mlist, cohort, reading, mapped)?;
         }
+        self.scratch.ss_rids.put(readings);
         // std::sort(cohort.readings, Reading::cmp_number).
         self.sort_cohort_readings(cohort);
         if !self.grammar.reopen_mappings.empty() {
-            let rs = self.doc.store.cohorts.get(cohort.0).readings.clone();
-            for reading in rs {
+            let n = self.doc.store.cohorts.get(cohort.0).readings.len();
+            for i in 0..n {
+                let reading = self.doc.store.cohorts.get(cohort.0).readings[i];
                 if let Some(m) = self.doc.store.readings.get(reading.0).mapping {
                     let mh = self.grammar.single_tags_list[m.0].hash;
                     if self.grammar.reopen_mappings.count(mh.get()) != 0 {
@@ -1186,10 +1221,11 @@ impl Engine<'_> {
     /// `std::sort(cohort.readings.begin(), .end(), Reading::cmp_number)` — a
     /// store-aware sort helper (the comparator reads two readings' scalars).
     fn sort_cohort_readings(&mut self, cohort: CohortId) {
-        let mut v = self.doc.store.cohorts.get(cohort.0).readings.clone();
-        v.sort_by(|&a, &b| {
-            let ra = self.doc.store.readings.get(a.0);
-            let rb = self.doc.store.readings.get(b.0);
+        let store = &mut self.doc.store;
+        let readings = &store.readings;
+        store.cohorts.get_mut(cohort.0).readings.sort_by(|&a, &b| {
+            let ra = readings.get(a.0);
+            let rb = readings.get(b.0);
             if Reading::cmp_number(ra, rb) {
                 std::cmp::Ordering::Less
             } else if Reading::cmp_number(rb, ra) {
@@ -1198,7 +1234,6 @@ impl Engine<'_> {
                 std::cmp::Ordering::Equal
             }
         });
-        self.doc.store.cohorts.get_mut(cohort.0).readings = v;
     }
 
     // =======================================================================
@@ -1212,12 +1247,13 @@ impl Engine<'_> {
     /// C++ `void mergeReadings(ReadingList& readings)` — collapses readings that
     /// differ only by mapping tags into one carrying all those mappings.
     pub fn merge_readings(&mut self, readings: &mut ReadingList) {
-        // mapped: hplain → (nm, Reading); mlist: hkey → ReadingList (BTreeMap so
-        // the rebuild iterates in key order == the C++ bc::flat_map order).
-        let mut mapped: std::collections::BTreeMap<u32, (u32, ReadingId)> =
-            std::collections::BTreeMap::new();
-        let mut mlist: std::collections::BTreeMap<u32, ReadingList> =
-            std::collections::BTreeMap::new();
+        // mapped: hplain → (nm, Reading). The C++ `mlist` (hkey → ReadingList,
+        // a bc::flat_map) is kept flat as `(hkey, arrival, Reading)` triples;
+        // sorting them by (hkey, arrival) lays the lists out back to back in
+        // key order, each in insertion order. Both come from scratch stacks,
+        // as this runs for every printed cohort.
+        let mut mapped = self.scratch.ss_merge_mapped.get();
+        let mut mlist = self.scratch.ss_merge_mlist.get();
 
         for &r in readings.iter() {
             let (mut hp, mut hplain) = {
@@ -1230,7 +1266,7 @@ impl Engine<'_> {
             };
             let mut nm = 0u32;
             if self.cfg.trace {
-                for &hb in &self.doc.store.readings.get(r.0).hit_by.clone() {
+                for &hb in &self.doc.store.readings.get(r.0).hit_by {
                     hp = hash_value(hb, hp);
                 }
             }
@@ -1249,7 +1285,7 @@ impl Engine<'_> {
                     hplain = hash_value(sh, hplain);
                 }
                 if self.cfg.trace {
-                    for &hb in &self.doc.store.readings.get(s.0).hit_by.clone() {
+                    for &hb in &self.doc.store.readings.get(s.0).hit_by {
                         hp = hash_value(hb, hp);
                     }
                 }
@@ -1267,20 +1303,21 @@ impl Engine<'_> {
                 }
             }
             mapped.insert(hplain, (nm, r));
-            mlist.entry(hp.wrapping_add(nm)).or_default().push(r);
+            mlist.push((hp.wrapping_add(nm), mlist.len(), r));
         }
+        mlist.sort_unstable_by_key(|&(key, arrival, _)| (key, arrival));
 
-        if mlist.len() == readings.len() {
+        if mlist.chunk_by(|a, b| a.0 == b.0).count() == readings.len() {
+            self.scratch.ss_merge_mapped.put(mapped);
+            self.scratch.ss_merge_mlist.put(mlist);
             return;
         }
 
         readings.clear();
-        let mut order: Vec<ReadingId> = Vec::new();
 
-        for (_key, clist) in mlist {
-            let front = clist[0];
-            let front_val = clone_reading_value(self.doc.store.readings.get(front.0));
-            let nr = alloc_reading_copy(&mut self.doc.store, &front_val);
+        for clist in mlist.chunk_by(|a, b| a.0 == b.0) {
+            let front = clist[0].2;
+            let nr = alloc_reading_copy_of(&mut self.doc.store, front);
             if let Some(m) = self.doc.store.readings.get(nr.0).mapping {
                 let mh = self.grammar.single_tags_list[m.0].hash;
                 erase(
@@ -1288,7 +1325,7 @@ impl Engine<'_> {
                     &mh.get(),
                 );
             }
-            for iter1 in clist {
+            for &(_, _, iter1) in clist {
                 let imap = self.doc.store.readings.get(iter1.0).mapping;
                 if let Some(im) = imap {
                     let imh = self.grammar.single_tags_list[im.0].hash;
@@ -1311,10 +1348,11 @@ impl Engine<'_> {
                 let opt = Some(iter1);
                 free_reading(&mut self.doc.store, opt);
             }
-            order.push(nr);
+            // readings.insert(begin, order.begin(), order.end())
+            readings.push(nr);
         }
 
-        order.sort_by(|&a, &b| {
+        readings.sort_by(|&a, &b| {
             let ra = self.doc.store.readings.get(a.0);
             let rb = self.doc.store.readings.get(b.0);
             if Reading::cmp_number(ra, rb) {
@@ -1325,8 +1363,8 @@ impl Engine<'_> {
                 std::cmp::Ordering::Equal
             }
         });
-        // readings.insert(begin, order.begin(), order.end())
-        *readings = order;
+        self.scratch.ss_merge_mapped.put(mapped);
+        self.scratch.ss_merge_mlist.put(mlist);
     }
 
     // [spec:cg3:def:grammar-applicator-reflow.cg3.grammar-applicator.merge-mappings-fn]
@@ -1335,14 +1373,16 @@ impl Engine<'_> {
     // [spec:cg3:sem:grammar-applicator.cg3.grammar-applicator.merge-mappings-fn]
     /// C++ `void mergeMappings(Cohort& cohort)`.
     pub fn merge_mappings(&mut self, cohort: CohortId) {
-        let mut rs = self.doc.store.cohorts.get(cohort.0).readings.clone();
+        // Each list is taken out for the merge and put back (merge_readings
+        // never reads the cohort), rather than cloned.
+        let mut rs = std::mem::take(&mut self.doc.store.cohorts.get_mut(cohort.0).readings);
         self.merge_readings(&mut rs);
         self.doc.store.cohorts.get_mut(cohort.0).readings = rs;
         if self.cfg.trace {
-            let mut del = self.doc.store.cohorts.get(cohort.0).deleted.clone();
+            let mut del = std::mem::take(&mut self.doc.store.cohorts.get_mut(cohort.0).deleted);
             self.merge_readings(&mut del);
             self.doc.store.cohorts.get_mut(cohort.0).deleted = del;
-            let mut dly = self.doc.store.cohorts.get(cohort.0).delayed.clone();
+            let mut dly = std::mem::take(&mut self.doc.store.cohorts.get_mut(cohort.0).delayed);
             self.merge_readings(&mut dly);
             self.doc.store.cohorts.get_mut(cohort.0).delayed = dly;
         }
@@ -1418,6 +1458,10 @@ impl Engine<'_> {
                 n.has_enclosures = ce;
             }
         }
+        // ADDED: the cohorts moving over are in the same pipeline grammar's
+        // terms.
+        let home = self.doc.store.single_windows.get(current.0).pipeline_home;
+        self.doc.store.single_windows.get_mut(nwin.0).pipeline_home = home;
 
         // Build a synthetic BEGIN cohort in nwin.
         let ccohort = crate::cohort::alloc_cohort(&mut self.doc.store, Some(nwin));
@@ -1553,8 +1597,9 @@ impl Matcher<'_> {
     /// before calling), so the signature is `&Tag` per the matchSet header.
     ///
     /// ICU `UnicodeString` ops map to `Vec<char>` splicing (`findAndReplace`,
-    /// `lastIndexOf`) and `char::to_uppercase`/`to_lowercase` (the ICU full
-    /// case mapping analog; parity risk for locale-specific mappings, noted).
+    /// `lastIndexOf`) and the grammar's [`CaseLocale`](crate::case_folding::CaseLocale)
+    /// (the ICU full case mapping analog; ADDED: locale-specific, where the C++
+    /// used ICU's root locale).
     pub fn generate_varstring_tag(&mut self, tag: &Tag) -> Result<TagId, crate::error::RunError> {
         let mut tmp: Vec<char> = tag.tag.chars().collect();
         let mut did_something = false;
@@ -1670,27 +1715,18 @@ impl Matcher<'_> {
                 let mode = tmp[m + 1];
                 // tmp.remove(mpos, 2)
                 tmp.drain(m..m + 2);
+                let locale = self.grammar.case_locale;
                 match mode {
-                    'u' => {
-                        let up: String = tmp[m].to_uppercase().collect();
-                        // setCharAt(mpos, range[0]) — first mapped char.
-                        if let Some(c0) = up.chars().next() {
-                            tmp[m] = c0;
-                        }
-                    }
+                    // setCharAt(mpos, range[0]) — first mapped char.
+                    'u' => tmp[m] = locale.upper_char(tmp[m]),
                     'U' => {
-                        let tail: String = tmp[m..].iter().collect::<String>().to_uppercase();
+                        let tail = locale.to_upper(&tmp[m..].iter().collect::<String>());
                         tmp.truncate(m);
                         tmp.extend(tail.chars());
                     }
-                    'l' => {
-                        let lo: String = tmp[m].to_lowercase().collect();
-                        if let Some(c0) = lo.chars().next() {
-                            tmp[m] = c0;
-                        }
-                    }
+                    'l' => tmp[m] = locale.lower_char(tmp[m]),
                     'L' => {
-                        let tail: String = tmp[m..].iter().collect::<String>().to_lowercase();
+                        let tail = locale.to_lower(&tmp[m..].iter().collect::<String>());
                         tmp.truncate(m);
                         tmp.extend(tail.chars());
                     }
@@ -1798,7 +1834,3 @@ impl Matcher<'_> {
         }
     }
 }
-
-// Wave 4 (w4-file-split-fmt): the verbatim Reading field-copy is
-// consolidated in `crate::reading::clone_verbatim`.
-use crate::reading::clone_verbatim as clone_reading_value;
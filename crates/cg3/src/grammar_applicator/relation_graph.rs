@@ -0,0 +1,335 @@
+//! ADDED — no C++ analog. The named relations of each window as a graph.
+//!
+//! `ADDRELATION`, `SETRELATION` and their plural forms keep a cohort's
+//! relations in [`Cohort::relations`](crate::cohort::Cohort::relations), by
+//! the relation's name and the target's global number; the stream shows them
+//! only as `ID:n` and `R:name:n` tags. When a window is printed, the engine
+//! can take its relations as a [`RelationGraph`] that names them, and:
+//!
+//! * write it out ([`Diagnostics::relation_export`]) as a Graphviz `digraph`
+//!   (`--relations-dot FILE`) and as JSON on a line of its own
+//!   (`--relations-json FILE`), one per window;
+//! * hand it to library code
+//!   ([`GrammarApplicator::run_grammar_on_text_related`](super::GrammarApplicator::run_grammar_on_text_related)),
+//!   which looks relations up by name, source and target and follows them
+//!   ([`RelationGraph::reachable`]).
+//!
+//! Cohorts are known by their global number, as `ID:n` tags print them: a
+//! relation may reach a cohort in another window, which the graph names but
+//! does not hold. A grammar with no relation rules leaves the input's `ID:n`
+//! and `R:name:n` tags in the stream as they came, and the graph reads them
+//! as they stand: a cohort is known by its `ID:n`, or by its global number
+//! when it has none. Relations added to a window's cohorts from a later window
+//! (`ADDRELATIONS` back to an antecedent) are there, as they are in the
+//! stream, since the window is only printed once the windows after it that
+//! can still see it have run.
+//!
+//! [`Diagnostics::relation_export`]: super::Diagnostics::relation_export
+
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use serde_json::{Value, json};
+
+use crate::arena::{CohortId, SwId};
+use crate::cohort::CT_REMOVED;
+use crate::error::RunError;
+use crate::tag::T_RELATION;
+use crate::text_offsets::strip_wordform;
+use crate::types::TagHash;
+
+use super::Engine;
+use super::core::tag_by_hash;
+use super::dep_tree::dot_escape;
+
+/// One cohort of a [`RelationGraph`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationNode {
+    pub cohort: CohortId,
+    /// The number relations know the cohort by (see the module doc).
+    pub id: u32,
+    /// The position in the window, from 1.
+    pub position: u32,
+    /// The wordform, without its `"<…>"`.
+    pub form: String,
+}
+
+/// One relation, from the cohort that holds it to its target, both by
+/// [`RelationNode::id`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Relation {
+    pub name: String,
+    pub source: u32,
+    pub target: u32,
+}
+
+/// One window's relations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationGraph {
+    /// The window's number.
+    pub window: u32,
+    /// The window's cohorts, in order.
+    pub nodes: Vec<RelationNode>,
+    /// The relations its cohorts hold, by source, then name, then target.
+    pub relations: Vec<Relation>,
+}
+
+impl RelationGraph {
+    /// The window's cohort known by `id`.
+    pub fn node(&self, id: u32) -> Option<&RelationNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// The relations called `name`.
+    pub fn named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Relation> + 'a {
+        self.relations.iter().filter(move |r| r.name == name)
+    }
+
+    /// The relations `source` holds.
+    pub fn from(&self, source: u32) -> impl Iterator<Item = &Relation> + '_ {
+        self.relations.iter().filter(move |r| r.source == source)
+    }
+
+    /// The relations that point at `target`.
+    pub fn to(&self, target: u32) -> impl Iterator<Item = &Relation> + '_ {
+        self.relations.iter().filter(move |r| r.target == target)
+    }
+
+    /// Every cohort reached from `source` by following relations out of it,
+    /// only those called `name` when given, in the order first reached.
+    /// `source` is there only if a cycle leads back to it.
+    pub fn reachable(&self, source: u32, name: Option<&str>) -> Vec<u32> {
+        let mut seen = BTreeSet::new();
+        let mut out = Vec::new();
+        let mut queue = std::collections::VecDeque::from([source]);
+        while let Some(cur) = queue.pop_front() {
+            for r in self.from(cur) {
+                if name.is_some_and(|n| n != r.name) || !seen.insert(r.target) {
+                    continue;
+                }
+                out.push(r.target);
+                queue.push_back(r.target);
+            }
+        }
+        out
+    }
+
+    /// The names of the relations, each once, in order.
+    pub fn names(&self) -> BTreeSet<&str> {
+        self.relations.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    /// The graph as a Graphviz `digraph`, each relation an arc labelled with
+    /// its name. A target outside the window is drawn by its global number.
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph window{} {{\n", self.window);
+        out += &format!("\tlabel=\"window {}\";\n", self.window);
+        for n in &self.nodes {
+            out += &format!(
+                "\tc{} [label=\"{}: {}\"];\n",
+                n.id,
+                n.position,
+                dot_escape(&n.form)
+            );
+        }
+        let outside: BTreeSet<u32> = self
+            .relations
+            .iter()
+            .map(|r| r.target)
+            .filter(|&t| self.node(t).is_none())
+            .collect();
+        for t in outside {
+            out += &format!("\tc{t} [label=\"{t}\" shape=plaintext];\n");
+        }
+        for r in &self.relations {
+            out += &format!(
+                "\tc{} -> c{} [label=\"{}\"];\n",
+                r.source,
+                r.target,
+                dot_escape(&r.name)
+            );
+        }
+        out += "}\n";
+        out
+    }
+
+    /// The graph as JSON:
+    /// `{"window":N,"cohorts":[{"id":G,"position":P,"form":"..."}],"relations":[{"name":"...","source":G,"target":G}]}`.
+    pub fn to_json(&self) -> Value {
+        let cohorts: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|n| json!({"id": n.id, "position": n.position, "form": n.form}))
+            .collect();
+        let relations: Vec<Value> = self
+            .relations
+            .iter()
+            .map(|r| json!({"name": r.name, "source": r.source, "target": r.target}))
+            .collect();
+        json!({"window": self.window, "cohorts": cohorts, "relations": relations})
+    }
+}
+
+/// Where the graphs go ([`Diagnostics::relation_export`](super::Diagnostics::relation_export)).
+#[derive(Default)]
+pub struct RelationExport {
+    /// One `digraph` per window.
+    pub dot: Option<Box<dyn Write + Send>>,
+    /// One JSON graph per window, each on a line of its own.
+    pub json: Option<Box<dyn Write + Send>>,
+    /// Whether to keep each graph in [`kept`](Self::kept) as well.
+    pub keep: bool,
+    pub kept: Vec<RelationGraph>,
+}
+
+impl RelationExport {
+    /// Write `graph` to each output, and keep it if asked to.
+    pub fn write(&mut self, graph: RelationGraph) -> std::io::Result<()> {
+        if let Some(dot) = &mut self.dot {
+            dot.write_all(graph.to_dot().as_bytes())?;
+        }
+        if let Some(json) = &mut self.json {
+            serde_json::to_writer(&mut *json, &graph.to_json())?;
+            json.write_all(b"\n")?;
+        }
+        if self.keep {
+            self.kept.push(graph);
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(dot) = &mut self.dot {
+            dot.flush()?;
+        }
+        if let Some(json) = &mut self.json {
+            json.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Engine<'_> {
+    /// The relations of `window`'s cohorts as they stand.
+    pub fn relation_graph(&self, window: SwId) -> RelationGraph {
+        let sw = self.doc.store.single_windows.get(window.0);
+        let mut nodes = Vec::new();
+        let mut relations = Vec::new();
+        for &cid in &sw.cohorts {
+            let c = self.doc.store.cohorts.get(cid.0);
+            if c.local_number == 0 || c.r#type.intersects(CT_REMOVED) {
+                continue;
+            }
+            let id = if self.grammar.has_relations {
+                let source = c.global_number.get();
+                for (&hash, targets) in c.relations.iter() {
+                    let tag = tag_by_hash(self.grammar, TagHash(hash));
+                    let name = &self.grammar.single_tags_list.get(tag.0).tag;
+                    for &target in targets.iter() {
+                        relations.push(Relation {
+                            name: name.clone(),
+                            source,
+                            target,
+                        });
+                    }
+                }
+                source
+            } else {
+                self.input_relations(cid, &mut relations)
+            };
+            let form = c
+                .wordform
+                .map(|wf| strip_wordform(&self.grammar.single_tags_list.get(wf.0).tag).to_string())
+                .unwrap_or_default();
+            nodes.push(RelationNode {
+                cohort: cid,
+                id,
+                position: c.local_number,
+                form,
+            });
+        }
+        nodes.sort_by_key(|n| n.position);
+        relations.sort();
+        relations.dedup();
+        RelationGraph {
+            window: sw.number,
+            nodes,
+            relations,
+        }
+    }
+
+    /// The `ID:n` and `R:name:n` tags on `cohort`'s readings, read without
+    /// taking them up: push the relations it holds and return the number it
+    /// is known by.
+    fn input_relations(&self, cohort: CohortId, relations: &mut Vec<Relation>) -> u32 {
+        let c = self.doc.store.cohorts.get(cohort.0);
+        let tags = || {
+            c.readings
+                .iter()
+                .flat_map(|r| self.doc.store.readings.get(r.0).tags_list.iter())
+                .map(|&h| {
+                    self.grammar
+                        .single_tags_list
+                        .get(tag_by_hash(self.grammar, TagHash(h)).0)
+                })
+                .filter(|t| t.r#type.intersects(T_RELATION))
+        };
+        let id = tags()
+            .find(|t| t.tag.starts_with("ID:"))
+            .map_or(c.global_number.get(), |t| t.dep_self);
+        for t in tags().filter(|t| t.tag.starts_with("R:")) {
+            let name = tag_by_hash(self.grammar, TagHash(t.comparison_hash));
+            relations.push(Relation {
+                name: self.grammar.single_tags_list.get(name.0).tag.clone(),
+                source: id,
+                target: t.dep_parent(),
+            });
+        }
+        id
+    }
+
+    /// Export `window`'s relations as configured; see the module doc.
+    pub(crate) fn finish_relation_graph(&mut self, window: SwId) -> Result<(), RunError> {
+        if self.diag.relation_export.is_none() {
+            return Ok(());
+        }
+        let graph = self.relation_graph(window);
+        if let Some(export) = &mut self.diag.relation_export {
+            export.write(graph)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relations_are_found_by_name_source_and_target() {
+        let rel = |name: &str, source, target| Relation {
+            name: name.to_string(),
+            source,
+            target,
+        };
+        let graph = RelationGraph {
+            window: 1,
+            nodes: Vec::new(),
+            relations: vec![
+                rel("coref", 3, 1),
+                rel("coref", 5, 3),
+                rel("err", 5, 6),
+                rel("err", 6, 9),
+            ],
+        };
+        assert_eq!(graph.named("err").count(), 2);
+        assert_eq!(graph.from(5).map(|r| r.target).collect::<Vec<_>>(), [3, 6]);
+        assert_eq!(graph.to(3).map(|r| r.source).collect::<Vec<_>>(), [5]);
+        assert_eq!(graph.reachable(5, None), [3, 6, 1, 9]);
+        assert_eq!(graph.reachable(5, Some("coref")), [3, 1]);
+        assert_eq!(
+            graph.names().into_iter().collect::<Vec<_>>(),
+            ["coref", "err"]
+        );
+    }
+}
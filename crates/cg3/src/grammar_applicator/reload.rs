@@ -0,0 +1,119 @@
+//! ADDED — no C++ analog. Replacing a running applicator's grammar between
+//! windows: the engine side of `vislcg3 --watch` and
+//! [`crate::grammar_watch`].
+//!
+//! A [`GrammarSwap`] is attached to an applicator before its run and handed to
+//! whatever loads the replacements, typically on another thread. Each window
+//! that starts after a replacement was [offered](GrammarSwap::offer) runs the
+//! replacement's rule passes in place of the applicator's own; a later offer
+//! replaces the earlier replacement. A window is always run by one grammar from
+//! start to end.
+//!
+//! The grammar the applicator was set up with keeps the stream, the way the
+//! first grammar of a [pipeline](super::pipeline) does: it reads and windows
+//! the input and prints the output, and the replacement runs on each window
+//! exactly as a pipeline stage would. What therefore does not follow a
+//! replacement:
+//! * windowing — input delimiters, soft/hard limits (a replacement can still
+//!   split its window with its own DELIMITERS);
+//! * dependency parsing, when the starting grammar had no dependency rules
+//!   and `--dep-delimit` was not given.
+//!
+//! `--trace` names rules in the grammar most recently swapped in, so a window
+//! still held as context across a swap is traced against a grammar it was not
+//! run by.
+
+use std::sync::{Arc, Mutex};
+
+use super::pipeline::PipelineStage;
+use super::{Engine, GrammarApplicator};
+use crate::grammar::Grammar;
+
+/// The channel a replacement grammar travels through to a running applicator.
+/// Cloning it gives another handle on the same channel.
+#[derive(Clone, Default)]
+pub struct GrammarSwap {
+    offered: Arc<Mutex<Option<PipelineStage>>>,
+}
+
+impl GrammarSwap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer `replacement` to the applicator this swap is attached to, from the
+    /// next window on. `replacement` is set up like any applicator
+    /// (`set_grammar`, then `set_options`) and indexed here. An earlier offer
+    /// the applicator has not picked up yet is dropped.
+    pub fn offer(&self, replacement: GrammarApplicator) {
+        let stage = PipelineStage::from_applicator(replacement);
+        *self.offered.lock().unwrap_or_else(|e| e.into_inner()) = Some(stage);
+    }
+
+    /// Whether every other handle is gone — the applicator included — so no
+    /// offer can be picked up any more.
+    pub fn is_orphaned(&self) -> bool {
+        Arc::strong_count(&self.offered) == 1
+    }
+
+    fn take(&self) -> Option<PipelineStage> {
+        self.offered
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+}
+
+/// An applicator's end of a [`GrammarSwap`], and the replacement it is
+/// running, if any.
+pub struct HotGrammar {
+    swap: GrammarSwap,
+    live: Option<PipelineStage>,
+}
+
+impl GrammarApplicator {
+    /// Pick up grammars offered through `swap` between windows. Replaces any
+    /// swap attached before, and the replacement it brought.
+    pub fn attach_grammar_swap(&mut self, swap: GrammarSwap) {
+        self.hot = Some(HotGrammar { swap, live: None });
+    }
+}
+
+impl Engine<'_> {
+    /// Runs the current window's rule passes with the newest grammar: a
+    /// replacement offered since the last window, else the one running, else
+    /// this view's own.
+    pub(crate) fn run_newest_grammar<F, W>(
+        &mut self,
+        fmt: &mut F,
+        output: &mut W,
+    ) -> Result<(), crate::error::RunError>
+    where
+        F: super::stream_format::StreamFormat,
+        W: std::io::Write,
+    {
+        let live = self.hot.as_deref_mut().and_then(|hot| {
+            if let Some(stage) = hot.swap.take() {
+                hot.live = Some(stage);
+            }
+            hot.live.take()
+        });
+        let Some(mut live) = live else {
+            return self.run_grammar_on_current_window(fmt, output);
+        };
+        let rv = self.run_stages(std::slice::from_mut(&mut live), fmt, output);
+        if let Some(hot) = self.hot.as_deref_mut() {
+            hot.live = Some(live);
+        }
+        rv
+    }
+
+    /// The grammar whose rule numbers a trace names: a replacement once one
+    /// is running, else this view's own.
+    pub fn trace_grammar(&self) -> &Grammar {
+        match self.hot.as_deref().and_then(|hot| hot.live.as_ref()) {
+            Some(live) => &live.grammar,
+            None => self.grammar,
+        }
+    }
+}
@@ -69,6 +69,7 @@ use crate::contextual_test::{
     POS_SPAN_LEFT, POS_SPAN_RIGHT, POS_TMPL_OVERRIDE, POS_UNKNOWN, POS_WITH,
 };
 use crate::inlines::{make_64, si32};
+use crate::runtime_diagnostics::RuntimeDiagnosticKind;
 use crate::single_window::{SingleWindow, less_cohort};
 use crate::tag::T_VARSTRING;
 use crate::types::GlobalNumber;
@@ -208,9 +209,9 @@ impl Matcher<'_> {
         }
         if test_pos.intersects(POS_ATTACH_TO) && self.get_attach_to().cohort != Some(cid) {
             // Clear readings for rules that care about readings.
-            let lists = self.rst_gather_lists(cid, test_pos);
+            let lists = Self::rst_gather_lists(self.cohorts.get(cid.0), test_pos);
             for list in lists.into_iter().flatten() {
-                for reading in list {
+                for &reading in list {
                     self.scratch.clear_matched(reading);
                 }
             }
@@ -345,24 +346,22 @@ impl Matcher<'_> {
 
     /// C++ `runSingleTest`'s `ReadingList* lists[4]` collection: slot 0 =
     /// `readings`; 1/2/3 = `deleted`/`delayed`/`ignored` when the matching
-    /// `POS_LOOK_*` flag is set. Cloned so the cohorts arena is not borrowed while
-    /// mutating the readings arena. Not a manifest symbol.
+    /// `POS_LOOK_*` flag is set. Takes the cohort rather than `self` so the
+    /// lists stay borrowed from the cohorts arena while the caller updates the
+    /// scratch flags. Not a manifest symbol.
     fn rst_gather_lists(
-        &self,
-        cohort: CohortId,
+        c: &crate::cohort::Cohort,
         pos: crate::contextual_test::PosFlags,
-    ) -> [Option<Vec<crate::arena::ReadingId>>; 4] {
-        let c = self.cohorts.get(cohort.0);
-        let mut lists: [Option<Vec<crate::arena::ReadingId>>; 4] =
-            [Some(c.readings.clone()), None, None, None];
+    ) -> [Option<&crate::reading::ReadingList>; 4] {
+        let mut lists = [Some(&c.readings), None, None, None];
         if pos.intersects(POS_LOOK_DELETED) {
-            lists[1] = Some(c.deleted.clone());
+            lists[1] = Some(&c.deleted);
         }
         if pos.intersects(POS_LOOK_DELAYED) {
-            lists[2] = Some(c.delayed.clone());
+            lists[2] = Some(&c.delayed);
         }
         if pos.intersects(POS_LOOK_IGNORED) {
-            lists[3] = Some(c.ignored.clone());
+            lists[3] = Some(&c.ignored);
         }
         lists
     }
@@ -1306,17 +1305,15 @@ impl Matcher<'_> {
                         deps = self.cohorts.get(pc.0).dep_children.as_slice().to_vec();
                     }
                     _ => {
-                        if self.cfg.verbosity_level > 0 {
-                            let (ds, dp) = {
-                                let c = self.cohorts.get(current.0);
-                                (c.dep_self, c.dep_parent)
-                            };
-                            tracing::warn!(
-                                "Warning: Cohort {} (parent {}) did not have any siblings.",
-                                ds.map_or(0, |g| g.get()),
-                                dp.map_or(crate::cohort::DEP_NO_PARENT, |g| g.get())
-                            );
-                        }
+                        let (ds, dp) = {
+                            let c = self.cohorts.get(current.0);
+                            (c.dep_self, c.dep_parent)
+                        };
+                        let kind = RuntimeDiagnosticKind::NoSiblings {
+                            cohort: ds.map_or(0, |g| g.get()),
+                            parent: dp.map_or(crate::cohort::DEP_NO_PARENT, |g| g.get()),
+                        };
+                        self.note(kind, Some(current));
                         return Ok(None);
                     }
                 }
@@ -1362,22 +1359,16 @@ impl Matcher<'_> {
             let mapped = self.registry.cohort_map.get(&GlobalNumber(dter)).copied();
             let cohort = match mapped {
                 None => {
-                    if self.cfg.verbosity_level > 0 {
-                        let ds = self.cohorts.get(current.0).dep_self.map_or(0, |g| g.get());
-                        if test_pos.intersects(POS_DEP_CHILD) {
-                            tracing::warn!(
-                                "Warning: Child dependency {} -> {} does not exist - ignoring.",
-                                ds,
-                                dter
-                            );
+                    let kind = RuntimeDiagnosticKind::MissingDependency {
+                        relation: if test_pos.intersects(POS_DEP_CHILD) {
+                            "Child"
                         } else {
-                            tracing::warn!(
-                                "Warning: Sibling dependency {} -> {} does not exist - ignoring.",
-                                ds,
-                                dter
-                            );
-                        }
-                    }
+                            "Sibling"
+                        },
+                        from: self.cohorts.get(current.0).dep_self.map_or(0, |g| g.get()),
+                        to: dter,
+                    };
+                    self.note(kind, Some(current));
                     continue;
                 }
                 Some(c) => c,
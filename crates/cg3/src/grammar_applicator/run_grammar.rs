@@ -24,6 +24,10 @@
 //! "symbols later rebuilt into a string" carve-out (see `inlines::scan`); the
 //! line-oriented Niceline/Plaintext readers use the native-`String` scanners.
 
+use crate::grammar_applicator::StreamFormatKind;
+use crate::runtime_diagnostics::{InputExcerpt, RuntimeDiagnosticKind};
+use crate::stream_command::StreamCommand;
+
 // [spec:cg3:def:grammar-applicator-run-grammar.cg3.test-string-against-fn]
 // [spec:cg3:sem:grammar-applicator-run-grammar.cg3.test-string-against-fn]
 /// C++ free fn `inline bool testStringAgainst(const UString& str,
@@ -54,6 +58,22 @@ pub fn test_string_against(str: &str, rxs: &[crate::tag_regex::TagRegex]) -> boo
     false
 }
 
+/// ADDED: a NUL-terminated line buffer as the text a diagnostic quotes.
+fn line_text(buf: &[char]) -> String {
+    let text: String = buf.iter().take_while(|&&c| c != '\0').collect();
+    text.trim().to_string()
+}
+
+/// ADDED: the raw line being read, quoted at its first non-space char — where
+/// a cohort or reading line that failed to parse starts.
+fn line_excerpt(num_lines: u32, line: &[char]) -> InputExcerpt {
+    let indent = line
+        .iter()
+        .take_while(|&&c| c != '\0' && crate::inlines::isspace(c))
+        .count();
+    InputExcerpt::from_chars(num_lines + 1, line, indent as u32 + 1)
+}
+
 /// C++ `u_strchr(s, needle)` over a `Vec<char>` scratch buffer: return the index
 /// of the first `needle` at or after `from`, scanning up to (not past) the NUL
 /// terminator, or `None`. Used by the inline SETVAR/REMVAR pointer walks.
@@ -111,6 +131,8 @@ struct GotReadingScope<'a> {
     /// C++ `UChar* cleaned` / `line` — the scratch line buffers.
     cleaned: &'a mut Vec<char>,
     line: &'a mut Vec<char>,
+    /// A tag's text, collected from `cleaned` for `addTag` (reused per tag).
+    tag_text: &'a mut String,
     /// C++ `std::vector<std::pair<size_t, Reading*>> indents`.
     indents: &'a mut Vec<(usize, crate::arena::ReadingId)>,
     all_mappings: &'a mut super::AllMappings,
@@ -223,6 +245,21 @@ impl super::Engine<'_> {
         Ok(c_reading)
     }
 
+    /// The C++ `for (auto iter : cCohort->readings) addTagToReading(*iter,
+    /// endtag)` run when a window is closed. Walks the list by position: adding
+    /// a tag never changes which readings the cohort holds.
+    fn add_end_tag_to_readings(
+        &mut self,
+        cc: crate::arena::CohortId,
+    ) -> Result<(), crate::error::RunError> {
+        let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
+        for i in 0..self.doc.store.cohorts.get(cc.0).readings.len() {
+            let r = self.doc.store.cohorts.get(cc.0).readings[i];
+            self.add_tag_to_reading(r, tid)?;
+        }
+        Ok(())
+    }
+
     /// The C++ `got_reading:` GOTO LABEL body from `runGrammarOnText` (the block
     /// entered by both the ` "` reading line — falling through — and the `; "`
     /// deleted-reading line — via `goto got_reading`). Restructured into a helper
@@ -239,6 +276,7 @@ impl super::Engine<'_> {
         let GotReadingScope {
             cleaned,
             line,
+            tag_text,
             indents,
             all_mappings,
             variables_set,
@@ -260,7 +298,13 @@ impl super::Engine<'_> {
         if !indents.is_empty() && indent > indents.last().unwrap().0 {
             let back = indents.last().unwrap().1;
             if self.doc.store.readings.get(back.0).next.is_some() {
-                // "Sub-reading … will be ignored and lost …": deferred emission.
+                // "Sub-reading … will be ignored and lost …".
+                let excerpt = line_excerpt(self.doc.num_lines, line);
+                self.note_input(
+                    RuntimeDiagnosticKind::SubReadingLost,
+                    Some(c_cohort),
+                    excerpt,
+                )?;
                 return Ok(GotReading::Continue);
             }
             let parent = self.doc.store.readings.get(back.0).parent;
@@ -307,7 +351,14 @@ impl super::Engine<'_> {
         // This does not consider wordforms as invalid readings since chained
         // CG-3 may produce such
         if cleaned[space] != '"' {
-            // "looked like a reading but wasn't - treated as text": deferred.
+            // "looked like a reading but wasn't - treated as text".
+            let text = line_text(line);
+            let excerpt = line_excerpt(self.doc.num_lines, line);
+            self.note_input(
+                RuntimeDiagnosticKind::NotAReading { text },
+                Some(c_cohort),
+                excerpt,
+            )?;
             if !indents.is_empty()
                 && self
                     .doc
@@ -344,9 +395,9 @@ impl super::Engine<'_> {
                     cleaned[space] = '\0';
                     space += 1;
                     if base < cleaned.len() && cleaned[base] != '\0' {
-                        let base_text: String =
-                            cleaned[base..].iter().take_while(|&&c| c != '\0').collect();
-                        let tag = self.add_tag(&base_text, crate::tag::TagType::empty())?;
+                        tag_text.clear();
+                        tag_text.extend(cleaned[base..].iter().take_while(|&&c| c != '\0'));
+                        let tag = self.add_tag(tag_text, crate::tag::TagType::empty())?;
                         let (ttype, first_char) = {
                             let t = &self.grammar.single_tags_list[tag.0];
                             (t.r#type, t.tag.chars().next().unwrap_or('\0'))
@@ -370,8 +421,9 @@ impl super::Engine<'_> {
             }
         }
         if base < cleaned.len() && cleaned[base] != '\0' {
-            let base_text: String = cleaned[base..].iter().take_while(|&&c| c != '\0').collect();
-            let tag = self.add_tag(&base_text, crate::tag::TagType::empty())?;
+            tag_text.clear();
+            tag_text.extend(cleaned[base..].iter().take_while(|&&c| c != '\0'));
+            let tag = self.add_tag(tag_text, crate::tag::TagType::empty())?;
             let (ttype, first_char) = {
                 let t = &self.grammar.single_tags_list[tag.0];
                 (t.r#type, t.tag.chars().next().unwrap_or('\0'))
@@ -385,7 +437,15 @@ impl super::Engine<'_> {
             }
         }
         if self.doc.store.readings.get(c_reading.0).baseform.is_none() {
-            // "Line %u had no valid baseform.": deferred emission.
+            // "Line %u had no valid baseform.".
+            let excerpt = line_excerpt(self.doc.num_lines, line);
+            self.note_input(
+                RuntimeDiagnosticKind::NoBaseform {
+                    format: StreamFormatKind::Cg,
+                },
+                Some(c_cohort),
+                excerpt,
+            )?;
         }
         if indents.is_empty() || indent <= indents.last().unwrap().0 {
             // cCohort->appendReading(cReading, *readings);
@@ -397,8 +457,13 @@ impl super::Engine<'_> {
         } else {
             if let Some(mlist) = all_mappings.get_mut(&c_reading) {
                 while mlist.len() > 1 {
-                    // "Sub-reading mapping … will be discarded.": deferred.
-                    mlist.pop();
+                    // "Sub-reading mapping … will be discarded.".
+                    let tag = mlist.pop().unwrap();
+                    let tag = self.grammar.single_tags_list[tag.0].tag.clone();
+                    self.note(
+                        RuntimeDiagnosticKind::MappingDiscarded { tag },
+                        Some(c_cohort),
+                    );
                 }
                 let mut ml = all_mappings.remove(&c_reading).unwrap();
                 self.split_mappings(&mut ml, c_cohort, c_reading, true)?;
@@ -552,10 +617,15 @@ impl super::Engine<'_> {
         // ux_stdin = &input; ux_stdout = &output;  (elided: Option<()> placeholders)
         // The good()/eof()/output/grammar validity checks (each CG3Quit(1) with a
         // u_fprintf diagnostic) are deferred with the I/O layer.
-        // No-hard/soft-delimiter warnings: deferred I/O (grammar->delimiters etc.).
+        self.note_missing_delimiters();
 
         let mut line: Vec<char> = vec!['\0'; 1024];
         let mut cleaned: Vec<char> = vec!['\0'; line.len() + 1];
+        let mut tag_text = String::new();
+        // A text line's content, for the stream-command checks (reused per line).
+        let mut cleaned_str = String::new();
+        // ADDED: the position in the input text, for `Cohort::offsets`.
+        let mut text_pos = crate::text_offsets::OffsetCursor::default();
         let mut ignoreinput = false;
         let mut did_soft_lookback = false;
         let mut is_deleted;
@@ -639,7 +709,10 @@ impl super::Engine<'_> {
                     space -= 1;
                 }
                 if cleaned[space] != '"' || cleaned[space - 1] != '>' {
-                    // "looked like a cohort but wasn't - treated as text": deferred.
+                    // "looked like a cohort but wasn't - treated as text".
+                    let text = line_text(&cleaned);
+                    let excerpt = line_excerpt(self.doc.num_lines, &line);
+                    self.note_input(RuntimeDiagnosticKind::NotACohort { text }, None, excerpt)?;
                     is_text = true;
                 } else {
                     cleaned[space + 1] = '\0';
@@ -694,11 +767,7 @@ impl super::Engine<'_> {
                         };
                         if sd_hit {
                             // verbose soft-limit warning: deferred.
-                            let rs = self.doc.store.cohorts.get(cc.0).readings.clone();
-                            for r in rs {
-                                let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
-                                self.add_tag_to_reading(r, tid)?;
-                            }
+                            self.add_end_tag_to_readings(cc)?;
                             self.split_all_mappings(&mut all_mappings, cc, true)?;
                             crate::single_window::append_cohort(
                                 &mut self.doc.store,
@@ -736,12 +805,10 @@ impl super::Engine<'_> {
                                 self.does_set_match_cohort_normal(cc, d, None)?
                             };
                         if over_hard || delim_hit {
-                            // (!is_conv && over_hard) "Hard limit ... forcing break": deferred.
-                            let rs = self.doc.store.cohorts.get(cc.0).readings.clone();
-                            for r in rs {
-                                let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
-                                self.add_tag_to_reading(r, tid)?;
+                            if !self.cfg.is_conv && over_hard {
+                                self.note_hard_limit(cc, StreamFormatKind::Cg);
                             }
+                            self.add_end_tag_to_readings(cc)?;
                             self.split_all_mappings(&mut all_mappings, cc, true)?;
                             crate::single_window::append_cohort(
                                 &mut self.doc.store,
@@ -825,12 +892,15 @@ impl super::Engine<'_> {
                     let cc = crate::cohort::alloc_cohort(&mut self.doc.store, Some(sw));
                     let gn = self.doc.cohorts.next_cohort_number();
                     // wordform = addTag(&cleaned[0]) (up to the NUL at space+1).
-                    let wf_text: String = cleaned.iter().take_while(|&&c| c != '\0').collect();
-                    let wf = self.add_tag(&wf_text, crate::tag::TagType::empty())?;
+                    tag_text.clear();
+                    tag_text.extend(cleaned.iter().take_while(|&&c| c != '\0'));
+                    let wf = self.add_tag(&tag_text, crate::tag::TagType::empty())?;
                     {
                         let c = self.doc.store.cohorts.get_mut(cc.0);
                         c.global_number = gn;
                         c.wordform = Some(wf);
+                        c.offsets =
+                            Some(text_pos.take(crate::text_offsets::strip_wordform(&tag_text)));
                     }
                     c_cohort = Some(cc);
                     l_cohort = Some(cc);
@@ -882,6 +952,7 @@ impl super::Engine<'_> {
                 let scope = GotReadingScope {
                     cleaned: &mut cleaned,
                     line: &mut line,
+                    tag_text: &mut tag_text,
                     indents: &mut indents,
                     all_mappings: &mut all_mappings,
                     variables_set: &mut variables_set,
@@ -904,7 +975,13 @@ impl super::Engine<'_> {
                     GotReading::Normal => {}
                 }
             } else {
-                // "looked like a reading but there was no containing cohort": deferred.
+                if cleaned[0] == ' ' && cleaned[1] == '"' {
+                    // "looked like a reading but there was no containing cohort".
+                    let text = line_text(&cleaned);
+                    let excerpt = line_excerpt(self.doc.num_lines, &line);
+                    let kind = RuntimeDiagnosticKind::OrphanReading { text };
+                    self.note_input(kind, None, excerpt)?;
+                }
                 is_text = true;
             }
 
@@ -912,13 +989,19 @@ impl super::Engine<'_> {
                 // (4) istext: plain text + stream commands.
                 if line[0] != '\0' {
                     let mut is_cmd = false;
-                    let cleaned_str: String = cleaned.iter().take_while(|&&c| c != '\0').collect();
-
-                    if cleaned_str == crate::strings::STR_CMD_FLUSH {
-                        // "FLUSH encountered … Flushing…": deferred.
+                    cleaned_str.clear();
+                    cleaned_str.extend(cleaned.iter().take_while(|&&c| c != '\0'));
+
+                    // ADDED: the commands are parsed in one place for every
+                    // format (see `crate::stream_command`).
+                    let command = StreamCommand::parse(&cleaned_str);
+                    if let Some(cmd @ (StreamCommand::Flush | StreamCommand::GetVars)) = &command {
+                        // "FLUSH encountered … Flushing…": deferred. ADDED:
+                        // GETVARS drains the same way but keeps the variables.
+                        let flush = *cmd == StreamCommand::Flush;
                         is_cmd = true;
                         let back_swindow = self.doc.stream.back();
-                        if let Some(bsw) = back_swindow {
+                        if flush && let Some(bsw) = back_swindow {
                             self.doc.store.single_windows.get_mut(bsw.0).flush_after = true;
                         }
                         if let (Some(cc), Some(sw)) = (c_cohort, c_swindow) {
@@ -940,11 +1023,7 @@ impl super::Engine<'_> {
                             if self.doc.store.cohorts.get(cc.0).readings.is_empty() {
                                 self.init_empty_cohort(cc)?;
                             }
-                            let rs = self.doc.store.cohorts.get(cc.0).readings.clone();
-                            for r in rs {
-                                let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
-                                self.add_tag_to_reading(r, tid)?;
-                            }
+                            self.add_end_tag_to_readings(cc)?;
                             // cReading = lReading = nullptr; etc.
                             l_reading = None;
                             c_cohort = None;
@@ -961,7 +1040,7 @@ impl super::Engine<'_> {
                         self.shuffle_windows_down();
                         while !self.doc.stream.previous.is_empty() {
                             let tmp = self.doc.stream.previous[0];
-                            fmt.print_single_window(self, tmp, output, false)?;
+                            fmt.print_finished_window(self, tmp, output)?;
                             crate::single_window::free_swindow(
                                 &mut self.doc.store,
                                 &mut self.doc.cohorts,
@@ -970,173 +1049,57 @@ impl super::Engine<'_> {
                             );
                             self.doc.stream.previous.remove(0);
                         }
-                        if back_swindow.is_none() {
-                            fmt.print_stream_command(self, crate::strings::STR_CMD_FLUSH, output);
+                        if flush {
+                            if back_swindow.is_none() {
+                                fmt.print_stream_command(
+                                    self,
+                                    crate::strings::STR_CMD_FLUSH,
+                                    output,
+                                );
+                            }
+                            line[0] = '\0';
+                            self.doc.variables.clear(0);
+                        } else {
+                            let reply = self.variables_reply(&variables_set, &variables_rem);
+                            fmt.print_stream_command(self, &reply, output);
+                            line[0] = '\0';
                         }
-                        line[0] = '\0';
-                        self.doc.variables.clear(0);
                         crate::uextras::u_fflush(output);
-                    } else if cleaned_str == crate::strings::STR_CMD_IGNORE {
+                    } else if command == Some(StreamCommand::Ignore) {
                         // "IGNORE encountered …": deferred.
                         is_cmd = true;
                         ignoreinput = true;
                         fmt.print_stream_command(self, crate::strings::STR_CMD_IGNORE, output);
                         line[0] = '\0';
-                    } else if cleaned_str == crate::strings::STR_CMD_RESUME {
+                    } else if command == Some(StreamCommand::Resume) {
                         // "RESUME encountered …": deferred.
                         is_cmd = true;
                         ignoreinput = false;
                         fmt.print_stream_command(self, crate::strings::STR_CMD_RESUME, output);
                         line[0] = '\0';
-                    } else if cleaned_str == crate::strings::STR_CMD_EXIT {
+                    } else if command == Some(StreamCommand::Exit) {
                         // "EXIT encountered …": deferred. C++ also sets is_cmd,
                         // dead there too: the goto/break leaves its last read.
                         fmt.print_stream_command(self, crate::strings::STR_CMD_EXIT, output);
                         break 'mainloop;
-                    } else if cleaned_str.starts_with(crate::strings::STR_CMD_SETVAR) {
-                        // <STREAMCMD:SETVAR:...> — inline parse (no parseSetVar method).
-                        is_cmd = true;
-                        cleaned[packoff - 1] = '\0';
-                        line[0] = '\0';
-
-                        // UChar* s = &cleaned[STR_CMD_SETVAR.size()];
-                        let mut s: Option<usize> =
-                            Some(crate::strings::STR_CMD_SETVAR.chars().count());
-                        let mut c = u_strchr(&cleaned, s.unwrap(), ',');
-                        let mut d = u_strchr(&cleaned, s.unwrap(), '=');
-                        if c.is_none() && d.is_none() {
-                            let s_text: String = cleaned[s.unwrap()..]
-                                .iter()
-                                .take_while(|&&ch| ch != '\0')
-                                .collect();
-                            let tag = self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                            let h = self.grammar.single_tags_list[tag.0].hash.get();
-                            *variables_set.index_or_insert(h) = self.grammar.tag_any;
-                            variables_rem.erase(h);
-                            variables_output.insert(h);
-                            if c_swindow.is_none() {
-                                *self.doc.variables.index_or_insert(h) = self.grammar.tag_any;
-                            }
-                        } else {
-                            let mut a: u32;
-                            let mut b: u32;
-                            while c.is_some() || d.is_some() {
-                                if d.is_some() && (c.is_none() || d.unwrap() < c.unwrap()) {
-                                    let di = d.unwrap();
-                                    cleaned[di] = '\0';
-                                    if cleaned[s.unwrap()] == '\0' {
-                                        // "no identifier before the =": default *.
-                                        a = self.grammar.tag_any;
-                                    } else {
-                                        let s_text: String = cleaned[s.unwrap()..]
-                                            .iter()
-                                            .take_while(|&&ch| ch != '\0')
-                                            .collect();
-                                        let atag =
-                                            self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                                        a = self.grammar.single_tags_list[atag.0].hash.get();
-                                    }
-                                    if let Some(ci) = c {
-                                        cleaned[ci] = '\0';
-                                        s = Some(ci + 1);
-                                    }
-                                    if cleaned[di + 1] == '\0' {
-                                        // "no value after the =": default *.
-                                        b = self.grammar.tag_any;
-                                    } else {
-                                        let d_text: String = cleaned[di + 1..]
-                                            .iter()
-                                            .take_while(|&&ch| ch != '\0')
-                                            .collect();
-                                        let btag =
-                                            self.add_tag(&d_text, crate::tag::TagType::empty())?;
-                                        b = self.grammar.single_tags_list[btag.0].hash.get();
-                                    }
-                                    if c.is_none() {
-                                        d = None;
-                                        s = None;
-                                    }
-                                    *variables_set.index_or_insert(a) = b;
-                                    variables_rem.erase(a);
-                                    variables_output.insert(a);
-                                } else if c.is_some() && (d.is_none() || c.unwrap() < d.unwrap()) {
-                                    let ci = c.unwrap();
-                                    cleaned[ci] = '\0';
-                                    if cleaned[s.unwrap()] == '\0' {
-                                        // "no identifier after the ,": default *.
-                                        a = self.grammar.tag_any;
-                                    } else {
-                                        let s_text: String = cleaned[s.unwrap()..]
-                                            .iter()
-                                            .take_while(|&&ch| ch != '\0')
-                                            .collect();
-                                        let atag =
-                                            self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                                        a = self.grammar.single_tags_list[atag.0].hash.get();
-                                    }
-                                    s = Some(ci + 1);
-                                    *variables_set.index_or_insert(a) = self.grammar.tag_any;
-                                    variables_rem.erase(a);
-                                    variables_output.insert(a);
-                                }
-                                if let Some(si) = s {
-                                    c = u_strchr(&cleaned, si, ',');
-                                    d = u_strchr(&cleaned, si, '=');
-                                    if c.is_none() && d.is_none() {
-                                        let s_text: String = cleaned[si..]
-                                            .iter()
-                                            .take_while(|&&ch| ch != '\0')
-                                            .collect();
-                                        let atag =
-                                            self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                                        a = self.grammar.single_tags_list[atag.0].hash.get();
-                                        *variables_set.index_or_insert(a) = self.grammar.tag_any;
-                                        variables_rem.erase(a);
-                                        variables_output.insert(a);
-                                        s = None;
-                                    }
-                                }
-                            }
-                        }
-                    } else if cleaned_str.starts_with(crate::strings::STR_CMD_REMVAR) {
-                        // <STREAMCMD:REMVAR:...> — inline parse (no parseRemVar method).
+                    } else if let Some(cmd) = &command {
+                        // SETVAR / REMVAR (the C++ parses both inline here).
                         is_cmd = true;
-                        cleaned[packoff - 1] = '\0';
                         line[0] = '\0';
-
-                        // UChar* s = &cleaned[STR_CMD_REMVAR.size()];
-                        let mut s: usize = crate::strings::STR_CMD_REMVAR.chars().count();
-                        let mut c = u_strchr(&cleaned, s, ',');
-                        while let Some(ci) = c {
-                            if cleaned[ci] == '\0' {
-                                break;
-                            }
-                            cleaned[ci] = '\0';
-                            if cleaned[s] != '\0' {
-                                let s_text: String =
-                                    cleaned[s..].iter().take_while(|&&ch| ch != '\0').collect();
-                                let atag = self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                                let a = self.grammar.single_tags_list[atag.0].hash.get();
-                                variables_set.erase(a);
-                                variables_rem.insert(a);
-                                variables_output.insert(a);
-                            }
-                            s = ci + 1;
-                            c = u_strchr(&cleaned, s, ',');
-                        }
-                        if cleaned[s] != '\0' {
-                            let s_text: String =
-                                cleaned[s..].iter().take_while(|&&ch| ch != '\0').collect();
-                            let atag = self.add_tag(&s_text, crate::tag::TagType::empty())?;
-                            let a = self.grammar.single_tags_list[atag.0].hash.get();
-                            variables_set.erase(a);
-                            variables_rem.insert(a);
-                            variables_output.insert(a);
-                        }
+                        self.stage_variables(
+                            cmd,
+                            c_swindow.is_none(),
+                            &mut variables_set,
+                            &mut variables_rem,
+                            &mut variables_output,
+                        )?;
                     }
 
                     if line[0] != '\0' {
                         let line_str: String = line.iter().take_while(|&&c| c != '\0').collect();
+                        if !is_cmd {
+                            text_pos.advance_cg_text(&line_str);
+                        }
                         if l_swindow.is_some()
                             && l_cohort.is_some()
                             && test_string_against(&line_str, &self.cfg.text_delimiters)
@@ -1150,11 +1113,7 @@ impl super::Engine<'_> {
                                 .text_post
                                 .push_str(&line_str);
                             let cc = c_cohort.unwrap();
-                            let rs = self.doc.store.cohorts.get(cc.0).readings.clone();
-                            for r in rs {
-                                let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
-                                self.add_tag_to_reading(r, tid)?;
-                            }
+                            self.add_end_tag_to_readings(cc)?;
                             self.split_all_mappings(&mut all_mappings, cc, true)?;
                             let sw = c_swindow.unwrap();
                             crate::single_window::append_cohort(
@@ -1232,11 +1191,7 @@ impl super::Engine<'_> {
             if self.doc.store.cohorts.get(cc.0).readings.is_empty() {
                 self.init_empty_cohort(cc)?;
             }
-            let rs = self.doc.store.cohorts.get(cc.0).readings.clone();
-            for r in rs {
-                let tid = super::core::tag_by_hash(self.grammar, self.cfg.endtag);
-                self.add_tag_to_reading(r, tid)?;
-            }
+            self.add_end_tag_to_readings(cc)?;
             // C++ also nulls cReading/cCohort here; nothing below reads them.
             c_swindow = None;
         }
@@ -1271,7 +1226,7 @@ impl super::Engine<'_> {
         self.shuffle_windows_down();
         while !self.doc.stream.previous.is_empty() {
             let tmp = self.doc.stream.previous[0];
-            fmt.print_single_window(self, tmp, output, false)?;
+            fmt.print_finished_window(self, tmp, output)?;
             let t = Some(tmp);
             crate::single_window::free_swindow(
                 &mut self.doc.store,
@@ -1342,11 +1297,14 @@ impl super::Engine<'_> {
     /// call the raw `WindowStream` method directly.
     pub(crate) fn shuffle_windows_down(&mut self) {
         if let Some(current) = self.doc.stream.current {
-            let vars = live_map_pairs(&mut self.doc.variables);
             let sww = self.doc.store.single_windows.get_mut(current.0);
             sww.variables_set.clear(0);
-            for (k, v) in vars {
-                sww.variables_set.insert((k, v));
+            // `variables` and the store are disjoint, so the live slots are
+            // copied across directly (the filter of `live_map_pairs`).
+            for &(k, v) in self.doc.variables.get().iter() {
+                if k != u32::MAX && k != u32::MAX - 1 {
+                    sww.variables_set.insert((k, v));
+                }
             }
         }
         self.doc.stream.shuffle_windows_down(&mut self.doc.store);
@@ -1451,4 +1409,59 @@ impl super::GrammarApplicator {
             .run_grammar_on_text_with_impl(fmt, input, output)
             .map_err(crate::error::Cg3Error::from)
     }
+
+    /// ADDED — no C++ analog. [`run_grammar_on_text`](Self::run_grammar_on_text),
+    /// returning the warnings the run raised and how many went uncounted past
+    /// the kept ones (see [`crate::runtime_diagnostics`]).
+    ///
+    /// A failed run returns only the error; what it raised before failing
+    /// stays on the applicator for
+    /// [`take_runtime_diagnostics`](Self::take_runtime_diagnostics).
+    pub fn run_grammar_on_text_diagnosed<R, W>(
+        &mut self,
+        input: &mut R,
+        output: &mut W,
+    ) -> Result<crate::runtime_diagnostics::RuntimeReport, crate::error::Cg3Error>
+    where
+        R: std::io::Read + std::io::Seek,
+        W: std::io::Write,
+    {
+        self.run_grammar_on_text(input, output)?;
+        Ok(self.take_runtime_diagnostics())
+    }
+
+    /// ADDED — no C++ analog. The warnings raised since the last call, by this
+    /// run or any other driver over the applicator, failed runs included.
+    pub fn take_runtime_diagnostics(&mut self) -> crate::runtime_diagnostics::RuntimeReport {
+        self.diag.runtime.take()
+    }
+
+    /// ADDED — no C++ analog. [`run_grammar_on_text`](Self::run_grammar_on_text),
+    /// returning each window's relations as it was printed (see
+    /// [`relation_graph`](super::relation_graph)). Any
+    /// [`relation_export`](super::Diagnostics::relation_export) already set up
+    /// is written to as well.
+    pub fn run_grammar_on_text_related<R, W>(
+        &mut self,
+        input: &mut R,
+        output: &mut W,
+    ) -> Result<Vec<super::relation_graph::RelationGraph>, crate::error::Cg3Error>
+    where
+        R: std::io::Read + std::io::Seek,
+        W: std::io::Write,
+    {
+        let had_export = self.diag.relation_export.is_some();
+        self.diag
+            .relation_export
+            .get_or_insert_with(Default::default)
+            .keep = true;
+        let result = self.run_grammar_on_text(input, output);
+        let export = self.diag.relation_export.as_mut().unwrap();
+        export.keep = false;
+        let graphs = std::mem::take(&mut export.kept);
+        if !had_export {
+            self.diag.relation_export = None;
+        }
+        result.map(|()| graphs)
+    }
 }
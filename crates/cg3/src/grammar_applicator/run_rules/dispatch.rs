@@ -84,10 +84,12 @@ impl crate::grammar_applicator::Engine<'_> {
             let target = self.get_apply_to().cohort.unwrap();
             let treadings = self.doc.store.cohorts.get(target.0).readings.len();
             if st.selected.len() < treadings && !st.selected.is_empty() {
-                let mut drop: ReadingList = Vec::new();
+                let mut drop = self.scratch.ss_rids.get();
                 let mut si = 0usize;
-                let readings = self.doc.store.cohorts.get(target.0).readings.clone();
-                for rd_orig in readings.iter().copied() {
+                // The walk only traces and flags readings; the list itself is not
+                // touched until the swap below, so it is read by position.
+                for ri in 0..treadings {
+                    let rd_orig = self.doc.store.cohorts.get(target.0).readings[ri];
                     let mut rd = rd_orig;
                     if rsub_reading != GSR_ANY {
                         if let Some(sr) = self.get_sub_reading(rd, rsub_reading) {
@@ -114,10 +116,10 @@ impl crate::grammar_applicator::Engine<'_> {
                     }
                 }
                 // target->readings.swap(selected)
-                {
-                    let sel = st.selected.clone();
-                    self.doc.store.cohorts.get_mut(target.0).readings = sel;
-                }
+                std::mem::swap(
+                    &mut self.doc.store.cohorts.get_mut(target.0).readings,
+                    &mut st.selected,
+                );
                 if rflags.intersects(RF_DELAYED) {
                     self.doc
                         .store
@@ -140,6 +142,7 @@ impl crate::grammar_applicator::Engine<'_> {
                         .deleted
                         .extend(drop.iter().copied());
                 }
+                self.scratch.ss_rids.put(drop);
                 st.readings_changed = true;
             }
             st.selected.clear();
@@ -209,7 +212,13 @@ impl crate::grammar_applicator::Engine<'_> {
                 let to_hash = self.grammar.single_tags_list.get(to.0).hash;
                 let anchor = self.grammar.anchors.find(to_hash.get());
                 if anchor == self.grammar.anchors.end() {
-                    // Warning: JUMP could not find anchor (I/O omitted).
+                    // Warning: JUMP could not find anchor.
+                    let anchor = self.grammar.single_tags_list.get(to.0).tag.clone();
+                    let kind =
+                        crate::runtime_diagnostics::RuntimeDiagnosticKind::JumpAnchorMissing {
+                            anchor,
+                        };
+                    self.note(kind, None);
                 } else {
                     let dest = anchor.get().1;
                     let lb = st.intersects.lower_bound(dest);
@@ -393,7 +402,7 @@ impl crate::grammar_applicator::Engine<'_> {
                 for r in rs {
                     self.add_tag_to_reading(r, endtag)?;
                     if self.update_valid_rules(
-                        &st.rules.clone(),
+                        &st.rules,
                         &mut st.intersects,
                         self.cfg.endtag.get(),
                         r,
@@ -764,7 +773,7 @@ impl crate::grammar_applicator::Engine<'_> {
             } else {
                 hash = self.add_tag_to_reading(reading, tter)?;
             }
-            if self.update_valid_rules(&st.rules.clone(), &mut st.intersects, hash.get(), reading) {
+            if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), reading) {
                 st.iter_val = rnumber;
             }
         }
@@ -776,8 +785,13 @@ impl crate::grammar_applicator::Engine<'_> {
     /// matched tag. Operates on the apply-to subreading.
     fn rr_fill_tag_list(&mut self, taglist: &mut TagList) -> Result<(), crate::error::RunError> {
         let reading = self.get_apply_to().subreading.unwrap();
-        let mut out: TagList = Vec::new();
-        for &tt in taglist.iter() {
+        // Filtered in place (kept tags compacted to the front) so a recycled
+        // list keeps its buffer.
+        let mut kept = 0usize;
+        let mut i = 0usize;
+        while i < taglist.len() {
+            let tt = taglist[i];
+            i += 1;
             let (thash, ttype) = {
                 let t = self.grammar.single_tags_list.get(tt.0);
                 (t.hash, t.r#type)
@@ -787,16 +801,18 @@ impl crate::grammar_applicator::Engine<'_> {
                 r.tags.find(thash.get()) != r.tags.end()
             };
             if present {
-                out.push(tt);
+                taglist[kept] = tt;
+                kept += 1;
             } else if ttype.intersects(T_SPECIAL) {
                 let tagv = self.grammar.single_tags_list.get(tt.0).clone();
                 let stag = self.does_tag_match_reading(reading, &tagv, false, true)?;
                 if stag != 0 {
-                    out.push(self.tag_by_hash(TagHash(stag)));
+                    taglist[kept] = self.tag_by_hash(TagHash(stag));
+                    kept += 1;
                 }
             }
         }
-        *taglist = out;
+        taglist.truncate(kept);
         Ok(())
     }
 
@@ -814,12 +830,12 @@ impl crate::grammar_applicator::Engine<'_> {
         let reading = self.get_apply_to().subreading.unwrap();
         let state_hash = self.doc.store.readings.get(reading.0).hash;
         self.doc.store.readings.get_mut(reading.0).noprint = false;
-        let mut mappings = TagList::new();
+        let mut mappings = self.scratch.ss_taglist.get();
         let maplist = self.grammar.rule_by_number.get(rule.0).maplist;
-        let mut the_tags = TagList::new();
-        if let Some(ml) = maplist {
-            the_tags = self.get_tag_list_of_set(ml, false);
-        }
+        let the_tags = match maplist {
+            Some(ml) => self.get_scratch_tag_list_of_set(ml, false),
+            None => self.scratch.ss_taglist.get(),
+        };
 
         let childset1 = self.grammar.rule_by_number.get(rule.0).childset1.get();
         let mut did_insert = false;
@@ -865,6 +881,8 @@ impl crate::grammar_applicator::Engine<'_> {
         if self.doc.store.readings.get(reading.0).hash != state_hash {
             st.readings_changed = true;
         }
+        self.scratch.ss_taglist.put(the_tags);
+        self.scratch.ss_taglist.put(mappings);
         Ok(())
     }
 
@@ -996,12 +1014,7 @@ impl crate::grammar_applicator::Engine<'_> {
                 } else {
                     hash = self.add_tag_to_reading(creading, tter)?;
                 }
-                if self.update_valid_rules(
-                    &st.rules.clone(),
-                    &mut st.intersects,
-                    hash.get(),
-                    creading,
-                ) {
+                if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), creading) {
                     st.iter_val = rnumber;
                 }
             }
@@ -1146,8 +1159,8 @@ impl crate::grammar_applicator::Engine<'_> {
         let state_hash = self.doc.store.readings.get(sr.0).hash;
         let sublist = self.grammar.rule_by_number.get(rule.0).sublist;
         let mut the_tags = match sublist {
-            Some(sl) => self.get_tag_list_of_set(sl, false),
-            None => TagList::new(),
+            Some(sl) => self.get_scratch_tag_list_of_set(sl, false),
+            None => self.scratch.ss_taglist.get(),
         };
         let appending = the_tags.len() == 1
             && self
@@ -1159,10 +1172,12 @@ impl crate::grammar_applicator::Engine<'_> {
 
         // FILL_TAG_LIST equivalent on the subreading.
         self.rr_fill_tag_list_of(sr, &mut the_tags)?;
-        let the_hashes: Vec<u32> = the_tags
-            .iter()
-            .map(|t| self.grammar.single_tags_list.get(t.0).hash.get())
-            .collect();
+        let mut the_hashes = self.scratch.ss_u32v.get();
+        the_hashes.extend(
+            the_tags
+                .iter()
+                .map(|t| self.grammar.single_tags_list.get(t.0).hash.get()),
+        );
         let substtag = self.cfg.substtag.get();
 
         let mut tpos: usize = usize::MAX;
@@ -1236,11 +1251,11 @@ impl crate::grammar_applicator::Engine<'_> {
             // C++ clamps then increments tpos here (`if (tpos >= size()) tpos =
             // size() - 1; ++tpos;`) but both stores are dead: every read below
             // happens after the `tpos = idx` reassignment in the splice loop.
-            let mut mappings = TagList::new();
+            let mut mappings = self.scratch.ss_taglist.get();
             let maplist = self.grammar.rule_by_number.get(rule.0).maplist;
             let map_tags = match maplist {
-                Some(ml) => self.get_tag_list_of_set(ml, false),
-                None => TagList::new(),
+                Some(ml) => self.get_scratch_tag_list_of_set(ml, false),
+                None => self.scratch.ss_taglist.get(),
             };
             let mut wf: Option<TagId> = None;
             let mapping_prefix = self.grammar.mapping_prefix;
@@ -1281,12 +1296,7 @@ impl crate::grammar_applicator::Engine<'_> {
                                 .insert(tpos, thash.get());
                             tpos += 1;
                         }
-                        if self.update_valid_rules(
-                            &st.rules.clone(),
-                            &mut st.intersects,
-                            thash.get(),
-                            sr,
-                        ) {
+                        if self.update_valid_rules(&st.rules, &mut st.intersects, thash.get(), sr) {
                             st.iter_val = rnumber;
                         }
                     }
@@ -1298,6 +1308,8 @@ impl crate::grammar_applicator::Engine<'_> {
             if !mappings.is_empty() {
                 self.split_mappings(&mut mappings, cohort, sr, true)?;
             }
+            self.scratch.ss_taglist.put(map_tags);
+            self.scratch.ss_taglist.put(mappings);
             // Wordform swap across the parent's readings (rare path).
             let parent = self.doc.store.readings.get(sr.0).parent.unwrap();
             let parent_wf = self.doc.store.cohorts.get(parent.0).wordform;
@@ -1334,13 +1346,15 @@ impl crate::grammar_applicator::Engine<'_> {
                     }
                 }
                 let wf_hash = self.grammar.single_tags_list.get(wf.0).hash;
-                self.update_valid_rules(&st.rules.clone(), &mut st.intersects, wf_hash.get(), sr);
+                self.update_valid_rules(&st.rules, &mut st.intersects, wf_hash.get(), sr);
                 st.iter_val = rnumber;
             }
         }
         if self.doc.store.readings.get(sr.0).hash != state_hash {
             st.readings_changed = true;
         }
+        self.scratch.ss_u32v.put(the_hashes);
+        self.scratch.ss_taglist.put(the_tags);
         Ok(())
     }
 
@@ -1351,8 +1365,13 @@ impl crate::grammar_applicator::Engine<'_> {
         reading: ReadingId,
         taglist: &mut TagList,
     ) -> Result<(), crate::error::RunError> {
-        let mut out: TagList = Vec::new();
-        for &tt in taglist.iter() {
+        // Filtered in place (kept tags compacted to the front) so a recycled
+        // list keeps its buffer.
+        let mut kept = 0usize;
+        let mut i = 0usize;
+        while i < taglist.len() {
+            let tt = taglist[i];
+            i += 1;
             let (thash, ttype) = {
                 let t = self.grammar.single_tags_list.get(tt.0);
                 (t.hash, t.r#type)
@@ -1362,16 +1381,18 @@ impl crate::grammar_applicator::Engine<'_> {
                 r.tags.find(thash.get()) != r.tags.end()
             };
             if present {
-                out.push(tt);
+                taglist[kept] = tt;
+                kept += 1;
             } else if ttype.intersects(T_SPECIAL) {
                 let tagv = self.grammar.single_tags_list.get(tt.0).clone();
                 let stag = self.does_tag_match_reading(reading, &tagv, false, true)?;
                 if stag != 0 {
-                    out.push(self.tag_by_hash(TagHash(stag)));
+                    taglist[kept] = self.tag_by_hash(TagHash(stag));
+                    kept += 1;
                 }
             }
         }
-        *taglist = out;
+        taglist.truncate(kept);
         Ok(())
     }
 
@@ -20,6 +20,7 @@ use crate::arena::{RuleId, SwId};
 use crate::interval_vector::Uint32IntervalVector;
 use crate::reading::ReadingList;
 use crate::strings::Keywords::*;
+use crate::types::Uint32Vector;
 
 // C++ anonymous `enum { RV_NOTHING = 1, RV_SOMETHING = 2, RV_DELIMITED = 4,
 // RV_TRACERULE = 8 };` — the return-value bit flags of runRulesOnSingleWindow.
@@ -39,17 +40,17 @@ const RV_TRACERULE: u32 = 8;
 /// sentinel for `get_sub_reading` / `rule.sub_reading`.
 const GSR_ANY: i32 = 32767;
 
-/// Expand a `uint32IntervalVector` to the ascending list of its member values
-/// (the C++ `for (auto v : iv)`). Not a manifest symbol — iteration helper.
-fn iv_to_vec(iv: &Uint32IntervalVector) -> Vec<u32> {
-    let mut out = Vec::new();
+/// Append the ascending member values of a `uint32IntervalVector` to `out`
+/// (the C++ `for (auto v : iv)`), so the loop body may borrow `self` mutably.
+/// Callers pass a recycled `ss_u32v` list. Not a manifest symbol — iteration
+/// helper.
+fn iv_extend(iv: &Uint32IntervalVector, out: &mut Uint32Vector) {
     let mut it = iv.begin();
     let end = iv.end();
     while it != end {
         out.push(it.value());
         it.advance();
     }
-    out
 }
 
 /// The shared, mutable per-`runRulesOnSingleWindow` state that C++ captures by
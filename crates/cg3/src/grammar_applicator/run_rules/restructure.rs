@@ -8,6 +8,7 @@ use crate::inlines::{hash_value, insert_if_exists, ui32};
 use crate::rule::{RF_BEFORE, RF_DETACH, RF_REVERSE};
 use crate::strings::Keywords::{self};
 use crate::tag::{T_BASEFORM, T_DEPENDENCY, T_MAPPING, T_VARSTRING, T_WORDFORM, TagList};
+use crate::text_offsets::{TextOffsets, strip_wordform};
 use crate::types::{GlobalNumber, TagHash};
 
 // C++ anonymous `enum { RV_NOTHING = 1, RV_SOMETHING = 2, RV_DELIMITED = 4,
@@ -405,12 +406,7 @@ impl crate::grammar_applicator::Engine<'_> {
                 } else {
                     hash = self.add_tag_to_reading(creading, tter)?;
                 }
-                if self.update_valid_rules(
-                    &st.rules.clone(),
-                    &mut st.intersects,
-                    hash.get(),
-                    creading,
-                ) {
+                if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), creading) {
                     st.iter_val = rnumber;
                 }
             }
@@ -462,6 +458,14 @@ impl crate::grammar_applicator::Engine<'_> {
                 .cohorts
                 .insert(ln, ccohort);
             self.rr_insert_into_all_cohorts_before(current, cohorts.front(), ccohort);
+            // ADDED: an empty span where the cohort was inserted.
+            self.doc.store.cohorts.get_mut(ccohort.0).offsets = self
+                .doc
+                .store
+                .cohorts
+                .get(cohorts.front().0)
+                .offsets
+                .map(TextOffsets::start);
         } else {
             let ln = self.doc.store.cohorts.get(cohorts.back().0).local_number as usize + 1;
             self.doc
@@ -471,6 +475,13 @@ impl crate::grammar_applicator::Engine<'_> {
                 .cohorts
                 .insert(ln, ccohort);
             self.rr_insert_into_all_cohorts_after(current, cohorts.back(), ccohort);
+            self.doc.store.cohorts.get_mut(ccohort.0).offsets = self
+                .doc
+                .store
+                .cohorts
+                .get(cohorts.back().0)
+                .offsets
+                .map(TextOffsets::end);
         }
         self.rr_renumber(current);
         self.doc.stream.rebuild_cohort_links(&mut self.doc.store);
@@ -686,12 +697,8 @@ impl crate::grammar_applicator::Engine<'_> {
             let brs = self.doc.store.cohorts.get(ccohort.0).readings.clone();
             for r in brs {
                 self.add_tag_to_reading(r, endtag_id)?;
-                if self.update_valid_rules(
-                    &st.rules.clone(),
-                    &mut st.intersects,
-                    self.cfg.endtag.get(),
-                    r,
-                ) {
+                if self.update_valid_rules(&st.rules, &mut st.intersects, self.cfg.endtag.get(), r)
+                {
                     st.iter_val = rnumber;
                 }
             }
@@ -757,6 +764,12 @@ impl crate::grammar_applicator::Engine<'_> {
 
         let (cc, mut spaces_in_added_wf) = self.rr_add_cohort(st, rule, merge_at, Some(&withs))?;
         self.scratch.context_stack.last_mut().unwrap().target.cohort = Some(cc);
+        // ADDED: the merged cohort spans the cohorts it replaces.
+        self.doc.store.cohorts.get_mut(cc.0).offsets = withs
+            .as_slice()
+            .iter()
+            .filter_map(|c| self.doc.store.cohorts.get(c.0).offsets)
+            .reduce(TextOffsets::cover);
 
         let rnumber = self.grammar.rule_by_number.get(rule.0).number;
         for c in withs.as_slice().to_vec() {
@@ -804,12 +817,8 @@ impl crate::grammar_applicator::Engine<'_> {
             let brs = self.doc.store.cohorts.get(back.0).readings.clone();
             for r in brs {
                 self.add_tag_to_reading(r, endtag_id)?;
-                if self.update_valid_rules(
-                    &st.rules.clone(),
-                    &mut st.intersects,
-                    self.cfg.endtag.get(),
-                    r,
-                ) {
+                if self.update_valid_rules(&st.rules, &mut st.intersects, self.cfg.endtag.get(), r)
+                {
                     st.iter_val = rnumber;
                 }
             }
@@ -908,10 +917,14 @@ impl crate::grammar_applicator::Engine<'_> {
         let ccohort = crate::cohort::alloc_cohort(&mut self.doc.store, Some(attach_parent));
         {
             let gn = self.doc.cohorts.next_cohort_number();
-            let wf = self.doc.store.cohorts.get(cohort.0).wordform;
+            let (wf, offsets) = {
+                let c = self.doc.store.cohorts.get(cohort.0);
+                (c.wordform, c.offsets)
+            };
             let cc = self.doc.store.cohorts.get_mut(ccohort.0);
             cc.global_number = gn;
             cc.wordform = wf;
+            cc.offsets = offsets;
         }
         let sets_any = self.grammar.sets_any.clone();
         insert_if_exists(
@@ -960,12 +973,8 @@ impl crate::grammar_applicator::Engine<'_> {
                     } else {
                         hash = self.add_tag_to_reading(creading, tter)?;
                     }
-                    if self.update_valid_rules(
-                        &st.rules.clone(),
-                        &mut st.intersects,
-                        hash.get(),
-                        creading,
-                    ) {
+                    if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), creading)
+                    {
                         st.iter_val = rnumber;
                     }
                 }
@@ -983,12 +992,8 @@ impl crate::grammar_applicator::Engine<'_> {
                     } else {
                         hash = self.add_tag_to_reading(creading, tter)?;
                     }
-                    if self.update_valid_rules(
-                        &st.rules.clone(),
-                        &mut st.intersects,
-                        hash.get(),
-                        creading,
-                    ) {
+                    if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), creading)
+                    {
                         st.iter_val = rnumber;
                     }
                 }
@@ -1034,12 +1039,7 @@ impl crate::grammar_applicator::Engine<'_> {
             for hash0 in wtags {
                 let tter = self.tag_by_hash(TagHash(hash0));
                 let hash = self.add_tag_to_reading(cwread, tter)?;
-                if self.update_valid_rules(
-                    &st.rules.clone(),
-                    &mut st.intersects,
-                    hash.get(),
-                    cwread,
-                ) {
+                if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), cwread) {
                     st.iter_val = rnumber;
                 }
             }
@@ -1270,12 +1270,8 @@ impl crate::grammar_applicator::Engine<'_> {
                     } else {
                         hash = self.add_tag_to_reading(creading, tter)?;
                     }
-                    if self.update_valid_rules(
-                        &st.rules.clone(),
-                        &mut st.intersects,
-                        hash.get(),
-                        creading,
-                    ) {
+                    if self.update_valid_rules(&st.rules, &mut st.intersects, hash.get(), creading)
+                    {
                         st.iter_val = rnumber;
                     }
                 }
@@ -1313,6 +1309,25 @@ impl crate::grammar_applicator::Engine<'_> {
             self.doc.store.cohorts.get_mut(apply.0).text = last_text;
         }
 
+        // ADDED: divide the split cohort's span among the new cohorts.
+        if let (Some(span), Some(wf)) = {
+            let c = self.doc.store.cohorts.get(apply.0);
+            (c.offsets, c.wordform)
+        } {
+            let tags = &self.grammar.single_tags_list;
+            let pieces: Vec<&str> = cohort_ids
+                .iter()
+                .map(|&c| {
+                    let wf = self.doc.store.cohorts.get(c.0).wordform.unwrap();
+                    strip_wordform(&tags.get(wf.0).tag)
+                })
+                .collect();
+            let spans = span.split(strip_wordform(&tags.get(wf.0).tag), &pieces);
+            for (&c, offsets) in cohort_ids.iter().zip(spans) {
+                self.doc.store.cohorts.get_mut(c.0).offsets = Some(offsets);
+            }
+        }
+
         // Dependency + named-relation re-attachment.
         let front_gn = if n > 0 {
             self.doc
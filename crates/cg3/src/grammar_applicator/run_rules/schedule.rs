@@ -31,27 +31,42 @@ impl crate::grammar_applicator::Engine<'_> {
         let mut retval = RV_NOTHING;
         let mut section_did_something = false;
 
-        let intersects = self
-            .doc
+        let mut st = match self.scratch.spare_rr_states.pop() {
+            Some(mut st) => {
+                st.current = current;
+                st.rules.clone_from(rules);
+                st.rule = RuleId(0);
+                st.iter_val = 0;
+                st.removed.clear();
+                st.selected.clear();
+                st.readings_changed = false;
+                st.should_repeat = false;
+                st.should_bail = false;
+                st.delimited = false;
+                st.do_sort = false;
+                st
+            }
+            None => RRState {
+                current,
+                rules: rules.clone(),
+                intersects: Uint32IntervalVector::new(),
+                rule: RuleId(0),
+                iter_val: 0,
+                removed: ReadingList::new(),
+                selected: ReadingList::new(),
+                readings_changed: false,
+                should_repeat: false,
+                should_bail: false,
+                delimited: false,
+                do_sort: false,
+            },
+        };
+        self.doc
             .store
             .single_windows
             .get(current.0)
             .valid_rules
-            .intersect(rules);
-        let mut st = Box::new(RRState {
-            current,
-            rules: rules.clone(),
-            intersects,
-            rule: RuleId(0),
-            iter_val: 0,
-            removed: ReadingList::new(),
-            selected: ReadingList::new(),
-            readings_changed: false,
-            should_repeat: false,
-            should_bail: false,
-            delimited: false,
-            do_sort: false,
-        });
+            .intersect_into(rules, &mut st.intersects);
 
         // current.parent->cohort_map[0] = current.cohorts.front()
         let front = self.doc.store.single_windows.get(current.0).cohorts[0];
@@ -172,6 +187,7 @@ impl crate::grammar_applicator::Engine<'_> {
         if st.delimited {
             retval |= RV_DELIMITED;
         }
+        self.scratch.spare_rr_states.push(st);
         Ok(retval)
     }
 
@@ -403,8 +419,7 @@ impl crate::grammar_applicator::Engine<'_> {
                 at += 1;
             }
             let rule = st.rule.0;
-            if self.update_valid_rules(&st.rules.clone(), &mut st.intersects, thash.get(), reading)
-            {
+            if self.update_valid_rules(&st.rules, &mut st.intersects, thash.get(), reading) {
                 st.iter_val = self.grammar.rule_by_number.get(rule).number;
             }
         }
@@ -2,7 +2,7 @@
 //!
 //! Split out of the wave-2 monolithic `run_rules.rs` (wave 4, w4-file-split-fmt).
 
-use crate::arena::{CohortId, CtxId, RuleId, SetId, SwId, TagId};
+use crate::arena::{CohortId, RuleId, SetId, SwId, TagId};
 use crate::cohort::{CT_ENCLOSED, CT_IGNORED, CT_REMOVED, CohortSet};
 use crate::contextual_test::{POS_NO_PASS_ORIGIN, POS_PASS_ORIGIN};
 use crate::inlines::ui32;
@@ -224,6 +224,8 @@ impl crate::grammar_applicator::Engine<'_> {
         // C++ parked `rocit`; inner frames and update_rule_to_cohorts may adjust
         // it, so it is re-read from the slot at every use.
         let depth = self.scratch.rocits.len() - 1;
+        // Per-reading frame snapshots, recycled cohort to cohort.
+        let mut reading_contexts = self.scratch.ss_ctx_list.get();
         loop {
             let rocit = self.scratch.rocits[depth];
             if rocit >= self.cs_ref(cohortset).size() {
@@ -357,7 +359,7 @@ impl crate::grammar_applicator::Engine<'_> {
             let mut num_active: usize = 0;
             let mut num_iff: usize = 0;
             let mut num_immutable: usize = 0;
-            let mut reading_contexts: Vec<crate::grammar_applicator::RuleContext> = Vec::new();
+            self.scratch.recycle_contexts(&mut reading_contexts);
 
             // Assume Iff is Remove until a context matches.
             if rtype0 == KIff {
@@ -396,7 +398,7 @@ impl crate::grammar_applicator::Engine<'_> {
 
             // Push the per-cohort context frame.
             {
-                let mut ctx = crate::grammar_applicator::RuleContext::default();
+                let mut ctx = self.scratch.ss_ctx.get();
                 ctx.target.cohort = Some(cohort);
                 ctx.is_with = rtype0 == KWith;
                 self.scratch.context_stack.push(ctx);
@@ -467,7 +469,7 @@ impl crate::grammar_applicator::Engine<'_> {
                     if r#type == KSelect {
                         self.scratch.matched_target.insert(reading);
                         self.scratch.matched_tests.insert(reading);
-                        reading_contexts.push(self.scratch.context_stack.last().unwrap().clone());
+                        reading_contexts.push(self.scratch.snapshot_context());
                     }
                     num_iff += 1;
                     num_immutable += 1;
@@ -508,7 +510,7 @@ impl crate::grammar_applicator::Engine<'_> {
                         f.unif_sets = us;
                     }
                     test_good = mtst;
-                    reading_contexts.push(self.scratch.context_stack.last().unwrap().clone());
+                    reading_contexts.push(self.scratch.snapshot_context());
                     i += 1;
                     continue;
                 }
@@ -581,14 +583,15 @@ impl crate::grammar_applicator::Engine<'_> {
                             .unwrap()
                             .context
                             .clear();
-                        let tests: Vec<CtxId> = self
-                            .grammar
-                            .rule_by_number
-                            .get(rule.0)
-                            .tests
-                            .iter()
-                            .copied()
-                            .collect();
+                        let mut tests = self.scratch.ss_ctx_ids.get();
+                        tests.extend(
+                            self.grammar
+                                .rule_by_number
+                                .get(rule.0)
+                                .tests
+                                .iter()
+                                .copied(),
+                        );
                         let mut ti = 0usize;
                         while ti < tests.len() {
                             let test = tests[ti];
@@ -660,6 +663,7 @@ impl crate::grammar_applicator::Engine<'_> {
                             // false` reset above runs before every read.
                             ti += 1;
                         }
+                        self.scratch.ss_ctx_ids.put(tests);
                     } else {
                         good = test_good;
                     }
@@ -771,7 +775,7 @@ impl crate::grammar_applicator::Engine<'_> {
                     self.scratch.regexgrps_z.insert(r_number, rgc_ct);
                     self.scratch.used_regex += 1;
                 }
-                reading_contexts.push(self.scratch.context_stack.last().unwrap().clone());
+                reading_contexts.push(self.scratch.snapshot_context());
                 i += 1;
             }
 
@@ -799,26 +803,26 @@ impl crate::grammar_applicator::Engine<'_> {
                     self.cs_mut(cohortset).erase_n(ro);
                     self.scratch.rocits[depth] = ro;
                 }
-                self.scratch.context_stack.pop();
+                self.scratch.pop_context();
                 continue;
             }
             // All readings valid → nothing to do for Select / safe Remove.
             if num_active == self.doc.store.cohorts.get(cohort.0).readings.len() {
                 if r#type == KSelect {
-                    self.scratch.context_stack.pop();
+                    self.scratch.pop_context();
                     continue;
                 }
                 if r#type == KRemove
                     && (!self.cfg.r#unsafe || (rflags.intersects(RF_SAFE)))
                     && !rflags.intersects(RF_UNSAFE)
                 {
-                    self.scratch.context_stack.pop();
+                    self.scratch.pop_context();
                     continue;
                 }
             }
 
             // Dispatch each matched reading.
-            for ctx in reading_contexts.into_iter() {
+            for ctx in reading_contexts.iter() {
                 let (mt, mtst) = {
                     let sr = ctx.target.subreading.unwrap();
                     (
@@ -832,11 +836,15 @@ impl crate::grammar_applicator::Engine<'_> {
                 if !mtst && rtype0 != KIff {
                     continue;
                 }
-                *self.scratch.context_stack.last_mut().unwrap() = ctx;
+                self.scratch
+                    .context_stack
+                    .last_mut()
+                    .unwrap()
+                    .clone_from(ctx);
                 self.scratch.reset_cohorts_for_loop = false;
                 self.reading_cb_dispatch(st)?;
                 if !self.scratch.finish_cohort_loop {
-                    self.scratch.context_stack.pop();
+                    self.scratch.pop_context();
                     return Ok(anything_changed);
                 }
                 if self.scratch.reset_cohorts_for_loop {
@@ -851,14 +859,16 @@ impl crate::grammar_applicator::Engine<'_> {
             self.scratch.reset_cohorts_for_loop = false;
             self.cohort_cb_dispatch(st)?;
             if !self.scratch.finish_cohort_loop {
-                self.scratch.context_stack.pop();
+                self.scratch.pop_context();
                 return Ok(anything_changed);
             }
             if self.scratch.reset_cohorts_for_loop {
                 cohortset = self.rr_reset_cohorts(current, rnumber);
             }
-            self.scratch.context_stack.pop();
+            self.scratch.pop_context();
         }
+        self.scratch.recycle_contexts(&mut reading_contexts);
+        self.scratch.ss_ctx_list.put(reading_contexts);
         Ok(anything_changed)
     }
 
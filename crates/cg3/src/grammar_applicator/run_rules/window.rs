@@ -325,14 +325,16 @@ impl crate::grammar_applicator::Engine<'_> {
     ) -> bool {
         let os = intersects.size();
         // grammar->rules_by_tag.find(hash)
-        let rsits: Option<Vec<u32>> = self.grammar.rules_by_tag.get(&hash).map(iv_to_vec);
-        if let Some(rsits) = rsits {
+        if let Some(iv) = self.grammar.rules_by_tag.get(&hash) {
+            let mut rsits = self.scratch.ss_u32v.get();
+            iv_extend(iv, &mut rsits);
             let c = self.doc.store.readings.get(reading.0).parent.unwrap();
-            for rsit in rsits {
+            for &rsit in &rsits {
                 if self.update_rule_to_cohorts(c, rsit) && rules.contains(rsit) {
                     intersects.insert(rsit);
                 }
             }
+            self.scratch.ss_u32v.put(rsits);
         }
         os != intersects.size()
     }
@@ -353,23 +355,29 @@ impl crate::grammar_applicator::Engine<'_> {
             }
         }
 
-        let cohorts = self.doc.store.single_windows.get(current.0).cohorts.clone();
-        for c in cohorts {
+        // Indexing adds cohorts to rule sets, never to the window, so walk
+        // `cohorts` by position instead of copying it.
+        let mut rsits = self.scratch.ss_u32v.get();
+        let mut i = 0;
+        while i < self.doc.store.single_windows.get(current.0).cohorts.len() {
+            let c = self.doc.store.single_windows.get(current.0).cohorts[i];
+            i += 1;
             let psize = self.doc.store.cohorts.get(c.0).possible_sets.len();
             for psit in 0..psize as u32 {
                 if !self.doc.store.cohorts.get(c.0).possible_sets[psit as usize] {
                     continue;
                 }
                 // grammar->rules_by_set.find(psit)
-                let rules_of_set: Option<Vec<u32>> =
-                    self.grammar.rules_by_set.get(&psit).map(iv_to_vec);
-                if let Some(rsits) = rules_of_set {
-                    for rsit in rsits {
+                if let Some(iv) = self.grammar.rules_by_set.get(&psit) {
+                    rsits.clear();
+                    iv_extend(iv, &mut rsits);
+                    for &rsit in &rsits {
                         self.update_rule_to_cohorts(c, rsit);
                     }
                 }
             }
         }
+        self.scratch.ss_u32v.put(rsits);
     }
 }
 
@@ -677,55 +685,61 @@ impl crate::grammar_applicator::Engine<'_> {
                 .retain(|id| readings.try_get(id.0).is_some());
         }
 
-        if !self.grammar.before_sections.is_empty() && !self.cfg.no_before_sections {
-            let rules = self.cfg.runsections.get(&-1).cloned().unwrap_or_default();
-            let rv = self.run_rules_on_single_window(current, &rules)?;
+        // `cfg` is a shared borrow independent of `self`, so each section's
+        // rule set is passed by reference rather than cloned per window.
+        let cfg = self.cfg;
+        let no_rules = Uint32IntervalVector::new();
+
+        if !self.grammar.before_sections.is_empty() && !cfg.no_before_sections {
+            let rules = cfg.runsections.get(&-1).unwrap_or(&no_rules);
+            let rv = self.run_rules_on_single_window(current, rules)?;
             if rv & (RV_DELIMITED | RV_TRACERULE) != 0 {
                 return Ok(rv);
             }
         }
 
-        if !self.grammar.rules.is_empty() && !self.cfg.no_sections {
-            let mut counter: std::collections::BTreeMap<i32, u32> =
-                std::collections::BTreeMap::new();
+        if !self.grammar.rules.is_empty() && !cfg.no_sections {
             // Iterate runsections (ordered by section key). Callbacks can change
-            // window state but not the runsections map; a plain key cursor mirrors
-            // the C++ `iter`/`++iter`.
-            let keys: Vec<i32> = self.cfg.runsections.keys().copied().collect();
-            let mut idx = 0usize;
+            // window state but not the runsections map; a plain cursor mirrors
+            // the C++ `iter`/`++iter`. The cursor never moves back, so the C++
+            // per-key `counter` map reduces to a run count for the current key.
+            let mut sections = cfg.runsections.iter();
+            let mut section = sections.next();
+            let mut runs = 0u32;
             let mut pass = 0usize;
-            while idx < keys.len() {
-                let key = keys[idx];
-                if key < 0
-                    || (self.cfg.section_max_count != 0
-                        && *counter.get(&key).unwrap_or(&0) >= self.cfg.section_max_count)
-                {
-                    idx += 1;
+            while let Some((&key, rules)) = section {
+                if key < 0 || (cfg.section_max_count != 0 && runs >= cfg.section_max_count) {
+                    section = sections.next();
+                    runs = 0;
                     pass = 0;
                     continue;
                 }
-                let rules = self.cfg.runsections.get(&key).cloned().unwrap();
-                let rv = self.run_rules_on_single_window(current, &rules)?;
-                *counter.entry(key).or_insert(0) += 1;
+                let rv = self.run_rules_on_single_window(current, rules)?;
+                runs += 1;
                 if rv & (RV_DELIMITED | RV_TRACERULE) != 0 {
                     return Ok(rv);
                 }
                 if rv & RV_SOMETHING == 0 {
-                    idx += 1;
+                    section = sections.next();
+                    runs = 0;
                     pass = 0;
                 } else {
                     pass += 1;
                 }
                 if pass >= 1000 {
-                    // Endless-loop warning (window wordform dump omitted — I/O pass).
+                    // Endless-loop warning (the window wordform dump is omitted).
+                    let kind = crate::runtime_diagnostics::RuntimeDiagnosticKind::EndlessLoop {
+                        passes: pass as u32,
+                    };
+                    self.note(kind, None);
                     break;
                 }
             }
         }
 
-        if !self.grammar.after_sections.is_empty() && !self.cfg.no_after_sections {
-            let rules = self.cfg.runsections.get(&-2).cloned().unwrap_or_default();
-            let rv = self.run_rules_on_single_window(current, &rules)?;
+        if !self.grammar.after_sections.is_empty() && !cfg.no_after_sections {
+            let rules = cfg.runsections.get(&-2).unwrap_or(&no_rules);
+            let rv = self.run_rules_on_single_window(current, rules)?;
             if rv & (RV_DELIMITED | RV_TRACERULE) != 0 {
                 return Ok(rv);
             }
@@ -997,7 +1011,7 @@ impl crate::grammar_applicator::Engine<'_> {
             // C++ `printSingleWindow(tmp, *ux_stdout)` — print to the live
             // output writer threaded in by the driver, in the most-derived
             // applicator's format.
-            fmt.print_single_window(self, tmp, output, false)?;
+            fmt.print_finished_window(self, tmp, output)?;
             let opt = Some(tmp);
             crate::single_window::free_swindow(
                 &mut self.doc.store,
@@ -1021,7 +1035,9 @@ impl crate::grammar_applicator::Engine<'_> {
 
         *pass += 1;
         if *pass > 1000 {
-            // Endless-loop warning (I/O pass omitted).
+            let kind =
+                crate::runtime_diagnostics::RuntimeDiagnosticKind::EndlessLoop { passes: *pass };
+            self.note(kind, None);
             return Ok(std::ops::ControlFlow::Break(()));
         }
 
@@ -1136,7 +1152,6 @@ impl crate::grammar_applicator::Engine<'_> {
         W: std::io::Write,
     {
         let current = self.doc.stream.current.unwrap();
-        self.scratch.did_final_enclosure = false;
 
         // Apply the window's variable deltas onto the global `variables` map.
         // The raw slot tables include EMPTY/DEL sentinel slots — filter them
@@ -1165,6 +1180,37 @@ impl crate::grammar_applicator::Engine<'_> {
         for k in vrem {
             self.doc.variables.erase(k);
         }
+
+        if self.hot.is_some() {
+            self.run_newest_grammar(fmt, output)?;
+        } else {
+            self.run_grammar_on_current_window(fmt, output)?;
+        }
+        if !self.stages.is_empty() {
+            self.run_pipeline_stages(fmt, output)?;
+        }
+        // ADDED: weight pruning, after every grammar has had the window.
+        self.prune_by_weight(current);
+        Ok(())
+    }
+
+    /// The part of [`run_grammar_on_window_with`](Self::run_grammar_on_window_with)
+    /// after the window's variable deltas are applied: dependency/relation
+    /// reflow, enclosure wrapping and the rule passes. Split out so each
+    /// [pipeline stage](crate::grammar_applicator::pipeline) runs it on the
+    /// same window without applying the deltas a second time.
+    pub(crate) fn run_grammar_on_current_window<F, W>(
+        &mut self,
+        fmt: &mut F,
+        output: &mut W,
+    ) -> Result<(), crate::error::RunError>
+    where
+        F: crate::grammar_applicator::stream_format::StreamFormat,
+        W: std::io::Write,
+    {
+        let current = self.doc.stream.current.unwrap();
+        self.scratch.did_final_enclosure = false;
+
         let (mk, mv) = (self.cfg.mprefix_key, self.cfg.mprefix_value);
         *self.doc.variables.index_or_insert(mk.get()) = mv.get();
 
@@ -59,6 +59,21 @@ pub trait StreamFormat {
 
     /// Virtual `printPlainTextLine(UStringView, std::ostream&)`.
     fn print_plain_text_line<W: Write>(&mut self, e: &mut Engine<'_>, line: &str, output: &mut W);
+
+    /// ADDED — no C++ analog. Print `window`, which the run is done with: how
+    /// every driver sends a window on its way. Its dependency tree and its
+    /// relations are checked and exported first, as configured (see
+    /// [`dep_tree`](super::dep_tree) and [`relation_graph`](super::relation_graph)).
+    fn print_finished_window<W: Write>(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+        output: &mut W,
+    ) -> Result<(), crate::error::RunError> {
+        e.finish_dep_tree(window)?;
+        e.finish_relation_graph(window)?;
+        self.print_single_window(e, window, output, false)
+    }
 }
 
 /// The base CG text format — the C++ `GrammarApplicator` print virtuals
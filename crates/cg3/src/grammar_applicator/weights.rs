@@ -0,0 +1,265 @@
+//! ADDED — no C++ analog. Reading weights, and pruning readings by them.
+//!
+//! A reading's weight is the value of its numeric weight tag, `<W:w>` (or
+//! `<W=w>`), the first one found on the reading or down its sub-reading
+//! chain; a reading without one is unweighted. As for HFST analysers, lower
+//! is better. Keeping the weight a tag is what carries it through every
+//! format: the FST reader turns its weight column into one (`cg-conv --wtag`,
+//! `--wfactor`), CG streams carry it as written, Apertium streams as `<W:w>`
+//! (see [`apertium_weight_tag`]), and JSONL also gives it to each reading as
+//! `"wt"`. Rules see it as any numeric tag
+//! (`(<W<2>)`, `(<W=MIN>)`).
+//!
+//! [`WeightPruning`] (`--max-weight`, `--n-best`) runs once the rules are
+//! done with a window, before it is printed. Pruned readings are deleted as a
+//! REMOVE would delete them, so `--trace` still shows them, and as with
+//! REMOVE a cohort never loses its last reading: when every reading is above
+//! the threshold, the lightest are kept. Unweighted readings are never above
+//! the threshold and rank as weight 0.
+
+use crate::arena::{CohortId, GenArena, ReadingId, SwId};
+use crate::cohort::CT_REMOVED;
+use crate::grammar::Grammar;
+use crate::inlines::hash_value_ustring;
+use crate::reading::Reading;
+use crate::tag::{COps, T_NUMERICAL, Tag};
+
+use super::{Engine, GrammarApplicator};
+
+/// Why a weight-pruning option value was refused.
+#[derive(Debug, thiserror::Error)]
+pub enum WeightOptionError {
+    #[error("Error: --max-weight expects a number, not `{0}`.")]
+    MaxWeight(String),
+    #[error("Error: --n-best expects a positive whole number, not `{0}`.")]
+    NBest(String),
+}
+
+/// Which readings to prune after each window; see the module doc.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WeightPruning {
+    /// Remove readings weighing more than this.
+    pub max_weight: Option<f64>,
+    /// Then keep only this many of the lightest readings of each cohort.
+    pub n_best: Option<usize>,
+}
+
+impl WeightPruning {
+    /// Parse the `--max-weight` and `--n-best` values given.
+    pub fn from_options(
+        max_weight: Option<&str>,
+        n_best: Option<&str>,
+    ) -> Result<Self, WeightOptionError> {
+        let max_weight = max_weight
+            .map(|v| match v.trim().parse::<f64>() {
+                Ok(w) if w.is_finite() => Ok(w),
+                _ => Err(WeightOptionError::MaxWeight(v.to_string())),
+            })
+            .transpose()?;
+        let n_best = n_best
+            .map(|v| match v.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(WeightOptionError::NBest(v.to_string())),
+            })
+            .transpose()?;
+        Ok(WeightPruning { max_weight, n_best })
+    }
+
+    /// Whether anything is pruned at all.
+    pub fn is_active(&self) -> bool {
+        self.max_weight.is_some() || self.n_best.is_some()
+    }
+}
+
+/// The weight carried by `reading`'s own tags, not its sub-readings'; `key` is
+/// the weight tag's hashed name (see [`weight_key`]).
+pub fn own_weight(
+    readings: &GenArena<Reading>,
+    grammar: &Grammar,
+    reading: ReadingId,
+    key: u32,
+) -> Option<f64> {
+    readings
+        .get(reading.0)
+        .tags_numerical
+        .values()
+        .map(|&tid| grammar.single_tags_list.get(tid.0))
+        .find(|tag| is_weight_tag(tag, key))
+        .map(|tag| tag.comparison_val)
+}
+
+/// Whether `tag` is a weight tag, `<W:w>` for `key` the hashed `W`.
+pub fn is_weight_tag(tag: &Tag, key: u32) -> bool {
+    tag.r#type.intersects(T_NUMERICAL)
+        && tag.comparison_hash == key
+        && tag.comparison_op == COps::OpEquals
+}
+
+/// The tag text an Apertium `<W:w>` tag stands for. Apertium tags lose their
+/// angle brackets on the way in, which would leave `W:w` a plain tag; a weight
+/// keeps them, as it has them in CG streams, so it is numeric.
+pub fn apertium_weight_tag(text: &str, name: &str) -> Option<String> {
+    let value = text.strip_prefix(name)?.strip_prefix(':')?;
+    value
+        .parse::<f64>()
+        .is_ok_and(f64::is_finite)
+        .then(|| format!("<{text}>"))
+}
+
+/// The weight of `reading`: its own, else the first down its sub-reading
+/// chain.
+pub fn reading_weight(
+    readings: &GenArena<Reading>,
+    grammar: &Grammar,
+    reading: ReadingId,
+    key: u32,
+) -> Option<f64> {
+    let mut cur = Some(reading);
+    while let Some(r) = cur {
+        if let Some(weight) = own_weight(readings, grammar, r, key) {
+            return Some(weight);
+        }
+        cur = readings.get(r.0).next;
+    }
+    None
+}
+
+/// The hashed name numeric tags are keyed by, for weight tag `name`.
+pub fn weight_key(name: &str) -> u32 {
+    hash_value_ustring(name, 0)
+}
+
+impl GrammarApplicator {
+    /// The weight of `reading`, by the configured weight tag
+    /// ([`EngineConfig::weight_tag`](super::EngineConfig::weight_tag)).
+    pub fn reading_weight(&self, reading: ReadingId) -> Option<f64> {
+        reading_weight(
+            &self.doc.store.readings,
+            &self.grammar,
+            reading,
+            weight_key(&self.cfg.weight_tag),
+        )
+    }
+}
+
+impl Engine<'_> {
+    /// Apply [`EngineConfig::weight_pruning`](super::EngineConfig::weight_pruning)
+    /// to every cohort of `window`.
+    pub(crate) fn prune_by_weight(&mut self, window: SwId) {
+        if !self.cfg.weight_pruning.is_active() {
+            return;
+        }
+        let cohorts = self
+            .doc
+            .store
+            .single_windows
+            .get(window.0)
+            .all_cohorts
+            .clone();
+        for cohort in cohorts {
+            if !self
+                .doc
+                .store
+                .cohorts
+                .get(cohort.0)
+                .r#type
+                .intersects(CT_REMOVED)
+            {
+                self.prune_cohort_by_weight(cohort);
+            }
+        }
+    }
+
+    /// [`prune_by_weight`](Self::prune_by_weight) for one cohort, for readers
+    /// that print cohorts outside any window.
+    pub(crate) fn prune_cohort_by_weight(&mut self, cohort: CohortId) {
+        if !self.cfg.weight_pruning.is_active() {
+            return;
+        }
+        let key = weight_key(&self.cfg.weight_tag);
+        let pruning = self.cfg.weight_pruning;
+        let weighed: Vec<(ReadingId, f64)> = self
+            .doc
+            .store
+            .cohorts
+            .get(cohort.0)
+            .readings
+            .iter()
+            .map(|&r| {
+                let weight = reading_weight(&self.doc.store.readings, self.grammar, r, key);
+                (r, weight.unwrap_or(0.0))
+            })
+            .collect();
+        if weighed.len() < 2 {
+            return;
+        }
+
+        let mut keep: Vec<(ReadingId, f64)> = match pruning.max_weight {
+            Some(max) => weighed.iter().copied().filter(|&(_, w)| w <= max).collect(),
+            None => weighed.clone(),
+        };
+        if keep.is_empty() {
+            let lightest = weighed
+                .iter()
+                .map(|&(_, w)| w)
+                .fold(f64::INFINITY, f64::min);
+            keep = weighed
+                .iter()
+                .copied()
+                .filter(|&(_, w)| w == lightest)
+                .collect();
+        }
+        if let Some(n) = pruning.n_best
+            && keep.len() > n
+        {
+            // Stable, so equal weights keep their stream order.
+            keep.sort_by(|a, b| a.1.total_cmp(&b.1));
+            keep.truncate(n);
+        }
+        if keep.len() == weighed.len() {
+            return;
+        }
+
+        let (kept, pruned): (Vec<ReadingId>, Vec<ReadingId>) = weighed
+            .iter()
+            .map(|&(r, _)| r)
+            .partition(|r| keep.iter().any(|&(k, _)| k == *r));
+        for &r in &pruned {
+            self.doc.store.readings.get_mut(r.0).deleted = true;
+        }
+        let c = self.doc.store.cohorts.get_mut(cohort.0);
+        c.readings = kept;
+        c.deleted.extend(pruned);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pruning_options() {
+        assert_eq!(
+            WeightPruning::from_options(Some("2.5"), Some("3")).unwrap(),
+            WeightPruning {
+                max_weight: Some(2.5),
+                n_best: Some(3)
+            }
+        );
+        assert!(!WeightPruning::from_options(None, None).unwrap().is_active());
+        assert!(matches!(
+            WeightPruning::from_options(Some("heavy"), None),
+            Err(WeightOptionError::MaxWeight(_))
+        ));
+        assert_eq!(
+            apertium_weight_tag("W:0.5", "W").as_deref(),
+            Some("<W:0.5>")
+        );
+        assert_eq!(apertium_weight_tag("W:x", "W"), None);
+        assert_eq!(apertium_weight_tag("WORD:1", "W"), None);
+        assert!(matches!(
+            WeightPruning::from_options(None, Some("0")),
+            Err(WeightOptionError::NBest(_))
+        ));
+    }
+}
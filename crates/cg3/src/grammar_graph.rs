@@ -0,0 +1,601 @@
+//! ADDED — no C++ analog. The structure of a loaded grammar as a graph.
+//!
+//! A large grammar is hard to read from its text: which sets are built from
+//! which, which rules use which sets and templates, which rules `JUMP` to which
+//! anchors, and which section holds what. [`GrammarGraph::new`] takes a
+//! reindexed [`Grammar`] apart into nodes — sections, rules, templates, sets
+//! and anchors — and arcs from each to what it uses:
+//!
+//! | from     | to       | label      | for                                        |
+//! |----------|----------|------------|--------------------------------------------|
+//! | section  | rule     | `rule`     | the rules the section holds                |
+//! | rule     | set      | `target`   | the target set                             |
+//! | rule     | set      | `operand`  | mapping, substitution and child sets       |
+//! | rule, template | set | `context` | a contextual test's target                |
+//! | rule, template | set | `barrier` | a `BARRIER` or `CBARRIER`                  |
+//! | rule, template | template | `template` | a `T:name` test                     |
+//! | rule     | rule     | `with`     | the sub-rules of a `WITH`                  |
+//! | rule     | anchor, rule | `jump` | the anchor or named rule a `JUMP` goes to |
+//! | anchor   | rule     | `anchor`   | the rule the anchor stands before          |
+//! | set      | set      | `set`      | the sets a `SET` is built from             |
+//!
+//! A named rule is its own anchor, so a `JUMP` to it goes straight to the rule.
+//! Inline sets, such as the `(n)` of `SELECT (n)`, are labelled with their tags.
+//! [`GrammarGraph::closure`] cuts the graph down to what one node uses and
+//! what uses it; `cg-comp --graph=FILE --graph-root=NAME` writes it as Graphviz
+//! DOT or, for a `.json` file, as JSON.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write as _;
+
+use serde_json::{Value, json};
+
+use crate::arena::{CtxId, RuleId};
+use crate::grammar::Grammar;
+use crate::grammar_applicator::dep_tree::dot_escape;
+use crate::inlines::is_internal;
+use crate::strings::{KEYWORDS_STR, Keywords};
+use crate::tag::T_SPECIAL;
+use crate::tag_trie::trie_get_tags_ordered;
+use crate::types::SetNumber;
+
+/// What a [`GraphNode`] stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeKind {
+    Section,
+    Rule,
+    Anchor,
+    Template,
+    Set,
+}
+
+impl NodeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NodeKind::Section => "section",
+            NodeKind::Rule => "rule",
+            NodeKind::Anchor => "anchor",
+            NodeKind::Template => "template",
+            NodeKind::Set => "set",
+        }
+    }
+
+    fn dot_shape(self) -> &'static str {
+        match self {
+            NodeKind::Section => "folder",
+            NodeKind::Rule => "box",
+            NodeKind::Anchor => "cds",
+            NodeKind::Template => "hexagon",
+            NodeKind::Set => "ellipse",
+        }
+    }
+}
+
+/// One section, rule, anchor, template or set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphNode {
+    pub kind: NodeKind,
+    /// Unique in the graph: `s3` for set number 3, `r12` for rule number 12,
+    /// `t0`, `a0` and `section1` in order.
+    pub id: String,
+    /// The name as written in the grammar; empty for an unnamed rule or an
+    /// inline set.
+    pub name: String,
+    /// What to show: the name, a rule's keyword, name and line, an inline
+    /// set's tags.
+    pub label: String,
+    /// The grammar line it was defined on; 0 when not known.
+    pub line: u32,
+}
+
+/// One arc, by index into [`GrammarGraph::nodes`]; the labels are listed in
+/// the module doc.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: &'static str,
+}
+
+/// A grammar's sections, rules, anchors, templates and sets, and what each
+/// uses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GrammarGraph {
+    pub nodes: Vec<GraphNode>,
+    /// By `from`, then `to`.
+    pub edges: Vec<GraphEdge>,
+}
+
+/// The state of [`GrammarGraph::new`].
+struct Builder<'g> {
+    grammar: &'g Grammar,
+    nodes: Vec<GraphNode>,
+    edges: BTreeSet<GraphEdge>,
+    sets: BTreeMap<u32, usize>,
+    templates: BTreeMap<CtxId, usize>,
+    rules: BTreeMap<u32, usize>,
+    /// Sets whose members have still to be added.
+    pending_sets: Vec<u32>,
+}
+
+impl Builder<'_> {
+    fn push(
+        &mut self,
+        kind: NodeKind,
+        id: String,
+        name: String,
+        label: String,
+        line: u32,
+    ) -> usize {
+        self.nodes.push(GraphNode {
+            kind,
+            id,
+            name,
+            label,
+            line,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: &'static str) {
+        self.edges.insert(GraphEdge { from, to, label });
+    }
+
+    /// The node of set number `n`, added on first sight.
+    fn set(&mut self, n: SetNumber) -> usize {
+        if let Some(&node) = self.sets.get(&n.get()) {
+            return node;
+        }
+        let set = self.grammar.set_by_number(n);
+        let (name, label) = if is_internal(&set.name) {
+            let label = if set.sets.is_empty() {
+                self.tags_label(n)
+            } else {
+                set.name.clone()
+            };
+            (String::new(), label)
+        } else {
+            (set.name.clone(), set.name.clone())
+        };
+        let node = self.push(
+            NodeKind::Set,
+            format!("s{}", n.get()),
+            name,
+            label,
+            set.line,
+        );
+        self.sets.insert(n.get(), node);
+        self.pending_sets.push(n.get());
+        node
+    }
+
+    /// An inline set's tags, as `LIST` writes them.
+    fn tags_label(&self, n: SetNumber) -> String {
+        let set = self.grammar.set_by_number(n);
+        let mut out = Vec::new();
+        for trie in [&set.trie, &set.trie_special] {
+            for tags in trie_get_tags_ordered(trie, self.grammar) {
+                let tags: Vec<&str> = tags
+                    .iter()
+                    .map(|t| self.grammar.single_tags_list[t.0].tag.as_str())
+                    .collect();
+                out.push(format!("({})", tags.join(" ")));
+            }
+        }
+        out.join(" ")
+    }
+
+    /// `from` uses set `n`, unless `n` is no set.
+    fn set_edge(&mut self, from: usize, n: SetNumber, label: &'static str) {
+        if n.get() != 0 {
+            let to = self.set(n);
+            self.edge(from, to, label);
+        }
+    }
+
+    /// The sets and templates the test `ctx` and the tests linked to it use.
+    fn test(&mut self, from: usize, ctx: CtxId) {
+        let mut queue = VecDeque::from([ctx]);
+        while let Some(ctx) = queue.pop_front() {
+            let test = &self.grammar.contexts_arena[ctx.0];
+            if let Some(tmpl) = test.tmpl
+                && let Some(&to) = self.templates.get(&tmpl)
+            {
+                self.edge(from, to, "template");
+            }
+            self.set_edge(from, test.target, "context");
+            self.set_edge(from, test.barrier, "barrier");
+            self.set_edge(from, test.cbarrier, "barrier");
+            queue.extend(test.ors.iter().copied());
+            queue.extend(test.linked);
+        }
+    }
+
+    /// Add the members of every set seen so far, and of theirs.
+    fn finish_sets(&mut self) {
+        while let Some(n) = self.pending_sets.pop() {
+            let from = self.sets[&n];
+            let members = self.grammar.set_by_number(SetNumber(n)).sets.clone();
+            for member in members {
+                self.set_edge(from, SetNumber(member), "set");
+            }
+        }
+    }
+}
+
+/// The rules in number order.
+fn rule_ids(grammar: &Grammar) -> Vec<RuleId> {
+    (0..grammar.rule_by_number.capacity())
+        .filter(|&i| grammar.rule_by_number.try_get(i).is_some())
+        .map(RuleId)
+        .collect()
+}
+
+/// A rule's own name; the parser names unnamed rules `_R_…`.
+fn rule_name(name: &str) -> &str {
+    if name.starts_with("_R_") { "" } else { name }
+}
+
+impl GrammarGraph {
+    /// The graph of `grammar`, which must have been reindexed.
+    pub fn new(grammar: &Grammar) -> Self {
+        let mut b = Builder {
+            grammar,
+            nodes: Vec::new(),
+            edges: BTreeSet::new(),
+            sets: BTreeMap::new(),
+            templates: BTreeMap::new(),
+            rules: BTreeMap::new(),
+            pending_sets: Vec::new(),
+        };
+        let rules = rule_ids(grammar);
+        let sub_rules: BTreeSet<RuleId> = rules
+            .iter()
+            .flat_map(|r| grammar.rule_by_number[r.0].sub_rules.iter().copied())
+            .collect();
+
+        // Sections, in the order they run.
+        let mut sections: Vec<i32> = rules
+            .iter()
+            .filter(|r| !sub_rules.contains(r))
+            .map(|r| grammar.rule_by_number[r.0].section)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        sections.sort_by_key(|&s| match s {
+            -1 => (0, 0),
+            -2 => (2, 0),
+            -3 => (3, 0),
+            s => (1, s),
+        });
+        let mut section_nodes = BTreeMap::new();
+        for s in sections {
+            let (id, label) = match s {
+                -1 => ("before-sections".to_string(), "BEFORE-SECTIONS".to_string()),
+                -2 => ("after-sections".to_string(), "AFTER-SECTIONS".to_string()),
+                -3 => ("null-section".to_string(), "NULL-SECTION".to_string()),
+                s => (format!("section{}", s + 1), format!("SECTION {}", s + 1)),
+            };
+            let node = b.push(NodeKind::Section, id, String::new(), label, 0);
+            section_nodes.insert(s, node);
+        }
+
+        for &r in &rules {
+            let rule = &grammar.rule_by_number[r.0];
+            let name = rule_name(&rule.name).to_string();
+            let keyword = KEYWORDS_STR[rule.r#type as usize];
+            let label = if name.is_empty() {
+                format!("{keyword}, line {}", rule.line)
+            } else {
+                format!("{keyword}:{name}, line {}", rule.line)
+            };
+            let node = b.push(
+                NodeKind::Rule,
+                format!("r{}", rule.number),
+                name,
+                label,
+                rule.line,
+            );
+            b.rules.insert(rule.number, node);
+        }
+
+        // Anchors that are not just a rule's own name.
+        let mut anchors: Vec<(u32, &str, u32)> = grammar
+            .anchors
+            .iter()
+            .map(|&(hash, at)| {
+                let tag = grammar.single_tags.find(hash).get().1;
+                (at, grammar.single_tags_list[tag.0].tag.as_str(), hash)
+            })
+            .collect();
+        anchors.sort();
+        let mut anchor_nodes = BTreeMap::new();
+        let mut own_anchors = 0;
+        for (at, name, hash) in anchors {
+            let rule = grammar.rule_by_number.try_get(at);
+            if let Some(rule) = rule
+                && rule.name == name
+            {
+                anchor_nodes.insert(hash, b.rules[&at]);
+                continue;
+            }
+            let id = format!("a{own_anchors}");
+            own_anchors += 1;
+            let node = b.push(NodeKind::Anchor, id, name.to_string(), name.to_string(), 0);
+            anchor_nodes.insert(hash, node);
+            if rule.is_some() {
+                b.edge(node, b.rules[&at], "anchor");
+            }
+        }
+
+        for (i, (hash, &ctx)) in grammar.templates.iter().enumerate() {
+            let name = match grammar.template_names.get(hash) {
+                Some(name) => name.clone(),
+                None => format!("T:{}", grammar.contexts_arena[ctx.0].hash),
+            };
+            let line = grammar.contexts_arena[ctx.0].line;
+            let node = b.push(
+                NodeKind::Template,
+                format!("t{i}"),
+                name.clone(),
+                name,
+                line,
+            );
+            b.templates.insert(ctx, node);
+        }
+        let templates: Vec<(CtxId, usize)> = b.templates.iter().map(|(&c, &n)| (c, n)).collect();
+        for (ctx, node) in templates {
+            b.test(node, ctx);
+        }
+
+        for &r in &rules {
+            let rule = &grammar.rule_by_number[r.0];
+            let node = b.rules[&rule.number];
+            if !sub_rules.contains(&r) {
+                b.edge(section_nodes[&rule.section], node, "rule");
+            }
+            b.set_edge(node, rule.target, "target");
+            if rule.r#type == Keywords::KJump {
+                let to = rule
+                    .maplist
+                    .and_then(|ml| grammar.get_tag_list_any_ret(ml).first().copied())
+                    .map(|t| &grammar.single_tags_list[t.0])
+                    .filter(|t| !t.r#type.intersects(T_SPECIAL))
+                    .and_then(|t| anchor_nodes.get(&t.hash.get()));
+                if let Some(&to) = to {
+                    b.edge(node, to, "jump");
+                }
+            } else if let Some(ml) = rule.maplist {
+                b.set_edge(node, grammar.sets_list[ml.0].number, "operand");
+            }
+            if let Some(sl) = rule.sublist {
+                b.set_edge(node, grammar.sets_list[sl.0].number, "operand");
+            }
+            b.set_edge(node, rule.childset1, "operand");
+            b.set_edge(node, rule.childset2, "operand");
+            for &ctx in rule
+                .tests
+                .iter()
+                .chain(&rule.dep_tests)
+                .chain(&rule.dep_target)
+            {
+                b.test(node, ctx);
+            }
+            for sub in &rule.sub_rules {
+                let to = b.rules[&grammar.rule_by_number[sub.0].number];
+                b.edge(node, to, "with");
+            }
+        }
+
+        // Sets the grammar defines but nothing uses.
+        for n in 1..grammar.sets_list_order.len() {
+            let set = grammar.set_by_number(SetNumber(n as u32));
+            if set.line != 0 && !is_internal(&set.name) {
+                b.set(SetNumber(n as u32));
+            }
+        }
+        b.finish_sets();
+
+        GrammarGraph {
+            nodes: b.nodes,
+            edges: b.edges.into_iter().collect(),
+        }
+    }
+
+    /// The node called `name`: a set, then a named rule, a template, an
+    /// anchor. `line:N` is the first rule on grammar line `N`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        if let Some(line) = name
+            .strip_prefix("line:")
+            .and_then(|l| l.parse::<u32>().ok())
+        {
+            return self
+                .nodes
+                .iter()
+                .position(|n| n.kind == NodeKind::Rule && n.line == line);
+        }
+        [
+            NodeKind::Set,
+            NodeKind::Rule,
+            NodeKind::Template,
+            NodeKind::Anchor,
+        ]
+        .into_iter()
+        .find_map(|kind| {
+            self.nodes
+                .iter()
+                .position(|n| n.kind == kind && n.name == name)
+        })
+    }
+
+    /// The part of the graph that node `root` uses, directly or not, and that
+    /// uses it: for a set, the sets it is built from and the sets, templates,
+    /// rules and sections over it; for a rule, its sets, templates and jumps
+    /// and its section.
+    pub fn closure(&self, root: usize) -> GrammarGraph {
+        let mut uses = vec![Vec::new(); self.nodes.len()];
+        let mut used_by = vec![Vec::new(); self.nodes.len()];
+        for e in &self.edges {
+            uses[e.from].push(e.to);
+            used_by[e.to].push(e.from);
+        }
+        let mut keep = BTreeSet::from([root]);
+        for adjacent in [&uses, &used_by] {
+            let mut seen = BTreeSet::from([root]);
+            let mut queue = VecDeque::from([root]);
+            while let Some(cur) = queue.pop_front() {
+                for &next in &adjacent[cur] {
+                    if seen.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+            keep.extend(seen);
+        }
+        let index: BTreeMap<usize, usize> = keep.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        GrammarGraph {
+            nodes: keep.iter().map(|&n| self.nodes[n].clone()).collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter_map(|e| {
+                    Some(GraphEdge {
+                        from: *index.get(&e.from)?,
+                        to: *index.get(&e.to)?,
+                        label: e.label,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// The graph as a Graphviz `digraph`, one shape per kind of node.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph grammar {\n");
+        for n in &self.nodes {
+            let _ = writeln!(
+                out,
+                "\t{} [label=\"{}\" shape={}];",
+                n.id,
+                dot_escape(&n.label),
+                n.kind.dot_shape()
+            );
+        }
+        for e in &self.edges {
+            let _ = writeln!(
+                out,
+                "\t{} -> {} [label=\"{}\"];",
+                self.nodes[e.from].id, self.nodes[e.to].id, e.label
+            );
+        }
+        out += "}\n";
+        out
+    }
+
+    /// The graph as JSON:
+    /// `{"nodes":[{"id","kind","name","label","line"}],"edges":[{"from","to","label"}]}`,
+    /// the edges naming their nodes by `id`.
+    pub fn to_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                json!({
+                    "id": n.id,
+                    "kind": n.kind.as_str(),
+                    "name": n.name,
+                    "label": n.label,
+                    "line": n.line,
+                })
+            })
+            .collect();
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|e| {
+                json!({
+                    "from": self.nodes[e.from].id,
+                    "to": self.nodes[e.to].id,
+                    "label": e.label,
+                })
+            })
+            .collect();
+        json!({"nodes": nodes, "edges": edges})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textual_parser::TextualParser;
+
+    fn graph(text: &str) -> GrammarGraph {
+        let mut parser = TextualParser::new(Grammar::default(), false);
+        parser.parse_grammar_utf8(text.as_bytes()).unwrap();
+        let mut grammar = parser.grammar;
+        let _ = grammar.reindex(false, false).unwrap();
+        GrammarGraph::new(&grammar)
+    }
+
+    fn arcs(graph: &GrammarGraph) -> Vec<(String, String, &'static str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let label = |n: usize| match graph.nodes[n].name.as_str() {
+                    "" => graph.nodes[n].label.clone(),
+                    name => name.to_string(),
+                };
+                (label(e.from), label(e.to), e.label)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sets_templates_rules_and_anchors_are_linked() {
+        let graph = graph(
+            "DELIMITERS = \"<.>\" ;\nLIST N = n ;\nLIST V = v ;\nSET NV = N - V ;\n\
+             LIST Det = det ;\nTEMPLATE next-noun = (1 N) ;\nSECTION\n\
+             SELECT:pick Det (T:next-noun) ;\nJUMP (done) (*) (0 V) ;\nANCHOR done ;\n\
+             REMOVE V (-1* Det BARRIER NV) ;\nJUMP (pick) (*) ;\n",
+        );
+        let arcs = arcs(&graph);
+        for arc in [
+            ("SECTION 1", "pick", "rule"),
+            ("pick", "Det", "target"),
+            ("pick", "next-noun", "template"),
+            ("next-noun", "N", "context"),
+            ("JUMP, line 9", "done", "jump"),
+            ("done", "REMOVE, line 11", "anchor"),
+            ("REMOVE, line 11", "NV", "barrier"),
+            ("NV", "V", "set"),
+            ("JUMP, line 12", "pick", "jump"),
+        ] {
+            let arc = (arc.0.to_string(), arc.1.to_string(), arc.2);
+            assert!(arcs.contains(&arc), "{arc:?} in {arcs:?}");
+        }
+
+        let det = graph.closure(graph.find("Det").unwrap());
+        let names: BTreeSet<&str> = det.nodes.iter().map(|n| n.label.as_str()).collect();
+        assert!(names.contains("SELECT:pick, line 8"));
+        assert!(names.contains("REMOVE, line 11"));
+        assert!(!names.contains("N"), "{names:?}");
+        let jump = graph.find("line:12").unwrap();
+        assert_eq!(graph.nodes[jump].label, "JUMP, line 12");
+        let nv = graph.closure(graph.find("NV").unwrap());
+        // N and V; REMOVE, its section, and the anchor and JUMP before it.
+        let labels: Vec<&str> = nv.nodes.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "SECTION 1",
+                "JUMP, line 9",
+                "REMOVE, line 11",
+                "done",
+                "N",
+                "V",
+                "NV"
+            ]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! ADDED — no C++ analog. Reloading a grammar when its files change, for a
+//! long-running applicator such as `vislcg3 --watch`.
+//!
+//! A [`GrammarWatch`] is the list of files one load read — for a textual
+//! grammar, the name of every [`ParseSource`] the parse recorded, so each
+//! `INCLUDE`d file is watched along with the grammar that includes it — with
+//! the modification time and length each had when last looked at.
+//! [`watch_grammar`] polls it on a thread of its own and, when something
+//! changed, loads the grammar again and [offers](GrammarSwap::offer) the result
+//! to the running applicator, which picks it up between windows (see
+//! [`crate::grammar_applicator::reload`]).
+//!
+//! Polling rather than a file-system notification API keeps this to `std`, and
+//! it sees what an editor's rename-over-save does to a file just as well: the
+//! path is stamped anew on each look, whatever inode it now names.
+//!
+//! A load that fails leaves the running grammar alone. The loader reports the
+//! failure — [`load_grammar`] returns it, for the caller to pass to
+//! [`crate::error::report_cli`], which renders a parse failure through
+//! [`crate::diagnostics`] — and the files are watched as they were, so the next
+//! save is tried again.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::error::{Cg3Error, GrammarError, ParseSource};
+use crate::grammar::Grammar;
+use crate::grammar_applicator::GrammarApplicator;
+use crate::grammar_applicator::reload::GrammarSwap;
+
+/// How often `vislcg3 --watch` looks at the grammar's files.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a file looked like: modification time and length, or `None` when it
+/// could not be read.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// The files a grammar was loaded from, and how each looked at the last look.
+#[derive(Debug, Clone)]
+pub struct GrammarWatch {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl GrammarWatch {
+    /// Watch `paths`, as they are now. A path given twice is watched once.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut files: Vec<(PathBuf, Stamp)> = Vec::new();
+        for path in paths {
+            if !files.iter().any(|(seen, _)| *seen == path) {
+                let stamp = stamp(&path);
+                files.push((path, stamp));
+            }
+        }
+        GrammarWatch { files }
+    }
+
+    /// Watch every source a textual parse read — `TextualParser::sources`.
+    pub fn of_sources(sources: &[ParseSource]) -> Self {
+        Self::new(sources.iter().map(|s| PathBuf::from(&s.name)))
+    }
+
+    /// The watched files.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Whether any watched file changed, appeared or vanished since the last
+    /// look. Every file is looked at again, so a change is reported once.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last) in &mut self.files {
+            let now = stamp(path);
+            if now != *last {
+                *last = now;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Load the grammar at `path` — textual or binary — into an applicator set up
+/// with `options`, and the watch over the files it was read from.
+///
+/// The library's plain load: none of the `vislcg3` extras (`--nrules`, the
+/// grammar's own `CMDARGS`, `--prefix`), which a caller wanting them does in
+/// its own loader.
+pub fn load_grammar(
+    path: &str,
+    options: &crate::options::OptionsTable,
+) -> Result<(GrammarApplicator, GrammarWatch), Cg3Error> {
+    let buffer =
+        crate::compression::read_file(path).map_err(|source| GrammarError::Unreadable {
+            path: path.to_string(),
+            source,
+        })?;
+    let (mut grammar, watch) = if buffer
+        .first_chunk::<4>()
+        .is_some_and(|&head| crate::inlines::is_cg3b(head))
+    {
+        let mut parser = crate::binary_grammar::BinaryGrammar::new(Grammar::default());
+        parser.parse_grammar_buffer(&buffer)?;
+        (parser.grammar, GrammarWatch::new([PathBuf::from(path)]))
+    } else {
+        let mut parser = crate::textual_parser::TextualParser::new(Grammar::default(), false);
+        parser.parse_grammar_named(&buffer, path)?;
+        let watch = GrammarWatch::of_sources(&parser.sources());
+        (parser.grammar, watch)
+    };
+    // `Done` either way: neither dump is asked for.
+    let _ = grammar.reindex(false, false)?;
+    let mut applicator = GrammarApplicator::new(grammar);
+    applicator.set_grammar()?;
+    applicator.set_options(options)?;
+    Ok((applicator, watch))
+}
+
+/// Poll `watch` every `interval` on a new thread; when a file changed, call
+/// `reload` and offer what it loaded through `swap`, then watch the files that
+/// load read. `reload` reports its own failures and returns `None`; the
+/// running grammar then stays.
+///
+/// The thread ends once nothing else holds `swap` — the applicator it was
+/// attached to has been dropped.
+pub fn watch_grammar<F>(
+    mut watch: GrammarWatch,
+    swap: GrammarSwap,
+    interval: Duration,
+    mut reload: F,
+) -> std::thread::JoinHandle<()>
+where
+    F: FnMut() -> Option<(GrammarApplicator, GrammarWatch)> + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while !swap.is_orphaned() {
+            std::thread::sleep(interval);
+            if !watch.changed() {
+                continue;
+            }
+            match reload() {
+                Some((replacement, files)) => {
+                    swap.offer(replacement);
+                    watch = files;
+                    tracing::info!("Grammar reloaded.");
+                }
+                None => tracing::warn!("Grammar not reloaded; the previous one stays in use."),
+            }
+        }
+    })
+}
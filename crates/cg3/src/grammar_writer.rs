@@ -58,6 +58,7 @@ use crate::contextual_test::{
 use crate::flat_unordered_set::Uint32FlatHashSet;
 use crate::grammar::Grammar;
 use crate::inlines::{is_internal, si32};
+use crate::normalization::Normalization;
 use crate::rule::{FLAGS_COUNT, RF_AFTER, RF_BEFORE, RF_WITHCHILD, Rule};
 use crate::set::ST_ORDERED;
 use crate::strings::Keywords;
@@ -418,6 +419,14 @@ impl GrammarWriter {
         if grammar.addcohort_attach {
             w!(output, "OPTIONS += addcohort-attach ;\n");
         }
+        match grammar.normalization {
+            Normalization::None => {}
+            Normalization::Nfc => w!(output, "OPTIONS += normalize-nfc ;\n"),
+            Normalization::Nfd => w!(output, "OPTIONS += normalize-nfd ;\n"),
+        }
+        if let Some(option) = grammar.case_locale.option() {
+            w!(output, "OPTIONS += {option} ;\n");
+        }
 
         w!(output, "\n");
 
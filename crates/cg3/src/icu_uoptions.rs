@@ -45,6 +45,13 @@ fn at(token: &[UChar], k: usize) -> UChar {
     if k < token.len() { token[k] } else { '\0' }
 }
 
+/// `option->value = ...`, also appending to the port-only `values` so a
+/// repeated option keeps every argument it was given.
+fn set_value(option: &mut UOption, value: String) {
+    option.values.push(value.clone());
+    option.value = value;
+}
+
 // [spec:cg3:def:icu-uoptions.u-parse-args-fn]
 // [spec:cg3:sem:icu-uoptions.u-parse-args-fn]
 // faithful port: the option searches scan `options[0..optionCount]` (the passed
@@ -98,7 +105,7 @@ pub fn u_parse_args(
                         {
                             // argument in the next argv[], and there is not an option in there
                             i += 1;
-                            options[opt].value = argv[i as usize].iter().collect();
+                            set_value(&mut options[opt], argv[i as usize].iter().collect());
                         } else if options[opt].has_arg == UOPT_REQUIRES_ARG {
                             // there is no argument, but one is required: return with error
                             return -i;
@@ -124,7 +131,7 @@ pub fn u_parse_args(
                         // parse the argument for the option, if any
                         if at(&argv[iu], arg_off) != '\0' {
                             // argument following in the same argv[]
-                            options[opt].value = argv[iu][arg_off..].iter().collect();
+                            set_value(&mut options[opt], argv[iu][arg_off..].iter().collect());
                             // do not process the rest of this arg as option letters
                             break;
                         } else if i + 1 < argc
@@ -133,7 +140,7 @@ pub fn u_parse_args(
                         {
                             // argument in the next argv[], and there is not an option in there
                             i += 1;
-                            options[opt].value = argv[i as usize].iter().collect();
+                            set_value(&mut options[opt], argv[i as usize].iter().collect());
                             // this break is redundant because we know that *arg==0
                             break;
                         } else if options[opt].has_arg == UOPT_REQUIRES_ARG {
@@ -182,6 +189,7 @@ mod tests {
             description: String::new(),
             does_occur: false,
             value: String::new(),
+            values: Vec::new(),
         }
     }
 
@@ -261,4 +269,22 @@ mod tests {
         assert_eq!(remaining, 2);
         assert_eq!(argv3[1], tok("-v"));
     }
+
+    // A repeated option keeps the last argument in `value` (the C++ behaviour)
+    // and every argument, in order, in `values`.
+    #[test]
+    fn repeated_option_records_every_value() {
+        let mut options = [opt("grammar", 'g', UOPT_REQUIRES_ARG)];
+        let mut argv = vec![
+            tok("prog"),
+            tok("-g"),
+            tok("a.cg3"),
+            tok("--grammar"),
+            tok("b.cg3"),
+            tok("-gc.cg3"),
+        ];
+        u_parse_args(argv.len() as i32, &mut argv, 1, &mut options);
+        assert_eq!(options[0].value, "c.cg3");
+        assert_eq!(options[0].values, ["a.cg3", "b.cg3", "c.cg3"]);
+    }
 }
@@ -141,8 +141,9 @@ pub fn hash_value_ustring(str: &str, hash: u32) -> u32 {
     if h == 0 {
         h = CG3_HASH_SEED;
     }
-    let units: Vec<u16> = str.encode_utf16().collect();
-    super_fast_hash_u16(&units, h)
+    // Streamed twice (length, then units) rather than collected: tag interning
+    // hashes every input tag, and this must not allocate per call.
+    super_fast_hash_units(str.encode_utf16(), str.encode_utf16().count(), h)
 }
 
 /// C++ `SuperFastHash(const UChar* data, size_t len, uint32_t hash)` — the
@@ -150,24 +151,28 @@ pub fn hash_value_ustring(str: &str, hash: u32) -> u32 {
 /// loop iteration; `rem = len & 1` single-unit tail; same avalanche + reserved
 /// remap as the byte overload. `hash == 0` degenerates to `len` (as in C++).
 pub fn super_fast_hash_u16(data: &[u16], hash: u32) -> u32 {
-    let len = data.len();
+    super_fast_hash_units(data.iter().copied(), data.len(), hash)
+}
+
+/// [`super_fast_hash_u16`] over a stream of exactly `len` code units. Not a
+/// manifest symbol — lets [`hash_value_ustring`] hash without a `Vec<u16>`.
+fn super_fast_hash_units(mut data: impl Iterator<Item = u16>, len: usize, hash: u32) -> u32 {
     let mut hash = if hash == 0 { len as u32 } else { hash };
     if len == 0 {
         return 0;
     }
     let rem = len & 1;
-    let mut i = 0usize;
     let mut n = len >> 1;
     while n > 0 {
-        hash = hash.wrapping_add(data[i] as u32);
-        let tmp = ((data[i + 1] as u32) << 11) ^ hash;
+        let (a, b) = (data.next().unwrap_or(0), data.next().unwrap_or(0));
+        hash = hash.wrapping_add(a as u32);
+        let tmp = ((b as u32) << 11) ^ hash;
         hash = (hash << 16) ^ tmp;
-        i += 2;
         hash = hash.wrapping_add(hash >> 11);
         n -= 1;
     }
     if rem == 1 {
-        hash = hash.wrapping_add(data[i] as u32);
+        hash = hash.wrapping_add(data.next().unwrap_or(0) as u32);
         hash ^= hash << 11;
         hash = hash.wrapping_add(hash >> 17);
     }
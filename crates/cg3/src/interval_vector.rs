@@ -195,12 +195,25 @@ impl<'a, T: IntervalScalar> Iterator for ConstIterator<'a, T> {
 /// C++ `class interval_vector<T>` (`src/interval_vector.hpp`): a set of `T`
 /// stored as a sorted list of merged `[lb, ub]` intervals. Ported bug-for-bug,
 /// including the `_size` drift.
-#[derive(Clone)]
 pub struct IntervalVector<T: IntervalScalar = u32> {
     elements: Vec<Interval<T>>,
     _size: usize,
 }
 
+impl<T: IntervalScalar> Clone for IntervalVector<T> {
+    fn clone(&self) -> Self {
+        IntervalVector {
+            elements: self.elements.clone(),
+            _size: self._size,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.elements.clone_from(&source.elements);
+        self._size = source._size;
+    }
+}
+
 impl<T: IntervalScalar> IntervalVector<T> {
     /// `interval_vector() : _size(0)`.
     pub fn new() -> Self {
@@ -452,6 +465,14 @@ impl<T: IntervalScalar> IntervalVector<T> {
     /// `_size` correct.
     pub fn intersect(&self, o: &IntervalVector<T>) -> IntervalVector<T> {
         let mut rv: IntervalVector<T> = IntervalVector::new();
+        self.intersect_into(o, &mut rv);
+        rv
+    }
+
+    /// `intersect`, writing into `rv` (cleared first) so a recycled vector
+    /// keeps its capacity.
+    pub fn intersect_into(&self, o: &IntervalVector<T>, rv: &mut IntervalVector<T>) {
+        rv.clear();
         if !self.empty() && !o.empty() {
             let ae = self.elements.len();
             let be = o.elements.len();
@@ -490,7 +511,6 @@ impl<T: IntervalScalar> IntervalVector<T> {
                 }
             }
         }
-        rv
     }
 }
 
@@ -33,9 +33,15 @@ use serde_json::{Map, Value, json};
 
 use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::grammar::Grammar;
-use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::grammar_applicator::weights::{own_weight, weight_key};
+use crate::grammar_applicator::{Engine, GrammarApplicator, StreamFormatKind};
+use crate::jsonl_schema::{HEADER_KEY, SCHEMA_VERSION, supported_version};
+use crate::runtime_diagnostics::{InputExcerpt, RuntimeDiagnosticKind};
 use crate::sorted_vector::Uint32SortedVector;
+use crate::stream_command::StreamCommand;
+use crate::strings::{STR_CMD_FLUSH, STR_CMD_REMVAR, STR_CMD_SETVAR};
 use crate::tag::{T_DEPENDENCY, T_MAPPING, T_RELATION, TagList};
+use crate::text_offsets::TextOffsets;
 use crate::types::{TagHash, UString, UStringView};
 
 /// C++ `grammar->single_tags[hash]` (operator[]) — resolve a hash to its
@@ -52,17 +58,6 @@ fn tag_by_hash(grammar: &Grammar, hash: TagHash) -> TagId {
     }
 }
 
-// C++ stream-command string constants (`Strings.hpp`). Only SETVAR/REMVAR/FLUSH
-// are defined in `grammar_applicator::core` (privately); JsonlApplicator also
-// needs IGNORE/RESUME/EXIT, so the full set is declared here verbatim from the
-// C++ `Strings.cpp` `STR_CMD_*` table.
-const STR_CMD_FLUSH: &str = "<STREAMCMD:FLUSH>";
-const STR_CMD_EXIT: &str = "<STREAMCMD:EXIT>";
-const STR_CMD_IGNORE: &str = "<STREAMCMD:IGNORE>";
-const STR_CMD_RESUME: &str = "<STREAMCMD:RESUME>";
-const STR_CMD_SETVAR: &str = "<STREAMCMD:SETVAR:";
-const STR_CMD_REMVAR: &str = "<STREAMCMD:REMVAR:";
-
 const CT_REMOVED: crate::cohort::CohortType = crate::cohort::CT_REMOVED;
 
 // [spec:cg3:def:jsonl-applicator.cg3.ustring-to-utf8-fn]
@@ -95,6 +90,12 @@ pub fn json_to_ustring(val: &Value) -> UString {
 /// members (the C++ subclass adds none), so `base` is the only field.
 pub struct JsonlApplicator<'a> {
     pub base: &'a mut GrammarApplicator,
+    /// ADDED: hold every line to the schema and stop at the first that breaks
+    /// it, rather than skipping what cannot be read (see
+    /// [`crate::jsonl_schema`]).
+    pub strict: bool,
+    /// ADDED: the line being read, for quoting malformed input.
+    line: String,
 }
 
 impl<'a> JsonlApplicator<'a> {
@@ -106,7 +107,11 @@ impl<'a> JsonlApplicator<'a> {
     /// grammar); `new` just wraps it. (The C++ explicit empty destructor exists
     /// only to anchor the vtable and has no Rust analog.)
     pub fn new(base: &'a mut GrammarApplicator) -> Self {
-        JsonlApplicator { base }
+        JsonlApplicator {
+            base,
+            strict: false,
+            line: String::new(),
+        }
     }
 
     // =======================================================================
@@ -118,6 +123,7 @@ impl<'a> JsonlApplicator<'a> {
     /// C++ `Reading* parseJsonReading(const json::Value& reading_obj, Cohort*
     /// parentCohort)`. Parses one reading object `{"l", "ts", "s"}`, recursing on
     /// subreadings; returns the new `ReadingId` or `None` on a non-object input.
+    /// ADDED: a `"wt"` number becomes a weight tag when `"ts"` has none.
     fn parse_json_reading(
         &mut self,
         reading_obj: &Value,
@@ -126,10 +132,11 @@ impl<'a> JsonlApplicator<'a> {
         let obj = match reading_obj {
             Value::Object(m) => m,
             _ => {
-                tracing::error!(
-                    "Error: Expected reading object, but got different type on line {}.",
-                    self.base.doc.num_lines
+                let kind = RuntimeDiagnosticKind::malformed(
+                    "Expected reading object, but got different type",
+                    "",
                 );
+                self.note_input(kind, None, 1)?;
                 return Ok(None);
             }
         };
@@ -158,16 +165,12 @@ impl<'a> JsonlApplicator<'a> {
                 let tid = self.base.add_tag(&base_tag, crate::tag::TagType::empty())?;
                 self.base.engine().add_tag_to_reading(c_reading, tid)?;
             } else {
-                tracing::warn!(
-                    "Warning: Empty 'l' (baseform) in reading on line {}.",
-                    self.base.doc.num_lines
-                );
+                let kind = RuntimeDiagnosticKind::skipped("Empty 'l' (baseform) in reading", "");
+                self.note_input(kind, Some(parent_cohort), 1)?;
             }
         } else {
-            tracing::warn!(
-                "Warning: Reading missing 'l' (baseform) on line {}.",
-                self.base.doc.num_lines
-            );
+            let kind = RuntimeDiagnosticKind::skipped("Reading missing 'l' (baseform)", "");
+            self.note_input(kind, Some(parent_cohort), 1)?;
         }
 
         // Tags ("ts").
@@ -198,6 +201,23 @@ impl<'a> JsonlApplicator<'a> {
             }
         }
 
+        // ADDED: weight ("wt"), unless the tags already carried one.
+        if let Some(wt) = obj.get("wt").and_then(Value::as_f64) {
+            let key = weight_key(&self.base.cfg.weight_tag);
+            if own_weight(
+                &self.base.doc.store.readings,
+                &self.base.grammar,
+                c_reading,
+                key,
+            )
+            .is_none()
+            {
+                let wtag = format!("<{}:{wt}>", self.base.cfg.weight_tag);
+                let tid = self.base.add_tag(&wtag, crate::tag::TagType::empty())?;
+                self.base.engine().add_tag_to_reading(c_reading, tid)?;
+            }
+        }
+
         // Subreading ("s").
         if let Some(sub_reading_val) = obj.get("s") {
             if sub_reading_val.is_object() {
@@ -205,16 +225,16 @@ impl<'a> JsonlApplicator<'a> {
                 if let Some(sub) = sub {
                     self.base.doc.store.readings.get_mut(c_reading.0).next = Some(sub);
                 } else {
-                    tracing::error!(
-                        "Error: Failed to parse subreading object on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind =
+                        RuntimeDiagnosticKind::malformed("Failed to parse subreading object", "");
+                    self.note_input(kind, Some(parent_cohort), 1)?;
                 }
             } else {
-                tracing::warn!(
-                    "Warning: Value for 's' (sub_reading) is not an object on line {}. Skipping.",
-                    self.base.doc.num_lines
+                let kind = RuntimeDiagnosticKind::skipped(
+                    "Value for 's' (sub_reading) is not an object",
+                    ". Skipping",
                 );
+                self.note_input(kind, Some(parent_cohort), 1)?;
             }
         }
 
@@ -230,10 +250,13 @@ impl<'a> JsonlApplicator<'a> {
         {
             let wf_hash = self.base.grammar.single_tags_list.get(wordform.0).hash;
             self.base.doc.store.readings.get_mut(c_reading.0).baseform = Some(wf_hash);
-            tracing::warn!(
-                "Warning: Reading on line {} ended up with no baseform. Using wordform.",
-                self.base.doc.num_lines
-            );
+            self.note_input(
+                RuntimeDiagnosticKind::NoBaseform {
+                    format: StreamFormatKind::Jsonl,
+                },
+                Some(parent_cohort),
+                1,
+            )?;
         }
 
         Ok(Some(c_reading))
@@ -263,10 +286,11 @@ impl<'a> JsonlApplicator<'a> {
         let wform_str = if let Some(w) = obj.get("w") {
             json_to_ustring(w)
         } else {
-            tracing::warn!(
-                "Warning: JSON cohort on line {} missing 'w' (wordform). Using empty.",
-                self.base.doc.num_lines
+            let kind = RuntimeDiagnosticKind::skipped(
+                "JSON cohort",
+                " missing 'w' (wordform). Using empty",
             );
+            self.note_input(kind, Some(c_cohort), 1)?;
             UString::new()
         };
         let mut wform_tag = UString::new();
@@ -321,10 +345,11 @@ impl<'a> JsonlApplicator<'a> {
         if let Some(Value::Array(readings_arr)) = obj.get("rs") {
             for reading_val in readings_arr {
                 if !reading_val.is_object() {
-                    tracing::warn!(
-                        "Warning: Non-object found in 'rs' (readings) array on line {}. Skipping.",
-                        self.base.doc.num_lines
+                    let kind = RuntimeDiagnosticKind::skipped(
+                        "Non-object found in 'rs' (readings) array",
+                        ". Skipping",
                     );
+                    self.note_input(kind, Some(c_cohort), 1)?;
                     continue;
                 }
                 let c_reading = self.parse_json_reading(reading_val, c_cohort)?;
@@ -332,10 +357,8 @@ impl<'a> JsonlApplicator<'a> {
                     crate::cohort::append_reading(&mut self.base.doc.store, c_cohort, c_reading);
                     self.base.doc.num_readings = self.base.doc.num_readings.wrapping_add(1);
                 } else {
-                    tracing::error!(
-                        "Error: Failed to parse main reading on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind = RuntimeDiagnosticKind::malformed("Failed to parse main reading", "");
+                    self.note_input(kind, Some(c_cohort), 1)?;
                 }
             }
         }
@@ -380,6 +403,19 @@ impl<'a> JsonlApplicator<'a> {
                 };
         }
 
+        // ADDED: source offsets ("ob" bytes / "ou" UTF-16), both or neither.
+        if let (Some((byte_start, byte_end)), Some((utf16_start, utf16_end))) = (
+            obj.get("ob").and_then(as_span),
+            obj.get("ou").and_then(as_span),
+        ) {
+            self.base.doc.store.cohorts.get_mut(c_cohort.0).offsets = Some(TextOffsets {
+                byte_start,
+                byte_end,
+                utf16_start,
+                utf16_end,
+            });
+        }
+
         // Deleted readings ("drs").
         if let Some(Value::Array(drs)) = obj.get("drs") {
             for dr_val in drs {
@@ -397,10 +433,9 @@ impl<'a> JsonlApplicator<'a> {
                         .deleted
                         .push(del_r);
                 } else {
-                    tracing::error!(
-                        "Error: Failed to parse deleted reading on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind =
+                        RuntimeDiagnosticKind::malformed("Failed to parse deleted reading", "");
+                    self.note_input(kind, Some(c_cohort), 1)?;
                 }
             }
         }
@@ -495,6 +530,7 @@ impl<'a> JsonlApplicator<'a> {
             }
 
             self.base.doc.num_lines = self.base.doc.num_lines.wrapping_add(1);
+            self.line.clone_from(&line_str);
 
             // Skip empty / all-whitespace lines.
             if line_str.is_empty()
@@ -507,36 +543,70 @@ impl<'a> JsonlApplicator<'a> {
 
             let doc: Value = match serde_json::from_str(&line_str) {
                 Ok(v) => v,
+                Err(e) if self.strict => {
+                    return Err(self.invalid(String::new(), format!("not valid JSON: {e}")));
+                }
                 Err(e) => {
-                    tracing::warn!(
-                        "Warning: Failed to parse JSON on line {}: {} (offset {}). Skipping line.",
-                        self.base.doc.num_lines,
-                        e,
-                        e.column()
+                    let kind = RuntimeDiagnosticKind::skipped(
+                        "Failed to parse JSON",
+                        &format!(": {e} (offset {}). Skipping line", e.column()),
                     );
+                    // serde_json counts the column in bytes.
+                    let column = line_str
+                        .get(..e.column().saturating_sub(1))
+                        .map_or(1, |s| s.chars().count() + 1);
+                    self.note_input(kind, None, column as u32)?;
                     continue;
                 }
             };
 
+            // ADDED: strict mode holds every line to the schema.
+            if self.strict
+                && let Err(v) = crate::jsonl_schema::validate(&doc)
+            {
+                return Err(self.invalid(v.pointer, v.problem));
+            }
+
             let obj = match &doc {
                 Value::Object(m) => m,
                 _ => {
-                    tracing::warn!(
-                        "Warning: JSON on line {} is not an object. Skipping line.",
-                        self.base.doc.num_lines
-                    );
+                    let kind =
+                        RuntimeDiagnosticKind::skipped("JSON", " is not an object. Skipping line");
+                    self.note_input(kind, None, 1)?;
                     continue;
                 }
             };
 
+            // ADDED: the schema version marker.
+            if let Some(version) = obj.get(HEADER_KEY) {
+                if let Some(Err(problem)) = version.as_u64().map(supported_version) {
+                    let kind = RuntimeDiagnosticKind::skipped(
+                        "Header",
+                        &format!(": {problem}. Reading on"),
+                    );
+                    // The marker's key, or the line's start when escapes hide it.
+                    let column = self
+                        .line
+                        .find(&format!("\"{HEADER_KEY}\""))
+                        .map_or(1, |at| self.line[..at].chars().count() + 1);
+                    self.note_input(kind, None, column as u32)?;
+                }
+                continue;
+            }
+
             // Command handling.
             if let Some(cmd_v) = obj.get("cmd") {
                 let cmd_ustr = json_to_ustring(cmd_v);
                 if !cmd_ustr.is_empty() {
-                    if cmd_ustr == STR_CMD_FLUSH {
-                        // verbose Info line: deferred.
+                    // ADDED: parsed as in every other format (see
+                    // `crate::stream_command`).
+                    let command = StreamCommand::parse(&cmd_ustr);
+                    if let Some(cmd @ (StreamCommand::Flush | StreamCommand::GetVars)) = &command {
+                        // verbose Info line: deferred. ADDED: GETVARS drains
+                        // the same way but keeps the variables.
+                        let flush = *cmd == StreamCommand::Flush;
                         let back_swindow = self.base.doc.stream.back();
-                        if let Some(bsw) = back_swindow {
+                        if flush && let Some(bsw) = back_swindow {
                             self.base
                                 .doc
                                 .store
@@ -574,7 +644,7 @@ impl<'a> JsonlApplicator<'a> {
                         self.base.engine().shuffle_windows_down();
                         while !self.base.doc.stream.previous.is_empty() {
                             let tmp = self.base.doc.stream.previous[0];
-                            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+                            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
                             crate::single_window::free_swindow(
                                 &mut self.base.doc.store,
                                 &mut self.base.doc.cohorts,
@@ -584,56 +654,47 @@ impl<'a> JsonlApplicator<'a> {
                             self.base.doc.stream.previous.remove(0);
                         }
 
-                        if back_swindow.is_none() {
-                            fmt.print_stream_command(&mut self.base.engine(), &cmd_ustr, output);
+                        if !flush {
+                            let reply = self
+                                .base
+                                .engine()
+                                .variables_reply(&variables_set, &variables_rem);
+                            fmt.print_stream_command(&mut self.base.engine(), &reply, output);
+                        } else {
+                            if back_swindow.is_none() {
+                                fmt.print_stream_command(
+                                    &mut self.base.engine(),
+                                    &cmd_ustr,
+                                    output,
+                                );
+                            }
+                            self.base.doc.variables.clear(0);
                         }
-
-                        self.base.doc.variables.clear(0);
                         let _ = output.flush();
                         // u_fflush(*ux_stderr): deferred.
-                    } else if cmd_ustr == STR_CMD_IGNORE {
+                    } else if command == Some(StreamCommand::Ignore) {
                         ignoreinput = true;
                         fmt.print_stream_command(&mut self.base.engine(), &cmd_ustr, output);
-                    } else if cmd_ustr == STR_CMD_RESUME {
+                    } else if command == Some(StreamCommand::Resume) {
                         ignoreinput = false;
                         fmt.print_stream_command(&mut self.base.engine(), &cmd_ustr, output);
-                    } else if cmd_ustr == STR_CMD_EXIT {
+                    } else if command == Some(StreamCommand::Exit) {
                         fmt.print_stream_command(&mut self.base.engine(), &cmd_ustr, output);
                         exit_requested = true;
                         break 'mainloop; // goto CGCMD_EXIT_JSONL
-                    } else if cmd_ustr.starts_with(STR_CMD_SETVAR) {
-                        // payload = cmd_ustr.substr(SETVAR.size(), size - SETVAR.size() - 1)
-                        let payload = substr_strip_prefix_and_last(&cmd_ustr, STR_CMD_SETVAR);
-                        let key_tag: TagId;
-                        let value_hash: u32;
-                        if let Some(eq) = payload.find('=') {
-                            let key_str = &payload[..eq];
-                            let value_str = &payload[eq + '='.len_utf8()..];
-                            key_tag = self.base.add_tag(key_str, crate::tag::TagType::empty())?;
-                            let vt = self.base.add_tag(value_str, crate::tag::TagType::empty())?;
-                            value_hash = self.base.grammar.single_tags_list.get(vt.0).hash.get();
-                        } else {
-                            key_tag = self.base.add_tag(&payload, crate::tag::TagType::empty())?;
-                            value_hash = self.base.grammar.tag_any;
-                        }
-                        let key_hash = self.base.grammar.single_tags_list.get(key_tag.0).hash.get();
-                        // variables_set[key_hash] = value_hash; (operator[] overwrites)
-                        *variables_set.index_or_insert(key_hash) = value_hash;
-                        variables_rem.erase(key_hash);
-                        variables_output.insert(key_hash);
-                    } else if cmd_ustr.starts_with(STR_CMD_REMVAR) {
-                        let payload = substr_strip_prefix_and_last(&cmd_ustr, STR_CMD_REMVAR);
-                        let key_tag = self.base.add_tag(&payload, crate::tag::TagType::empty())?;
-                        let key_hash = self.base.grammar.single_tags_list.get(key_tag.0).hash.get();
-                        variables_set.erase(key_hash);
-                        variables_rem.insert(key_hash);
-                        variables_output.insert(key_hash);
+                    } else if let Some(cmd) = &command {
+                        // SETVAR / REMVAR (the C++ takes one `name[=value]`).
+                        self.base.engine().stage_variables(
+                            cmd,
+                            c_swindow.is_none(),
+                            &mut variables_set,
+                            &mut variables_rem,
+                            &mut variables_output,
+                        )?;
                     }
                 } else {
-                    tracing::warn!(
-                        "Warning: Empty 'cmd' value on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind = RuntimeDiagnosticKind::skipped("Empty 'cmd' value", "");
+                    self.note_input(kind, None, 1)?;
                 }
                 continue;
             }
@@ -674,10 +735,8 @@ impl<'a> JsonlApplicator<'a> {
                         fmt.print_plain_text_line(&mut self.base.engine(), &t_ustr, output);
                     }
                 } else {
-                    tracing::warn!(
-                        "Warning: Empty 't' value on line {}.",
-                        self.base.doc.num_lines
-                    );
+                    let kind = RuntimeDiagnosticKind::skipped("Empty 't' value", "");
+                    self.note_input(kind, None, 1)?;
                 }
                 continue;
             } else if obj.contains_key("w") {
@@ -757,11 +816,9 @@ impl<'a> JsonlApplicator<'a> {
                         });
                     if hard_hit {
                         if cohorts_len >= self.base.cfg.hard_limit as usize {
-                            tracing::warn!(
-                                "Warning: Hard limit of {} cohorts reached at line {} - forcing break.",
-                                self.base.cfg.hard_limit,
-                                self.base.doc.num_lines
-                            );
+                            self.base
+                                .engine()
+                                .note_hard_limit(cc, StreamFormatKind::Jsonl);
                         }
                         let rs = self.base.doc.store.cohorts.get(cc.0).readings.clone();
                         for r in rs {
@@ -806,7 +863,7 @@ impl<'a> JsonlApplicator<'a> {
             self.base.engine().shuffle_windows_down();
             while !self.base.doc.stream.previous.is_empty() {
                 let tmp = self.base.doc.stream.previous[0];
-                fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+                fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
                 crate::single_window::free_swindow(
                     &mut self.base.doc.store,
                     &mut self.base.doc.cohorts,
@@ -861,6 +918,27 @@ impl<'a> JsonlApplicator<'a> {
         Ok(())
     }
 
+    /// ADDED — no C++ analog: the strict-mode error for the current line.
+    fn invalid(&self, pointer: String, problem: String) -> crate::error::RunError {
+        crate::error::RunError::InvalidJsonl {
+            line: self.base.doc.num_lines,
+            pointer,
+            problem,
+        }
+    }
+
+    /// ADDED — no C++ analog: note `kind`, a problem with the current line,
+    /// quoted at `column`.
+    fn note_input(
+        &mut self,
+        kind: RuntimeDiagnosticKind,
+        cohort: Option<CohortId>,
+        column: u32,
+    ) -> Result<(), crate::error::RunError> {
+        let excerpt = InputExcerpt::new(self.base.doc.num_lines, &self.line, column);
+        self.base.engine().note_input(kind, cohort, excerpt)
+    }
+
     /// `addTagToReading(*iter, endtag)` — the C++ `uint32_t` overload: resolve the
     /// `endtag` hash to its `TagId` (via `grammar->single_tags[hash]`), then add.
     /// Not a manifest symbol — a helper deduplicating the repeated end-tagging.
@@ -871,11 +949,24 @@ impl<'a> JsonlApplicator<'a> {
     }
 }
 
-/// Jsonl print-vtable strategy (no persistent state).
+/// Jsonl print-vtable strategy. ADDED: its one setting is whether streams
+/// open with the schema version marker (`--jsonl-header`).
 #[derive(Default)]
-pub struct JsonlFormat;
+pub struct JsonlFormat {
+    pub header: bool,
+}
 
 impl JsonlFormat {
+    /// ADDED — no C++ analog: `{"cg3jsonl":<version>}`, naming the
+    /// [`crate::jsonl_schema`] version the stream follows, when
+    /// [`header`](Self::header) is set. Written before a run's first line.
+    pub(crate) fn write_header<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        if self.header {
+            writeln!(output, "{}", json!({ HEADER_KEY: SCHEMA_VERSION }))?;
+        }
+        Ok(())
+    }
+
     // =======================================================================
     // buildJsonTags / buildJsonReading — serialisation helpers
     // =======================================================================
@@ -950,6 +1041,8 @@ impl JsonlFormat {
     /// reading_json, json::Document::AllocatorType& allocator)`. Builds the object
     /// `{"l": <baseform>, "ts": [<tags>], "s": {<subreading>}}` — `"l"` always
     /// present (empty string when no baseform); `"ts"`/`"s"` only when non-empty.
+    /// ADDED: `"wt"`, the weight the reading's own tags carry, when they carry
+    /// one (see [`crate::grammar_applicator::weights`]).
     /// Recurses on `reading->next`. The returned `Map` preserves insertion order
     /// (l, ts, s).
     fn build_json_reading_e(&self, e: &Engine<'_>, reading: ReadingId) -> Map<String, Value> {
@@ -988,6 +1081,12 @@ impl JsonlFormat {
             reading_json.insert("ts".to_string(), Value::Array(tags_json));
         }
 
+        // ADDED: the weight its tags carry ("wt").
+        let key = weight_key(&e.cfg.weight_tag);
+        if let Some(wt) = own_weight(&e.doc.store.readings, e.grammar, reading, key) {
+            reading_json.insert("wt".to_string(), json!(wt));
+        }
+
         // Subreading ("s").
         let next = e.doc.store.readings.get(reading.0).next;
         if let Some(next) = next {
@@ -1032,7 +1131,9 @@ impl JsonlFormat {
     // [spec:cg3:sem:jsonl-applicator.cg3.jsonl-applicator.print-cohort-fn]
     /// C++ `void printCohort(Cohort* cohort, std::ostream& output, bool
     /// profiling)`. Serialises one cohort as one JSON object per line, in
-    /// insertion order `w, sts, z, ds, dp, rs, drs`.
+    /// insertion order `w, sts, z, ds, dp, rs, drs`. ADDED: `ob` / `ou`, the
+    /// cohort's [`Cohort::offsets`](crate::cohort::Cohort::offsets) as
+    /// `[start, end]` in bytes and UTF-16 code units, after `dp`.
     ///
     /// DIVERGENCE(ORDER): RapidJSON emits members in insertion order. serde_json's
     /// [`Map`] is a `BTreeMap` unless the `preserve_order` feature is enabled
@@ -1157,6 +1258,12 @@ impl JsonlFormat {
             }
         }
 
+        // ADDED: source offsets ("ob" bytes / "ou" UTF-16), when known.
+        if let Some(o) = e.doc.store.cohorts.get(cohort.0).offsets {
+            doc.insert("ob".to_string(), json!([o.byte_start, o.byte_end]));
+            doc.insert("ou".to_string(), json!([o.utf16_start, o.utf16_end]));
+        }
+
         // Readings ("rs").
         let mut readings = e.doc.store.cohorts.get(cohort.0).readings.clone();
         sort_readings(&e.doc.store, &mut readings);
@@ -1329,18 +1436,15 @@ fn as_uint(v: &Value) -> Option<u32> {
     v.as_u64().and_then(|u| u32::try_from(u).ok())
 }
 
-/// C++ `cmd_ustr.substr(prefix.size(), cmd_ustr.size() - prefix.size() - 1)` —
-/// strip the leading `prefix` and the single final char (the assumed `>`).
-fn substr_strip_prefix_and_last(cmd: &str, prefix: &str) -> String {
-    // Work in chars to mirror the UTF-16-length arithmetic faithfully enough for
-    // the ASCII prefixes/`>` used here.
-    let chars: Vec<char> = cmd.chars().collect();
-    let plen = prefix.chars().count();
-    if chars.len() <= plen {
-        return String::new();
+/// ADDED — no C++ analog. A `[start, end]` offset pair.
+fn as_span(v: &Value) -> Option<(usize, usize)> {
+    match v.as_array()?.as_slice() {
+        [start, end] => Some((
+            usize::try_from(start.as_u64()?).ok()?,
+            usize::try_from(end.as_u64()?).ok()?,
+        )),
+        _ => None,
     }
-    // Drop the prefix and the last char.
-    chars[plen..chars.len() - 1].iter().collect()
 }
 
 /// C++ `std::sort(list, Reading::cmp_number)` over a reading-id list, resolving
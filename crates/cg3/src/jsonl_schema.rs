@@ -0,0 +1,293 @@
+//! ADDED — no C++ analog. The versioned schema of JSONL streams, and the
+//! strict reading of them.
+//!
+//! [`SCHEMA`] is a JSON Schema for one line of a stream (also shipped as
+//! `schema/jsonl-v1.schema.json`), whatever produced it. Asked to
+//! (`--jsonl-header`), cg3 starts a stream with a header line naming the
+//! version it follows, `{"cg3jsonl":1}`; readers that predate it skip it as an
+//! object of no kind they know.
+//!
+//! By default the reader takes what it can from each line and skips the rest,
+//! as the C++ reader does. In strict mode (`--jsonl-strict`) [`validate`]
+//! checks each line against the schema first, and the run stops at the first
+//! line that does not follow it: an unknown key (`"rss"` for `"rs"`), a value
+//! of the wrong type, a line that is not a JSON object, or a header naming a
+//! version this build does not read.
+
+use serde_json::{Map, Value};
+
+/// The version of the schema streams are written in, and the newest read.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// The JSON Schema of a stream line, version [`SCHEMA_VERSION`].
+pub const SCHEMA: &str = include_str!("../schema/jsonl-v1.schema.json");
+
+/// The key of the header line.
+pub const HEADER_KEY: &str = "cg3jsonl";
+
+/// Where a line breaks the schema, and how.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer to the offending value; empty for the whole line.
+    pub pointer: String,
+    pub problem: String,
+}
+
+const COHORT_KEYS: &[&str] = &["w", "sts", "z", "ds", "dp", "ob", "ou", "rs", "drs"];
+const READING_KEYS: &[&str] = &["l", "ts", "wt", "s"];
+
+/// Check one stream line against the schema.
+pub fn validate(line: &Value) -> Result<(), Violation> {
+    let Value::Object(obj) = line else {
+        return Err(violation(
+            "",
+            format!("expected an object, found {}", kind(line)),
+        ));
+    };
+    if obj.contains_key(HEADER_KEY) {
+        only_keys(obj, "", &[HEADER_KEY])?;
+        let version = &obj[HEADER_KEY];
+        match version.as_u64() {
+            Some(v) if v >= 1 => {
+                supported_version(v).map_err(|problem| violation("/cg3jsonl", problem))
+            }
+            _ => Err(expected(
+                &pointer("", HEADER_KEY),
+                "a positive whole number",
+                version,
+            )),
+        }
+    } else if obj.contains_key("cmd") {
+        only_keys(obj, "", &["cmd"])?;
+        string(obj, "", "cmd")
+    } else if obj.contains_key("w") {
+        cohort(obj)
+    } else if obj.contains_key("t") {
+        only_keys(obj, "", &["t"])?;
+        string(obj, "", "t")
+    } else {
+        Err(violation(
+            "",
+            "not a cohort (\"w\"), text (\"t\"), command (\"cmd\") or header (\"cg3jsonl\")"
+                .to_string(),
+        ))
+    }
+}
+
+/// Whether this build reads streams of schema `version`.
+pub fn supported_version(version: u64) -> Result<(), String> {
+    if version <= SCHEMA_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "schema version {version} is newer than this build reads ({SCHEMA_VERSION})"
+        ))
+    }
+}
+
+fn cohort(obj: &Map<String, Value>) -> Result<(), Violation> {
+    only_keys(obj, "", COHORT_KEYS)?;
+    string(obj, "", "w")?;
+    strings(obj, "", "sts")?;
+    string(obj, "", "z")?;
+    for key in ["ds", "dp"] {
+        if let Some(v) = obj.get(key)
+            && v.as_u64().is_none_or(|n| u32::try_from(n).is_err())
+        {
+            return Err(expected(
+                &pointer("", key),
+                "a whole number from 0 to 4294967295",
+                v,
+            ));
+        }
+    }
+    for (key, other) in [("ob", "ou"), ("ou", "ob")] {
+        let Some(v) = obj.get(key) else { continue };
+        let at = pointer("", key);
+        match v.as_array().map(Vec::as_slice) {
+            Some([start, end]) => {
+                for (i, n) in [start, end].into_iter().enumerate() {
+                    if n.as_u64().is_none() {
+                        return Err(expected(&format!("{at}/{i}"), "a whole number", n));
+                    }
+                }
+            }
+            _ => return Err(expected(&at, "a [start, end] pair", v)),
+        }
+        if !obj.contains_key(other) {
+            return Err(violation(
+                &at,
+                format!("\"{key}\" needs \"{other}\" beside it"),
+            ));
+        }
+    }
+    for key in ["rs", "drs"] {
+        let Some(v) = obj.get(key) else { continue };
+        let at = pointer("", key);
+        let Value::Array(readings) = v else {
+            return Err(expected(&at, "an array of readings", v));
+        };
+        for (i, r) in readings.iter().enumerate() {
+            reading(r, &format!("{at}/{i}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn reading(v: &Value, at: &str) -> Result<(), Violation> {
+    let Value::Object(obj) = v else {
+        return Err(expected(at, "a reading object", v));
+    };
+    only_keys(obj, at, READING_KEYS)?;
+    string(obj, at, "l")?;
+    strings(obj, at, "ts")?;
+    if let Some(wt) = obj.get("wt")
+        && !wt.is_number()
+    {
+        return Err(expected(&pointer(at, "wt"), "a number", wt));
+    }
+    if let Some(sub) = obj.get("s") {
+        reading(sub, &pointer(at, "s"))?;
+    }
+    Ok(())
+}
+
+fn only_keys(obj: &Map<String, Value>, at: &str, known: &[&str]) -> Result<(), Violation> {
+    let Some(key) = obj.keys().find(|k| !known.contains(&k.as_str())) else {
+        return Ok(());
+    };
+    let mut problem = format!("unknown key \"{key}\"");
+    if let Some(near) = known.iter().find(|k| edit_distance(key, k) <= 1) {
+        problem.push_str(&format!("; did you mean \"{near}\"?"));
+    }
+    Err(violation(&pointer(at, key), problem))
+}
+
+fn string(obj: &Map<String, Value>, at: &str, key: &str) -> Result<(), Violation> {
+    match obj.get(key) {
+        Some(v) if !v.is_string() => Err(expected(&pointer(at, key), "a string", v)),
+        _ => Ok(()),
+    }
+}
+
+fn strings(obj: &Map<String, Value>, at: &str, key: &str) -> Result<(), Violation> {
+    let Some(v) = obj.get(key) else {
+        return Ok(());
+    };
+    let at = pointer(at, key);
+    let Value::Array(items) = v else {
+        return Err(expected(&at, "an array of strings", v));
+    };
+    match items.iter().position(|item| !item.is_string()) {
+        Some(i) => Err(expected(&format!("{at}/{i}"), "a string", &items[i])),
+        None => Ok(()),
+    }
+}
+
+/// `at` extended by `key`, escaped as RFC 6901 has it.
+fn pointer(at: &str, key: &str) -> String {
+    format!("{at}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn violation(pointer: &str, problem: String) -> Violation {
+    Violation {
+        pointer: pointer.to_string(),
+        problem,
+    }
+}
+
+fn expected(at: &str, what: &str, found: &Value) -> Violation {
+    violation(at, format!("expected {what}, found {}", kind(found)))
+}
+
+fn kind(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Levenshtein distance, for suggesting the key meant.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + usize::from(ca != cb))
+                .min(row[j] + 1)
+                .min(above + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keys(schema: &Value, def: &str) -> Vec<String> {
+        let mut keys: Vec<String> = schema["$defs"][def]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// The published schema and the validator know the same keys.
+    #[test]
+    fn schema_matches_the_validator() {
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        let sorted = |list: &[&str]| {
+            let mut list: Vec<String> = list.iter().map(|k| k.to_string()).collect();
+            list.sort();
+            list
+        };
+        assert_eq!(keys(&schema, "cohort"), sorted(COHORT_KEYS));
+        assert_eq!(keys(&schema, "reading"), sorted(READING_KEYS));
+        assert_eq!(keys(&schema, "header"), [HEADER_KEY]);
+        assert!(
+            schema["title"]
+                .as_str()
+                .unwrap()
+                .ends_with(&format!("version {SCHEMA_VERSION}"))
+        );
+    }
+
+    #[test]
+    fn violations_point_at_the_value() {
+        let ok = json!({"w": "a", "ob": [0, 1], "ou": [0, 1], "rs": [{"l": "a", "ts": ["N"], "wt": 0.5, "s": {"l": "b"}}]});
+        assert_eq!(validate(&ok), Ok(()));
+        assert_eq!(validate(&json!({"cg3jsonl": 1})), Ok(()));
+
+        let bad = |v: Value| validate(&v).unwrap_err();
+        let v = bad(json!({"w": "a", "rss": []}));
+        assert_eq!(v.pointer, "/rss");
+        assert!(v.problem.contains("did you mean \"rs\""), "{}", v.problem);
+        assert_eq!(
+            bad(json!({"w": "a", "rs": [{"l": "a", "ts": ["N", 3]}]})).pointer,
+            "/rs/0/ts/1"
+        );
+        assert_eq!(
+            bad(json!({"w": "a", "rs": [{"s": {"l": 1}}]})).pointer,
+            "/rs/0/s/l"
+        );
+        assert_eq!(bad(json!({"w": "a", "ob": [0, 1]})).pointer, "/ob");
+        assert_eq!(bad(json!({"w": "a", "dp": -1})).pointer, "/dp");
+        assert_eq!(bad(json!({"cg3jsonl": 2})).pointer, "/cg3jsonl");
+        assert_eq!(bad(json!({"x/y": 1})).pointer, "");
+        assert_eq!(bad(json!([1])).pointer, "");
+        assert_eq!(bad(json!({"t": "x", "a/b": 1})).pointer, "/a~1b");
+    }
+}
@@ -12,7 +12,9 @@
 pub mod arena;
 pub mod bloomish;
 pub mod diagnostics;
+pub mod diagnostics_export;
 pub mod error;
+pub mod flat_map;
 pub mod flat_unordered_map;
 pub mod flat_unordered_set;
 pub mod inlines;
@@ -36,13 +38,18 @@ pub mod single_window;
 pub mod store;
 pub mod tag;
 pub mod tag_trie;
+pub mod text_offsets;
+pub mod tokenizer;
 pub mod window;
 
 // --- Wave 2 support utilities (io / platform / parser-support / options) ---
 pub mod ast;
+pub mod case_folding;
+pub mod compression;
 pub mod filesystem;
 pub mod icu_uoptions;
 pub mod igrammar_parser;
+pub mod normalization;
 pub mod options;
 pub mod options_conv;
 pub mod options_parser;
@@ -52,9 +59,12 @@ pub mod uextras;
 
 // --- Wave 2 parser + serialization layer ---
 pub mod binary_grammar;
+pub mod grammar_graph;
 pub mod grammar_sources;
+pub mod grammar_watch;
 pub mod grammar_writer;
 pub mod parser_helpers;
+pub mod tag_filter;
 pub mod tag_regex;
 pub mod textual_parser;
 
@@ -64,15 +74,22 @@ pub mod grammar_applicator;
 // --- Wave 2 output/format applicators + profiler + relabeller ---
 pub mod apertium_applicator;
 pub mod binary_applicator;
+pub mod binary_inspect;
+pub mod errors_format;
 pub mod format_converter;
 pub mod fst_applicator;
 pub mod jsonl_applicator;
+pub mod jsonl_schema;
 pub mod matxin_applicator;
+pub mod matxin_format;
 pub mod mwesplit_applicator;
 pub mod niceline_applicator;
 pub mod plaintext_applicator;
 pub mod profiler;
 pub mod relabeller;
+pub mod runtime_diagnostics;
+pub mod stream_command;
+pub mod stream_filters;
 
 // --- Wave 2 CLI tool entry points ---
 pub mod tools;
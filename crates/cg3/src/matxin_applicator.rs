@@ -30,9 +30,10 @@ use std::io::Write;
 
 use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::cohort::{CT_REMOVED, alloc_cohort, append_reading, unignore_all};
-use crate::grammar_applicator::GrammarApplicator;
+use crate::grammar_applicator::{GrammarApplicator, StreamFormatKind};
 use crate::inlines::{hash_value, insert_if_exists};
 use crate::reading::{Reading, ReadingList, alloc_reading, alloc_reading_copy};
+use crate::runtime_diagnostics::{LineTail, RuntimeDiagnosticKind};
 use crate::single_window::append_cohort;
 use crate::store::RuntimeStore;
 use crate::tag::{T_BASEFORM, T_MAPPING, T_WORDFORM, TagVector};
@@ -69,6 +70,10 @@ pub struct MatxinApplicator {
     pub deps: BTreeMap<i32, Vec<i32>>,
     pub null_flush: bool,
     pub running_with_null_flush: bool,
+    /// ADDED: the end of the input line being read, for quoting malformed
+    /// input. Kept across the chunks of a null-flushed stream, so its lines
+    /// count on.
+    tail: LineTail,
 }
 
 // ---------------------------------------------------------------------------
@@ -115,9 +120,37 @@ impl MatxinApplicator {
             deps: BTreeMap::new(),
             null_flush: false,
             running_with_null_flush: false,
+            tail: LineTail::default(),
         }
     }
 
+    /// ADDED: `u_fgetc`, keeping the char for a quote of its line.
+    fn getc<R: std::io::Read>(&mut self, input: &mut R) -> char {
+        let c = u_fgetc(input);
+        if c != U_EOF {
+            self.tail.push(c);
+        }
+        c
+    }
+
+    /// ADDED: note `kind`, a problem with the input, quoted at the last char
+    /// read.
+    fn note_input(
+        &mut self,
+        kind: RuntimeDiagnosticKind,
+        cohort: Option<CohortId>,
+    ) -> Result<(), crate::error::RunError> {
+        let excerpt = self.tail.excerpt(self.tail.column());
+        self.base.engine().note_input(kind, cohort, excerpt)
+    }
+
+    /// ADDED: the note for a stream that ends inside a cohort, which the C++
+    /// read on past forever.
+    fn note_unterminated_cohort(&mut self, cc: CohortId) -> Result<(), crate::error::RunError> {
+        let kind = RuntimeDiagnosticKind::malformed("The input ended inside a cohort", "");
+        self.note_input(kind, Some(cc))
+    }
+
     // [spec:cg3:def:matxin-applicator.cg3.matxin-applicator.get-null-flush-fn]
     // [spec:cg3:sem:matxin-applicator.cg3.matxin-applicator.get-null-flush-fn]
     /// C++ `bool MatxinApplicator::getNullFlush()` — trivial getter.
@@ -289,9 +322,16 @@ impl MatxinApplicator {
                 if ch == '<' {
                     multi = false;
                     if intag {
-                        tracing::error!(
-                            "Error: The Matxin stream format does not allow '<' in tag names."
+                        let message = String::from(
+                            "The Matxin stream format does not allow '<' in tag names",
                         );
+                        self.note_input(
+                            RuntimeDiagnosticKind::MalformedInput {
+                                message,
+                                line_at: None,
+                            },
+                            None,
+                        )?;
                         c += 1;
                         continue;
                     }
@@ -316,9 +356,16 @@ impl MatxinApplicator {
                 } else if ch == '>' {
                     multi = false;
                     if !intag {
-                        tracing::error!(
-                            "Error: The Matxin stream format does not allow '>' outside tag names."
+                        let message = String::from(
+                            "The Matxin stream format does not allow '>' outside tag names",
                         );
+                        self.note_input(
+                            RuntimeDiagnosticKind::MalformedInput {
+                                message,
+                                line_at: None,
+                            },
+                            None,
+                        )?;
                         c += 1;
                         continue;
                     }
@@ -753,21 +800,7 @@ impl MatxinApplicator {
         }
 
         // No-delimiter warnings.
-        let no_hard = self.base.grammar.delimiters.is_none();
-        let no_soft = self.base.grammar.soft_delimiters.is_none();
-        if no_hard {
-            if no_soft {
-                tracing::warn!(
-                    "Warning: No soft or hard delimiters defined in grammar. Hard limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.hard_limit
-                );
-            } else {
-                tracing::warn!(
-                    "Warning: No hard delimiters defined in grammar. Soft limit of {} cohorts may break windows in unintended places.",
-                    self.base.cfg.soft_limit
-                );
-            }
-        }
+        self.base.engine().note_missing_delimiters();
 
         // C++ `UChar inchar = 0;` — the `while ((inchar = u_fgetc(input)) …)`
         // head assigns it before every read, so no initializer is needed here.
@@ -808,7 +841,7 @@ impl MatxinApplicator {
             // C++ `while ((inchar = u_fgetc(input)) != 0)` then `if (input.eof())
             // break;`. A read of '\0' terminates the loop (the `!= 0` guard); an
             // EOF (U_EOF) also terminates it (the `input.eof()` break).
-            inchar = u_fgetc(input);
+            inchar = self.getc(input);
             if inchar == '\0' || inchar == U_EOF {
                 break;
             }
@@ -821,7 +854,7 @@ impl MatxinApplicator {
             }
 
             if inchar == '\\' && !incohort && !superblank {
-                let n = u_fgetc(input);
+                let n = self.getc(input);
                 if let Some(cc) = c_cohort {
                     self.base.doc.store.cohorts.get_mut(cc.0).text.push(inchar);
                     self.base.doc.store.cohorts.get_mut(cc.0).text.push(n);
@@ -921,15 +954,9 @@ impl MatxinApplicator {
                 };
                 if hard || delim_match {
                     if !self.base.cfg.is_conv && cohorts_size >= self.base.cfg.hard_limit {
-                        let wf_tid = self.base.doc.store.cohorts.get(cc.0).wordform.unwrap();
-                        let wftag = self.base.grammar.single_tags_list.get(wf_tid.0).tag.clone();
-                        tracing::warn!(
-                            "Warning: Hard limit of {} cohorts reached at cohort {} (#{}) on line {} - forcing break.",
-                            self.base.cfg.hard_limit,
-                            wftag,
-                            self.base.doc.num_cohorts,
-                            self.base.doc.num_lines
-                        );
+                        self.base
+                            .engine()
+                            .note_hard_limit(cc, StreamFormatKind::Matxin);
                     }
                     self.add_endtag_all(cc)?;
                     append_cohort(
@@ -1009,11 +1036,16 @@ impl MatxinApplicator {
             // Read the wordform.
             let mut wordform: UString = String::from("\"<");
             loop {
-                inchar = u_fgetc(input);
+                inchar = self.getc(input);
+                if inchar == U_EOF {
+                    self.note_unterminated_cohort(cc)?;
+                    incohort = false;
+                    break;
+                }
                 if inchar == '/' || inchar == '<' {
                     break;
                 } else if inchar == '\\' {
-                    inchar = u_fgetc(input);
+                    inchar = self.getc(input);
                     wordform.push(inchar);
                 } else {
                     wordform.push(inchar);
@@ -1033,9 +1065,14 @@ impl MatxinApplicator {
                 self.base.doc.store.cohorts.get_mut(cc.0).wread = Some(wread);
                 let mut tagbuf: UString = String::new();
                 loop {
-                    inchar = u_fgetc(input);
+                    inchar = self.getc(input);
+                    if inchar == U_EOF {
+                        self.note_unterminated_cohort(cc)?;
+                        incohort = false;
+                        break;
+                    }
                     if inchar == '\\' {
-                        inchar = u_fgetc(input);
+                        inchar = self.getc(input);
                         tagbuf.push(inchar);
                         continue;
                     }
@@ -1060,9 +1097,14 @@ impl MatxinApplicator {
 
             // Read the readings.
             while incohort {
-                inchar = u_fgetc(input);
+                inchar = self.getc(input);
+                if inchar == U_EOF {
+                    self.note_unterminated_cohort(cc)?;
+                    incohort = false;
+                    break;
+                }
                 if inchar == '\\' {
-                    inchar = u_fgetc(input);
+                    inchar = self.getc(input);
                     current_reading.push(inchar);
                     continue;
                 }
@@ -1114,10 +1156,12 @@ impl MatxinApplicator {
                 None => true,
             };
             if no_baseform {
-                tracing::warn!(
-                    "Warning: Line {} had no valid baseform.",
-                    self.base.doc.num_lines
-                );
+                self.note_input(
+                    RuntimeDiagnosticKind::NoBaseform {
+                        format: StreamFormatKind::Matxin,
+                    },
+                    Some(cc),
+                )?;
             }
             self.base.doc.num_lines = self.base.doc.num_lines.wrapping_add(1);
         }
@@ -1151,6 +1195,10 @@ impl MatxinApplicator {
         self.base.engine().shuffle_windows_down();
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
+            // ADDED: the exports `StreamFormat::print_finished_window` does.
+            let mut e = self.base.engine();
+            e.finish_dep_tree(tmp)?;
+            e.finish_relation_graph(tmp)?;
             self.print_single_window(tmp, output, false)?;
             let opt = Some(tmp);
             crate::single_window::free_swindow(
@@ -0,0 +1,835 @@
+//! ADDED — no C++ analog. Matxin XML as a [`FormatConverter`] stream format,
+//! read and written: `cg-conv -m` / `-M`, `vislcg3 --in-matxin` /
+//! `--out-matxin`.
+//!
+//! Upstream's only Matxin support is the [`MatxinApplicator`] of `cg-proc -f 2`,
+//! which reads Apertium and writes Matxin; the converter had no Matxin arm at
+//! all. That writer stays bug-for-bug for `cg-proc`. This one is the
+//! converter's, without its faults:
+//! * nodes and dependencies are collected per window, so one `<SENTENCE>`
+//!   never carries nodes of an earlier one;
+//! * every attribute value is escaped, once — `&` is `&amp;`, not `&amp;&`;
+//! * a reading with sub-readings, which Matxin cannot represent, is a
+//!   [`RunError::SubReadingsUnsupported`] rather than a process exit, and the
+//!   converter closes the document on that as on any failure, so what was
+//!   written stays well-formed;
+//! * `ord` numbers a node within its sentence.
+//!
+//! ## Reading
+//! Each `<SENTENCE>` is one window; DELIMITERS and the soft/hard limits do not
+//! split it, a sentence being what the tree is built over. Each `<NODE>` is a
+//! cohort, in `ord` order, with one reading: the baseform `lem` (the form when
+//! `lem` is missing or empty), the `|`-separated `mi` tags, and each
+//! `|`-separated `si` value as a mapping tag, the mapping prefix put back. A
+//! node's head is the `<NODE>` it is nested in, or the sentence root; that
+//! becomes the cohort's dependency, as a `#ord->head` tag would, so a Matxin
+//! stream always has dependencies.
+//!
+//! Other elements inside a sentence — Matxin's `<CHUNK>` — are containers: the
+//! first `<NODE>` directly inside one heads the rest of its contents, and the
+//! container attaches where a `<NODE>` in its place would. Everything outside
+//! the sentences, text, comments and the XML declaration are skipped.
+//!
+//! ## Writing
+//! One `<NODE>` per cohort, from its first printable reading: `lem` is the
+//! baseform, `si` the mapping tags and `mi` the remaining tags. The
+//! dependency decides the nesting; a cohort without one attaches under the
+//! first node attached to the root, or to the root when there is none yet —
+//! upstream's fallback, per sentence. A head outside the sentence is taken as
+//! the root, and a dependency loop is broken at the root. Stream commands,
+//! text between cohorts, static tags and further readings have no place in
+//! the document and are dropped.
+//!
+//! [`FormatConverter`]: crate::format_converter::FormatConverter
+//! [`MatxinApplicator`]: crate::matxin_applicator::MatxinApplicator
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Seek, Write};
+
+use crate::arena::{CohortId, SwId, TagId};
+use crate::cohort::{CT_REMOVED, alloc_cohort, append_reading, unignore_all};
+use crate::error::RunError;
+use crate::grammar::Grammar;
+use crate::grammar_applicator::stream_format::StreamFormat;
+use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::inlines::insert_if_exists;
+use crate::reading::alloc_reading;
+use crate::single_window::{append_cohort, free_swindow};
+use crate::tag::{T_BASEFORM, T_DEPENDENCY, T_MAPPING, T_RELATION, T_WORDFORM, TagType};
+use crate::types::{GlobalNumber, TagHash};
+use crate::uextras::ux_strip_bom;
+
+fn tag_by_hash(grammar: &Grammar, hash: TagHash) -> TagId {
+    let it = grammar.single_tags.find(hash.get());
+    if it != grammar.single_tags.end() {
+        it.get().1
+    } else {
+        TagId(0)
+    }
+}
+
+fn malformed(line: u32, problem: impl Into<String>) -> RunError {
+    RunError::MalformedMatxin {
+        line,
+        problem: problem.into(),
+    }
+}
+
+/// One `<NODE>`, as read or about to be written.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct MatxinNode {
+    ord: u32,
+    /// The `ord` of the node this one depends on; 0 is the sentence root.
+    head: u32,
+    form: String,
+    lem: String,
+    mi: String,
+    si: String,
+}
+
+// ---------------------------------------------------------------------------
+// Reading
+// ---------------------------------------------------------------------------
+
+/// A piece of markup: a start tag — with its attributes, and whether it closes
+/// itself — or an end tag.
+enum Markup {
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        empty: bool,
+    },
+    End {
+        name: String,
+    },
+}
+
+/// Undo the XML escapes: the five named entities and character references.
+/// Anything else after a `&` is kept as it stands.
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let ch = match &rest[1..semi] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                num => num
+                    .strip_prefix("#x")
+                    .or_else(|| num.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| num.strip_prefix('#').map(str::parse::<u32>))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            ch.map(|ch| (ch, semi))
+        });
+        match decoded {
+            Some((ch, semi)) => {
+                out.push(ch);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Splits a byte stream into [`Markup`], skipping text, comments, CDATA,
+/// processing instructions and declarations.
+struct MarkupReader<R> {
+    input: BufReader<R>,
+    /// The line being read, from 1.
+    line: u32,
+}
+
+impl<R: Read> MarkupReader<R> {
+    fn new(input: R) -> Self {
+        MarkupReader {
+            input: BufReader::new(input),
+            line: 1,
+        }
+    }
+
+    fn byte(&mut self) -> Result<Option<u8>, RunError> {
+        let buf = self.input.fill_buf()?;
+        let Some(&b) = buf.first() else {
+            return Ok(None);
+        };
+        self.input.consume(1);
+        if b == b'\n' {
+            self.line += 1;
+        }
+        Ok(Some(b))
+    }
+
+    /// The next byte of a piece of markup that started on line `start`.
+    fn markup_byte(&mut self, start: u32) -> Result<u8, RunError> {
+        self.byte()?
+            .ok_or_else(|| malformed(start, "the input ends inside markup"))
+    }
+
+    fn next(&mut self) -> Result<Option<Markup>, RunError> {
+        loop {
+            loop {
+                match self.byte()? {
+                    None => return Ok(None),
+                    Some(b'<') => break,
+                    Some(_) => {}
+                }
+            }
+            let start = self.line;
+            let mut body = Vec::new();
+            match self.markup_byte(start)? {
+                b'?' => {
+                    while !body.ends_with(b"?>") {
+                        body.push(self.markup_byte(start)?);
+                    }
+                    continue;
+                }
+                b'!' => {
+                    loop {
+                        let b = self.markup_byte(start)?;
+                        body.push(b);
+                        let done = if body.starts_with(b"--") {
+                            body.len() >= 5 && body.ends_with(b"-->")
+                        } else if body.starts_with(b"[CDATA[") {
+                            body.ends_with(b"]]>")
+                        } else {
+                            b == b'>'
+                        };
+                        if done {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                first => body.push(first),
+            }
+            let mut quote = None;
+            loop {
+                let b = self.markup_byte(start)?;
+                match quote {
+                    Some(q) if b == q => quote = None,
+                    Some(_) => {}
+                    None if b == b'"' || b == b'\'' => quote = Some(b),
+                    None if b == b'>' => break,
+                    None => {}
+                }
+                body.push(b);
+            }
+            return parse_markup(&String::from_utf8_lossy(&body), start).map(Some);
+        }
+    }
+}
+
+/// Parse the text between `<` and `>` of a start or end tag.
+fn parse_markup(body: &str, line: u32) -> Result<Markup, RunError> {
+    if let Some(name) = body.strip_prefix('/') {
+        return Ok(Markup::End {
+            name: name.trim().to_string(),
+        });
+    }
+    let (body, empty) = match body.trim_end().strip_suffix('/') {
+        Some(body) => (body, true),
+        None => (body, false),
+    };
+    let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let name = &body[..name_end];
+    if name.is_empty() {
+        return Err(malformed(line, format!("`<{body}>` has no element name")));
+    }
+    let mut attrs = Vec::new();
+    let mut rest = body[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| malformed(line, format!("an attribute of <{name}> has no value")))?;
+        let key = rest[..eq].trim();
+        rest = rest[eq + 1..].trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|&q| q == '"' || q == '\'')
+            .ok_or_else(|| malformed(line, format!("attribute {key} of <{name}> is not quoted")))?;
+        let close = rest[1..]
+            .find(quote)
+            .ok_or_else(|| malformed(line, format!("attribute {key} of <{name}> is not closed")))?;
+        attrs.push((key.to_string(), unescape(&rest[1..1 + close])));
+        rest = rest[close + 2..].trim_start();
+    }
+    Ok(Markup::Start {
+        name: name.to_string(),
+        attrs,
+        empty,
+    })
+}
+
+fn attr<'x>(attrs: &'x [(String, String)], key: &str) -> Option<&'x str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+/// An element open inside a sentence.
+struct Frame {
+    name: String,
+    /// The node the element's contents depend on: a `<NODE>` itself, or the
+    /// first `<NODE>` directly inside any other element.
+    head: Option<u32>,
+    /// Where the element itself attaches.
+    attach: u32,
+}
+
+/// The `<SENTENCE>` being read.
+#[derive(Default)]
+struct Sentence {
+    nodes: Vec<MatxinNode>,
+    open: Vec<Frame>,
+}
+
+impl Sentence {
+    fn attach_point(&self) -> u32 {
+        self.open
+            .last()
+            .map_or(0, |frame| frame.head.unwrap_or(frame.attach))
+    }
+
+    fn start(
+        &mut self,
+        name: String,
+        attrs: &[(String, String)],
+        empty: bool,
+        line: u32,
+    ) -> Result<(), RunError> {
+        let attach = self.attach_point();
+        match name.as_str() {
+            "NODE" => {
+                let ord = attr(attrs, "ord")
+                    .and_then(|ord| ord.trim().parse::<u32>().ok())
+                    .filter(|&ord| ord != 0)
+                    .ok_or_else(|| malformed(line, "a <NODE> has no positive ord"))?;
+                if self.nodes.iter().any(|node| node.ord == ord) {
+                    return Err(malformed(line, format!("two <NODE>s have ord {ord}")));
+                }
+                if let Some(container) = self.open.last_mut()
+                    && container.head.is_none()
+                {
+                    container.head = Some(ord);
+                }
+                self.nodes.push(MatxinNode {
+                    ord,
+                    head: attach,
+                    form: attr(attrs, "form").unwrap_or_default().to_string(),
+                    lem: attr(attrs, "lem").unwrap_or_default().to_string(),
+                    mi: attr(attrs, "mi").unwrap_or_default().to_string(),
+                    si: attr(attrs, "si").unwrap_or_default().to_string(),
+                });
+                if !empty {
+                    self.open.push(Frame {
+                        name,
+                        head: Some(ord),
+                        attach,
+                    });
+                }
+            }
+            "SENTENCE" => return Err(malformed(line, "a <SENTENCE> inside a <SENTENCE>")),
+            _ if !empty => self.open.push(Frame {
+                name,
+                head: None,
+                attach,
+            }),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Close element `name`; `true` when that ends the sentence.
+    fn end(&mut self, name: &str, line: u32) -> Result<bool, RunError> {
+        match self.open.pop() {
+            Some(frame) if frame.name == name => Ok(false),
+            Some(frame) => Err(malformed(
+                line,
+                format!("</{name}> where </{}> was expected", frame.name),
+            )),
+            None if name == "SENTENCE" => Ok(true),
+            None => Err(malformed(
+                line,
+                format!("</{name}> where </SENTENCE> was expected"),
+            )),
+        }
+    }
+}
+
+/// The Matxin input driver, over a borrowed engine — the analogue of the other
+/// formats' `runGrammarOnText`.
+pub struct MatxinReader<'a> {
+    pub base: &'a mut GrammarApplicator,
+}
+
+impl<'a> MatxinReader<'a> {
+    pub fn new(base: &'a mut GrammarApplicator) -> Self {
+        MatxinReader { base }
+    }
+
+    /// Read a Matxin document from `input`, run the grammar over each sentence,
+    /// and print through `fmt`.
+    pub fn run_grammar_on_text<F, R, W>(
+        &mut self,
+        fmt: &mut F,
+        input: &mut R,
+        output: &mut W,
+    ) -> Result<(), crate::error::Cg3Error>
+    where
+        F: StreamFormat,
+        R: Read + Seek,
+        W: Write,
+    {
+        self.run_grammar_on_text_impl(fmt, input, output)
+            .map_err(crate::error::Cg3Error::from)
+    }
+
+    fn run_grammar_on_text_impl<F, R, W>(
+        &mut self,
+        fmt: &mut F,
+        input: &mut R,
+        output: &mut W,
+    ) -> Result<(), RunError>
+    where
+        F: StreamFormat,
+        R: Read + Seek,
+        W: Write,
+    {
+        self.base.index();
+        let reset_after: u32 = (self.base.cfg.num_windows + 4) * 2 + 1;
+        self.base.doc.stream.window_span = self.base.cfg.num_windows;
+
+        ux_strip_bom(input);
+        let mut markup = MarkupReader::new(input);
+        let mut sentence: Option<Sentence> = None;
+
+        while let Some(piece) = markup.next()? {
+            let line = markup.line;
+            self.base.doc.num_lines = line;
+            let Some(open) = sentence.as_mut() else {
+                match piece {
+                    // A `<SENTENCE/>` has no nodes to run, like an empty
+                    // `<SENTENCE></SENTENCE>`.
+                    Markup::Start {
+                        name, empty: false, ..
+                    } if name == "SENTENCE" => {
+                        sentence = Some(Sentence::default());
+                    }
+                    Markup::Start { name, .. } if name == "NODE" => {
+                        return Err(malformed(line, "a <NODE> outside any <SENTENCE>"));
+                    }
+                    _ => {}
+                }
+                continue;
+            };
+            let done = match piece {
+                Markup::Start { name, attrs, empty } => {
+                    open.start(name, &attrs, empty, line)?;
+                    false
+                }
+                Markup::End { name } => open.end(&name, line)?,
+            };
+            if !done {
+                continue;
+            }
+            let nodes = sentence.take().map(|s| s.nodes).unwrap_or_default();
+            if nodes.is_empty() {
+                continue;
+            }
+            self.append_sentence(nodes)?;
+            if self.base.doc.stream.next.len() > self.base.cfg.num_windows as usize {
+                self.base.engine().shuffle_windows_down();
+                self.base.engine().run_grammar_on_window_with(fmt, output)?;
+                if self.base.doc.num_windows.is_multiple_of(reset_after) {
+                    self.base.reset_indexes();
+                }
+            }
+        }
+        if sentence.is_some() {
+            return Err(malformed(markup.line, "the input ends inside a <SENTENCE>"));
+        }
+        self.base.doc.input_eof = true;
+
+        while self.base.engine().rotate_next().is_some() {
+            self.base.engine().run_grammar_on_window_with(fmt, output)?;
+        }
+        if self.base.doc.stream.current.is_some() {
+            self.base.engine().run_grammar_on_window_with(fmt, output)?;
+        }
+        self.base.engine().shuffle_windows_down();
+        while !self.base.doc.stream.previous.is_empty() {
+            let tmp = self.base.doc.stream.previous[0];
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
+            free_swindow(
+                &mut self.base.doc.store,
+                &mut self.base.doc.cohorts,
+                &mut self.base.doc.deps,
+                Some(tmp),
+            );
+            self.base.doc.stream.previous.remove(0);
+        }
+        let _ = output.flush();
+        Ok(())
+    }
+
+    /// A window of one cohort per node, in `ord` order.
+    fn append_sentence(&mut self, mut nodes: Vec<MatxinNode>) -> Result<(), RunError> {
+        nodes.sort_by_key(|node| node.ord);
+        let sw = self
+            .base
+            .doc
+            .stream
+            .alloc_append_single_window(&mut self.base.doc.store);
+        self.base.engine().init_empty_single_window(sw)?;
+        self.base.doc.num_windows = self.base.doc.num_windows.wrapping_add(1);
+        self.base.doc.deps.has_dep = true;
+
+        let mut last = None;
+        for node in nodes {
+            let cc = alloc_cohort(&mut self.base.doc.store, Some(sw));
+            let gn = self.base.doc.cohorts.next_cohort_number();
+            self.base.doc.store.cohorts.get_mut(cc.0).global_number = gn;
+            self.base.doc.num_cohorts = self.base.doc.num_cohorts.wrapping_add(1);
+
+            let wf = self
+                .base
+                .add_tag(&format!("\"<{}>\"", node.form), TagType::empty())?;
+            self.base.doc.store.cohorts.get_mut(cc.0).wordform = Some(wf);
+
+            let reading = alloc_reading(&mut self.base.doc.store, Some(cc));
+            self.base.engine().add_tag_to_reading(reading, wf)?;
+            let lemma = if node.lem.is_empty() {
+                &node.form
+            } else {
+                &node.lem
+            };
+            let bf = self
+                .base
+                .add_tag(&format!("\"{lemma}\""), TagType::empty())?;
+            self.base.engine().add_tag_to_reading(reading, bf)?;
+            for mi in node.mi.split('|').filter(|t| !t.is_empty()) {
+                let tag = self.base.add_tag(mi, TagType::empty())?;
+                self.base.engine().add_tag_to_reading(reading, tag)?;
+            }
+            let prefix = self.base.grammar.mapping_prefix;
+            let mut mappings = Vec::new();
+            for si in node.si.split('|').filter(|t| !t.is_empty()) {
+                mappings.push(
+                    self.base
+                        .add_tag(&format!("{prefix}{si}"), TagType::empty())?,
+                );
+            }
+            if !mappings.is_empty() {
+                self.base
+                    .engine()
+                    .split_mappings(&mut mappings, cc, reading, true)?;
+            }
+            append_reading(&mut self.base.doc.store, cc, reading);
+            self.base.doc.num_readings = self.base.doc.num_readings.wrapping_add(1);
+            insert_if_exists(
+                &mut self.base.doc.store.cohorts.get_mut(cc.0).possible_sets,
+                self.base.grammar.sets_any.as_ref(),
+            );
+
+            {
+                let c = self.base.doc.store.cohorts.get_mut(cc.0);
+                c.dep_self = Some(GlobalNumber(node.ord));
+                c.dep_parent = Some(GlobalNumber(node.head));
+            }
+            append_cohort(
+                &mut self.base.doc.store,
+                &mut self.base.doc.cohorts,
+                &mut self.base.doc.deps,
+                sw,
+                cc,
+            );
+            self.base.doc.deps.dep_highest_seen = GlobalNumber(node.ord);
+            last = Some(cc);
+        }
+
+        if let Some(cc) = last {
+            let endtag = tag_by_hash(&self.base.grammar, self.base.cfg.endtag);
+            let rs = self.base.doc.store.cohorts.get(cc.0).readings.clone();
+            for r in rs {
+                self.base.engine().add_tag_to_reading(r, endtag)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Writing
+// ---------------------------------------------------------------------------
+
+/// Escape an attribute value.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// The Matxin print vtable. Windows are written as they are printed, inside a
+/// `<corpus>` opened by the first of them; [`MatxinFormat::finish`] closes it
+/// at the end of the stream.
+#[derive(Default)]
+pub struct MatxinFormat {
+    corpus_open: bool,
+}
+
+impl MatxinFormat {
+    fn open_corpus<W: Write>(&mut self, output: &mut W) {
+        if !self.corpus_open {
+            let _ = writeln!(output, "<corpus>");
+            self.corpus_open = true;
+        }
+    }
+
+    /// Close the document — an empty `<corpus>` when no window was written. The
+    /// next window printed starts a new one. Called at the end of every run,
+    /// failed or not.
+    pub fn finish<W: Write>(&mut self, output: &mut W) {
+        self.open_corpus(output);
+        let _ = writeln!(output, "</corpus>");
+        let _ = output.flush();
+        self.corpus_open = false;
+    }
+
+    /// The node for `cohort`, without its `ord` and `head`.
+    fn node_of(e: &Engine<'_>, cohort: CohortId) -> Result<MatxinNode, RunError> {
+        let c = e.doc.store.cohorts.get(cohort.0);
+        let mut node = MatxinNode::default();
+        if let Some(wf) = c.wordform {
+            let tag = &e.grammar.single_tags_list[wf.0].tag;
+            node.form = tag
+                .strip_prefix("\"<")
+                .and_then(|t| t.strip_suffix(">\""))
+                .unwrap_or(tag)
+                .to_string();
+        }
+        let Some(reading) = c
+            .readings
+            .iter()
+            .map(|&r| e.doc.store.readings.get(r.0))
+            .find(|r| !r.noprint)
+        else {
+            return Ok(node);
+        };
+        if reading.next.is_some() {
+            return Err(RunError::SubReadingsUnsupported);
+        }
+        if let Some(bf) = reading.baseform {
+            let tag = &e.grammar.single_tags_list[tag_by_hash(e.grammar, bf).0].tag;
+            node.lem = tag
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(tag)
+                .to_string();
+        }
+
+        let mut seen = Vec::new();
+        let mut mi = Vec::new();
+        let mut si = Vec::new();
+        for &hash in &reading.tags_list {
+            if hash == e.cfg.begintag.get() || hash == e.cfg.endtag.get() {
+                continue;
+            }
+            if e.cfg.unique_tags {
+                if seen.contains(&hash) {
+                    continue;
+                }
+                seen.push(hash);
+            }
+            let tag = &e.grammar.single_tags_list[tag_by_hash(e.grammar, TagHash(hash)).0];
+            if tag.r#type.intersects(T_BASEFORM | T_WORDFORM)
+                || (tag.r#type.intersects(T_DEPENDENCY)
+                    && e.doc.deps.has_dep
+                    && !e.cfg.dep_original)
+                || (tag.r#type.intersects(T_RELATION) && e.doc.deps.has_relations)
+            {
+                continue;
+            }
+            if tag.r#type.intersects(T_MAPPING) || tag.tag.starts_with(e.grammar.mapping_prefix) {
+                let mut chars = tag.tag.chars();
+                chars.next();
+                si.push(chars.as_str());
+            } else {
+                mi.push(tag.tag.as_str());
+            }
+        }
+        node.mi = mi.join("|");
+        node.si = si.join("|");
+        Ok(node)
+    }
+
+    /// The `ord` of the node `cohort`'s dependency names: `Some(0)` for the
+    /// root, `None` when there is no dependency to follow.
+    fn head_of(e: &Engine<'_>, cohort: CohortId, ords: &HashMap<CohortId, u32>) -> Option<u32> {
+        let parent = e.doc.store.cohorts.get(cohort.0).dep_parent?;
+        if parent == GlobalNumber(0) {
+            return Some(0);
+        }
+        let head = e.doc.cohorts.cohort_map.get(&parent)?;
+        if *head == cohort {
+            return None;
+        }
+        Some(ords.get(head).copied().unwrap_or(0))
+    }
+
+    pub(crate) fn print_single_window_e<W: Write>(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+        output: &mut W,
+        profiling: bool,
+    ) -> Result<(), RunError> {
+        self.open_corpus(output);
+
+        let all_cohorts = e.doc.store.single_windows.get(window.0).all_cohorts.clone();
+        let mut cohorts = Vec::with_capacity(all_cohorts.len());
+        let mut nodes = Vec::with_capacity(all_cohorts.len());
+        for cohort in all_cohorts {
+            let c = e.doc.store.cohorts.get(cohort.0);
+            if c.local_number == 0 || c.r#type.intersects(CT_REMOVED) {
+                continue;
+            }
+            if !profiling {
+                unignore_all(&mut e.doc.store, cohort);
+                if !e.cfg.split_mappings {
+                    e.merge_mappings(cohort);
+                }
+            }
+            nodes.push(Self::node_of(e, cohort)?);
+            cohorts.push(cohort);
+        }
+
+        let ords: HashMap<CohortId, u32> = cohorts
+            .iter()
+            .enumerate()
+            .map(|(i, &cohort)| (cohort, i as u32 + 1))
+            .collect();
+        let mut first_under_root = None;
+        for (i, &cohort) in cohorts.iter().enumerate() {
+            let head =
+                Self::head_of(e, cohort, &ords).unwrap_or_else(|| first_under_root.unwrap_or(0));
+            if head == 0 && first_under_root.is_none() {
+                first_under_root = Some(i as u32 + 1);
+            }
+            nodes[i].ord = i as u32 + 1;
+            nodes[i].head = head;
+        }
+
+        let number = e.doc.store.single_windows.get(window.0).number;
+        let _ = writeln!(output, "  <SENTENCE ord=\"{number}\" alloc=\"0\">");
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); nodes.len() + 1];
+        for (i, node) in nodes.iter().enumerate() {
+            children[node.head as usize].push(i + 1);
+        }
+        let mut printed = vec![false; nodes.len() + 1];
+        printed[0] = true;
+        for &child in &children[0] {
+            print_node(&nodes, &children, child, 1, &mut printed, output);
+        }
+        // Whatever the root does not reach is on a dependency loop.
+        for ord in 1..=nodes.len() {
+            if !printed[ord] {
+                print_node(&nodes, &children, ord, 1, &mut printed, output);
+            }
+        }
+        let _ = writeln!(output, "  </SENTENCE>");
+        let _ = output.flush();
+        Ok(())
+    }
+}
+
+fn print_node<W: Write>(
+    nodes: &[MatxinNode],
+    children: &[Vec<usize>],
+    ord: usize,
+    depth: usize,
+    printed: &mut [bool],
+    output: &mut W,
+) {
+    printed[ord] = true;
+    let node = &nodes[ord - 1];
+    let indent = "  ".repeat(depth + 1);
+    let _ = write!(
+        output,
+        "{indent}<NODE ord=\"{ord}\" alloc=\"0\" form=\"{}\" lem=\"{}\" mi=\"{}\" si=\"{}\"",
+        escape(&node.form),
+        escape(&node.lem),
+        escape(&node.mi),
+        escape(&node.si)
+    );
+    let below: Vec<usize> = children[ord]
+        .iter()
+        .copied()
+        .filter(|&child| !printed[child])
+        .collect();
+    if below.is_empty() {
+        let _ = writeln!(output, "/>");
+        return;
+    }
+    let _ = writeln!(output, ">");
+    for child in below {
+        print_node(nodes, children, child, depth + 1, printed, output);
+    }
+    let _ = writeln!(output, "{indent}</NODE>");
+}
+
+impl StreamFormat for MatxinFormat {
+    /// Cohorts are only written as part of their window.
+    fn print_cohort<W: Write>(
+        &mut self,
+        _e: &mut Engine<'_>,
+        _cohort: CohortId,
+        _output: &mut W,
+        _profiling: bool,
+    ) -> Result<(), RunError> {
+        Ok(())
+    }
+
+    fn print_single_window<W: Write>(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+        output: &mut W,
+        profiling: bool,
+    ) -> Result<(), RunError> {
+        self.print_single_window_e(e, window, output, profiling)
+    }
+
+    fn print_stream_command<W: Write>(&mut self, _e: &mut Engine<'_>, _cmd: &str, _output: &mut W) {
+    }
+
+    fn print_plain_text_line<W: Write>(
+        &mut self,
+        _e: &mut Engine<'_>,
+        _line: &str,
+        _output: &mut W,
+    ) {
+    }
+}
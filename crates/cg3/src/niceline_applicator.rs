@@ -33,11 +33,12 @@ use std::io::{Read, Seek, Write};
 use crate::arena::{CohortId, ReadingId, SwId, TagId};
 use crate::cohort::{CT_RELATED, CT_REMOVED, unignore_all};
 use crate::grammar::Grammar;
-use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::grammar_applicator::{Engine, GrammarApplicator, StreamFormatKind};
 use crate::inlines::{isnl, skipto_nospan};
+use crate::runtime_diagnostics::{InputExcerpt, RuntimeDiagnosticKind};
 use crate::tag::{T_DEPENDENCY, T_MAPPING, T_RELATION};
 use crate::types::TagHash;
-use crate::uextras::{get_line_clean, u_fflush, u_fputc, ux_strip_bom};
+use crate::uextras::{get_line_clean, raw_column, u_fflush, u_fputc, ux_strip_bom};
 
 /// C++ `grammar->single_tags[hash]` (operator[]) — resolve a hash to its
 /// `TagId`. operator[] would default-insert a null `Tag*` on a miss (deref
@@ -93,10 +94,9 @@ impl<'a> NicelineApplicator<'a> {
     /// PORT NOTES: `input`/`output` are generic Rust handles (C++ `std::istream&`
     /// / `std::ostream&`). Storing them into `ux_stdin`/`ux_stdout` is elided
     /// (`Option<()>` placeholders). The `input.good()/eof()/output/grammar`
-    /// validity guards and every `u_fprintf(ux_stderr,…)` diagnostic (including
-    /// the "looked like a cohort but wasn't" and "no valid baseform" warnings)
-    /// are deferred with the I/O layer, but their control-flow effects
-    /// (`goto istext`, baseform fallback) are preserved. `line`/`cleaned` are
+    /// validity guards are deferred with the I/O layer; the "looked like a
+    /// cohort but wasn't", "no valid baseform" and hard-limit warnings are
+    /// runtime diagnostics (`crate::runtime_diagnostics`). `line`/`cleaned` are
     /// native `String`s filled by `get_line_clean`; the C++ `UChar*` pointer
     /// walks become BYTE-offset `usize` cursors read via `inlines::char_at`
     /// (which yields `'\0'` past the end, matching the NUL-terminated buffer).
@@ -115,6 +115,14 @@ impl<'a> NicelineApplicator<'a> {
             .map_err(crate::error::Cg3Error::from)
     }
 
+    /// ADDED: the line being read, quoted at byte `cleaned_at` of its cleaned
+    /// copy.
+    fn excerpt(&self, line: &str, cleaned: &str, cleaned_at: usize) -> InputExcerpt {
+        let at = cleaned.get(..cleaned_at).map_or(0, |s| s.chars().count());
+        let column = raw_column(line.chars(), at);
+        InputExcerpt::new(self.base.doc.num_lines + 1, line, column)
+    }
+
     fn run_grammar_on_text_impl<F, R, W>(
         &mut self,
         fmt: &mut F,
@@ -129,7 +137,7 @@ impl<'a> NicelineApplicator<'a> {
         // ux_stdin = &input; ux_stdout = &output; (elided: Option<()> placeholders)
         // The good()/eof()/output/grammar validity checks (each CG3Quit(1) with a
         // u_fprintf diagnostic) are deferred with the I/O layer.
-        // No-hard/soft-delimiter warnings: deferred I/O.
+        self.base.engine().note_missing_delimiters();
 
         let mut line = String::new();
         let mut cleaned = String::new();
@@ -183,7 +191,11 @@ impl<'a> NicelineApplicator<'a> {
                 if crate::inlines::char_at(&cleaned, space) != '\0'
                     && crate::inlines::char_at(&cleaned, space) != '\t'
                 {
-                    // "looked like a cohort but wasn't - treated as text": deferred.
+                    // "looked like a cohort but wasn't - treated as text".
+                    let text = cleaned[..space].to_string();
+                    let kind = RuntimeDiagnosticKind::NotACohort { text };
+                    let excerpt = self.excerpt(&line, &cleaned, 0);
+                    self.base.engine().note_input(kind, None, excerpt)?;
                     is_text = true;
                 } else {
                     // The C++ NUL-cuts the buffer at the TAB; natively the
@@ -281,7 +293,11 @@ impl<'a> NicelineApplicator<'a> {
                                     .does_set_match_cohort_normal(cc, d, None)?
                             };
                         if over_hard || delim_hit {
-                            // (!is_conv && over_hard) "Hard limit ... forcing break": deferred.
+                            if !self.base.cfg.is_conv && over_hard {
+                                self.base
+                                    .engine()
+                                    .note_hard_limit(cc, StreamFormatKind::Niceline);
+                            }
                             let rs = self.base.doc.store.cohorts.get(cc.0).readings.clone();
                             for r in rs {
                                 let te = self.base.cfg.endtag;
@@ -367,6 +383,7 @@ impl<'a> NicelineApplicator<'a> {
 
                         // base = space; skip a leading quoted baseform / [bracket].
                         let mut base = space;
+                        let reading_at = space;
                         if crate::inlines::char_at(&cleaned, space) == '"' {
                             space += 1;
                             skipto_nospan(&cleaned, &mut space, '"');
@@ -461,7 +478,11 @@ impl<'a> NicelineApplicator<'a> {
                                 self.base.grammar.single_tags_list[wfid.0].hash
                             };
                             self.base.doc.store.readings.get_mut(cr.0).baseform = Some(h);
-                            // "Line %u had no valid baseform." warning: deferred.
+                            let kind = RuntimeDiagnosticKind::NoBaseform {
+                                format: StreamFormatKind::Niceline,
+                            };
+                            let excerpt = self.excerpt(&line, &cleaned, reading_at);
+                            self.base.engine().note_input(kind, Some(cc), excerpt)?;
                         }
                         if !mappings.is_empty() {
                             self.base
@@ -554,7 +575,7 @@ impl<'a> NicelineApplicator<'a> {
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
             // C++ virtual printSingleWindow — the most-derived format decides.
-            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
             let t = Some(tmp);
             crate::single_window::free_swindow(
                 &mut self.base.doc.store,
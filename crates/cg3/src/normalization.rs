@@ -0,0 +1,107 @@
+//! ADDED — no C++ analog. Unicode normalisation of tags, so that a wordform
+//! typed with a precomposed `á` and one typed as `a` + U+0301 are the same
+//! tag.
+//!
+//! Data in many orthographies arrives in a mix of NFC and NFD, and the C++
+//! compared tags code unit by code unit, so a grammar list written in one form
+//! never matched input in the other. A grammar that sets a [`Normalization`]
+//! — `OPTIONS += normalize-nfc ;` (or `normalize-nfd`), or `--normalize` on
+//! the command line — has every tag put into that form twice over:
+//!
+//! * as the grammar is read, in [`parse_tag`](crate::parser_helpers::parse_tag)
+//!   and [`Grammar::allocate_tag`](crate::grammar::Grammar::allocate_tag), after
+//!   `\uXXXX` escapes are expanded, so a regex or `"…"i` tag is compiled from
+//!   the normalised text;
+//! * as the input is read, in the applicator's `add_tag`, which every format
+//!   reader creates its wordforms, baseforms and tags through.
+//!
+//! Both sides in one form is what makes a plain, regex and case-insensitive
+//! tag match alike. The option takes effect where it is set, so it belongs at
+//! the top of the grammar, before the first tag it should cover.
+
+use std::borrow::Cow;
+
+use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick, is_nfd_quick};
+
+/// The form tags are put into.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// As they come, as in the C++.
+    #[default]
+    None,
+    /// Composed.
+    Nfc,
+    /// Decomposed.
+    Nfd,
+}
+
+/// A `--normalize` value that names no form.
+#[derive(Debug, thiserror::Error)]
+#[error("Error: --normalize must be nfc, nfd or none, not `{0}`")]
+pub struct UnknownNormalization(pub String);
+
+impl std::str::FromStr for Normalization {
+    type Err = UnknownNormalization;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Normalization::None),
+            "nfc" | "NFC" => Ok(Normalization::Nfc),
+            "nfd" | "NFD" => Ok(Normalization::Nfd),
+            _ => Err(UnknownNormalization(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Normalization::None => "none",
+            Normalization::Nfc => "nfc",
+            Normalization::Nfd => "nfd",
+        })
+    }
+}
+
+impl Normalization {
+    /// `text` in this form; borrowed when it already is, which for the ASCII
+    /// and already-normalised text of most streams is a quick check and no
+    /// allocation.
+    pub fn apply<'a>(self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalization::None => Cow::Borrowed(text),
+            Normalization::Nfc => {
+                if is_nfc_quick(text.chars()) == IsNormalized::Yes {
+                    Cow::Borrowed(text)
+                } else {
+                    Cow::Owned(text.nfc().collect())
+                }
+            }
+            Normalization::Nfd => {
+                if is_nfd_quick(text.chars()) == IsNormalized::Yes {
+                    Cow::Borrowed(text)
+                } else {
+                    Cow::Owned(text.nfd().collect())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forms_meet() {
+        let composed = "\"<čála>\"";
+        let decomposed = "\"<c\u{30c}a\u{301}la>\"";
+        assert_eq!(Normalization::Nfc.apply(decomposed), composed);
+        assert_eq!(Normalization::Nfd.apply(composed), decomposed);
+        assert!(matches!(
+            Normalization::Nfc.apply(composed),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(Normalization::None.apply(decomposed), decomposed);
+    }
+}
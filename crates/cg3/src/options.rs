@@ -22,6 +22,9 @@
 //! };
 //! ```
 //!
+//! plus one port-only field, `values`, which records every argument a
+//! repeated option was given (`value` still holds the last).
+//!
 //! Mapping: `const char* longName` -> `Option<&'static str>` (the `nullptr`
 //! default becomes `None`, matching u_parseArgs' `if (longName && ...)` guard);
 //! `char shortName` -> [`crate::types::UChar`] (`char`), with the C++ `0`
@@ -57,6 +60,10 @@ pub struct UOption {
     pub description: String,
     pub does_occur: bool,
     pub value: String,
+    /// ADDED — no C++ analog. Every argument the option was given, in command
+    /// line order; `value` keeps only the last, as in the C++. Lets a
+    /// repeatable option (`-g` for a grammar pipeline) see all of them.
+    pub values: Vec<String>,
 }
 
 impl UOption {
@@ -69,6 +76,7 @@ impl UOption {
             description: desc.to_string(),
             does_occur: false,
             value: String::new(),
+            values: Vec::new(),
         }
     }
 
@@ -82,18 +90,16 @@ impl UOption {
             description: String::new(),
             does_occur: false,
             value: String::new(),
+            values: Vec::new(),
         }
     }
 }
 
-// DIVERGENCE (operator decision): three upstream CLI options are removed, not
+// DIVERGENCE (operator decision): two upstream CLI options are removed, not
 // transcribed, so this enum + table are intentionally NOT a 1:1 mirror of the
 // C++ `options` array (see plan node `option-wiring`):
 //   * `--dry-run`         — dead in the reference too; its gate was deleted
 //                           upstream (declared + written, never read).
-//   * `--out-matxin`      — neither FormatConverter has a Matxin arm, so it
-//                           silently emitted CG; dropped rather than reproducing
-//                           the no-op quirk.
 //   * `--show-tag-hashes` — a stderr hash-dump whose numbers are port-internal
 //                           only (port hashes UTF-8, upstream UTF-16) and which
 //                           relied on the class-static mutable-stream wart.
@@ -167,6 +173,8 @@ pub enum Opt {
     ShowSetHashes,
     DumpAst,
     NoBreak,
+    /// ADDED — no C++ analog: `--watch`.
+    Watch,
     InCg,
     InNiceline,
     InApertium,
@@ -174,6 +182,8 @@ pub enum Opt {
     InPlain,
     InJsonl,
     InBinary,
+    /// ADDED — no C++ analog: `--in-matxin`.
+    InMatxin,
     OutCg,
     OutApertium,
     OutFst,
@@ -181,6 +191,41 @@ pub enum Opt {
     OutPlain,
     OutJsonl,
     OutBinary,
+    /// `--out-matxin`: upstream declared it, but its FormatConverter had no
+    /// Matxin arm, so it silently emitted CG; it now writes Matxin.
+    OutMatxin,
+    /// ADDED — no C++ analog: `--out-errors`.
+    OutErrors,
+    /// ADDED — no C++ analog: `--tokenizer FILE`.
+    Tokenizer,
+    /// ADDED — no C++ analog: `--split-sentences`.
+    SplitSentences,
+    /// ADDED — no C++ analog: `--jsonl-strict`.
+    JsonlStrict,
+    /// ADDED — no C++ analog: `--jsonl-header`.
+    JsonlHeader,
+    /// ADDED — no C++ analog: `--max-weight W`.
+    MaxWeight,
+    /// ADDED — no C++ analog: `--n-best N`.
+    NBest,
+    /// ADDED — no C++ analog: `--compress gzip|zstd|none`.
+    Compress,
+    /// ADDED — no C++ analog: `--diagnostics-format human|json|sarif`.
+    DiagnosticsFormat,
+    /// ADDED — no C++ analog: `--strict-input`.
+    StrictInput,
+    /// ADDED — no C++ analog: `--normalize nfc|nfd|none`.
+    Normalize,
+    /// ADDED — no C++ analog: `--dep-validate`.
+    DepValidate,
+    /// ADDED — no C++ analog: `--dep-dot FILE`.
+    DepDot,
+    /// ADDED — no C++ analog: `--dep-json FILE`.
+    DepJson,
+    /// ADDED — no C++ analog: `--relations-dot FILE`.
+    RelationsDot,
+    /// ADDED — no C++ analog: `--relations-json FILE`.
+    RelationsJson,
     NumOptions,
 }
 
@@ -211,7 +256,7 @@ pub fn options() -> OptionsTable {
             "grammar",
             'g',
             UOPT_REQUIRES_ARG,
-            "specifies the grammar file to use for disambiguation",
+            "specifies the grammar file to use for disambiguation; repeat to run several grammars in sequence",
         ),
         UOption::new(
             "grammar-out",
@@ -532,6 +577,12 @@ pub fn options() -> OptionsTable {
             UOPT_NO_ARG,
             "inhibits any extra whitespace in output",
         ),
+        UOption::new(
+            "watch",
+            '\0',
+            UOPT_NO_ARG,
+            "reloads the grammar when it or a file it includes changes; input is read as it arrives",
+        ),
         UOption::new(
             "in-cg",
             '\0',
@@ -574,6 +625,12 @@ pub fn options() -> OptionsTable {
             UOPT_NO_ARG,
             "sets input format to binary (experimental)",
         ),
+        UOption::new(
+            "in-matxin",
+            '\0',
+            UOPT_NO_ARG,
+            "sets input format to Matxin XML",
+        ),
         UOption::new(
             "out-cg",
             '\0',
@@ -616,6 +673,108 @@ pub fn options() -> OptionsTable {
             UOPT_NO_ARG,
             "sets output format to binary (experimental)",
         ),
+        UOption::new(
+            "out-matxin",
+            '\0',
+            UOPT_NO_ARG,
+            "sets output format to Matxin XML",
+        ),
+        UOption::new(
+            "out-errors",
+            '\0',
+            UOPT_NO_ARG,
+            "sets output format to grammar-checker errors as JSON",
+        ),
+        UOption::new(
+            "tokenizer",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "loads plain-text tokenization rules from a file",
+        ),
+        UOption::new(
+            "split-sentences",
+            '\0',
+            UOPT_NO_ARG,
+            "ends plain-text windows at sentence-final punctuation",
+        ),
+        UOption::new(
+            "jsonl-strict",
+            '\0',
+            UOPT_NO_ARG,
+            "rejects JSONL input lines that break its schema, naming line and key",
+        ),
+        UOption::new(
+            "jsonl-header",
+            '\0',
+            UOPT_NO_ARG,
+            "starts JSONL output with the schema version marker",
+        ),
+        UOption::new(
+            "max-weight",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "removes readings weighing more than W after the grammar has run",
+        ),
+        UOption::new(
+            "n-best",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "keeps only the N lightest readings of each cohort after the grammar has run",
+        ),
+        UOption::new(
+            "compress",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "compresses output as gzip, zstd or none; defaults to the --stdout file's extension",
+        ),
+        UOption::new(
+            "diagnostics-format",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes diagnostics to stderr as human (default), json or sarif",
+        ),
+        UOption::new(
+            "strict-input",
+            '\0',
+            UOPT_NO_ARG,
+            "stops at the first malformed input line instead of skipping it",
+        ),
+        UOption::new(
+            "normalize",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "puts grammar and input tags into Unicode nfc or nfd (default none)",
+        ),
+        UOption::new(
+            "dep-validate",
+            '\0',
+            UOPT_NO_ARG,
+            "warns about windows whose dependencies do not form one projective tree",
+        ),
+        UOption::new(
+            "dep-dot",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's dependency tree to FILE as a Graphviz digraph",
+        ),
+        UOption::new(
+            "dep-json",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's dependency tree to FILE as a line of JSON",
+        ),
+        UOption::new(
+            "relations-dot",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's named relations to FILE as a Graphviz digraph",
+        ),
+        UOption::new(
+            "relations-json",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's named relations to FILE as a line of JSON",
+        ),
     ]
 }
 
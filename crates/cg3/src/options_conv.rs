@@ -28,6 +28,7 @@ fn uo(long: &'static str, short: UChar, has_arg: u8, desc: &'static str) -> UOpt
         description: desc.to_string(),
         does_occur: false,
         value: String::new(),
+        values: Vec::new(),
     }
 }
 fn uo3(long: &'static str, short: UChar, has_arg: u8) -> UOption {
@@ -38,6 +39,7 @@ fn uo3(long: &'static str, short: UChar, has_arg: u8) -> UOption {
         description: String::new(),
         does_occur: false,
         value: String::new(),
+        values: Vec::new(),
     }
 }
 
@@ -58,7 +60,21 @@ pub enum Opt {
     InPlain,
     InJsonl,
     InBinary,
+    /// ADDED — no C++ analog: `-m` / `--in-matxin`.
+    InMatxin,
     AddTags,
+    /// ADDED — no C++ analog: `--tokenizer FILE`.
+    Tokenizer,
+    /// ADDED — no C++ analog: `--split-sentences`.
+    SplitSentences,
+    /// ADDED — no C++ analog: `--jsonl-strict`.
+    JsonlStrict,
+    /// ADDED — no C++ analog: `--jsonl-header`.
+    JsonlHeader,
+    /// ADDED — no C++ analog: `--strict-input`.
+    StrictInput,
+    /// ADDED — no C++ analog: `--normalize nfc|nfd|none`.
+    Normalize,
     OutCg,
     OutCg2,
     OutApertium,
@@ -67,8 +83,17 @@ pub enum Opt {
     OutPlain,
     OutJsonl,
     OutBinary,
+    OutMatxin,
+    /// ADDED — no C++ analog: `-E` / `--out-errors`.
+    OutErrors,
     FstWfactor,
     FstWtag,
+    /// ADDED — no C++ analog: `--max-weight W`.
+    MaxWeight,
+    /// ADDED — no C++ analog: `--n-best N`.
+    NBest,
+    /// ADDED — no C++ analog: `--compress gzip|zstd|none`.
+    Compress,
     SubDelimiter,
     SubRtl,
     SubLtr,
@@ -78,6 +103,30 @@ pub enum Opt {
     UnicodeTags,
     PipeDeleted,
     NoBreak,
+    /// ADDED — no C++ analog: `--keep-tags REGEX`.
+    KeepTags,
+    /// ADDED — no C++ analog: `--drop-tags REGEX`.
+    DropTags,
+    /// ADDED — no C++ analog: `--drop-tag-types TYPES`.
+    DropTagTypes,
+    /// ADDED — no C++ analog: `--rename-prefix OLD=NEW`.
+    RenamePrefix,
+    /// ADDED — no C++ analog: `--strip-deleted`.
+    StripDeleted,
+    /// ADDED — no C++ analog: `--max-subreading-depth N`.
+    MaxSubreadingDepth,
+    /// ADDED — no C++ analog: `--untrace`.
+    Untrace,
+    /// ADDED — no C++ analog: `--sort`.
+    Sort,
+    /// ADDED — no C++ analog: `--stabilize-relations`.
+    StabilizeRelations,
+    /// ADDED — no C++ analog: `--inspect-binary`.
+    InspectBinary,
+    /// ADDED — no C++ analog: `--relations-dot FILE`.
+    RelationsDot,
+    /// ADDED — no C++ analog: `--relations-json FILE`.
+    RelationsJson,
     NumOptionsConv,
 }
 
@@ -134,12 +183,54 @@ pub fn options_conv() -> ConvOptionsTable {
             UOPT_NO_ARG,
             "sets input format to binary (experimental)",
         ),
+        uo(
+            "in-matxin",
+            'm',
+            UOPT_NO_ARG,
+            "sets input format to Matxin XML",
+        ),
         uo(
             "add-tags",
             '\0',
             UOPT_NO_ARG,
             "adds minimal analysis to readings (implies -x)",
         ),
+        uo(
+            "tokenizer",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "loads plain-text tokenization rules from a file (implies -x)",
+        ),
+        uo(
+            "split-sentences",
+            '\0',
+            UOPT_NO_ARG,
+            "ends plain-text windows at sentence-final punctuation (implies -x)",
+        ),
+        uo(
+            "jsonl-strict",
+            '\0',
+            UOPT_NO_ARG,
+            "rejects JSONL input lines that break its schema (implies -j)",
+        ),
+        uo(
+            "jsonl-header",
+            '\0',
+            UOPT_NO_ARG,
+            "starts JSONL output with the schema version marker",
+        ),
+        uo(
+            "strict-input",
+            '\0',
+            UOPT_NO_ARG,
+            "stops at the first malformed input line instead of skipping it",
+        ),
+        uo(
+            "normalize",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "puts input tags into Unicode nfc or nfd (default none)",
+        ),
         uo(
             "out-cg",
             'C',
@@ -183,6 +274,18 @@ pub fn options_conv() -> ConvOptionsTable {
             UOPT_NO_ARG,
             "sets output format to binary (experimental)",
         ),
+        uo(
+            "out-matxin",
+            'M',
+            UOPT_NO_ARG,
+            "sets output format to Matxin XML",
+        ),
+        uo(
+            "out-errors",
+            'E',
+            UOPT_NO_ARG,
+            "sets output format to grammar-checker errors as JSON",
+        ),
         uo(
             "wfactor",
             'W',
@@ -195,6 +298,24 @@ pub fn options_conv() -> ConvOptionsTable {
             UOPT_REQUIRES_ARG,
             "FST weight tag prefix (defaults to W)",
         ),
+        uo(
+            "max-weight",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "removes readings weighing more than W",
+        ),
+        uo(
+            "n-best",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "keeps only the N lightest readings of each cohort",
+        ),
+        uo(
+            "compress",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "compresses output as gzip, zstd or none",
+        ),
         uo(
             "sub-delim",
             'S',
@@ -239,6 +360,78 @@ pub fn options_conv() -> ConvOptionsTable {
             UOPT_NO_ARG,
             "inhibits any extra whitespace in output",
         ),
+        uo(
+            "keep-tags",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "keeps only reading tags matching the regex (repeatable)",
+        ),
+        uo(
+            "drop-tags",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "drops reading tags matching the regex (repeatable)",
+        ),
+        uo(
+            "drop-tag-types",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "drops tags of the types: mapping, dependency, relation, numeric, trace",
+        ),
+        uo(
+            "rename-prefix",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "renames the tag prefix OLD to NEW, given as OLD=NEW (repeatable)",
+        ),
+        uo(
+            "strip-deleted",
+            '\0',
+            UOPT_NO_ARG,
+            "drops deleted readings from the output",
+        ),
+        uo(
+            "max-subreading-depth",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "drops sub-readings deeper than N; 0 keeps only the top readings",
+        ),
+        uo(
+            "untrace",
+            '\0',
+            UOPT_NO_ARG,
+            "drops deleted readings and --trace rule names, as cg-untrace (implies --deleted)",
+        ),
+        uo(
+            "sort",
+            '\0',
+            UOPT_NO_ARG,
+            "sorts mapping tags and readings, dropping duplicates, as cg-sort (implies --deleted)",
+        ),
+        uo(
+            "stabilize-relations",
+            '\0',
+            UOPT_NO_ARG,
+            "renumbers ID: and R: tags in first-seen order, as cg-stabilize-relations",
+        ),
+        uo(
+            "inspect-binary",
+            '\0',
+            UOPT_NO_ARG,
+            "decodes a binary stream packet by packet with byte offsets, flagging protocol violations",
+        ),
+        uo(
+            "relations-dot",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's named relations to FILE as a Graphviz digraph",
+        ),
+        uo(
+            "relations-json",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "writes each window's named relations to FILE as a line of JSON",
+        ),
     ]
 }
 
@@ -96,6 +96,7 @@ mod tests {
             description: String::new(),
             does_occur: false,
             value: String::new(),
+            values: Vec::new(),
         }
     }
 
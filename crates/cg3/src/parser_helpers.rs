@@ -206,6 +206,12 @@ pub fn parse_tag<S: ParseTagState>(
         }
     }
 
+    // ADDED: put into the grammar's form once the escapes are code points (see
+    // `crate::normalization`).
+    if let std::borrow::Cow::Owned(normal) = state.grammar().normalization.apply(&to_owned) {
+        to_owned = normal;
+    }
+
     // Dedup: `single_tags[thash]->tag == to` → return existing.
     let thash = hash_value_ustring(&to_owned, 0);
     {
@@ -425,7 +431,11 @@ pub fn parse_tag<S: ParseTagState>(
             // icase_tags scan (empty during textual parse).
             let icase_ids: Vec<TagId> = state.grammar().icase_tags.iter().copied().collect();
             for tid in icase_ids {
-                if ux_str_case_compare(&tag.tag, &state.grammar().single_tags_list[tid.0].tag) {
+                let grammar = state.grammar();
+                if grammar
+                    .case_locale
+                    .caseless_eq(&tag.tag, &grammar.single_tags_list[tid.0].tag)
+                {
                     tag.r#type |= T_TEXTUAL;
                 }
             }
@@ -698,14 +708,6 @@ fn scan_star_u_colon_s(s: &str) -> Option<String> {
     Some(chars[start..i].iter().collect())
 }
 
-/// `uextras.hpp` `ux_strCaseCompare` — full case-fold equality (Unicode
-/// lowercase-fold approximation; ICU parity risk for non-ASCII).
-fn ux_str_case_compare(a: &str, b: &str) -> bool {
-    a.chars()
-        .flat_map(char::to_lowercase)
-        .eq(b.chars().flat_map(char::to_lowercase))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
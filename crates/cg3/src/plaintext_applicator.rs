@@ -19,24 +19,31 @@
 //! accumulates into ONE `SingleWindow` — only the `cCohort`-independent
 //! soft-lookback `delimitAt` path can ever split it. This is faithfully
 //! preserved (the gated blocks are ported verbatim even though they are
-//! effectively dead).
+//! effectively dead). ADDED: `split_sentences` ends a window after each
+//! sentence-final token instead.
+//!
+//! ## Tokenisation
+//! The split itself lives behind [`crate::tokenizer::Tokenizer`] (ADDED); the
+//! default [`UnicodeTokenizer`](crate::tokenizer::UnicodeTokenizer) is the
+//! C++ split, with ICU `u_ispunct`'s full Unicode punctuation category.
 //!
 //! ## Engine / core mismatches (noted)
-//! * ICU `u_ispunct` → `char::is_ascii_punctuation` (ASCII-only approximation —
-//!   NON-ASCII punctuation like `«»¡¿` will NOT be peeled, a known parity gap
-//!   vs. ICU's full Unicode punctuation classification).
 //! * ICU `u_isupper` → `char::is_uppercase`; `UnicodeString::toLower()` →
-//!   `str::to_lowercase` (locale-independent full Unicode lowering).
+//!   [`CaseLocale::to_lower`](crate::case_folding::CaseLocale::to_lower) in the
+//!   grammar's case locale (ADDED: the C++ lowered in ICU's root locale).
 //! * `does_set_match_cohort_normal` gained a 4th `context` param (pass `None`);
 //!   `add_tag` is `add_tag(&str, type)`.
 
 use std::io::{Read, Seek, Write};
 use std::ops::DerefMut;
+use std::sync::Arc;
 
 use crate::arena::{CohortId, SwId, TagId};
 use crate::cohort::CT_REMOVED;
 use crate::grammar::Grammar;
 use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::text_offsets::OffsetCursor;
+use crate::tokenizer::{Tokenizer, UnicodeTokenizer};
 use crate::types::TagHash;
 use crate::uextras::{get_line_clean, u_fflush, u_fputc, ux_strip_bom};
 
@@ -59,6 +66,13 @@ pub struct PlaintextApplicator<B = Box<GrammarApplicator>> {
     /// C++ `bool add_tags = false` — when set, magic readings get a `<cg-conv>`
     /// tag + case tags and are printed; by default readings are `noprint`.
     pub add_tags: bool,
+    /// ADDED — no C++ analog. How lines are cut into tokens; the C++ split by
+    /// default.
+    pub tokenizer: Arc<dyn Tokenizer>,
+    /// ADDED — no C++ analog. End a window after each token the tokenizer
+    /// says ends a sentence; otherwise the whole stream is one window (see
+    /// the module doc).
+    pub split_sentences: bool,
 }
 
 impl PlaintextApplicator<Box<GrammarApplicator>> {
@@ -88,6 +102,8 @@ where
         PlaintextApplicator {
             base,
             add_tags: false,
+            tokenizer: Arc::new(UnicodeTokenizer),
+            split_sentences: false,
         }
     }
 
@@ -148,6 +164,8 @@ where
         let mut cleaned = String::new();
         let ignoreinput = false;
         let mut did_soft_lookback = false;
+        // ADDED: the position in the input text, for `Cohort::offsets`.
+        let mut text_pos = OffsetCursor::default();
 
         self.base.index();
 
@@ -311,70 +329,53 @@ where
                     }
                 }
 
-                // New window (fires only once, on the first token line).
-                if c_swindow.is_none() {
-                    let sw = {
-                        let base = &mut *self.base;
-                        base.doc
-                            .stream
-                            .alloc_append_single_window(&mut base.doc.store)
-                    };
-                    self.base.engine().init_empty_single_window(sw)?;
-                    l_swindow = Some(sw);
-                    // lCohort = cSWindow->cohorts[0] (the boundary cohort).
-                    l_cohort = Some(self.base.doc.store.single_windows.get(sw.0).cohorts[0]);
-                    c_swindow = Some(sw);
-                    c_cohort = None;
-                    self.base.doc.num_windows += 1;
-                    did_soft_lookback = false;
-                }
-
-                // Drain a window if enough queued (dead: next never grows here).
-                if self.base.doc.stream.next.len() > self.base.cfg.num_windows as usize {
-                    self.base.engine().shuffle_windows_down();
-                    self.base.engine().run_grammar_on_window_with(fmt, output)?;
-                    if self.base.doc.num_windows.is_multiple_of(reset_after) {
-                        self.base.engine().reset_indexes();
-                    }
-                    // verbose progress: deferred.
-                }
-
-                // Raw split on spaces (empty fields dropped, as the C++
-                // NUL-splitting produced).
-                let tokens_raw: Vec<String> = cleaned
-                    .split(' ')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
+                // Tokenisation. ADDED: the tokenizer is pluggable (see
+                // `crate::tokenizer`; the default is the C++ split). Tokens are
+                // slices of `line` up to its line break, so each keeps its
+                // place in the input.
+                let body_len = line
+                    .find(|c| crate::inlines::isnl(c) || c == '\0')
+                    .unwrap_or(line.len());
+                let body = &line[..body_len];
+                let ranges = self.tokenizer.tokenize(body);
+                let spans = text_pos.spans(body, &ranges);
+                let tokens: Vec<Vec<char>> = ranges
+                    .iter()
+                    .map(|r| body[r.clone()].chars().collect())
                     .collect();
 
-                // Punctuation splitting.
-                let mut tokens: Vec<Vec<char>> = Vec::new();
-                for raw in &tokens_raw {
-                    let mut p: Vec<char> = raw.chars().collect();
-                    let mut start = 0usize;
-                    let mut len = p.len();
-                    // Peel LEADING punctuation into single-char tokens.
-                    while start < p.len() && len > 0 && u_ispunct(p[start]) {
-                        tokens.push(vec![p[start]]);
-                        start += 1;
-                        len -= 1;
-                    }
-                    let tkz = tokens.len();
-                    // Peel TRAILING punctuation (appended in reverse order).
-                    while len > 0 && u_ispunct(p[start + len - 1]) {
-                        tokens.push(vec![p[start + len - 1]]);
-                        p[start + len - 1] = '\0';
-                        len -= 1;
+                // Cohort creation.
+                for (token, &span) in tokens.iter().zip(&spans) {
+                    // New window: once, on the first token line, and (ADDED) after
+                    // each sentence when `split_sentences` is set.
+                    if c_swindow.is_none() {
+                        let sw = {
+                            let base = &mut *self.base;
+                            base.doc
+                                .stream
+                                .alloc_append_single_window(&mut base.doc.store)
+                        };
+                        self.base.engine().init_empty_single_window(sw)?;
+                        l_swindow = Some(sw);
+                        // C++ `lCohort = cSWindow->cohorts[0]` and `cCohort =
+                        // nullptr` are dropped: the token's own cohort replaces
+                        // the one, and the other is already null.
+                        c_swindow = Some(sw);
+                        self.base.doc.num_windows += 1;
+                        did_soft_lookback = false;
                     }
-                    // Insert the remaining middle token at position tkz.
-                    if len > 0 && p[start] != '\0' {
-                        let middle: Vec<char> = p[start..start + len].to_vec();
-                        tokens.insert(tkz, middle);
+
+                    // Drain a window if enough queued (only `split_sentences` grows
+                    // `next`).
+                    if self.base.doc.stream.next.len() > self.base.cfg.num_windows as usize {
+                        self.base.engine().shuffle_windows_down();
+                        self.base.engine().run_grammar_on_window_with(fmt, output)?;
+                        if self.base.doc.num_windows.is_multiple_of(reset_after) {
+                            self.base.engine().reset_indexes();
+                        }
+                        // verbose progress: deferred.
                     }
-                }
 
-                // Cohort creation.
-                for token in &tokens {
                     let first_upper = !token.is_empty() && u_isupper(token[0]);
                     let mut all_upper = first_upper;
                     let mut mixed_upper = false;
@@ -396,6 +397,7 @@ where
                         let c = self.base.doc.store.cohorts.get_mut(cc.0);
                         c.global_number = gn;
                         c.wordform = Some(wf);
+                        c.offsets = Some(span);
                     }
                     // C++ points cCohort at this cohort and nulls it again right
                     // after the append below with no read in between, so the
@@ -421,7 +423,7 @@ where
                             .baseform
                             .unwrap_or(TagHash(0));
                         self.base.engine().del_tag_from_reading_hash(cr, baseform);
-                        let lowered: String = token_str.to_lowercase();
+                        let lowered = self.base.grammar.case_locale.to_lower(&token_str);
                         let base_tag_text = format!("\"{lowered}\"");
                         let bt = self
                             .base
@@ -457,6 +459,19 @@ where
                         );
                     }
                     c_cohort = None;
+
+                    // ADDED: end the window after sentence-final punctuation.
+                    if self.split_sentences && self.tokenizer.ends_sentence(&token_str) {
+                        let rs = self.base.doc.store.cohorts.get(cc.0).readings.clone();
+                        for r in rs {
+                            let te = self.base.cfg.endtag;
+                            let tid = tag_by_hash(&self.base.grammar, te);
+                            self.base.engine().add_tag_to_reading(r, tid)?;
+                        }
+                        l_swindow = Some(sw);
+                        c_swindow = None;
+                        did_soft_lookback = false;
+                    }
                 }
             } else {
                 is_text = true;
@@ -485,6 +500,7 @@ where
                 }
             }
 
+            text_pos.advance(&line);
             self.base.doc.num_lines += 1;
             line.clear();
             cleaned.clear();
@@ -530,7 +546,7 @@ where
         self.base.engine().shuffle_windows_down();
         while !self.base.doc.stream.previous.is_empty() {
             let tmp = self.base.doc.stream.previous[0];
-            fmt.print_single_window(&mut self.base.engine(), tmp, output, false)?;
+            fmt.print_finished_window(&mut self.base.engine(), tmp, output)?;
             let t = Some(tmp);
             {
                 let base = &mut *self.base;
@@ -638,13 +654,6 @@ impl crate::grammar_applicator::stream_format::StreamFormat for PlaintextFormat
     }
 }
 
-/// ICU `u_ispunct` approximation — ASCII punctuation only (parity gap: ICU
-/// classifies the full Unicode punctuation categories; non-ASCII punctuation is
-/// NOT peeled here).
-fn u_ispunct(c: char) -> bool {
-    c.is_ascii_punctuation()
-}
-
 /// ICU `u_isupper` → Rust `char::is_uppercase` (full Unicode uppercase).
 fn u_isupper(c: char) -> bool {
     c.is_uppercase()
@@ -28,6 +28,13 @@ pub trait Poolable {
     fn clear(&mut self);
 }
 
+// Plain vectors recycle as empty buffers (scratch lists in the engine).
+impl<T> Poolable for Vec<T> {
+    fn clear(&mut self) {
+        Vec::clear(self);
+    }
+}
+
 // [spec:cg3:def:pool.cg3.pool]
 pub struct Pool<T> {
     p: Vec<Box<T>>,
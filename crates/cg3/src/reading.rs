@@ -146,6 +146,37 @@ fn copy_ctor_fields(r: &Reading) -> Reading {
     }
 }
 
+/// [`copy_ctor_fields`] written into an existing value: the container fields
+/// are `clone_from`-ed so `dst` keeps (and, when large enough, reuses) its
+/// capacity. `alloc_reading_copy` hands it a parked spare, which is how a
+/// `COPY`/`APPEND`/sub-reading clone avoids fresh tag-vector allocations. NOT a
+/// manifest symbol — port infra.
+fn copy_ctor_into(dst: &mut Reading, r: &Reading) {
+    dst.mapped = r.mapped;
+    dst.deleted = r.deleted;
+    dst.noprint = r.noprint;
+    dst.immutable = r.immutable;
+    dst.active = r.active;
+    dst.baseform = r.baseform;
+    dst.hash = r.hash;
+    dst.hash_plain = r.hash_plain;
+    dst.number = r.number.wrapping_add(100);
+    dst.tags_bloom = r.tags_bloom;
+    dst.tags_plain_bloom = r.tags_plain_bloom;
+    dst.tags_textual_bloom = r.tags_textual_bloom;
+    dst.mapping = r.mapping;
+    dst.parent = r.parent;
+    dst.next = r.next;
+    dst.hit_by.clone_from(&r.hit_by);
+    dst.tags_list.clone_from(&r.tags_list);
+    dst.tags.clone_from(&r.tags);
+    dst.tags_plain.clone_from(&r.tags_plain);
+    dst.tags_textual.clone_from(&r.tags_textual);
+    dst.tags_numerical.clone_from(&r.tags_numerical);
+    dst.tags_string.clone_from(&r.tags_string);
+    dst.tags_string_hash = r.tags_string_hash;
+}
+
 /// Verbatim field-for-field copy (like `operator=`). Used only to detach a
 /// sub-reading source out of the arena before recursing, so the recursive call
 /// can borrow the store mutably without aliasing. NOT the copy ctor (it does
@@ -193,7 +224,8 @@ pub(crate) fn clone_verbatim(r: &Reading) -> Reading {
 /// there is NO pooled-vs-new divergence here.
 ///
 /// `p` is `Option<CohortId>` (not the task's bare `CohortId`) to preserve the
-/// C++ `p ? … : 0` null branch faithfully.
+/// C++ `p ? … : 0` null branch faithfully. The blank value is taken from
+/// `store.spare_readings` when one is parked, reusing its tag containers.
 pub fn alloc_reading(store: &mut RuntimeStore, p: Option<CohortId>) -> ReadingId {
     let number = match p {
         // UI32(p->readings.size() * 1000 + 1000)
@@ -208,11 +240,9 @@ pub fn alloc_reading(store: &mut RuntimeStore, p: Option<CohortId>) -> ReadingId
         ),
         None => 0,
     };
-    let r = Reading {
-        number,
-        parent: p,
-        ..Default::default()
-    };
+    let mut r = store.spare_readings.get();
+    r.number = number;
+    r.parent = p;
     ReadingId(store.readings.alloc(r))
 }
 
@@ -231,10 +261,28 @@ pub fn alloc_reading(store: &mut RuntimeStore, p: Option<CohortId>) -> ReadingId
 ///
 /// The parent slot is allocated (and its pooled/new fate decided) BEFORE the
 /// `next` chain is deep-cloned, matching the C++ order (`pool.get()` for the
-/// parent, then recursion for the children).
+/// parent, then recursion for the children). The copy is written into a value
+/// from `store.spare_readings` when one is parked (see [`copy_ctor_into`]).
 pub fn alloc_reading_copy(store: &mut RuntimeStore, o: &Reading) -> ReadingId {
+    let mut r = store.spare_readings.get();
+    copy_ctor_into(&mut r, o);
+    place_reading_copy(store, r)
+}
+
+/// [`alloc_reading_copy`] of a reading that lives in the store, by id. Copies
+/// straight from the arena slot into the recycled value, where the by-value
+/// form needs the caller to detach a [`clone_verbatim`] snapshot first. Not a
+/// manifest symbol.
+pub fn alloc_reading_copy_of(store: &mut RuntimeStore, o: ReadingId) -> ReadingId {
+    let mut r = store.spare_readings.get();
+    copy_ctor_into(&mut r, store.readings.get(o.0));
+    place_reading_copy(store, r)
+}
+
+/// Shared tail of the two copy allocators: place the copy `r` in the arena
+/// (applying the pooled-slot flag reset) and deep-copy its `next` chain.
+fn place_reading_copy(store: &mut RuntimeStore, r: Reading) -> ReadingId {
     let pooled = store.readings.will_reuse();
-    let r = copy_ctor_fields(o);
     let child_src = r.next;
     let idx = store.readings.alloc(r);
     // Pooled reuse (pool.get() returned a cleared object) forces both flags off.
@@ -245,8 +293,7 @@ pub fn alloc_reading_copy(store: &mut RuntimeStore, o: &Reading) -> ReadingId {
     }
     // if (r->next) { r->next = alloc_reading(*r->next); }
     if let Some(child_id) = child_src {
-        let src = clone_verbatim(store.readings.get(child_id.0));
-        let new_child = alloc_reading_copy(store, &src);
+        let new_child = alloc_reading_copy_of(store, child_id);
         store.readings.get_mut(idx).next = Some(new_child);
     }
     ReadingId(idx)
@@ -285,7 +332,9 @@ pub fn reverse(store: &mut RuntimeStore, head: ReadingId) -> ReadingId {
 pub fn free_reading(store: &mut RuntimeStore, r: Option<ReadingId>) {
     let Some(id) = r else { return };
     reading_clear(store, id);
-    store.readings.free_slot(id.0);
+    if let Some(spare) = store.readings.free_slot(id.0) {
+        store.spare_readings.put(spare);
+    }
 }
 
 // [spec:cg3:def:reading.cg3.reading.clear-fn]
@@ -338,6 +387,38 @@ pub fn reading_clear(store: &mut RuntimeStore, id: ReadingId) {
     }
 }
 
+/// Spare-list reset for `RuntimeStore::spare_readings` and the embedded
+/// `SingleWindow::bag_of_tags`. The value is detached from the arena (its
+/// `next` chain, if any, was already released by [`reading_clear`]), so this is
+/// the plain field reset of `Reading::clear()` keeping container capacity.
+impl crate::pool::Poolable for Reading {
+    fn clear(&mut self) {
+        self.mapped = false;
+        self.deleted = false;
+        self.noprint = false;
+        self.immutable = false;
+        self.active = false;
+        self.baseform = None;
+        self.hash = 0;
+        self.hash_plain = 0;
+        self.number = 0;
+        self.tags_bloom.clear();
+        self.tags_plain_bloom.clear();
+        self.tags_textual_bloom.clear();
+        self.mapping = None;
+        self.parent = None;
+        self.next = None;
+        self.hit_by.clear();
+        self.tags_list.clear();
+        self.tags.clear();
+        self.tags_plain.clear();
+        self.tags_textual.clear();
+        self.tags_numerical.clear();
+        self.tags_string.clear();
+        self.tags_string_hash = 0;
+    }
+}
+
 // [spec:cg3:def:reading.cg3.reading.reading-fn]
 // [spec:cg3:sem:reading.cg3.reading.reading-fn]
 /// C++ copy constructor `Reading::Reading(const Reading& r)`.
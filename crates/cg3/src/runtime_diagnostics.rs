@@ -0,0 +1,692 @@
+//! ADDED — no C++ analog. The warnings a run raises about its input and its
+//! rules, as values an embedder gets back.
+//!
+//! The C++ writes these to `ux_stderr` as it goes, and the port had either
+//! logged them through `tracing` or dropped them where `ux_stderr` was a
+//! placeholder. Now every one becomes a [`RuntimeDiagnostic`]: its
+//! [`kind`](RuntimeDiagnosticKind), the input line, the window and cohort it
+//! concerns, and the rule in flight. The engine keeps them on
+//! [`Diagnostics::runtime`](crate::grammar_applicator::Diagnostics::runtime) and
+//! still logs each one, so the command-line tools still report them on
+//! stderr, each as its [`Display`](RuntimeDiagnostic): the line the C++
+//! wrote (`Warning: Line 3 had empty tag.`).
+//!
+//! An embedder collects them as a [`RuntimeReport`] with
+//! [`GrammarApplicator::run_grammar_on_text_diagnosed`](crate::grammar_applicator::GrammarApplicator::run_grammar_on_text_diagnosed)
+//! or [`take_runtime_diagnostics`](crate::grammar_applicator::GrammarApplicator::take_runtime_diagnostics),
+//! and [`crate::diagnostics::render_runtime_diagnostics`] quotes the rule each
+//! one names when the grammar sources can be had (a textual load, or a `.cg3b`
+//! with its `.cg3src` companion).
+//!
+//! A run keeps the first [`MAX_KEPT`] and counts the rest, so an endless stream
+//! of bad input cannot grow the log without bound; the report says how many
+//! it only counted.
+//!
+//! A reader that meets input it cannot use as it stands reports it through
+//! [`Engine::note_input`], quoting the line as an [`InputExcerpt`]; under
+//! [`EngineConfig::strict_input`](crate::grammar_applicator::EngineConfig::strict_input)
+//! that stops the run with a [`RunError::MalformedInput`] instead of reading on.
+
+use crate::arena::{CohortId, GenArena, RuleId};
+use crate::cohort::Cohort;
+use crate::error::RunError;
+use crate::grammar::Grammar;
+use crate::grammar_applicator::dep_tree::DepIssue;
+use crate::grammar_applicator::{Engine, Matcher, StreamFormatKind};
+use crate::single_window::SingleWindow;
+
+/// How many diagnostics a run keeps before it only counts them.
+pub const MAX_KEPT: usize = 10_000;
+
+/// How many chars of a line an [`InputExcerpt`] keeps on either side of its
+/// column; a minified JSONL line can run to megabytes.
+pub const EXCERPT_REACH: usize = 80;
+
+/// How much a diagnostic matters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing; the C++ only said it under `--verbose`.
+    Info,
+    Warning,
+    /// The input could not be read as meant; the reader skipped or guessed.
+    Error,
+}
+
+impl Severity {
+    /// The word the C++ messages open with.
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// What a diagnostic is about. The message names no line; the
+/// [`RuntimeDiagnostic`] around it carries the place, and its
+/// [`Display`](RuntimeDiagnostic) puts the two together as the C++ did.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum RuntimeDiagnosticKind {
+    #[error("`{text}` looked like a cohort but wasn't - treated as text")]
+    NotACohort { text: String },
+    #[error("`{text}` looked like a reading but wasn't - treated as text")]
+    NotAReading { text: String },
+    #[error("`{text}` looked like a reading but there was no containing cohort - treated as text")]
+    OrphanReading { text: String },
+    #[error("Reading had no valid baseform")]
+    NoBaseform {
+        /// The reader that raised it; the C++ readers word it differently.
+        format: StreamFormatKind,
+    },
+    #[error("Empty tag")]
+    EmptyTag,
+    #[error("Did not find matching > to close the tag")]
+    UnclosedTag,
+    /// A reader's complaint about input it could not use as it stands, in the
+    /// reader's own words (see [`malformed`](Self::malformed)).
+    #[error("{message}")]
+    MalformedInput {
+        message: String,
+        /// The byte of `message` at which the C++ named the line, if it did.
+        line_at: Option<usize>,
+    },
+    /// Input a reader passed over or patched up, in the reader's own words
+    /// (see [`skipped`](Self::skipped)).
+    #[error("{message}")]
+    SkippedInput {
+        message: String,
+        /// The byte of `message` at which the C++ named the line, if it did.
+        line_at: Option<usize>,
+    },
+    #[error("Sub-reading would be the second sub-reading of its reading - ignored and lost")]
+    SubReadingLost,
+    /// A sub-reading carried more than one mapping tag; all but the first go.
+    #[error("Sub-reading mapping `{tag}` will be discarded")]
+    MappingDiscarded { tag: String },
+    #[error(
+        "No soft or hard delimiters defined in grammar. Hard limit of {hard_limit} cohorts may break windows in unintended places"
+    )]
+    NoDelimiters { hard_limit: u32 },
+    #[error(
+        "No hard delimiters defined in grammar. Soft limit of {soft_limit} cohorts may break windows in unintended places"
+    )]
+    NoHardDelimiters { soft_limit: u32 },
+    #[error(
+        "Hard limit of {limit} cohorts reached at cohort {wordform} (#{number}) - forcing break"
+    )]
+    HardLimit {
+        limit: u32,
+        wordform: String,
+        /// The cohort's global number.
+        number: u32,
+        /// The reader that raised it; the C++ JSONL reader named no cohort.
+        format: StreamFormatKind,
+    },
+    #[error("Dependency between {parent} and {child} would cause a loop. Will not attach them")]
+    DependencyLoop { parent: u32, child: u32 },
+    #[error(
+        "Dependency between {parent} and {child} would cause crossing branches. Will not attach them"
+    )]
+    DependencyCrossing { parent: u32, child: u32 },
+    #[error(
+        "Dependency between {parent} and {child} spans the window boundaries. Enumeration will be global from here on"
+    )]
+    DependencySpansWindows { parent: u32, child: u32 },
+    #[error("Cohort {cohort} (parent {parent}) did not have any siblings")]
+    NoSiblings { cohort: u32, parent: u32 },
+    #[error("{relation} dependency {from} -> {to} does not exist - ignoring")]
+    MissingDependency {
+        /// `Child` or `Sibling`.
+        relation: &'static str,
+        from: u32,
+        to: u32,
+    },
+    #[error("Endless loop detected - the rules still changed the window after {passes} passes")]
+    EndlessLoop { passes: u32 },
+    #[error("JUMP could not find anchor `{anchor}`")]
+    JumpAnchorMissing { anchor: String },
+    /// A window's finished dependency tree is not a well-formed tree (see
+    /// [`crate::grammar_applicator::dep_tree`]).
+    #[error("{issue}, in window {window}")]
+    DependencyTree { window: u32, issue: DepIssue },
+}
+
+impl RuntimeDiagnosticKind {
+    /// A [`MalformedInput`](Self::MalformedInput) whose C++ sentence named the
+    /// line between `head` and `tail`.
+    pub fn malformed(head: &str, tail: &str) -> RuntimeDiagnosticKind {
+        RuntimeDiagnosticKind::MalformedInput {
+            message: format!("{head}{tail}"),
+            line_at: Some(head.len()),
+        }
+    }
+
+    /// A [`SkippedInput`](Self::SkippedInput) whose C++ sentence named the line
+    /// between `head` and `tail`.
+    pub fn skipped(head: &str, tail: &str) -> RuntimeDiagnosticKind {
+        RuntimeDiagnosticKind::SkippedInput {
+            message: format!("{head}{tail}"),
+            line_at: Some(head.len()),
+        }
+    }
+
+    /// The severity this kind always has.
+    pub fn severity(&self) -> Severity {
+        use RuntimeDiagnosticKind as K;
+        match self {
+            K::DependencySpansWindows { .. } => Severity::Info,
+            K::MalformedInput { .. } => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// Whether the C++ only printed it under `--verbose`; the log follows
+    /// suit, the collected values do not.
+    pub fn is_verbose(&self) -> bool {
+        use RuntimeDiagnosticKind as K;
+        matches!(
+            self,
+            K::OrphanReading { .. }
+                | K::DependencyLoop { .. }
+                | K::DependencyCrossing { .. }
+                | K::DependencySpansWindows { .. }
+                | K::NoSiblings { .. }
+                | K::MissingDependency { .. }
+        )
+    }
+
+    /// A short stable name, for machine-readable output.
+    pub fn code(&self) -> &'static str {
+        use RuntimeDiagnosticKind as K;
+        match self {
+            K::NotACohort { .. } => "not-a-cohort",
+            K::NotAReading { .. } => "not-a-reading",
+            K::OrphanReading { .. } => "orphan-reading",
+            K::NoBaseform { .. } => "no-baseform",
+            K::EmptyTag => "empty-tag",
+            K::UnclosedTag => "unclosed-tag",
+            K::MalformedInput { .. } => "malformed-input",
+            K::SkippedInput { .. } => "skipped-input",
+            K::SubReadingLost => "sub-reading-lost",
+            K::MappingDiscarded { .. } => "mapping-discarded",
+            K::NoDelimiters { .. } => "no-delimiters",
+            K::NoHardDelimiters { .. } => "no-hard-delimiters",
+            K::HardLimit { .. } => "hard-limit",
+            K::DependencyLoop { .. } => "dependency-loop",
+            K::DependencyCrossing { .. } => "dependency-crossing",
+            K::DependencySpansWindows { .. } => "dependency-spans-windows",
+            K::NoSiblings { .. } => "no-siblings",
+            K::MissingDependency { .. } => "missing-dependency",
+            K::EndlessLoop { .. } => "endless-loop",
+            K::JumpAnchorMissing { .. } => "jump-anchor-missing",
+            K::DependencyTree { issue, .. } => issue.code(),
+        }
+    }
+}
+
+/// The input line a reader could not use, quoted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputExcerpt {
+    /// 1-based.
+    pub line: u32,
+    /// 1-based, in chars of the whole line (not of [`text`](Self::text)).
+    pub column: u32,
+    /// The line without its newline, cut to [`EXCERPT_REACH`] chars either
+    /// side of the column.
+    pub text: String,
+    /// The column within [`text`](Self::text), 1-based.
+    pub text_column: u32,
+}
+
+impl InputExcerpt {
+    /// Quote `line` (1-based) at `column` (1-based, clamped to the line).
+    pub fn new(line: u32, line_text: &str, column: u32) -> InputExcerpt {
+        InputExcerpt::of_tail(line, line_text.chars(), 1, column)
+    }
+
+    /// Quote a line held as chars up to its first NUL, as the line readers
+    /// keep it.
+    pub fn from_chars(line: u32, buf: &[char], column: u32) -> InputExcerpt {
+        let text: String = buf.iter().take_while(|&&c| c != '\0').collect();
+        InputExcerpt::new(line, &text, column)
+    }
+
+    /// Quote a line of which only `tail`, from column `tail_from` on, is still
+    /// held — a reader of a stream that may be one endless line keeps no more.
+    pub fn of_tail(
+        line: u32,
+        tail: impl IntoIterator<Item = char>,
+        tail_from: u32,
+        column: u32,
+    ) -> InputExcerpt {
+        let chars: Vec<char> = tail.into_iter().collect();
+        let len = chars.len()
+            - chars
+                .iter()
+                .rev()
+                .take_while(|&&c| matches!(c, '\n' | '\r'))
+                .count();
+        let skipped = tail_from.max(1) as usize - 1;
+        // A column before the tail is reported as it is and marked at the
+        // start of what is held.
+        let column = (column.max(1) as usize - 1).min(skipped + len);
+        let at = column.saturating_sub(skipped);
+        let from = at.saturating_sub(EXCERPT_REACH);
+        let to = (at + EXCERPT_REACH).min(len);
+        InputExcerpt {
+            line,
+            column: column as u32 + 1,
+            text: chars[from..to].iter().collect(),
+            text_column: (at - from) as u32 + 1,
+        }
+    }
+}
+
+/// The end of the line a char-at-a-time reader is on: the last
+/// `4 * EXCERPT_REACH` chars at most, so a stream that is one endless line
+/// costs no more to quote than any other.
+#[derive(Clone, Debug, Default)]
+pub struct LineTail {
+    chars: std::collections::VecDeque<char>,
+    /// The chars of the line read so far.
+    read: u32,
+    /// The newlines read so far.
+    newlines: u32,
+}
+
+impl LineTail {
+    /// Take the next char of the input; a newline starts a new line.
+    pub fn push(&mut self, c: char) {
+        if c == '\n' {
+            self.chars.clear();
+            self.read = 0;
+            self.newlines += 1;
+            return;
+        }
+        if self.chars.len() == 4 * EXCERPT_REACH {
+            self.chars.drain(..2 * EXCERPT_REACH);
+        }
+        self.chars.push_back(c);
+        self.read += 1;
+    }
+
+    /// The 1-based line being read.
+    pub fn line(&self) -> u32 {
+        self.newlines + 1
+    }
+
+    /// The 1-based column of the last char taken.
+    pub fn column(&self) -> u32 {
+        self.read
+    }
+
+    /// Quote the line being read at `column`.
+    pub fn excerpt(&self, column: u32) -> InputExcerpt {
+        let from = self.read - self.chars.len() as u32 + 1;
+        InputExcerpt::of_tail(self.line(), self.chars.iter().copied(), from, column)
+    }
+}
+
+/// One diagnostic and where the run was when it arose.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeDiagnostic {
+    pub kind: RuntimeDiagnosticKind,
+    /// The reader's line count as the C++ reported it — for the CG reader the
+    /// lines read before this one. A diagnostic with an
+    /// [`excerpt`](Self::excerpt) has the excerpt's 1-based line here.
+    pub line: u32,
+    /// The number of the window concerned.
+    pub window: Option<u32>,
+    /// The global number of the cohort concerned.
+    pub cohort: Option<u32>,
+    /// The number of the rule in flight.
+    pub rule: Option<u32>,
+    /// That rule's line in the grammar.
+    pub rule_line: Option<u32>,
+    /// The offending input, for a problem with the input itself.
+    pub excerpt: Option<InputExcerpt>,
+}
+
+impl RuntimeDiagnostic {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+/// The line as the C++ wrote it to `ux_stderr`, naming the input line where
+/// it did; the rest of the place is on the value.
+impl std::fmt::Display for RuntimeDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use RuntimeDiagnosticKind as K;
+        let line = self.line;
+        write!(f, "{}: ", self.severity())?;
+        match &self.kind {
+            K::NotACohort { text } => write!(
+                f,
+                "{text} on line {line} looked like a cohort but wasn't - treated as text"
+            ),
+            K::NotAReading { text } => write!(
+                f,
+                "{text} on line {line} looked like a reading but wasn't - treated as text"
+            ),
+            K::OrphanReading { text } => write!(
+                f,
+                "{text} on line {line} looked like a reading but there was no containing cohort - treated as text"
+            ),
+            K::NoBaseform {
+                format: StreamFormatKind::Apertium,
+            } => write!(
+                f,
+                "Cohort {} on line {line} had no valid baseform",
+                self.cohort.unwrap_or(0)
+            ),
+            K::NoBaseform {
+                format: StreamFormatKind::Jsonl,
+            } => write!(
+                f,
+                "Reading on line {line} ended up with no baseform. Using wordform"
+            ),
+            K::NoBaseform { .. } => write!(f, "Line {line} had no valid baseform"),
+            K::EmptyTag => write!(f, "Line {line} had empty tag"),
+            K::UnclosedTag => write!(f, "Did not find matching > to close the tag on line {line}"),
+            K::MalformedInput {
+                message,
+                line_at: Some(at),
+            }
+            | K::SkippedInput {
+                message,
+                line_at: Some(at),
+            } => {
+                let (head, tail) = message.split_at(*at);
+                write!(f, "{head} on line {line}{tail}")
+            }
+            K::SubReadingLost => write!(
+                f,
+                "Sub-reading on line {line} will be ignored and lost as each reading currently only can have one sub-reading"
+            ),
+            K::MappingDiscarded { tag } => write!(
+                f,
+                "Sub-reading mapping {tag} on line {line} will be discarded"
+            ),
+            K::HardLimit {
+                limit,
+                format: StreamFormatKind::Jsonl,
+                ..
+            } => write!(
+                f,
+                "Hard limit of {limit} cohorts reached at line {line} - forcing break"
+            ),
+            K::HardLimit {
+                limit,
+                wordform,
+                number,
+                ..
+            } => write!(
+                f,
+                "Hard limit of {limit} cohorts reached at cohort {wordform} (#{number}) on line {line} - forcing break"
+            ),
+            K::EndlessLoop { .. } => {
+                write!(f, "Endless loop detected before input line {line}")
+            }
+            K::JumpAnchorMissing { anchor } => write!(
+                f,
+                "JUMP on line {} could not find anchor '{anchor}'",
+                self.rule_line.unwrap_or(0)
+            ),
+            kind => write!(f, "{kind}"),
+        }?;
+        f.write_str(".")
+    }
+}
+
+/// The diagnostics of a run so far.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeLog {
+    pub kept: Vec<RuntimeDiagnostic>,
+    /// How many arrived after [`MAX_KEPT`] were kept.
+    pub dropped: u64,
+    /// Keep without logging: the diagnostics go out some other way
+    /// (`--diagnostics-format`, see [`crate::diagnostics_export`]).
+    pub quiet: bool,
+}
+
+impl RuntimeLog {
+    /// Log `diag` and keep it.
+    pub fn push(&mut self, diag: RuntimeDiagnostic, verbose: bool) {
+        if !self.quiet && (!diag.kind.is_verbose() || verbose) {
+            match diag.severity() {
+                Severity::Info => tracing::info!("{diag}"),
+                Severity::Warning => tracing::warn!("{diag}"),
+                Severity::Error => tracing::error!("{diag}"),
+            }
+        }
+        if self.kept.len() < MAX_KEPT {
+            self.kept.push(diag);
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// Hand over what was kept and the count of the rest, starting afresh.
+    pub fn take(&mut self) -> RuntimeReport {
+        RuntimeReport {
+            diagnostics: std::mem::take(&mut self.kept),
+            dropped: std::mem::take(&mut self.dropped),
+        }
+    }
+}
+
+/// The diagnostics handed back from a run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuntimeReport {
+    /// The first [`MAX_KEPT`], in the order they arose.
+    pub diagnostics: Vec<RuntimeDiagnostic>,
+    /// How many more there were.
+    pub dropped: u64,
+}
+
+impl Engine<'_> {
+    /// Record `kind` at the current input line and rule, about `cohort` when
+    /// given.
+    pub fn note(&mut self, kind: RuntimeDiagnosticKind, cohort: Option<CohortId>) {
+        let rule = self.rule_in_flight();
+        let diag = self.place(kind, cohort, rule);
+        self.diag.runtime.push(diag, self.cfg.verbosity_level > 0);
+    }
+
+    /// [`note`](Self::note) for what is found once the rules are done, which
+    /// no rule is to blame for.
+    pub fn note_after_rules(&mut self, kind: RuntimeDiagnosticKind, cohort: Option<CohortId>) {
+        let diag = self.place(kind, cohort, (None, None));
+        self.diag.runtime.push(diag, self.cfg.verbosity_level > 0);
+    }
+
+    fn place(
+        &self,
+        kind: RuntimeDiagnosticKind,
+        cohort: Option<CohortId>,
+        rule: (Option<u32>, Option<u32>),
+    ) -> RuntimeDiagnostic {
+        place(
+            kind,
+            self.doc.num_lines,
+            cohort,
+            (&self.doc.store.cohorts, &self.doc.store.single_windows),
+            rule,
+        )
+    }
+
+    /// Record `kind`, a problem with the input, quoting it; under
+    /// [`strict_input`](crate::grammar_applicator::EngineConfig::strict_input),
+    /// fail with it instead of reading on.
+    pub fn note_input(
+        &mut self,
+        kind: RuntimeDiagnosticKind,
+        cohort: Option<CohortId>,
+        excerpt: InputExcerpt,
+    ) -> Result<(), RunError> {
+        if self.cfg.strict_input {
+            return Err(RunError::MalformedInput {
+                input: self.cfg.input_name.clone(),
+                excerpt,
+                problem: Box::new(kind),
+            });
+        }
+        let rule = self.rule_in_flight();
+        let mut diag = self.place(kind, cohort, rule);
+        diag.line = excerpt.line;
+        diag.excerpt = Some(excerpt);
+        self.diag.runtime.push(diag, self.cfg.verbosity_level > 0);
+        Ok(())
+    }
+
+    /// The warning every driver gives before reading, when the grammar
+    /// leaves window breaking to the limits.
+    pub fn note_missing_delimiters(&mut self) {
+        if self.grammar.delimiters.is_some() {
+            return;
+        }
+        let kind = if self.grammar.soft_delimiters.is_none() {
+            RuntimeDiagnosticKind::NoDelimiters {
+                hard_limit: self.cfg.hard_limit,
+            }
+        } else {
+            RuntimeDiagnosticKind::NoHardDelimiters {
+                soft_limit: self.cfg.soft_limit,
+            }
+        };
+        self.note(kind, None);
+    }
+
+    /// The warning a reader gives when it breaks the window at `cohort`
+    /// because the hard limit is reached.
+    pub fn note_hard_limit(&mut self, cohort: CohortId, format: StreamFormatKind) {
+        let c = self.doc.store.cohorts.get(cohort.0);
+        let wordform = c
+            .wordform
+            .map(|t| self.grammar.single_tags_list.get(t.0).tag.clone())
+            .unwrap_or_default();
+        let kind = RuntimeDiagnosticKind::HardLimit {
+            limit: self.cfg.hard_limit,
+            wordform,
+            number: c.global_number.get(),
+            format,
+        };
+        self.note(kind, Some(cohort));
+    }
+
+    fn rule_in_flight(&self) -> (Option<u32>, Option<u32>) {
+        rule_in_flight(self.grammar, self.scratch.current_rule)
+    }
+}
+
+impl Matcher<'_> {
+    /// [`Engine::note`] from inside the contextual tests.
+    pub fn note(&mut self, kind: RuntimeDiagnosticKind, cohort: Option<CohortId>) {
+        let rule = rule_in_flight(self.grammar, self.scratch.current_rule);
+        let diag = place(
+            kind,
+            *self.num_lines,
+            cohort,
+            (self.cohorts, self.single_windows),
+            rule,
+        );
+        self.runtime.push(diag, self.cfg.verbosity_level > 0);
+    }
+}
+
+/// `kind` at input line `line`, about `cohort` and the window holding it.
+fn place(
+    kind: RuntimeDiagnosticKind,
+    line: u32,
+    cohort: Option<CohortId>,
+    (cohorts, single_windows): (&GenArena<Cohort>, &GenArena<SingleWindow>),
+    (rule, rule_line): (Option<u32>, Option<u32>),
+) -> RuntimeDiagnostic {
+    let (window, cohort) = match cohort {
+        Some(c) => {
+            let c = cohorts.get(c.0);
+            let window = c.parent.map(|sw| single_windows.get(sw.0).number);
+            (window, Some(c.global_number.get()))
+        }
+        None => (None, None),
+    };
+    RuntimeDiagnostic {
+        kind,
+        line,
+        window,
+        cohort,
+        rule,
+        rule_line,
+        excerpt: None,
+    }
+}
+
+/// The number and grammar line of the rule running, if any.
+fn rule_in_flight(grammar: &Grammar, current_rule: Option<RuleId>) -> (Option<u32>, Option<u32>) {
+    match current_rule {
+        Some(rid) => {
+            let r = &grammar.rule_by_number[rid.0];
+            (Some(r.number), (r.line != 0).then_some(r.line))
+        }
+        None => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_bounded_log() {
+        let diag = RuntimeDiagnostic {
+            kind: RuntimeDiagnosticKind::NoBaseform {
+                format: StreamFormatKind::Cg,
+            },
+            line: 3,
+            window: Some(1),
+            cohort: Some(2),
+            rule: None,
+            rule_line: None,
+            excerpt: None,
+        };
+        assert_eq!(diag.to_string(), "Warning: Line 3 had no valid baseform.");
+        let mut log = RuntimeLog::default();
+        for _ in 0..MAX_KEPT + 2 {
+            log.push(diag.clone(), false);
+        }
+        assert_eq!((log.kept.len(), log.dropped), (MAX_KEPT, 2));
+        let report = log.take();
+        assert_eq!((report.diagnostics.len(), report.dropped), (MAX_KEPT, 2));
+        assert_eq!((log.kept.len(), log.dropped), (0, 0));
+    }
+
+    #[test]
+    fn excerpts_are_cut_around_their_column() {
+        let long = format!("{}X{}\n", "a".repeat(200), "b".repeat(200));
+        let e = InputExcerpt::new(4, &long, 201);
+        assert_eq!((e.line, e.column), (4, 201));
+        assert_eq!(e.text.chars().count(), 2 * EXCERPT_REACH);
+        assert_eq!(e.text.chars().nth(e.text_column as usize - 1), Some('X'));
+        let short = InputExcerpt::new(1, "^a/b<n\n", 99);
+        assert_eq!((short.column, short.text.as_str()), (7, "^a/b<n"));
+        let tail = InputExcerpt::of_tail(2, "c/d$".chars(), 501, 503);
+        assert_eq!((tail.column, tail.text_column), (503, 3));
+
+        let mut tail = LineTail::default();
+        "x\n"
+            .chars()
+            .chain(std::iter::repeat_n('a', 1000))
+            .for_each(|c| tail.push(c));
+        let e = tail.excerpt(999);
+        assert_eq!(e.line, 2);
+        assert_eq!((e.column, e.text_column), (999, EXCERPT_REACH as u32 + 1));
+    }
+}
@@ -72,12 +72,6 @@ impl<C: Poolable + Default> Default for ScopedStack<C> {
 mod tests {
     use super::*;
 
-    impl Poolable for Vec<u32> {
-        fn clear(&mut self) {
-            Vec::clear(self);
-        }
-    }
-
     // get() hands out cleared scratch objects; put() recycles capacity (the
     // C++ proxy's clear-on-release), and nested get()s coexist safely.
     // [spec:cg3:sem:scoped-stack.cg3.scoped-stack.scoped-stack-fn/test]
@@ -61,6 +61,42 @@ pub struct SingleWindow {
     /// C++ `Reading bag_of_tags` — an embedded (by-value) `Reading`.
     /// Cross-concern: resolves once the `reading` module lands.
     pub bag_of_tags: crate::reading::Reading,
+    /// ADDED — no C++ analog. Which grammar of an in-process pipeline the
+    /// window's tag ids and set bits belong to: 0 for the applicator that
+    /// reads the stream, `n` for its `n`th stage (see
+    /// [`pipeline`](crate::grammar_applicator::pipeline)).
+    pub pipeline_home: usize,
+}
+
+/// Spare-list reset for `RuntimeStore::spare_single_windows`, applied after
+/// [`single_window_clear`] has torn the window down; it re-asserts the blank
+/// state without dropping any buffers. `rule_to_cohorts` keeps its length (the
+/// engine resizes it to the rule count per window) and, as in
+/// `single_window_clear`, `nested_rule_to_cohorts` is left as is — its only
+/// user clears it before every fill.
+impl crate::pool::Poolable for SingleWindow {
+    fn clear(&mut self) {
+        self.number = 0;
+        self.has_enclosures = false;
+        self.flush_after = false;
+        self.next = None;
+        self.previous = None;
+        self.parent = None;
+        self.text.clear();
+        self.text_post.clear();
+        self.all_cohorts.clear();
+        self.cohorts.clear();
+        self.valid_rules.clear();
+        self.hit_external.clear();
+        for cs in &mut self.rule_to_cohorts {
+            cs.clear();
+        }
+        self.variables_set.clear(0);
+        self.variables_rem.clear(0);
+        self.variables_output.clear();
+        crate::pool::Poolable::clear(&mut self.bag_of_tags);
+        self.pipeline_home = 0;
+    }
 }
 
 // [spec:cg3:def:single-window.cg3.compare-cohort]
@@ -104,11 +140,13 @@ pub struct CompareCohort;
 /// NO pooled-vs-new divergence: writing a fresh `SingleWindow { parent: p, .. }`
 /// into a reused-or-new arena slot is exact. `p` is the `Window` placeholder
 /// handle (`Option<u32>`); the singleton `Window` has no arena id.
+///
+/// The blank value is a parked `store.spare_single_windows` entry when there is
+/// one, so its cohort vectors and per-rule `rule_to_cohorts` sets come back
+/// with their capacity from the previous window.
 pub fn alloc_swindow(store: &mut RuntimeStore, p: Option<u32>) -> SwId {
-    let sw = SingleWindow {
-        parent: p,
-        ..SingleWindow::default()
-    };
+    let mut sw = store.spare_single_windows.get();
+    sw.parent = p;
     SwId(store.single_windows.alloc(sw))
 }
 
@@ -133,7 +171,9 @@ pub fn free_swindow(
 ) {
     let Some(id) = s else { return };
     single_window_clear(store, cohorts, deps, id);
-    store.single_windows.free_slot(id.0);
+    if let Some(spare) = store.single_windows.free_slot(id.0) {
+        store.spare_single_windows.put(spare);
+    }
 }
 
 /// Shared teardown prologue — the identical body of `~SingleWindow()` and the
@@ -160,16 +200,18 @@ fn single_window_teardown(
     if store.single_windows.get(sw_id.0).cohorts.len() > 1 {
         let back = *store.single_windows.get(sw_id.0).cohorts.last().unwrap();
         let threshold = store.cohorts.get(back.0).global_number;
-        let mut to_erase: Vec<u32> = Vec::new();
-        {
-            for &pair in deps.relation_map.iter() {
-                if pair.1 <= threshold.get() {
-                    to_erase.push(pair.0);
+        if !deps.relation_map.empty() {
+            let mut to_erase: Vec<u32> = Vec::new();
+            {
+                for &pair in deps.relation_map.iter() {
+                    if pair.1 <= threshold.get() {
+                        to_erase.push(pair.0);
+                    }
                 }
             }
-        }
-        for k in to_erase {
-            deps.relation_map.erase(k);
+            for k in to_erase {
+                deps.relation_map.erase(k);
+            }
         }
     }
 
@@ -177,12 +219,14 @@ fn single_window_teardown(
     // free_cohort → cohort_clear so the cohort is erased from the document
     // window's cohort_map/dep_window — a bare free_slot leaves stale map entries
     // that later resolve dep links to freed slots (C++ Cohort::clear() erases
-    // them).
-    let all = store.single_windows.get(sw_id.0).all_cohorts.clone();
-    for iter in all {
+    // them). The list is moved out for the loop and handed back afterwards
+    // (still full — `single_window_clear` empties it) so its buffer survives.
+    let all = std::mem::take(&mut store.single_windows.get_mut(sw_id.0).all_cohorts);
+    for &iter in &all {
         let h = Some(iter);
         crate::cohort::free_cohort(store, Some((&mut *cohorts, &mut *deps)), h);
     }
+    store.single_windows.get_mut(sw_id.0).all_cohorts = all;
 
     // (3) Splice out of the sibling doubly-linked list.
     let next = store.single_windows.get(sw_id.0).next;
@@ -261,8 +305,10 @@ pub fn single_window_clear(
     sw.variables_output.clear();
     // bag_of_tags is an embedded Reading value (not an arena object); its C++
     // `clear()` resets it to blank. `reading_clear` needs an arena id, so the
-    // value is reset to Default here (a bag-of-tags never holds a `next` chain).
-    sw.bag_of_tags = crate::reading::Reading::default();
+    // value gets the detached Poolable reset (a bag-of-tags never holds a `next`
+    // chain), which keeps its tag containers' capacity.
+    crate::pool::Poolable::clear(&mut sw.bag_of_tags);
+    sw.pipeline_home = 0;
     // QUIRK: nested_rule_to_cohorts intentionally NOT reset.
 }
 
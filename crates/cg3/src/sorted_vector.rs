@@ -91,12 +91,27 @@ pub type SizeType = usize;
 /// C++ `class sorted_vector<T, Comp>` (`src/sorted_vector.hpp`): a
 /// `std::vector<T>` kept sorted by `comp` and holding unique elements (a
 /// sorted set). Ported bug-for-bug.
-#[derive(Clone)]
 pub struct SortedVector<T, Comp = Less> {
     elements: Container<T>,
     comp: Comp,
 }
 
+// Hand-written rather than derived so `clone_from` reaches `Vec::clone_from`,
+// which reuses the destination's buffer (the derive would reallocate).
+impl<T: Clone, Comp: Clone> Clone for SortedVector<T, Comp> {
+    fn clone(&self) -> Self {
+        SortedVector {
+            elements: self.elements.clone(),
+            comp: self.comp.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.elements.clone_from(&source.elements);
+        self.comp.clone_from(&source.comp);
+    }
+}
+
 impl<T, Comp: Default> SortedVector<T, Comp> {
     // [spec:cg3:def:sorted-vector.cg3.sorted-vector.sorted-vector-fn]
     // [spec:cg3:sem:sorted-vector.cg3.sorted-vector.sorted-vector-fn]
@@ -8,11 +8,19 @@
 //! readings) destructure the store into its fields to split the borrows:
 //! `let RuntimeStore { cohorts, readings, .. } = store;`.
 //!
+//! The arenas recycle slot *indices*; the C++ pools also recycled the objects'
+//! heap storage (a cleared `Cohort` kept its vectors' capacity). The
+//! `spare_*` lists restore that half: a freed object is cleared and parked
+//! (the [`ScopedStack`] spare-list idiom) and the next `alloc_*` reuses it, so
+//! once every arena and spare list has grown to the working-set size a window
+//! cycles through the store without touching the heap.
+//!
 //! Not a manifest symbol — port infrastructure standing in for the pools.
 
 use crate::arena::GenArena;
 use crate::cohort::Cohort;
 use crate::reading::Reading;
+use crate::scoped_stack::ScopedStack;
 use crate::single_window::SingleWindow;
 
 #[derive(Default)]
@@ -20,6 +28,14 @@ pub struct RuntimeStore {
     pub cohorts: GenArena<Cohort>,
     pub readings: GenArena<Reading>,
     pub single_windows: GenArena<SingleWindow>,
+    /// Cleared cohorts returned by `free_cohort`, handed out by `alloc_cohort`.
+    pub spare_cohorts: ScopedStack<Cohort>,
+    /// Cleared readings returned by `free_reading`, handed out by
+    /// `alloc_reading` / `alloc_reading_copy`.
+    pub spare_readings: ScopedStack<Reading>,
+    /// Cleared single-windows returned by `free_swindow`, handed out by
+    /// `alloc_swindow`.
+    pub spare_single_windows: ScopedStack<SingleWindow>,
 }
 
 impl RuntimeStore {
@@ -0,0 +1,339 @@
+//! ADDED — no C++ analog. Stream commands (`<STREAMCMD:…>`) parsed in one
+//! place for every input format, and the global variables they and
+//! embedders set.
+//!
+//! Upstream, the CG reader, the Apertium reader (`parseStreamVar`) and the
+//! JSONL reader each parse `SETVAR` / `REMVAR` themselves, and not alike:
+//! JSONL takes a single `name[=value]`, and the CG and Apertium readers only
+//! update the live variables for a lone bare name. Here every reader hands
+//! the command text to [`StreamCommand::parse`] and stages the variables
+//! through [`Engine::stage_variables`], so `SETVAR:a=b,c` means the same in
+//! every format: `a` set to `b`, and `c` set with no value.
+//!
+//! `<STREAMCMD:GETVARS>` is new. The reader runs and prints the windows read
+//! so far, as for `FLUSH` but keeping the variables, then answers with
+//! `<STREAMCMD:VARS:name=value,name>` listing every variable and its value
+//! as the next window will see them (`<STREAMCMD:VARS:>` when there are
+//! none). The `_MPREFIX` variable each window sets for itself is left out,
+//! whatever the format. The binary format carries its commands as bytes and
+//! has none for variables; it passes the answer on as a text packet.
+//!
+//! Embedders read and change the same variables between runs through
+//! [`GrammarApplicator::variables`](crate::grammar_applicator::GrammarApplicator::variables)
+//! and its siblings. There is no hook to change them between the windows of
+//! one run; within a run only the stream's own commands do.
+
+use crate::grammar_applicator::{Engine, GrammarApplicator};
+use crate::strings::{
+    STR_CMD_EXIT, STR_CMD_FLUSH, STR_CMD_GETVARS, STR_CMD_IGNORE, STR_CMD_REMVAR, STR_CMD_RESUME,
+    STR_CMD_SETVAR, STR_CMD_VARS,
+};
+
+/// A variable a `SETVAR` sets; `value` is `None` for a bare name, which sets
+/// the variable with no value (`*`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VarSetting {
+    /// Empty when the command left it out, which stages `*` with a warning.
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// One parsed stream command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamCommand {
+    Flush,
+    Exit,
+    Ignore,
+    Resume,
+    SetVar(Vec<VarSetting>),
+    /// The names to remove; empty names are dropped.
+    RemVar(Vec<String>),
+    GetVars,
+}
+
+impl StreamCommand {
+    /// Parse a whole command, `<STREAMCMD:…>` included; `None` for anything
+    /// else, which readers pass on as text.
+    ///
+    /// `SETVAR:` takes a comma-separated list of `name` or `name=value`; a
+    /// value runs to the next comma, so it may hold `=`. `REMVAR:` takes a
+    /// comma-separated list of names.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            STR_CMD_FLUSH => return Some(Self::Flush),
+            STR_CMD_EXIT => return Some(Self::Exit),
+            STR_CMD_IGNORE => return Some(Self::Ignore),
+            STR_CMD_RESUME => return Some(Self::Resume),
+            STR_CMD_GETVARS => return Some(Self::GetVars),
+            _ => {}
+        }
+        if let Some(payload) = text.strip_prefix(STR_CMD_SETVAR) {
+            let payload = payload.strip_suffix('>')?;
+            let settings = payload
+                .split(',')
+                .map(|item| match item.split_once('=') {
+                    Some((name, value)) => VarSetting {
+                        name: name.to_string(),
+                        value: Some(value.to_string()),
+                    },
+                    None => VarSetting {
+                        name: item.to_string(),
+                        value: None,
+                    },
+                })
+                .collect();
+            return Some(Self::SetVar(settings));
+        }
+        if let Some(payload) = text.strip_prefix(STR_CMD_REMVAR) {
+            let payload = payload.strip_suffix('>')?;
+            let names = payload
+                .split(',')
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+            return Some(Self::RemVar(names));
+        }
+        None
+    }
+}
+
+/// The `<STREAMCMD:VARS:…>` answer to `GETVARS` for `vars`, in order.
+pub fn vars_reply(vars: &[(String, Option<String>)]) -> String {
+    let list: Vec<String> = vars
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!("{name}={value}"),
+            None => name.clone(),
+        })
+        .collect();
+    format!("{STR_CMD_VARS}{}>", list.join(","))
+}
+
+impl Engine<'_> {
+    /// Stage a `SETVAR` or `REMVAR` the reader just met: the deltas go to
+    /// `variables_set` / `variables_rem` for the next window to take, and
+    /// every touched name to `variables_output` so that window prints it.
+    /// With `live` (no window is being read) the global variables change at
+    /// once as well. Other commands are ignored.
+    pub fn stage_variables(
+        &mut self,
+        cmd: &StreamCommand,
+        live: bool,
+        variables_set: &mut crate::flat_unordered_map::Uint32FlatHashMap,
+        variables_rem: &mut crate::flat_unordered_set::Uint32FlatHashSet,
+        variables_output: &mut crate::sorted_vector::Uint32SortedVector,
+    ) -> Result<(), crate::error::RunError> {
+        let tag_any = self.grammar.tag_any;
+        match cmd {
+            StreamCommand::SetVar(settings) => {
+                for (i, setting) in settings.iter().enumerate() {
+                    let name = if setting.name.is_empty() {
+                        if setting.value.is_some() {
+                            tracing::warn!(
+                                "Warning: SETVAR on line {} had no identifier before the =! Defaulting to identifier *.",
+                                self.doc.num_lines
+                            );
+                        } else if i > 0 {
+                            tracing::warn!(
+                                "Warning: SETVAR on line {} had no identifier after the ,! Defaulting to identifier *.",
+                                self.doc.num_lines
+                            );
+                        }
+                        tag_any
+                    } else {
+                        self.intern(&setting.name)?
+                    };
+                    let value = match setting.value.as_deref() {
+                        None => tag_any,
+                        Some("") => {
+                            tracing::warn!(
+                                "Warning: SETVAR on line {} had no value after the =! Defaulting to value *.",
+                                self.doc.num_lines
+                            );
+                            tag_any
+                        }
+                        Some(value) => self.intern(value)?,
+                    };
+                    *variables_set.index_or_insert(name) = value;
+                    variables_rem.erase(name);
+                    variables_output.insert(name);
+                    if live {
+                        *self.doc.variables.index_or_insert(name) = value;
+                    }
+                }
+            }
+            StreamCommand::RemVar(names) => {
+                for name in names {
+                    let name = self.intern(name)?;
+                    variables_set.erase(name);
+                    variables_rem.insert(name);
+                    variables_output.insert(name);
+                    if live {
+                        self.doc.variables.erase(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The `GETVARS` answer: the global variables with the deltas still
+    /// waiting for the next window applied, sorted by name.
+    pub fn variables_reply(
+        &self,
+        variables_set: &crate::flat_unordered_map::Uint32FlatHashMap,
+        variables_rem: &crate::flat_unordered_set::Uint32FlatHashSet,
+    ) -> String {
+        let mut vars: std::collections::BTreeMap<u32, u32> = self
+            .doc
+            .variables
+            .iter()
+            .chain(variables_set.iter())
+            .copied()
+            .collect();
+        vars.retain(|&name, _| !variables_rem.contains(name) && !self.is_internal_variable(name));
+        let mut named: Vec<(String, Option<String>)> = vars
+            .into_iter()
+            .map(|(name, value)| {
+                let value = (value != self.grammar.tag_any).then(|| self.tag_text(value));
+                (self.tag_text(name), value)
+            })
+            .collect();
+        named.sort();
+        vars_reply(&named)
+    }
+
+    /// Whether `name` is the `_MPREFIX` variable each window sets, which is
+    /// not listed.
+    fn is_internal_variable(&self, name: u32) -> bool {
+        name == self.cfg.mprefix_key.get()
+    }
+
+    pub(crate) fn intern(&mut self, text: &str) -> Result<u32, crate::error::RunError> {
+        let tag = self.add_tag(text, crate::tag::TagType::empty())?;
+        Ok(self.grammar.single_tags_list[tag.0].hash.get())
+    }
+
+    pub(crate) fn tag_text(&self, hash: u32) -> String {
+        let it = self.grammar.single_tags.find(hash);
+        if it == self.grammar.single_tags.end() {
+            return String::new();
+        }
+        self.grammar.single_tags_list[it.get().1.0].tag.clone()
+    }
+}
+
+impl GrammarApplicator {
+    /// The global variables as the next window will start with them, sorted
+    /// by name; a `None` value is a variable set without one. `_MPREFIX` is
+    /// left out, as from `GETVARS`.
+    ///
+    /// This and its siblings work between runs, before or after
+    /// [`run_grammar_on_text`](Self::run_grammar_on_text); a run only sees
+    /// them change through the stream's `SETVAR` and `REMVAR` commands.
+    pub fn variables(&mut self) -> Vec<(String, Option<String>)> {
+        let tag_any = self.grammar.tag_any;
+        let e = self.engine();
+        let mut vars: Vec<(String, Option<String>)> = e
+            .doc
+            .variables
+            .iter()
+            .filter(|&&(name, _)| !e.is_internal_variable(name))
+            .map(|&(name, value)| {
+                (
+                    e.tag_text(name),
+                    (value != tag_any).then(|| e.tag_text(value)),
+                )
+            })
+            .collect();
+        vars.sort();
+        vars
+    }
+
+    /// The value of variable `name`: `None` if it is not set, `Some(None)` if
+    /// it is set without a value.
+    pub fn variable(&mut self, name: &str) -> Option<Option<String>> {
+        self.variables()
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Set variable `name`, to `value` or to no value, for the windows read
+    /// from here on. A `FLUSH` in the stream clears it again.
+    pub fn set_variable(
+        &mut self,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<(), crate::error::RunError> {
+        let mut e = self.engine();
+        let name = e.intern(name)?;
+        let value = match value {
+            Some(value) => e.intern(value)?,
+            None => e.grammar.tag_any,
+        };
+        *e.doc.variables.index_or_insert(name) = value;
+        Ok(())
+    }
+
+    /// Remove variable `name`; whether it was set.
+    pub fn remove_variable(&mut self, name: &str) -> bool {
+        let e = self.engine();
+        let hash = e
+            .doc
+            .variables
+            .iter()
+            .map(|&(hash, _)| hash)
+            .find(|&hash| e.tag_text(hash) == name);
+        if let Some(hash) = hash {
+            e.doc.variables.erase(hash);
+        }
+        hash.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_command() {
+        assert_eq!(
+            StreamCommand::parse("<STREAMCMD:FLUSH>"),
+            Some(StreamCommand::Flush)
+        );
+        assert_eq!(
+            StreamCommand::parse("<STREAMCMD:GETVARS>"),
+            Some(StreamCommand::GetVars)
+        );
+        assert_eq!(
+            StreamCommand::parse("<STREAMCMD:SETVAR:a=b,c,d=e=f>"),
+            Some(StreamCommand::SetVar(vec![
+                VarSetting {
+                    name: "a".into(),
+                    value: Some("b".into())
+                },
+                VarSetting {
+                    name: "c".into(),
+                    value: None
+                },
+                VarSetting {
+                    name: "d".into(),
+                    value: Some("e=f".into())
+                },
+            ]))
+        );
+        assert_eq!(
+            StreamCommand::parse("<STREAMCMD:REMVAR:a,,b>"),
+            Some(StreamCommand::RemVar(vec!["a".into(), "b".into()]))
+        );
+        assert_eq!(StreamCommand::parse("<STREAMCMD:SETVAR:a"), None);
+        assert_eq!(StreamCommand::parse("<STREAMCMD:NOPE>"), None);
+        assert_eq!(
+            vars_reply(&[("a".into(), Some("b".into())), ("c".into(), None)]),
+            "<STREAMCMD:VARS:a=b,c>"
+        );
+        assert_eq!(vars_reply(&[]), "<STREAMCMD:VARS:>");
+    }
+}
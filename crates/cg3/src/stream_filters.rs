@@ -0,0 +1,296 @@
+//! ADDED — no C++ analog. The upstream `scripts/cg-untrace`, `scripts/cg-sort`
+//! and `scripts/cg-stabilize-relations` as filters over the window model, so
+//! they work on every stream format the converter reads rather than on CG
+//! text alone. They ship as the `cg-untrace`, `cg-sort` and
+//! `cg-stabilize-relations` tools, and as the matching `cg-conv` options.
+//!
+//! Each filter changes a window (or lone cohort) just before it is printed,
+//! like a [`TagFilter`], from the [`ConvFormat`](crate::format_converter)
+//! print path:
+//!
+//! * [`untrace_cohort`] drops deleted readings and the rule names `--trace`
+//!   leaves on readings ([`is_trace_tag`](crate::tag_filter::is_trace_tag));
+//!   `ID:` and `R:` relation tags stay.
+//! * [`sort_cohort`] sorts each reading's mapping tags, drops readings that
+//!   are then identical, and orders the rest by their text, so two runs that
+//!   only differ in reading order compare equal. Deleted readings are sorted
+//!   apart from the live ones.
+//! * [`RelationStabilizer`] renumbers the targets of `ID:n` and `R:name:n`
+//!   tags in first-seen order over the whole stream, so relation numbering is
+//!   comparable across runs.
+//!
+//! The numbers a stabilizer rewrites are those the stream carries as tags; a
+//! binary stream's decoded relations print with the numbers the reader gives
+//! their cohorts, and are left alone.
+
+use std::collections::HashMap;
+
+use crate::arena::{CohortId, ReadingId, SwId};
+use crate::cohort::CT_REMOVED;
+use crate::grammar_applicator::Engine;
+use crate::tag::{T_MAPPING, TagType};
+use crate::tag_filter::TagFilter;
+use crate::types::TagHash;
+
+/// Which of the filters to run, and the state the stabilizer keeps between
+/// windows.
+#[derive(Clone, Debug, Default)]
+pub struct StreamFilters {
+    pub untrace: bool,
+    pub sort: bool,
+    /// Renumber relations when set.
+    pub stabilizer: Option<RelationStabilizer>,
+}
+
+impl StreamFilters {
+    /// Whether it changes anything at all.
+    pub fn is_active(&self) -> bool {
+        self.untrace || self.sort || self.stabilizer.is_some()
+    }
+
+    /// Filter every cohort of `window`.
+    pub fn apply_window(
+        &mut self,
+        e: &mut Engine<'_>,
+        window: SwId,
+    ) -> Result<(), crate::error::RunError> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let cohorts = e.doc.store.single_windows.get(window.0).all_cohorts.clone();
+        for cohort in cohorts {
+            self.apply_cohort(e, cohort)?;
+        }
+        Ok(())
+    }
+
+    /// Filter one cohort: untrace, then stabilize, then sort, so the sort
+    /// sees the tags the other two leave.
+    pub fn apply_cohort(
+        &mut self,
+        e: &mut Engine<'_>,
+        cohort: CohortId,
+    ) -> Result<(), crate::error::RunError> {
+        if self.untrace {
+            untrace_cohort(e, cohort)?;
+        }
+        if let Some(stabilizer) = &mut self.stabilizer {
+            stabilizer.apply_cohort(e, cohort)?;
+        }
+        if self.sort {
+            sort_cohort(e, cohort)?;
+        }
+        Ok(())
+    }
+}
+
+/// `cg-untrace` for one cohort: drop its deleted readings and the `--trace`
+/// rule names on its readings.
+pub fn untrace_cohort(e: &mut Engine<'_>, cohort: CohortId) -> Result<(), crate::error::RunError> {
+    let filter = TagFilter {
+        drop_trace: true,
+        strip_deleted: true,
+        ..Default::default()
+    };
+    filter.apply_cohort(e, cohort)
+}
+
+/// `cg-sort` for one cohort; mapping tags are those of type `T_MAPPING`, so
+/// the converter's mapping prefix (`cg-conv --prefix`) decides them.
+pub fn sort_cohort(e: &mut Engine<'_>, cohort: CohortId) -> Result<(), crate::error::RunError> {
+    if e.doc
+        .store
+        .cohorts
+        .get(cohort.0)
+        .r#type
+        .intersects(CT_REMOVED)
+    {
+        return Ok(());
+    }
+    let c = e.doc.store.cohorts.get(cohort.0);
+    let (readings, deleted) = (c.readings.clone(), c.deleted.clone());
+    for &top in readings.iter().chain(&deleted) {
+        let mut cur = Some(top);
+        while let Some(r) = cur {
+            sort_mapping_tags(e, r)?;
+            cur = e.doc.store.readings.get(r.0).next;
+        }
+    }
+    let readings = sorted_unique(e, readings);
+    let deleted = sorted_unique(e, deleted);
+    // The CG printer orders readings by number, the others by list order.
+    for (i, &r) in readings.iter().chain(&deleted).enumerate() {
+        e.doc.store.readings.get_mut(r.0).number = i as u32 + 1;
+    }
+    let c = e.doc.store.cohorts.get_mut(cohort.0);
+    c.readings = readings;
+    c.deleted = deleted;
+    Ok(())
+}
+
+/// Sort the mapping tags among themselves, leaving the other tags in place.
+fn sort_mapping_tags(e: &mut Engine<'_>, reading: ReadingId) -> Result<(), crate::error::RunError> {
+    let tags = e.doc.store.readings.get(reading.0).tags_list.clone();
+    let (slots, mut mappings): (Vec<usize>, Vec<(String, u32)>) = tags
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &hash)| {
+            let (text, ty) = tag_text(e, hash);
+            ty.intersects(T_MAPPING).then_some((i, (text, hash)))
+        })
+        .unzip();
+    mappings.sort();
+    let mut sorted = tags.clone();
+    for (slot, (_, hash)) in slots.into_iter().zip(mappings) {
+        sorted[slot] = hash;
+    }
+    if sorted != tags {
+        e.doc.store.readings.get_mut(reading.0).tags_list = sorted;
+        e.reflow_reading(reading)?;
+    }
+    Ok(())
+}
+
+/// `readings` ordered by their text, with the later of any two identical
+/// ones freed.
+fn sorted_unique(e: &mut Engine<'_>, readings: Vec<ReadingId>) -> Vec<ReadingId> {
+    let mut keyed: Vec<(String, ReadingId)> = readings
+        .into_iter()
+        .map(|r| (reading_key(e, r), r))
+        .collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut kept: Vec<(String, ReadingId)> = Vec::with_capacity(keyed.len());
+    for (key, r) in keyed {
+        if kept.last().is_some_and(|(last, _)| *last == key) {
+            crate::reading::free_reading(&mut e.doc.store, Some(r));
+        } else {
+            kept.push((key, r));
+        }
+    }
+    kept.into_iter().map(|(_, r)| r).collect()
+}
+
+/// A reading's tags as CG prints them, mapping tags last, one line per
+/// sub-reading.
+fn reading_key(e: &Engine<'_>, reading: ReadingId) -> String {
+    let mut key = String::new();
+    let mut cur = Some(reading);
+    while let Some(r) = cur {
+        let r = e.doc.store.readings.get(r.0);
+        let wordform = r
+            .parent
+            .and_then(|c| e.doc.store.cohorts.get(c.0).wordform)
+            .map(|t| e.grammar.single_tags_list.get(t.0).hash);
+        let mut mappings = Vec::new();
+        for &hash in &r.tags_list {
+            if Some(TagHash(hash)) == wordform {
+                continue;
+            }
+            let (text, ty) = tag_text(e, hash);
+            if ty.intersects(T_MAPPING) {
+                mappings.push(text);
+            } else {
+                key.push(' ');
+                key.push_str(&text);
+            }
+        }
+        for text in mappings {
+            key.push(' ');
+            key.push_str(&text);
+        }
+        key.push('\n');
+        cur = r.next;
+    }
+    key
+}
+
+fn tag_text(e: &Engine<'_>, hash: u32) -> (String, TagType) {
+    let tid = e.grammar.single_tags.find(hash).get().1;
+    let tag = e.grammar.single_tags_list.get(tid.0);
+    (tag.tag.clone(), tag.r#type)
+}
+
+/// `cg-stabilize-relations`: the numbers seen so far and what they became.
+#[derive(Clone, Debug, Default)]
+pub struct RelationStabilizer {
+    ids: HashMap<String, usize>,
+}
+
+impl RelationStabilizer {
+    /// Renumber the relation tags of every reading of `cohort`, sub-readings
+    /// and deleted readings included.
+    pub fn apply_cohort(
+        &mut self,
+        e: &mut Engine<'_>,
+        cohort: CohortId,
+    ) -> Result<(), crate::error::RunError> {
+        let c = e.doc.store.cohorts.get(cohort.0);
+        if c.r#type.intersects(CT_REMOVED) {
+            return Ok(());
+        }
+        let tops: Vec<ReadingId> = c.readings.iter().chain(&c.deleted).copied().collect();
+        for top in tops {
+            let mut cur = Some(top);
+            while let Some(r) = cur {
+                self.renumber(e, r)?;
+                cur = e.doc.store.readings.get(r.0).next;
+            }
+        }
+        Ok(())
+    }
+
+    fn renumber(
+        &mut self,
+        e: &mut Engine<'_>,
+        reading: ReadingId,
+    ) -> Result<(), crate::error::RunError> {
+        let tags = e.doc.store.readings.get(reading.0).tags_list.clone();
+        let mut renumbered = tags.clone();
+        for (slot, &hash) in tags.iter().enumerate() {
+            let (text, _) = tag_text(e, hash);
+            let Some((head, number)) = split_relation(&text) else {
+                continue;
+            };
+            let next = self.ids.len() + 1;
+            let id = *self.ids.entry(number.to_string()).or_insert(next);
+            let stable = format!("{head}{id}");
+            if stable != text {
+                let tid = e.add_tag(&stable, TagType::empty())?;
+                renumbered[slot] = e.grammar.single_tags_list.get(tid.0).hash.get();
+            }
+        }
+        if renumbered != tags {
+            e.doc.store.readings.get_mut(reading.0).tags_list = renumbered;
+            e.reflow_reading(reading)?;
+        }
+        Ok(())
+    }
+}
+
+/// `ID:12` → `("ID:", "12")`, `R:obj:12` → `("R:obj:", "12")`.
+fn split_relation(text: &str) -> Option<(&str, &str)> {
+    let at = if text.starts_with("ID:") {
+        3
+    } else {
+        let name = text.strip_prefix("R:")?;
+        2 + name.find(':').filter(|&i| i > 0)? + 1
+    };
+    let number = &text[at..];
+    (!number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| (&text[..at], number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relation_tags() {
+        assert_eq!(split_relation("ID:12"), Some(("ID:", "12")));
+        assert_eq!(split_relation("R:obj:3"), Some(("R:obj:", "3")));
+        assert_eq!(split_relation("R::3"), None);
+        assert_eq!(split_relation("R:obj:x"), None);
+        assert_eq!(split_relation("ID:"), None);
+        assert_eq!(split_relation("SELECT:12"), None);
+    }
+}
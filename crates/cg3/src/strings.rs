@@ -376,6 +376,10 @@ pub const STR_CMD_IGNORE: &str = "<STREAMCMD:IGNORE>";
 pub const STR_CMD_RESUME: &str = "<STREAMCMD:RESUME>";
 pub const STR_CMD_SETVAR: &str = "<STREAMCMD:SETVAR:";
 pub const STR_CMD_REMVAR: &str = "<STREAMCMD:REMVAR:";
+/// ADDED — no C++ analog: asks for the variables (see [`crate::stream_command`]).
+pub const STR_CMD_GETVARS: &str = "<STREAMCMD:GETVARS>";
+/// ADDED — no C++ analog: the answer to [`STR_CMD_GETVARS`].
+pub const STR_CMD_VARS: &str = "<STREAMCMD:VARS:";
 pub const STR_DELIMITSET: &str = "_S_DELIMITERS_";
 pub const STR_SOFTDELIMITSET: &str = "_S_SOFT_DELIMITERS_";
 pub const STR_TEXTDELIMITSET: &str = "_S_TEXT_DELIMITERS_";
@@ -679,8 +679,9 @@ impl Clone for Tag {
 /// The `grammar->regex_tags` scan (`uregex_setText` + `uregex_find`) becomes an
 /// unanchored `Regex::is_match` against the tag text using each regex-tag's
 /// compiled `regexp` (anchoring is baked into the pattern at compile time in the
-/// parser layer). `grammar->icase_tags` uses `ux_str_case_compare` (ICU
-/// `u_strCaseCompare`, approximated with Unicode lowercase folding).
+/// parser layer). `grammar->icase_tags` compares by the grammar's
+/// [`case_locale`](Grammar::case_locale) (ICU `u_strCaseCompare`, as full
+/// case folding).
 pub fn parse_tag_raw(this: &mut Tag, to: &str, grammar: &mut Grammar) {
     this.r#type = TagType::empty();
     let to_chars: Vec<char> = to.chars().collect();
@@ -726,7 +727,10 @@ pub fn parse_tag_raw(this: &mut Tag, to: &str, grammar: &mut Grammar) {
     // grammar->icase_tags scan.
     let icase_ids: Vec<TagId> = grammar.icase_tags.iter().copied().collect();
     for tid in icase_ids {
-        if ux_str_case_compare(&this.tag, &grammar.single_tags_list[tid.0].tag) {
+        if grammar
+            .case_locale
+            .caseless_eq(&this.tag, &grammar.single_tags_list[tid.0].tag)
+        {
             this.r#type |= T_TEXTUAL;
         }
     }
@@ -942,15 +946,6 @@ fn add_tag(grammar: &mut Grammar, mut tag: Tag) -> TagId {
 // reimplemented here so this file compiles standalone (cf. `math_parser.rs`).
 // ---------------------------------------------------------------------------
 
-/// `uextras.hpp` `ux_strCaseCompare(a, b)`: ICU `u_strCaseCompare` with
-/// `U_FOLD_CASE_DEFAULT`, true on full case-fold equality. Approximated with
-/// Unicode simple lowercase folding (ICU-vs-Rust parity risk for non-ASCII).
-fn ux_str_case_compare(a: &str, b: &str) -> bool {
-    a.chars()
-        .flat_map(char::to_lowercase)
-        .eq(b.chars().flat_map(char::to_lowercase))
-}
-
 /// ICU `u_isdigit` (decimal-digit category), approximated with Rust's Unicode
 /// numeric table.
 fn u_isdigit(c: char) -> bool {
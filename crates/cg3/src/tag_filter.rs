@@ -0,0 +1,306 @@
+//! ADDED — no C++ analog. Dropping and renaming reading tags as a converted
+//! stream is printed, without a grammar (`cg-conv --drop-tags` and friends).
+//!
+//! A [`TagFilter`] sits in the [`ConvFormat`](crate::format_converter) print
+//! path, so it applies to every output format alike. It changes each window
+//! (or lone cohort) just before it is printed:
+//!
+//! * a reading's tags are kept only if they match a `--keep-tags` regex (when
+//!   any is given), do not match a `--drop-tags` regex, and are not of a
+//!   `--drop-tag-types` kind; a regex matches anywhere in the tag, so anchor
+//!   it with `^…$` to match whole tags. The wordform and baseform are always
+//!   kept.
+//! * `--rename-prefix OLD=NEW` then gives each tag starting with `OLD` the
+//!   prefix `NEW` instead; the first rule that applies wins.
+//! * `--strip-deleted` drops the readings a grammar deleted, which some
+//!   formats print (`;` readings in CG, `"drs"` in JSONL).
+//! * `--max-subreading-depth N` cuts sub-reading chains below depth `N`; 0
+//!   leaves only the top readings.
+//!
+//! The kinds `--drop-tag-types` knows are the tag types the reader gives a
+//! tag, `mapping` (`@…`), `dependency` (`#1->2`), `relation` (`R:…`) and
+//! `numeric` (`<W:1>`), plus `trace`: the rule names `--trace` prints, such as
+//! `SELECT:12` or `MAP:40:name`, which a stream read back in carries as
+//! plain tags.
+
+use regex::Regex;
+
+use crate::arena::{CohortId, ReadingId, SwId};
+use crate::cohort::CT_REMOVED;
+use crate::grammar_applicator::Engine;
+use crate::strings::KEYWORDS_STR;
+use crate::tag::{T_DEPENDENCY, T_MAPPING, T_NUMERICAL, T_RELATION, TagType};
+use crate::types::TagHash;
+
+/// Why a tag filter option value was refused.
+#[derive(Debug, thiserror::Error)]
+pub enum TagFilterError {
+    #[error("Error: --{option} expects a regular expression, not `{pattern}`: {source}")]
+    Regex {
+        option: &'static str,
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error(
+        "Error: --drop-tag-types expects mapping, dependency, relation, numeric or trace, not `{0}`."
+    )]
+    TagType(String),
+    #[error("Error: --rename-prefix expects OLD=NEW, not `{0}`.")]
+    Rename(String),
+    #[error("Error: --max-subreading-depth expects a whole number, not `{0}`.")]
+    Depth(String),
+}
+
+/// Which tags and readings to drop, and which tags to rename; see the module
+/// doc.
+#[derive(Clone, Debug, Default)]
+pub struct TagFilter {
+    pub keep: Vec<Regex>,
+    pub drop: Vec<Regex>,
+    /// Tags of any of these types are dropped.
+    pub drop_types: TagType,
+    /// Drop `--trace` rule names.
+    pub drop_trace: bool,
+    /// `(old, new)` prefixes.
+    pub renames: Vec<(String, String)>,
+    pub strip_deleted: bool,
+    pub max_subreading_depth: Option<usize>,
+}
+
+impl TagFilter {
+    /// Parse the option values given; `drop_types` is comma-separated.
+    pub fn from_options(
+        keep: &[String],
+        drop: &[String],
+        drop_types: Option<&str>,
+        renames: &[String],
+        strip_deleted: bool,
+        max_subreading_depth: Option<&str>,
+    ) -> Result<Self, TagFilterError> {
+        let regexes = |option, patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(p).map_err(|source| TagFilterError::Regex {
+                        option,
+                        pattern: p.clone(),
+                        source,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut filter = TagFilter {
+            keep: regexes("keep-tags", keep)?,
+            drop: regexes("drop-tags", drop)?,
+            strip_deleted,
+            ..Default::default()
+        };
+        for name in drop_types.into_iter().flat_map(|t| t.split(',')) {
+            let name = name.trim();
+            let lower = name.to_ascii_lowercase();
+            match lower.strip_prefix("t_").unwrap_or(&lower) {
+                "mapping" => filter.drop_types |= T_MAPPING,
+                "dependency" => filter.drop_types |= T_DEPENDENCY,
+                "relation" => filter.drop_types |= T_RELATION,
+                "numeric" | "numerical" => filter.drop_types |= T_NUMERICAL,
+                "trace" => filter.drop_trace = true,
+                _ => return Err(TagFilterError::TagType(name.to_string())),
+            }
+        }
+        for rename in renames {
+            match rename.split_once('=') {
+                Some((old, new)) if !old.is_empty() => {
+                    filter.renames.push((old.to_string(), new.to_string()));
+                }
+                _ => return Err(TagFilterError::Rename(rename.clone())),
+            }
+        }
+        filter.max_subreading_depth = max_subreading_depth
+            .map(|v| {
+                v.trim()
+                    .parse()
+                    .map_err(|_| TagFilterError::Depth(v.to_string()))
+            })
+            .transpose()?;
+        Ok(filter)
+    }
+
+    /// Whether it changes anything at all.
+    pub fn is_active(&self) -> bool {
+        !self.keep.is_empty()
+            || !self.drop.is_empty()
+            || !self.drop_types.is_empty()
+            || self.drop_trace
+            || !self.renames.is_empty()
+            || self.strip_deleted
+            || self.max_subreading_depth.is_some()
+    }
+
+    /// Filter every cohort of `window`.
+    pub fn apply_window(
+        &self,
+        e: &mut Engine<'_>,
+        window: SwId,
+    ) -> Result<(), crate::error::RunError> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let cohorts = e.doc.store.single_windows.get(window.0).all_cohorts.clone();
+        for cohort in cohorts {
+            self.apply_cohort(e, cohort)?;
+        }
+        Ok(())
+    }
+
+    /// Filter one cohort.
+    pub fn apply_cohort(
+        &self,
+        e: &mut Engine<'_>,
+        cohort: CohortId,
+    ) -> Result<(), crate::error::RunError> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let c = e.doc.store.cohorts.get(cohort.0);
+        if c.r#type.intersects(CT_REMOVED) {
+            return Ok(());
+        }
+        let readings = c.readings.clone();
+        if self.strip_deleted {
+            let c = e.doc.store.cohorts.get_mut(cohort.0);
+            let deleted = std::mem::take(&mut c.deleted);
+            for r in deleted {
+                crate::reading::free_reading(&mut e.doc.store, Some(r));
+            }
+        }
+        let deleted = e.doc.store.cohorts.get(cohort.0).deleted.clone();
+        for top in readings.into_iter().chain(deleted) {
+            let mut depth = 0;
+            let mut cur = Some(top);
+            while let Some(r) = cur {
+                self.filter_tags(e, r)?;
+                let next = e.doc.store.readings.get(r.0).next;
+                if next.is_some() && self.max_subreading_depth == Some(depth) {
+                    e.doc.store.readings.get_mut(r.0).next = None;
+                    crate::reading::free_reading(&mut e.doc.store, next);
+                    break;
+                }
+                depth += 1;
+                cur = next;
+            }
+        }
+        Ok(())
+    }
+
+    fn filter_tags(
+        &self,
+        e: &mut Engine<'_>,
+        reading: ReadingId,
+    ) -> Result<(), crate::error::RunError> {
+        if self.keep.is_empty()
+            && self.drop.is_empty()
+            && self.drop_types.is_empty()
+            && !self.drop_trace
+            && self.renames.is_empty()
+        {
+            return Ok(());
+        }
+        let (tags, baseform, parent) = {
+            let r = e.doc.store.readings.get(reading.0);
+            (r.tags_list.clone(), r.baseform, r.parent)
+        };
+        let wordform = parent
+            .and_then(|c| e.doc.store.cohorts.get(c.0).wordform)
+            .map(|t| e.grammar.single_tags_list.get(t.0).hash);
+
+        let mut kept = Vec::with_capacity(tags.len());
+        for hash in tags.iter().copied() {
+            if Some(TagHash(hash)) == baseform || Some(TagHash(hash)) == wordform {
+                kept.push(hash);
+                continue;
+            }
+            let tid = e.grammar.single_tags.find(hash).get().1;
+            let (text, ty) = {
+                let tag = e.grammar.single_tags_list.get(tid.0);
+                (tag.tag.clone(), tag.r#type)
+            };
+            if !self.keeps(&text, ty) {
+                continue;
+            }
+            match self
+                .renames
+                .iter()
+                .find_map(|(old, new)| Some((text.strip_prefix(old.as_str())?, new)))
+            {
+                Some((rest, new)) => {
+                    let renamed = e.add_tag(&format!("{new}{rest}"), TagType::empty())?;
+                    kept.push(e.grammar.single_tags_list.get(renamed.0).hash.get());
+                }
+                None => kept.push(hash),
+            }
+        }
+        if kept != tags {
+            e.doc.store.readings.get_mut(reading.0).tags_list = kept;
+            e.reflow_reading(reading)?;
+        }
+        Ok(())
+    }
+
+    fn keeps(&self, text: &str, ty: TagType) -> bool {
+        (self.keep.is_empty() || self.keep.iter().any(|re| re.is_match(text)))
+            && !self.drop.iter().any(|re| re.is_match(text))
+            && !ty.intersects(self.drop_types)
+            && !(self.drop_trace && is_trace_tag(text))
+    }
+}
+
+/// Whether `text` has the shape of a rule name `--trace` prints:
+/// `KEYWORD[(…)]:line[:name]`, `KEYWORD:name`, or `ENCL:pass`.
+pub fn is_trace_tag(text: &str) -> bool {
+    let Some((head, rest)) = text.split_once(':') else {
+        return false;
+    };
+    let keyword = head.split_once('(').map_or(head, |(k, _)| k);
+    !rest.is_empty()
+        && !keyword.is_empty()
+        && (keyword == "ENCL" || KEYWORDS_STR.contains(&keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_and_trace_tags() {
+        let filter = TagFilter::from_options(
+            &[],
+            &["^<".to_string()],
+            Some("T_MAPPING, trace"),
+            &["§=@".to_string()],
+            false,
+            Some("1"),
+        )
+        .unwrap();
+        assert_eq!(filter.drop_types, T_MAPPING);
+        assert!(filter.drop_trace);
+        assert_eq!(filter.renames, [("§".to_string(), "@".to_string())]);
+        assert_eq!(filter.max_subreading_depth, Some(1));
+        assert!(!TagFilter::default().is_active());
+
+        let refused = |types, rename: &str| {
+            TagFilter::from_options(&[], &[], types, &[rename.to_string()], false, None).is_err()
+        };
+        assert!(refused(Some("colour"), "a=b"));
+        assert!(refused(None, "ab"));
+        assert!(refused(None, "=b"));
+        assert!(TagFilter::from_options(&["(".to_string()], &[], None, &[], false, None).is_err());
+
+        assert!(is_trace_tag("SELECT:12"));
+        assert!(is_trace_tag("ADDRELATION(x):3:name"));
+        assert!(is_trace_tag("ENCL:1"));
+        assert!(!is_trace_tag("SELECT"));
+        assert!(!is_trace_tag("R:obj:2"));
+        assert!(!is_trace_tag("<W:1>"));
+    }
+}
@@ -25,15 +25,14 @@
 //!     recognises only `\n`, where ICU allows exactly one terminator drawn from
 //!     a wider set. Translating is what makes it exact; it is also only
 //!     expressible at all because this engine has lookahead.
-//!
-//! Reported by name, because the engine would otherwise match the WRONG thing
-//! without complaining — the failure mode worth spending code on:
-//!   * `[:name=value:]` in-set properties, which parse as a literal character
-//!     set.
-//!   * `\N{NAME}`, where fancy-regex's `\N` is "any char except newline" and
-//!     the brace is literal, versus ICU's named character.
-//!   * `\0ooo` octal escapes, which fancy-regex reads as a backreference.
-//!
+//!   * `[:name=value:]` / `[:^name=value:]` in-set properties → `\p{name=value}`
+//!     / `\P{name=value}`. Left alone, both Rust engines parse them as a
+//!     literal character set.
+//!   * `\N{NAME}` → the named code point as `\x{..}`. fancy-regex's `\N` is
+//!     "any char except newline" and would leave the brace literal.
+//!   * `\0ooo` octal escapes → the code point as `\x{..}`, by ICU's rules: one
+//!     to three octal digits, the third only if the value stays within `\377`.
+//!     fancy-regex would read them as a backreference.
 //!   * `$` → the same end anchor. ICU's `$` IS `\Z`. This is invisible for a
 //!     tag, whose haystack is one tag's text, but the text-delimiter haystack
 //!     is the raw input line with its newline still attached, so an anchored
@@ -61,13 +60,10 @@ pub const BACKTRACK_LIMIT: usize = 1_000_000;
 /// Why a tag's pattern would not compile.
 #[derive(Debug, Clone)]
 pub enum TagRegexErrorKind {
-    /// An ICU construct this engine would accept but interpret differently.
-    /// `construct` is the user-facing name, `offset` its char index in the
+    /// A construct ICU rejects as well, such as an unknown `\N{NAME}` or a `\0`
+    /// with no octal digit after it. `offset` is its char index in the
     /// original pattern.
-    Unsupported {
-        construct: &'static str,
-        offset: usize,
-    },
+    Invalid { problem: String, offset: usize },
     /// The pattern reached the engine and was rejected there. Carries the
     /// underlying error so a consumer can inspect it rather than parse text.
     ///
@@ -82,12 +78,12 @@ impl PartialEq for TagRegexErrorKind {
     fn eq(&self, other: &TagRegexErrorKind) -> bool {
         match (self, other) {
             (
-                TagRegexErrorKind::Unsupported {
-                    construct: a,
+                TagRegexErrorKind::Invalid {
+                    problem: a,
                     offset: x,
                 },
-                TagRegexErrorKind::Unsupported {
-                    construct: b,
+                TagRegexErrorKind::Invalid {
+                    problem: b,
                     offset: y,
                 },
             ) => a == b && x == y,
@@ -102,10 +98,9 @@ impl PartialEq for TagRegexErrorKind {
 impl std::fmt::Display for TagRegexErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TagRegexErrorKind::Unsupported { construct, offset } => write!(
-                f,
-                "{construct} at offset {offset} is an ICU regex feature this engine reads differently"
-            ),
+            TagRegexErrorKind::Invalid { problem, offset } => {
+                write!(f, "{problem} at offset {offset}")
+            }
             TagRegexErrorKind::Syntax(e) => write!(f, "{e}"),
         }
     }
@@ -305,14 +300,20 @@ fn escape_literal(s: &str) -> String {
     out
 }
 
+/// `c` as an escape that means the code point alone, in or out of a class and
+/// under every flag.
+fn push_code_point(out: &mut String, c: char) {
+    out.push_str(&format!("\\x{{{:x}}}", c as u32));
+}
+
 /// Rewrite the ICU constructs this engine spells differently, and reject the
-/// ones it would silently misread.
+/// ones ICU rejects too.
 ///
 /// Single pass, because `\Q...\E` spans suppress all other interpretation: a
 /// `\Z` inside a quoted span is literal text, so detection and translation
 /// cannot be separate passes over the same string.
-// [spec:cg3:req:tag-regex.icu-translation+1]
-// [spec:cg3:req:tag-regex.silent-divergence]
+// [spec:cg3:req:tag-regex.icu-translation+2]
+// [spec:cg3:req:tag-regex.silent-divergence+1]
 pub fn translate_icu_pattern(pattern: &str) -> Result<String, TagRegexErrorKind> {
     let cs: Vec<char> = pattern.chars().collect();
     let n = cs.len();
@@ -326,7 +327,7 @@ pub fn translate_icu_pattern(pattern: &str) -> Result<String, TagRegexErrorKind>
     // See `enables_multiline`.
     let translate_dollar = !enables_multiline(&cs);
 
-    let unsupported = |construct, offset| TagRegexErrorKind::Unsupported { construct, offset };
+    let invalid = |problem: String, offset| TagRegexErrorKind::Invalid { problem, offset };
 
     while i < n {
         let c = cs[i];
@@ -385,12 +386,41 @@ pub fn translate_icu_pattern(pattern: &str) -> Result<String, TagRegexErrorKind>
                     }
                 }
                 // ICU: the named character. fancy-regex: `\N` is "any char
-                // except newline" and `{NAME}` is literal — a silent mismatch.
+                // except newline" and `{NAME}` is literal.
                 'N' if cs.get(i + 2) == Some(&'{') => {
-                    return Err(unsupported("`\\N{NAME}` named character", i));
+                    let Some(close) = cs[i + 3..].iter().position(|&c| c == '}') else {
+                        return Err(invalid("unterminated `\\N{`".to_string(), i));
+                    };
+                    let name: String = cs[i + 3..i + 3 + close].iter().collect();
+                    let Some(named) = unicode_names2::character(name.trim()) else {
+                        return Err(invalid(format!("unknown character name `{name}`"), i));
+                    };
+                    push_code_point(&mut out, named);
+                    i += 4 + close;
+                }
+                // ICU: an octal escape, `\0` and one to three octal digits; a
+                // third digit only counts if the value stays within 0377.
+                // fancy-regex: a backreference.
+                '0' => {
+                    let mut value = 0u32;
+                    let mut j = i + 2;
+                    while j < n && j < i + 5 {
+                        let Some(digit) = cs[j].to_digit(8) else {
+                            break;
+                        };
+                        if value * 8 + digit > 0o377 {
+                            break;
+                        }
+                        value = value * 8 + digit;
+                        j += 1;
+                    }
+                    if j == i + 2 {
+                        return Err(invalid("`\\0` with no octal digit".to_string(), i));
+                    }
+                    // Every value up to 0377 is a code point.
+                    push_code_point(&mut out, char::from_u32(value).unwrap_or('\0'));
+                    i = j;
                 }
-                // ICU: an octal escape. fancy-regex: a backreference.
-                '0' => return Err(unsupported("`\\0` octal escape", i)),
                 other => {
                     out.push('\\');
                     out.push(other);
@@ -412,16 +442,27 @@ pub fn translate_icu_pattern(pattern: &str) -> Result<String, TagRegexErrorKind>
         }
 
         // ICU in-set property syntax. Both Rust engines read `[:script=Greek:]`
-        // as a literal set of the characters `:scriptGek=` and match the wrong
-        // thing without complaining. (`[[:alpha:]]`, which has no `=`, is a
-        // real POSIX class and passes.)
+        // as a literal set of the characters `:scriptGek=`, so it is spelled
+        // as the property escape both understand. (`[[:alpha:]]`, which has no
+        // `=`, is a real POSIX class and passes.) A set of its own at the top
+        // level, one member among others inside a class.
         if c == '[' && cs.get(i + 1) == Some(&':') {
             let mut j = i + 2;
             while j + 1 < n && !(cs[j] == ':' && cs[j + 1] == ']') {
                 j += 1;
             }
             if j + 1 < n && cs[i + 2..j].contains(&'=') {
-                return Err(unsupported("ICU in-set property `[:name=value:]`", i));
+                let negated = cs[i + 2] == '^';
+                let body: String = cs[i + 2 + usize::from(negated)..j].iter().collect();
+                let escape = format!("\\{}{{{}}}", if negated { 'P' } else { 'p' }, body.trim());
+                // Checked on its own, so a property the engine does not know
+                // is named rather than lost in a whole-pattern parse error.
+                if regex::Regex::new(&escape).is_err() {
+                    return Err(invalid(format!("unknown Unicode property `{body}`"), i));
+                }
+                out.push_str(&escape);
+                i = j + 2;
+                continue;
             }
         }
 
@@ -490,7 +531,7 @@ mod tests {
     }
 
     /// The reproducer: `grc-disambiguator.bin` in the `se.drb` bundle.
-    // [spec:cg3:req:tag-regex.icu-translation+1/test]
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
     #[test]
     fn se_drb_reproducer_compiles() {
         let pattern = r#""\Q$1\E.*"S$"#;
@@ -514,7 +555,7 @@ mod tests {
 
     /// A bare `\E` is an escaped literal `E` in ICU, NOT a no-op. Verified
     /// against ICU 78.3: `a\Eb` matches `aEb` and does not match `ab`.
-    // [spec:cg3:req:tag-regex.icu-translation+1/test]
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
     #[test]
     fn bare_end_quote_is_a_literal_e() {
         assert_eq!(tr(r"a\Eb"), "aEb");
@@ -570,7 +611,7 @@ mod tests {
 
     /// ICU's `\Z` allows exactly one trailing terminator, from a set wider than
     /// `\n`. fancy-regex's own `\Z` gets both halves wrong, so it is translated.
-    // [spec:cg3:req:tag-regex.icu-translation+1/test]
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
     #[test]
     fn end_anchor_matches_icu() {
         for (haystack, expected) in [
@@ -613,22 +654,83 @@ mod tests {
         assert!(matches(r"a*+b", "aaab"));
     }
 
-    /// Constructs this engine would misread rather than reject.
-    // [spec:cg3:req:tag-regex.silent-divergence/test]
+    /// ICU's in-set properties, as ICU 78.3 matches them: `[:Script=Greek:]+`
+    /// finds `αβγ` and not `abc`, and the negated form the reverse.
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
     #[test]
-    fn silent_divergences_are_named() {
-        for (pattern, expected) in [
-            (r"[:script=Greek:]", "ICU in-set property `[:name=value:]`"),
-            (r"\N{LATIN SMALL LETTER A}", "`\\N{NAME}` named character"),
-            (r"\0101", "`\\0` octal escape"),
+    fn in_set_properties_are_property_escapes() {
+        assert_eq!(tr(r"[:Script=Greek:]+"), r"\p{Script=Greek}+");
+        assert_eq!(tr(r"[:^gc=Lu:]"), r"\P{gc=Lu}");
+        assert_eq!(tr(r"[[:sc=Grek:]a]"), r"[\p{sc=Grek}a]");
+        for (pattern, haystack, expected) in [
+            (r"^[:Script=Greek:]+$", "αβγ", true),
+            (r"^[:Script=Greek:]+$", "abc", false),
+            (r"^[:^Script=Greek:]+$", "abc", true),
+            (r"^[:General_Category=Uppercase_Letter:]$", "Ä", true),
+            (r"^[:gc=Lu:]$", "ä", false),
+            (r"^[[:sc=Cyrl:]x]+$", "xжx", true),
+            (r"^[[:sc=Cyrl:]x]+$", "xyx", false),
         ] {
-            match kind(pattern) {
-                TagRegexErrorKind::Unsupported { construct, .. } => {
-                    assert_eq!(construct, expected, "for {pattern}")
-                }
-                other => panic!("{pattern}: expected Unsupported, got {other:?}"),
-            }
+            assert_eq!(
+                matches(pattern, haystack),
+                expected,
+                "{pattern} on {haystack}"
+            );
         }
+        // POSIX classes have no `=` and stay as they are.
+        assert_eq!(tr(r"[[:alpha:]]"), r"[[:alpha:]]");
+        // An unknown property is named, as ICU refuses it too.
+        assert!(matches!(
+            kind(r"a[:sc=Nopes:]"),
+            TagRegexErrorKind::Invalid { offset: 1, .. }
+        ));
+    }
+
+    /// ICU's named characters: `\N{LATIN SMALL LETTER E WITH ACUTE}` is `é`,
+    /// in a class as well, and an unknown name is an error in ICU too.
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
+    #[test]
+    fn named_characters_are_code_points() {
+        assert_eq!(tr(r"\N{LATIN SMALL LETTER A}"), r"\x{61}");
+        assert!(matches(r"^caf\N{LATIN SMALL LETTER E WITH ACUTE}$", "café"));
+        assert!(!matches(
+            r"^caf\N{LATIN SMALL LETTER E WITH ACUTE}$",
+            "cafe"
+        ));
+        assert!(matches(r"^[\N{HYPHEN-MINUS}\N{EN DASH}]$", "–"));
+        assert!(!matches(r"^[\N{HYPHEN-MINUS}\N{EN DASH}]$", "b"));
+        // `\N{...}` is not "any char" followed by a literal brace.
+        assert!(!matches(r"^\N{SNOWMAN}$", "x{SNOWMAN}"));
+        assert!(matches(r"^\N{SNOWMAN}$", "☃"));
+        assert!(matches!(
+            kind(r"\N{NO SUCH CHARACTER}"),
+            TagRegexErrorKind::Invalid { offset: 0, .. }
+        ));
+        assert!(matches!(
+            kind(r"a\N{SNOWMAN"),
+            TagRegexErrorKind::Invalid { offset: 1, .. }
+        ));
+    }
+
+    /// ICU's octal escapes, by its scanner: `\0101` is `A`; the third digit is
+    /// dropped when it would pass 0377, so `\0400` is a space and a literal
+    /// `0`; digits past the third are literal; a `\0` with no octal digit is an
+    /// error (U_REGEX_BAD_ESCAPE_SEQUENCE).
+    // [spec:cg3:req:tag-regex.icu-translation+2/test]
+    #[test]
+    fn octal_escapes_are_code_points() {
+        assert_eq!(tr(r"\0101"), r"\x{41}");
+        assert!(matches(r"^\0101$", "A"));
+        assert!(matches(r"^\0400$", " 0"));
+        assert!(matches(r"^\01234$", "S4"));
+        assert!(matches(r"^\07$", "\u{7}"));
+        assert!(matches(r"^[\060-\071]+$", "0123"));
+        // Not a backreference to group 0.
+        assert!(!matches(r"^(a)\01$", "aa"));
+        assert!(matches!(
+            kind(r"a\08"),
+            TagRegexErrorKind::Invalid { offset: 1, .. }
+        ));
     }
 
     /// A `}` closing a Unicode-property brace must not be rescanned as syntax.
@@ -701,14 +803,17 @@ mod tests {
 
     #[test]
     fn display_names_the_tag_and_the_construct() {
-        let e = compile_tag_regex(r"[:script=Greek:]", false)
+        let e = compile_tag_regex(r"\N{NO SUCH CHARACTER}", false)
             .expect_err("must fail")
             .with_tag(r#""foo"r"#)
             .with_line(42);
         let s = e.to_string();
         assert!(s.contains(r#""foo"r"#), "{s}");
         assert!(s.contains("line 42"), "{s}");
-        assert!(s.contains("in-set property"), "{s}");
+        assert!(
+            s.contains("unknown character name `NO SUCH CHARACTER`"),
+            "{s}"
+        );
     }
 
     /// A catastrophic pattern must surface as a bounded failure, not a hang.
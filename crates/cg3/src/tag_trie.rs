@@ -255,11 +255,23 @@ pub fn trie_has_type(trie: &TagTrie, type_: crate::tag::TagType, grammar: &Gramm
 // every tag of every path onto `the_tags` (no node search). Output order matches
 // the C++ flat_map hash order, so `grammar` is required.
 /// See [`trie_get_tag_list_find`] for the spec'd sibling overload.
+///
+/// Runs per rule application (mapping/add tag lists), so unlike the other
+/// ordered walks it does not go through [`ordered_entries`]: the level's keys
+/// are sorted in place at the tail of `the_tags` — by `(hash, TagId)`, the
+/// order the stable sort yields — and each child's expansion, appended at the
+/// end, is rotated in behind its key. Walking the keys back to front keeps the
+/// already-expanded suffix intact. Nothing is allocated beyond `the_tags`.
 pub fn trie_get_tag_list_append(trie: &TagTrie, the_tags: &mut TagList, grammar: &Grammar) {
-    for (k, node) in ordered_entries(trie, grammar) {
-        the_tags.push(k);
-        if let Some(sub) = &node.trie {
+    let start = the_tags.len();
+    the_tags.extend(trie.keys().copied());
+    the_tags[start..].sort_unstable_by_key(|k| (grammar.single_tags_list[k.0].hash, *k));
+    for i in (start..start + trie.len()).rev() {
+        if let Some(sub) = &trie[&the_tags[i]].trie {
+            let before = the_tags.len();
             trie_get_tag_list_append(sub, the_tags, grammar);
+            let n = the_tags.len() - before;
+            the_tags[i + 1..].rotate_right(n);
         }
     }
 }
@@ -498,6 +510,29 @@ mod tests {
         assert_eq!(list, vec![a, b, c]);
     }
 
+    // The in-place append walk matches the ordered DFS: roots whose TagId order
+    // disagrees with hash order, several with children, appended after
+    // existing content (which must stay untouched).
+    #[test]
+    fn tag_list_append_in_place_order() {
+        let mut g = Grammar::default();
+        let z = mk_tag(&mut g, 90, 0, crate::tag::TagType::empty());
+        let a = mk_tag(&mut g, 10, 1, crate::tag::TagType::empty());
+        let m = mk_tag(&mut g, 50, 2, crate::tag::TagType::empty());
+        let x = mk_tag(&mut g, 70, 3, crate::tag::TagType::empty());
+        let y = mk_tag(&mut g, 60, 4, crate::tag::TagType::empty());
+
+        let mut trie = TagTrie::new();
+        trie_insert(&mut trie, &vec![z, x], 0);
+        trie_insert(&mut trie, &vec![a, x, y], 0);
+        trie_insert(&mut trie, &vec![a, y], 0);
+        trie_insert(&mut trie, &vec![m], 0);
+
+        let mut the_tags = vec![z];
+        trie_get_tag_list_append(&trie, &mut the_tags, &g);
+        assert_eq!(the_tags, vec![z, a, y, x, y, m, z, x]);
+    }
+
     // trie_get_tags reproduces the documented SORT-THEN-POP corruption: on a
     // terminal it sorts the shared `tv` prefix by hash and pops the HIGHEST-hash
     // element (not the just-pushed one), corrupting the prefix for later siblings.
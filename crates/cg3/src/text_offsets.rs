@@ -0,0 +1,205 @@
+//! ADDED — no C++ analog. Where a cohort's wordform came from in the input
+//! text, as byte and UTF-16 offsets.
+//!
+//! The text a stream stands for is what its reader can recover of the
+//! original: a plaintext line as it stands; the blanks of an Apertium stream,
+//! superblank contents included but not their brackets or word-bound blanks
+//! (`[[...]]`), with the surface forms between them; the `:` text lines of a
+//! CG stream (see [`push_cg_text`]) with the wordforms between them. Offsets
+//! count from the start of the run's input, past any byte-order mark.
+//!
+//! Cohorts the grammar makes keep offsets into the same text: an ADDCOHORT
+//! cohort has an empty span where it was inserted, a MERGECOHORTS cohort spans
+//! the cohorts it replaced, and SPLITCOHORT divides the span of the cohort it
+//! splits (see [`TextOffsets::split`]).
+
+/// A span of the input text, in UTF-8 bytes and UTF-16 code units; each `end`
+/// is exclusive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextOffsets {
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub utf16_start: usize,
+    pub utf16_end: usize,
+}
+
+impl TextOffsets {
+    /// The empty span where `self` starts.
+    pub fn start(self) -> TextOffsets {
+        TextOffsets {
+            byte_end: self.byte_start,
+            utf16_end: self.utf16_start,
+            ..self
+        }
+    }
+
+    /// The empty span where `self` ends.
+    pub fn end(self) -> TextOffsets {
+        TextOffsets {
+            byte_start: self.byte_end,
+            utf16_start: self.utf16_end,
+            ..self
+        }
+    }
+
+    /// The smallest span covering both.
+    pub fn cover(self, other: TextOffsets) -> TextOffsets {
+        TextOffsets {
+            byte_start: self.byte_start.min(other.byte_start),
+            byte_end: self.byte_end.max(other.byte_end),
+            utf16_start: self.utf16_start.min(other.utf16_start),
+            utf16_end: self.utf16_end.max(other.utf16_end),
+        }
+    }
+
+    /// Divide `self`, the span of `source`, among `pieces`: each piece gets
+    /// the span of its first occurrence in `source` after the previous piece.
+    /// When `self` is not `source`'s length or a piece is not found in order —
+    /// `"im"` split into `"i"` and `"am"` — every piece gets all of `self`.
+    pub fn split(self, source: &str, pieces: &[&str]) -> Vec<TextOffsets> {
+        let whole = vec![self; pieces.len()];
+        if self.byte_end - self.byte_start != source.len() {
+            return whole;
+        }
+        let mut spans = Vec::with_capacity(pieces.len());
+        let mut at = 0;
+        for piece in pieces {
+            let Some(found) = source[at..].find(piece) else {
+                return whole;
+            };
+            let mut cursor = OffsetCursor {
+                byte: self.byte_start,
+                utf16: self.utf16_start,
+            };
+            cursor.advance(&source[..at + found]);
+            spans.push(cursor.take(piece));
+            at += found + piece.len();
+        }
+        spans
+    }
+}
+
+/// A position in the input text, advanced as a reader consumes it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OffsetCursor {
+    pub byte: usize,
+    pub utf16: usize,
+}
+
+impl OffsetCursor {
+    /// Move past `text`.
+    pub fn advance(&mut self, text: &str) {
+        self.byte += text.len();
+        self.utf16 += text.encode_utf16().count();
+    }
+
+    /// Move past `ch`.
+    pub fn advance_char(&mut self, ch: char) {
+        self.byte += ch.len_utf8();
+        self.utf16 += ch.len_utf16();
+    }
+
+    /// Move past `text`, returning its span.
+    pub fn take(&mut self, text: &str) -> TextOffsets {
+        let (byte_start, utf16_start) = (self.byte, self.utf16);
+        self.advance(text);
+        TextOffsets {
+            byte_start,
+            byte_end: self.byte,
+            utf16_start,
+            utf16_end: self.utf16,
+        }
+    }
+
+    /// The spans of `ranges`, byte ranges of `text`, which starts here.
+    pub fn spans(self, text: &str, ranges: &[std::ops::Range<usize>]) -> Vec<TextOffsets> {
+        let mut utf16 = vec![0; text.len() + 1];
+        let mut units = 0;
+        for (i, ch) in text.char_indices() {
+            utf16[i] = units;
+            units += ch.len_utf16();
+        }
+        utf16[text.len()] = units;
+        ranges
+            .iter()
+            .map(|r| TextOffsets {
+                byte_start: self.byte + r.start,
+                byte_end: self.byte + r.end,
+                utf16_start: self.utf16 + utf16[r.start],
+                utf16_end: self.utf16 + utf16[r.end],
+            })
+            .collect()
+    }
+
+    /// Move past the text a CG stream's text lines stand for.
+    pub fn advance_cg_text(&mut self, lines: &str) {
+        let mut text = String::new();
+        push_cg_text(lines, &mut text);
+        self.advance(&text);
+    }
+}
+
+/// `"<form>"` → `form`.
+pub fn strip_wordform(tag: &str) -> &str {
+    tag.strip_prefix("\"<")
+        .and_then(|t| t.strip_suffix(">\""))
+        .unwrap_or(tag)
+}
+
+/// Append the text CG stream text lines stand for to `out`. A `:` line is a
+/// blank as `hfst-tokenise` writes them: what follows the `:`, with `\n` and
+/// `\t` unescaped, and without the line break. Any other line is taken as it
+/// stands.
+pub fn push_cg_text(lines: &str, out: &mut String) {
+    for line in lines.split_inclusive('\n') {
+        let Some(blank) = line.strip_prefix(':') else {
+            out.push_str(line);
+            continue;
+        };
+        let blank = blank.strip_suffix('\n').unwrap_or(blank);
+        let blank = blank.strip_suffix('\r').unwrap_or(blank);
+        let mut chars = blank.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                out.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_locates_pieces_in_order() {
+        let mut cursor = OffsetCursor { byte: 10, utf16: 8 };
+        let span = cursor.take("née-York");
+        let parts = span.split("née-York", &["née", "York"]);
+        assert_eq!(
+            parts[0],
+            TextOffsets {
+                byte_start: 10,
+                byte_end: 14,
+                utf16_start: 8,
+                utf16_end: 11
+            }
+        );
+        assert_eq!((parts[1].byte_start, parts[1].utf16_start), (15, 12));
+        assert_eq!(span.split("née-York", &["new", "york"]), vec![span; 2]);
+    }
+
+    #[test]
+    fn cg_text_lines() {
+        let mut out = String::new();
+        push_cg_text(": \n:\\n\n<p>\n", &mut out);
+        assert_eq!(out, " \n<p>\n");
+    }
+}
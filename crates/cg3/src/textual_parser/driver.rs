@@ -6,12 +6,14 @@ use std::collections::BTreeMap;
 
 use crate::arena::{CtxId, RuleId, SetId, TagId};
 use crate::ast::{ASTHelper, ASTType};
+use crate::case_folding::CaseLocale;
 use crate::contextual_test::{POS_CAREFUL, POS_NUMERIC_BRANCH, copy_cntx};
 use crate::grammar::Grammar;
 use crate::igrammar_parser::IGrammarParser;
 use crate::inlines::{
     hash_value_ustring, isspace, skipln_chars, skipto_chars, skiptows_chars, skipws_chars, ui32,
 };
+use crate::normalization::Normalization;
 use crate::set::{ST_TAG_UNIFY, Set};
 use crate::strings::Keywords;
 use crate::tag::{T_REGEXP_LINE, T_SPECIAL, T_VARSTRING};
@@ -247,6 +249,32 @@ impl TextualParser {
                 self.grammar.lines += skipws_chars(buf, pos, '\0', '\0', false);
                 found = true;
             }
+            // ADDED: Unicode normalisation of every tag from here on.
+            if simplecasecmp(buf, *pos, STR_NORMALIZE_NFC) {
+                *pos += slen(STR_NORMALIZE_NFC);
+                self.grammar.normalization = Normalization::Nfc;
+                self.grammar.lines += skipws_chars(buf, pos, '\0', '\0', false);
+                found = true;
+            }
+            if simplecasecmp(buf, *pos, STR_NORMALIZE_NFD) {
+                *pos += slen(STR_NORMALIZE_NFD);
+                self.grammar.normalization = Normalization::Nfd;
+                self.grammar.lines += skipws_chars(buf, pos, '\0', '\0', false);
+                found = true;
+            }
+            // ADDED: language-sensitive case rules.
+            for (name, locale) in [
+                (STR_CASEFOLD_TR, CaseLocale::Turkic),
+                (STR_CASEFOLD_AZ, CaseLocale::Turkic),
+                (STR_CASEFOLD_LT, CaseLocale::Lithuanian),
+            ] {
+                if simplecasecmp(buf, *pos, name) {
+                    *pos += slen(name);
+                    self.grammar.case_locale = locale;
+                    self.grammar.lines += skipws_chars(buf, pos, '\0', '\0', false);
+                    found = true;
+                }
+            }
             if !found {
                 return Err(self.error_near(*pos));
             }
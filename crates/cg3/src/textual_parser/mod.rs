@@ -140,6 +140,13 @@ const STR_SELF_NO_BARRIER: &str = "self-no-barrier";
 const STR_ORDERED: &str = "ordered";
 const STR_ADDCOHORT_ATTACH: &str = "addcohort-attach";
 const STR_SAFE_SETPARENT: &str = "safe-setparent";
+/// ADDED — no C++ analog: the `OPTIONS` that set [`Grammar::normalization`].
+const STR_NORMALIZE_NFC: &str = "normalize-nfc";
+const STR_NORMALIZE_NFD: &str = "normalize-nfd";
+/// ADDED — no C++ analog: the `OPTIONS` that set [`Grammar::case_locale`].
+const STR_CASEFOLD_TR: &str = "casefold-tr";
+const STR_CASEFOLD_AZ: &str = "casefold-az";
+const STR_CASEFOLD_LT: &str = "casefold-lt";
 
 /// C++ `g_flags[FLAGS_COUNT]` — the rule-flag keyword names (index == FL_*).
 const G_FLAGS: [&str; FLAGS_COUNT] = [
@@ -0,0 +1,314 @@
+//! ADDED — no C++ analog. Plain-text tokenisation for
+//! [`PlaintextApplicator`](crate::plaintext_applicator::PlaintextApplicator).
+//!
+//! A [`Tokenizer`] finds the wordforms in one line of plain text. The line
+//! is first cut into chunks at whitespace; what a tokenizer does within a
+//! chunk is its own business, but every token is a slice of the line, so
+//! tokens keep their place in the input ([`crate::text_offsets`]).
+//!
+//! [`UnicodeTokenizer`], the default, is the C++ tokenizer: it peels
+//! punctuation off both ends of each chunk one character at a time and keeps
+//! the rest as one token. Punctuation is Unicode general category P, as ICU
+//! `u_ispunct` has it, so `«»¡¿` and CJK punctuation come off as well. The C++
+//! order is kept: leading punctuation, the word, then trailing punctuation
+//! innermost last (`word.)` gives `word`, `)`, `.`).
+//!
+//! [`RuleTokenizer`] adds rules from a file (`cg-conv --tokenizer FILE`,
+//! `vislcg3 --tokenizer FILE`), one per line, `#` starting a comment:
+//! * `abbrev Dr. e.g. etc.` — words kept whole, final period included,
+//!   when followed by the end of the chunk or by punctuation;
+//! * `token REGEX` — anything the pattern matches at the start of a chunk or
+//!   after punctuation is one token (URLs, numbers with separators);
+//! * `split REGEX` — a word the pattern matches in full becomes one token per
+//!   non-empty capture group (clitics: `split (?i)(\w+)('s)`).
+//!
+//! Patterns are [`regex`] syntax. Outside rules, punctuation is peeled as by
+//! the default, trailing punctuation in reading order.
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::inlines::isspace;
+
+static PUNCTUATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\A\p{P}\z").unwrap());
+static SENTENCE_TERMINAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\A\p{P}*\p{Sentence_Terminal}\p{P}*\z").unwrap());
+
+/// ICU `u_ispunct`: whether `c` is in Unicode general category P.
+pub fn is_punctuation(c: char) -> bool {
+    PUNCTUATION.is_match(c.encode_utf8(&mut [0; 4]))
+}
+
+/// Splits plain-text lines into tokens.
+pub trait Tokenizer: Send + Sync {
+    /// The tokens of `line`, which holds no line break, as byte ranges of it in
+    /// cohort order.
+    fn tokenize(&self, line: &str) -> Vec<Range<usize>>;
+
+    /// Whether a sentence ends with `token`: by default, when it is all
+    /// punctuation and some of it is sentence-final (Unicode
+    /// `Sentence_Terminal`: `.`, `!`, `?`, `。`, `।`, ...).
+    fn ends_sentence(&self, token: &str) -> bool {
+        SENTENCE_TERMINAL.is_match(token)
+    }
+}
+
+/// The whitespace-separated chunks of `line`, with their byte offsets.
+fn chunks(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = line.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while rest.next_if(|&(_, c)| isspace(c)).is_some() {}
+        let (start, _) = *rest.peek()?;
+        let mut end = start;
+        while let Some((i, c)) = rest.next_if(|&(_, c)| !isspace(c)) {
+            end = i + c.len_utf8();
+        }
+        Some((start, &line[start..end]))
+    })
+}
+
+/// The default tokenizer; see the module doc.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnicodeTokenizer;
+
+impl Tokenizer for UnicodeTokenizer {
+    fn tokenize(&self, line: &str) -> Vec<Range<usize>> {
+        let mut tokens = Vec::new();
+        for (at, chunk) in chunks(line) {
+            let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+            let one = |i: usize| {
+                let (offset, c) = chars[i];
+                at + offset..at + offset + c.len_utf8()
+            };
+            let (mut lo, mut hi) = (0, chars.len());
+            while lo < hi && is_punctuation(chars[lo].1) {
+                tokens.push(one(lo));
+                lo += 1;
+            }
+            let word_at = tokens.len();
+            while hi > lo && is_punctuation(chars[hi - 1].1) {
+                tokens.push(one(hi - 1));
+                hi -= 1;
+            }
+            if lo < hi {
+                tokens.insert(word_at, one(lo).start..one(hi - 1).end);
+            }
+        }
+        tokens
+    }
+}
+
+/// Why a tokenizer rules file could not be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum TokenizerRulesError {
+    #[error("Error: Error opening {path} for reading!")]
+    Open {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Error: {path}:{line}: unknown tokenizer rule `{rule}`")]
+    UnknownRule {
+        path: String,
+        line: usize,
+        rule: String,
+    },
+    #[error("Error: {path}:{line}: `{rule}` needs an argument")]
+    MissingArgument {
+        path: String,
+        line: usize,
+        rule: &'static str,
+    },
+    #[error("Error: {path}:{line}: invalid pattern")]
+    Pattern {
+        path: String,
+        line: usize,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// A tokenizer driven by abbreviation, token and split rules; see the module
+/// doc.
+#[derive(Clone, Debug, Default)]
+pub struct RuleTokenizer {
+    abbreviations: Vec<String>,
+    tokens: Vec<Regex>,
+    splits: Vec<Regex>,
+}
+
+impl RuleTokenizer {
+    /// Load rules from a file.
+    pub fn from_file(path: &Path) -> Result<Self, TokenizerRulesError> {
+        let text = std::fs::read_to_string(path).map_err(|source| TokenizerRulesError::Open {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&text, &path.display().to_string())
+    }
+
+    /// Parse rules; `name` is the file they came from, for errors.
+    pub fn parse(text: &str, name: &str) -> Result<Self, TokenizerRulesError> {
+        let mut rules = RuleTokenizer::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (rule, arg) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let arg = arg.trim();
+            let rule: &'static str = match rule {
+                "abbrev" => "abbrev",
+                "token" => "token",
+                "split" => "split",
+                _ => {
+                    return Err(TokenizerRulesError::UnknownRule {
+                        path: name.to_string(),
+                        line: n + 1,
+                        rule: rule.to_string(),
+                    });
+                }
+            };
+            if arg.is_empty() {
+                return Err(TokenizerRulesError::MissingArgument {
+                    path: name.to_string(),
+                    line: n + 1,
+                    rule,
+                });
+            }
+            let pattern = |anchored: String| {
+                Regex::new(&anchored).map_err(|source| TokenizerRulesError::Pattern {
+                    path: name.to_string(),
+                    line: n + 1,
+                    source,
+                })
+            };
+            match rule {
+                "abbrev" => rules
+                    .abbreviations
+                    .extend(arg.split_whitespace().map(str::to_string)),
+                "token" => rules.tokens.push(pattern(format!(r"\A(?:{arg})"))?),
+                _ => rules.splits.push(pattern(format!(r"\A(?:{arg})\z"))?),
+            }
+        }
+        Ok(rules)
+    }
+
+    /// The length of the longest abbreviation or `token` match `rest` starts
+    /// with.
+    fn protected(&self, rest: &str) -> Option<usize> {
+        let abbreviations = self.abbreviations.iter().filter_map(|a| {
+            let after = rest.strip_prefix(a.as_str())?;
+            match after.chars().next() {
+                None => Some(a.len()),
+                Some(c) if is_punctuation(c) => Some(a.len()),
+                Some(_) => None,
+            }
+        });
+        let tokens = self
+            .tokens
+            .iter()
+            .filter_map(|re| re.find(rest).map(|m| m.end()));
+        abbreviations.chain(tokens).filter(|&len| len > 0).max()
+    }
+
+    /// Push `word`, at `at` in the line, split by the first `split` rule that
+    /// matches it.
+    fn push_word(&self, at: usize, word: &str, tokens: &mut Vec<Range<usize>>) {
+        let before = tokens.len();
+        if let Some(caps) = self.splits.iter().find_map(|re| re.captures(word)) {
+            tokens.extend(
+                caps.iter()
+                    .skip(1)
+                    .flatten()
+                    .filter(|m| !m.is_empty())
+                    .map(|m| at + m.start()..at + m.end()),
+            );
+        }
+        if tokens.len() == before {
+            tokens.push(at..at + word.len());
+        }
+    }
+}
+
+impl Tokenizer for RuleTokenizer {
+    fn tokenize(&self, line: &str) -> Vec<Range<usize>> {
+        let mut tokens = Vec::new();
+        for (at, chunk) in chunks(line) {
+            let word_end = chunk.trim_end_matches(is_punctuation).len();
+            let mut pos = 0;
+            while pos < chunk.len() {
+                let rest = &chunk[pos..];
+                if let Some(len) = self.protected(rest) {
+                    tokens.push(at + pos..at + pos + len);
+                    pos += len;
+                    continue;
+                }
+                let c = rest.chars().next().unwrap();
+                if is_punctuation(c) {
+                    tokens.push(at + pos..at + pos + c.len_utf8());
+                    pos += c.len_utf8();
+                    continue;
+                }
+                self.push_word(at + pos, &chunk[pos..word_end], &mut tokens);
+                pos = word_end;
+            }
+        }
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words<'a>(t: &dyn Tokenizer, line: &'a str) -> Vec<&'a str> {
+        t.tokenize(line).into_iter().map(|r| &line[r]).collect()
+    }
+
+    #[test]
+    fn unicode_punctuation_is_peeled_in_the_c_order() {
+        assert_eq!(
+            words(&UnicodeTokenizer, "«¡Hola!» (word.) 東京。"),
+            [
+                "«", "¡", "Hola", "»", "!", "(", "word", ")", ".", "東京", "。"
+            ]
+        );
+        assert!(UnicodeTokenizer.ends_sentence("?!"));
+        assert!(UnicodeTokenizer.ends_sentence("。"));
+        assert!(!UnicodeTokenizer.ends_sentence(","));
+        assert!(!UnicodeTokenizer.ends_sentence("Dr."));
+    }
+
+    #[test]
+    fn rules_protect_and_split() {
+        let rules = RuleTokenizer::parse(
+            "# test\nabbrev Dr. etc.\ntoken https?://[^\\s)]+\ntoken \\d+(?:[.,]\\d+)+\nsplit (?i)(\\w+)('s)\n",
+            "rules",
+        )
+        .unwrap();
+        assert_eq!(
+            words(&rules, "(Dr. Smith's site: http://a.b/c), 3,5 etc."),
+            [
+                "(",
+                "Dr.",
+                "Smith",
+                "'s",
+                "site",
+                ":",
+                "http://a.b/c",
+                ")",
+                ",",
+                "3,5",
+                "etc."
+            ]
+        );
+        assert!(matches!(
+            RuleTokenizer::parse("split (", "rules"),
+            Err(TokenizerRulesError::Pattern { line: 1, .. })
+        ));
+    }
+}
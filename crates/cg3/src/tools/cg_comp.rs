@@ -4,16 +4,35 @@
 //! binary form via [`crate::binary_grammar::BinaryGrammar::write_binary_grammar`].
 //! Rejects an already-binary input. This whole flow is LIVE (no gated engine
 //! paths): text parse → reindex → binary write.
+//!
+//! ADDED: a compressed grammar is read as it comes, and an `output_file`
+//! named `.gz` or `.zst` is written compressed (see [`crate::compression`]).
+//!
+//! ADDED: `--diagnostics-format json|sarif`, anywhere on the command line,
+//! writes the parse errors to stderr as one document (see
+//! [`crate::diagnostics_export`]).
+//!
+//! ADDED: `--graph=FILE` writes the grammar's sets, templates, rules, anchors
+//! and sections as a graph (see [`crate::grammar_graph`]): JSON for a file
+//! named `.json`, Graphviz DOT otherwise. `--graph-root=NAME` keeps only what
+//! the set, rule, template or anchor `NAME` uses and what uses it. With
+//! `--graph`, `output_file` may be left out to write the graph alone.
 
 use std::fs::File;
 use std::io::{Read, Write};
 
 use crate::binary_grammar::BinaryGrammar;
+use crate::compression::{self, Compression};
+use crate::diagnostics_export::DiagnosticsFormat;
 use crate::grammar::Grammar;
+use crate::grammar_graph::GrammarGraph;
 use crate::inlines::is_cg3b;
 use crate::textual_parser::TextualParser;
 
-use super::{EXIT_FAILURE, basename, fail, print_divvun_version_line};
+use super::{
+    EXIT_FAILURE, basename, fail, finish_diagnostics, print_divvun_version_line,
+    set_diagnostics_format,
+};
 
 // [spec:cg3:def:cg-comp.end-program-fn+3]
 // [spec:cg3:sem:cg-comp.end-program-fn+3]
@@ -30,6 +49,16 @@ fn end_program(name: Option<&str>) -> i32 {
             basename(name)
         );
         println!("USAGE: {} grammar_file output_file", basename(name));
+        // ADDED.
+        println!(
+            "       --diagnostics-format FMT  writes diagnostics to stderr as human (default), json or sarif"
+        );
+        println!(
+            "       --graph FILE              writes the set/template/rule/anchor/section graph as DOT, or JSON for FILE.json; output_file is then optional"
+        );
+        println!(
+            "       --graph-root NAME         keeps only what set, rule, template or anchor NAME (or line:N for a rule) uses and what uses it"
+        );
     }
     // exit(EXIT_FAILURE);
     EXIT_FAILURE
@@ -42,8 +71,25 @@ pub fn main_comp(args: &[String]) -> i32 {
     // UErrorCode status = U_ZERO_ERROR;
     let status: i32 = 0;
 
+    // ADDED: take `--diagnostics-format` and `--graph` out before counting
+    // the arguments.
+    let mut args = args.to_vec();
+    let format = take_value(&mut args, "--diagnostics-format");
+    if let Some(format) = format {
+        match format.parse::<DiagnosticsFormat>() {
+            Ok(format) => set_diagnostics_format(format, "cg-comp"),
+            Err(e) => {
+                tracing::error!("{e}");
+                return EXIT_FAILURE;
+            }
+        }
+    }
+    let graph = take_value(&mut args, "--graph");
+    let graph_root = take_value(&mut args, "--graph-root");
+
     // if (argc != 3) endProgram(argv[0]);
-    if args.len() != 3 {
+    // ADDED: the output file is optional with `--graph`.
+    if args.len() != 3 && !(graph.is_some() && args.len() == 2) {
         return end_program(args.first().map(|s| s.as_str()));
     }
 
@@ -61,7 +107,10 @@ pub fn main_comp(args: &[String]) -> i32 {
         }
     };
     let mut head = [0u8; 4];
-    if input.read_exact(&mut head).is_err() {
+    if compression::decompressed(&mut input)
+        .and_then(|mut grammar| grammar.read_exact(&mut head))
+        .is_err()
+    {
         tracing::error!("Error: Error reading first 4 bytes from grammar!");
         return EXIT_FAILURE;
     }
@@ -81,7 +130,7 @@ pub fn main_comp(args: &[String]) -> i32 {
     // The C++ filename overload stat+reads the file; this reads it here (the
     // `.cg3b` magic was already sniffed off the front above) and hands the parse
     // the bytes AND the path, so a diagnostic can name the file it came from.
-    let buffer = match std::fs::read(&args[1]) {
+    let buffer = match compression::read_file(&args[1]) {
         Ok(b) => b,
         Err(_) => {
             tracing::error!("Error: Error opening {} for reading!", args[1]);
@@ -127,6 +176,17 @@ pub fn main_comp(args: &[String]) -> i32 {
         tracing::info!("Grammar has dependency rules.");
     }
 
+    // ADDED: the grammar graph, and nothing else when there is no output file.
+    if let Some(path) = &graph
+        && let Err(code) = write_graph(&grammar, path, graph_root.as_deref())
+    {
+        return code;
+    }
+    if args.len() == 2 {
+        finish_diagnostics();
+        return status;
+    }
+
     // std::ofstream gout(argv[2], ...); if (gout) { BinaryGrammar writer; writer.writeBinaryGrammar(gout); }
     //
     // Serialised into memory first, so the companion file below can stamp the
@@ -137,6 +197,18 @@ pub fn main_comp(args: &[String]) -> i32 {
         return fail(&e);
     }
     let grammar = writer.grammar;
+    // ADDED: compressed by the output file's extension; the companion stamps
+    // the compressed bytes, which are what is on disk.
+    let blob = match compression::compress(
+        &blob,
+        Compression::from_path(std::path::Path::new(&args[2])),
+    ) {
+        Ok(blob) => blob,
+        Err(_) => {
+            tracing::error!("Could not write grammar to {}", args[2]);
+            return EXIT_FAILURE;
+        }
+    };
     match File::create(&args[2]) {
         Ok(mut gout) => {
             if gout.write_all(&blob).is_err() {
@@ -158,5 +230,51 @@ pub fn main_comp(args: &[String]) -> i32 {
     }
 
     // u_cleanup dropped.
+    finish_diagnostics();
     status
 }
+
+/// ADDED — no C++ analog. Take `name VALUE` (or `name=VALUE`) out of `args`
+/// and return the value; the last one wins.
+fn take_value(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let mut value = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut it = std::mem::take(args).into_iter();
+    while let Some(arg) = it.next() {
+        if let Some(v) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            value = Some(v.to_string());
+        } else if arg == name {
+            value = Some(it.next().unwrap_or_default());
+        } else {
+            rest.push(arg);
+        }
+    }
+    *args = rest;
+    value
+}
+
+/// ADDED — no C++ analog. Write `grammar`'s graph to `path`, cut down to
+/// `root` when given.
+fn write_graph(grammar: &Grammar, path: &str, root: Option<&str>) -> Result<(), i32> {
+    let mut graph = GrammarGraph::new(grammar);
+    if let Some(root) = root {
+        match graph.find(root) {
+            Some(node) => graph = graph.closure(node),
+            None => {
+                tracing::error!(
+                    "Error: There is no set, rule, template or anchor {root} in the grammar."
+                );
+                return Err(EXIT_FAILURE);
+            }
+        }
+    }
+    let text = if path.ends_with(".json") {
+        format!("{}\n", graph.to_json())
+    } else {
+        graph.to_dot()
+    };
+    std::fs::write(path, text).map_err(|e| {
+        tracing::error!("Error: Could not write the grammar graph to {path}: {e}");
+        EXIT_FAILURE
+    })
+}
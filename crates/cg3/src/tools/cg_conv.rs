@@ -7,23 +7,63 @@
 //! the ported drivers need `R: Read + Seek`. FST/plaintext-only options are
 //! stored on the converter's persistent format strategies.
 //!
-//! DIVERGENCE: `-M` / `--out-matxin` is not offered. The C++ output-format
-//! switch had no `case OUT_MATXIN`, so `-M` silently emitted CG (neither
-//! FormatConverter has a Matxin arm); the option is removed rather than carried
-//! as a no-op. See plan node `option-wiring`.
+//! DIVERGENCE: `-M` / `--out-matxin` writes Matxin. The C++ output-format
+//! switch had no `case OUT_MATXIN`, so `-M` silently emitted CG; the ADDED
+//! `-m` / `--in-matxin` reads it (see [`crate::matxin_format`]). The ADDED
+//! `-E` / `--out-errors` writes grammar-checker errors as JSON (see
+//! [`crate::errors_format`]). The ADDED `--tokenizer FILE` and
+//! `--split-sentences` configure plaintext input (see [`crate::tokenizer`]).
+//! The ADDED `--jsonl-strict` holds JSONL input to its schema, and
+//! `--jsonl-header` starts JSONL output with its version marker (see
+//! [`crate::jsonl_schema`]).
+//! The ADDED `--strict-input` stops at the first malformed input line.
+//! The ADDED `--normalize nfc|nfd` puts the input's tags into that Unicode form
+//! (see [`crate::normalization`]).
+//! The ADDED `--inspect-binary` prints a binary stream packet by packet
+//! instead of converting it (see [`crate::binary_inspect`]).
+//! The ADDED `--keep-tags`, `--drop-tags`, `--drop-tag-types`,
+//! `--rename-prefix`, `--strip-deleted` and `--max-subreading-depth` filter
+//! what is printed (see [`crate::tag_filter`]).
+//! The ADDED `--untrace`, `--sort` and `--stabilize-relations` run the
+//! filters of the same-named tools, which are this converter run through
+//! [`main_conv_as`] (see [`crate::stream_filters`]).
+//! The ADDED `--max-weight W` and `--n-best N` prune readings by weight (see
+//! [`crate::grammar_applicator::weights`]). Compressed input is read as it
+//! comes, and the ADDED `--compress` compresses the output (see
+//! [`crate::compression`]).
 
+use crate::compression::{self, CompressedWriter, Compression};
+use crate::grammar_applicator::relation_graph::RelationExport;
+use crate::grammar_applicator::weights::WeightPruning;
 use crate::icu_uoptions::u_parse_args;
 use crate::options_conv::{Opt, options_conv, options_default, options_override};
 use crate::options_parser::parse_opts_env;
+use crate::stream_filters::{RelationStabilizer, StreamFilters};
+use crate::tag_filter::TagFilter;
+use crate::tokenizer::RuleTokenizer;
 
-use super::{U_ILLEGAL_ARGUMENT_ERROR, U_ZERO_ERROR, fail, to_uargv};
+use super::{
+    EXIT_FAILURE, U_ILLEGAL_ARGUMENT_ERROR, U_ZERO_ERROR, export_file, fail, finish_graph_exports,
+    finish_output, to_uargv,
+};
+
+/// The `cg-conv` entry point; see [`main_conv_as`].
+pub fn main_conv(args: &[String]) -> i32 {
+    main_conv_as("cg-conv", args, None)
+}
 
 // [spec:cg3:def:cg-conv.main-fn]
 // [spec:cg3:sem:cg-conv.main-fn]
 /// C++ `int main(int argc, char* argv[])`.
+///
+/// ADDED: run as `tool`, which names itself in the usage text. A `filter`
+/// (`Untrace`, `Sort` or `StabilizeRelations`) is switched on whatever the
+/// arguments say, and the output then keeps the input format unless an
+/// output option is given; that is what `cg-untrace`, `cg-sort` and
+/// `cg-stabilize-relations` are.
 // faithful port: the C++ `for (i=0; i<NUM_OPTIONS_CONV; ++i)` scans cover the
 // whole table — its length IS the enum constant (`ConvOptionsTable`).
-pub fn main_conv(args: &[String]) -> i32 {
+pub fn main_conv_as(tool: &str, args: &[String], filter: Option<Opt>) -> i32 {
     // UErrorCode status = U_ZERO_ERROR;
     // ICU init dropped (UTF-8 port).
 
@@ -56,12 +96,17 @@ pub fn main_conv(args: &[String]) -> i32 {
         }
     }
 
+    // ADDED: the filter a filter tool always runs.
+    if let Some(filter) = filter {
+        options_conv[filter as usize].does_occur = true;
+    }
+
     let occ = |opts: &crate::options_conv::ConvOptionsTable, o: Opt| opts[o as usize].does_occur;
 
     if argc < 0 || occ(&options_conv, Opt::Help1) || occ(&options_conv, Opt::Help2) {
         // FILE* out = (argc < 0) ? stderr : stdout;
         let mut out = String::new();
-        out.push_str("Usage: cg-conv [OPTIONS]\n");
+        out.push_str(&format!("Usage: {tool} [OPTIONS]\n"));
         out.push('\n');
         out.push_str("Environment variable:\n");
         out.push_str(" CG3_CONV_DEFAULT: Sets default cmdline options_conv, which the actual passed options_conv will override.\n");
@@ -109,6 +154,12 @@ pub fn main_conv(args: &[String]) -> i32 {
         out.push_str("   ds  dependency self                                     t  text line\n");
         out.push_str("   dp  dependency parent\n");
         out.push_str("    z  text line(s) suffix\n");
+        // ADDED: the version marker `--jsonl-header` starts streams with.
+        out.push_str(&format!(
+            "\nHeader: {{\"{}\":{}}}, the version of the schema the stream follows\n",
+            crate::jsonl_schema::HEADER_KEY,
+            crate::jsonl_schema::SCHEMA_VERSION
+        ));
         out.push_str(
             "===============================================================================\n",
         );
@@ -151,8 +202,33 @@ pub fn main_conv(args: &[String]) -> i32 {
     // ux_stripBOM(std::cin); — the ported drivers need `R: Read + Seek`, and
     // stdin is not seekable, so the whole stream is buffered into a Cursor first
     // (faithful for the char-by-char state machines the applicators run).
-    let mut input_bytes = Vec::new();
-    let _ = std::io::Read::read_to_end(&mut std::io::stdin(), &mut input_bytes);
+    // ADDED: decompressed on the way in.
+    let input_bytes = match compression::read_all(std::io::stdin()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Error: Could not decompress the input: {e}");
+            return EXIT_FAILURE;
+        }
+    };
+    // ADDED: describe a binary stream instead of converting it.
+    if occ(&options_conv, Opt::InspectBinary) {
+        let report = crate::binary_inspect::inspect(&input_bytes);
+        let mut out = std::io::stdout().lock();
+        let status = report
+            .write_report(&mut out)
+            .and_then(|()| std::io::Write::flush(&mut out));
+        if let Err(e) = status
+            && e.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            tracing::error!("Error: Could not write the report: {e}");
+            return EXIT_FAILURE;
+        }
+        return if report.is_clean() {
+            U_ZERO_ERROR
+        } else {
+            EXIT_FAILURE
+        };
+    }
     let mut instream = std::io::Cursor::new(input_bytes);
     crate::uextras::ux_strip_bom(&mut instream);
 
@@ -165,6 +241,46 @@ pub fn main_conv(args: &[String]) -> i32 {
         options_conv[Opt::InPlain as usize].does_occur = true;
         applicator.set_plaintext_add_tags(true);
     }
+    // ADDED: plaintext tokenizer rules and sentence windows (both imply -x).
+    if occ(&options_conv, Opt::Tokenizer) {
+        options_conv[Opt::InPlain as usize].does_occur = true;
+        let path = &options_conv[Opt::Tokenizer as usize].value;
+        match RuleTokenizer::from_file(std::path::Path::new(path)) {
+            Ok(rules) => applicator.set_plaintext_tokenizer(std::sync::Arc::new(rules)),
+            Err(e) => {
+                tracing::error!("{e}");
+                if let Some(cause) = std::error::Error::source(&e) {
+                    tracing::error!("{cause}");
+                }
+                return EXIT_FAILURE;
+            }
+        }
+    }
+    if occ(&options_conv, Opt::SplitSentences) {
+        options_conv[Opt::InPlain as usize].does_occur = true;
+        applicator.set_plaintext_split_sentences(true);
+    }
+    // ADDED: strict JSONL reading (implies -j).
+    if occ(&options_conv, Opt::JsonlStrict) {
+        options_conv[Opt::InJsonl as usize].does_occur = true;
+        applicator.set_jsonl_strict(true);
+    }
+    // ADDED: the schema version marker, on request.
+    applicator.set_jsonl_header(occ(&options_conv, Opt::JsonlHeader));
+    // ADDED: stop at malformed input rather than skip it.
+    if occ(&options_conv, Opt::StrictInput) {
+        applicator.base_mut().cfg.strict_input = true;
+    }
+    // ADDED: Unicode normalisation of the input's tags.
+    if occ(&options_conv, Opt::Normalize) {
+        match options_conv[Opt::Normalize as usize].value.parse() {
+            Ok(n) => applicator.base_mut().grammar.normalization = n,
+            Err(e) => {
+                tracing::error!("{e}");
+                return EXIT_FAILURE;
+            }
+        }
+    }
 
     if occ(&options_conv, Opt::InCg) {
         fmt = StreamFormatKind::Cg;
@@ -180,6 +296,8 @@ pub fn main_conv(args: &[String]) -> i32 {
         fmt = StreamFormatKind::Jsonl;
     } else if occ(&options_conv, Opt::InBinary) {
         fmt = StreamFormatKind::Binary;
+    } else if occ(&options_conv, Opt::InMatxin) {
+        fmt = StreamFormatKind::Matxin;
     }
 
     if occ(&options_conv, Opt::InAuto) || fmt == StreamFormatKind::Invalid {
@@ -216,6 +334,8 @@ pub fn main_conv(args: &[String]) -> i32 {
     }
     if occ(&options_conv, Opt::FstWtag) {
         applicator.set_fst_wtag(options_conv[Opt::FstWtag as usize].value.clone());
+        // ADDED: the weights --max-weight / --n-best and JSONL "wt" read.
+        applicator.base_mut().cfg.weight_tag = options_conv[Opt::FstWtag as usize].value.clone();
     }
     if occ(&options_conv, Opt::FstWfactor) {
         let wfactor = options_conv[Opt::FstWfactor as usize]
@@ -224,6 +344,80 @@ pub fn main_conv(args: &[String]) -> i32 {
             .unwrap();
         applicator.set_fst_wfactor(wfactor);
     }
+    // ADDED: prune readings by weight.
+    let max_weight = occ(&options_conv, Opt::MaxWeight)
+        .then(|| options_conv[Opt::MaxWeight as usize].value.as_str());
+    let n_best =
+        occ(&options_conv, Opt::NBest).then(|| options_conv[Opt::NBest as usize].value.as_str());
+    match WeightPruning::from_options(max_weight, n_best) {
+        Ok(pruning) => applicator.base_mut().cfg.weight_pruning = pruning,
+        Err(e) => {
+            tracing::error!("{e}");
+            return EXIT_FAILURE;
+        }
+    }
+
+    // ADDED: export each window's relations.
+    let file = |opt: Opt| {
+        let opt = &options_conv[opt as usize];
+        opt.does_occur.then(|| export_file(&opt.value)).transpose()
+    };
+    let relations = RelationExport {
+        dot: match file(Opt::RelationsDot) {
+            Ok(f) => f,
+            Err(code) => return code,
+        },
+        json: match file(Opt::RelationsJson) {
+            Ok(f) => f,
+            Err(code) => return code,
+        },
+        ..Default::default()
+    };
+    if relations.dot.is_some() || relations.json.is_some() {
+        // The conversion grammar has no relation rules, so the input's
+        // `ID:`/`R:` tags are exported as they stand and printed untouched.
+        applicator.base_mut().diag.relation_export = Some(relations);
+    }
+
+    // ADDED: drop and rename tags and readings on the way out.
+    let tag_filter = TagFilter::from_options(
+        &options_conv[Opt::KeepTags as usize].values,
+        &options_conv[Opt::DropTags as usize].values,
+        occ(&options_conv, Opt::DropTagTypes)
+            .then(|| options_conv[Opt::DropTagTypes as usize].value.as_str()),
+        &options_conv[Opt::RenamePrefix as usize].values,
+        occ(&options_conv, Opt::StripDeleted),
+        occ(&options_conv, Opt::MaxSubreadingDepth).then(|| {
+            options_conv[Opt::MaxSubreadingDepth as usize]
+                .value
+                .as_str()
+        }),
+    );
+    match tag_filter {
+        Ok(tag_filter) => applicator.set_tag_filter(tag_filter),
+        Err(e) => {
+            tracing::error!("{e}");
+            return EXIT_FAILURE;
+        }
+    }
+
+    // ADDED: the cg-untrace, cg-sort and cg-stabilize-relations filters.
+    applicator.set_stream_filters(StreamFilters {
+        untrace: occ(&options_conv, Opt::Untrace),
+        sort: occ(&options_conv, Opt::Sort),
+        stabilizer: occ(&options_conv, Opt::StabilizeRelations).then(RelationStabilizer::default),
+    });
+
+    // ADDED: compressed output.
+    let compress = occ(&options_conv, Opt::Compress)
+        .then(|| options_conv[Opt::Compress as usize].value.as_str());
+    let compression = match Compression::for_output(compress, None) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("{e}");
+            return EXIT_FAILURE;
+        }
+    };
 
     // fmt_output selection.
     applicator.base_mut().cfg.fmt_output = StreamFormatKind::Cg;
@@ -240,11 +434,39 @@ pub fn main_conv(args: &[String]) -> i32 {
         applicator.base_mut().cfg.fmt_output = StreamFormatKind::Jsonl;
     } else if occ(&options_conv, Opt::OutBinary) {
         applicator.base_mut().cfg.fmt_output = StreamFormatKind::Binary;
+    } else if occ(&options_conv, Opt::OutMatxin) {
+        applicator.base_mut().cfg.fmt_output = StreamFormatKind::Matxin;
+    } else if occ(&options_conv, Opt::OutErrors) {
+        applicator.base_mut().cfg.fmt_output = StreamFormatKind::Errors;
+    }
+    // ADDED: a filter tool writes the format it reads unless told otherwise.
+    let out_given = [
+        Opt::OutCg,
+        Opt::OutApertium,
+        Opt::OutFst,
+        Opt::OutNiceline,
+        Opt::OutPlain,
+        Opt::OutJsonl,
+        Opt::OutBinary,
+        Opt::OutMatxin,
+        Opt::OutErrors,
+    ]
+    .into_iter()
+    .any(|o| occ(&options_conv, o));
+    if filter.is_some() && !out_given && fmt != StreamFormatKind::Invalid {
+        applicator.base_mut().cfg.fmt_output = fmt;
+        if fmt == StreamFormatKind::Apertium {
+            applicator.base_mut().cfg.unicode_tags = true;
+        }
     }
 
     if occ(&options_conv, Opt::UnicodeTags) {
         applicator.base_mut().cfg.unicode_tags = true;
     }
+    // ADDED: untracing and sorting need deleted readings read as such.
+    if occ(&options_conv, Opt::Untrace) || occ(&options_conv, Opt::Sort) {
+        options_conv[Opt::PipeDeleted as usize].does_occur = true;
+    }
     if occ(&options_conv, Opt::PipeDeleted) {
         applicator.base_mut().cfg.pipe_deleted = true;
     }
@@ -270,11 +492,14 @@ pub fn main_conv(args: &[String]) -> i32 {
     applicator.base_mut().cfg.verbosity_level = 0;
 
     // applicator.runGrammarOnText(*instream, std::cout);
-    let mut stdout = std::io::stdout();
+    let mut stdout = CompressedWriter::new(std::io::stdout(), compression);
     if let Err(e) = applicator.run_grammar_on_text(&mut instream, &mut stdout) {
         return fail(&e);
     }
+    if let Err(code) = finish_graph_exports(applicator.base_mut()) {
+        return code;
+    }
 
     // u_cleanup dropped. C++ main returns nothing on this path (implicit 0).
-    U_ZERO_ERROR
+    finish_output(stdout, U_ZERO_ERROR)
 }
@@ -4,11 +4,15 @@
 //! words via [`crate::mwesplit_applicator::MweSplitApplicator`], and writes the
 //! result to stdout. No grammar file: the applicator builds its own minimal
 //! dummy grammar in its constructor.
+//!
+//! ADDED: compressed input is read as it comes, and `--compress` compresses
+//! the output (see [`crate::compression`]).
 
+use crate::compression::{self, CompressedWriter, Compression};
 use crate::icu_uoptions::u_parse_args;
-use crate::options::{UOPT_NO_ARG, UOption};
+use crate::options::{UOPT_NO_ARG, UOPT_REQUIRES_ARG, UOption};
 
-use super::{fail, to_uargv};
+use super::{EXIT_FAILURE, fail, finish_output, to_uargv, uoption as uo};
 
 // [spec:cg3:def:cg-mwesplit.options-mwe.options]
 /// C++ `OptionsMWE::OPTIONS` — the tiny option enum for cg-mwesplit (help only).
@@ -16,22 +20,11 @@ use super::{fail, to_uargv};
 pub enum Opt {
     Help1,
     Help2,
+    /// ADDED — no C++ analog: `--compress gzip|zstd|none`.
+    Compress,
     NumOptionsMwe,
 }
 
-/// Local `UOption` aggregate-init helper (the crate's `UOption::new` is private
-/// to `crate::options`, and we may not edit that module).
-fn uo(long: &'static str, short: char, has_arg: u8, desc: &'static str) -> UOption {
-    UOption {
-        long_name: Some(long),
-        short_name: short,
-        has_arg,
-        description: desc.to_string(),
-        does_occur: false,
-        value: String::new(),
-    }
-}
-
 /// C++ `OptionsMWE::options_mwe[]` — the two help aliases. Built as owned local
 /// state (the C++ global array is mutated in place by `u_parseArgs`); indexed by
 /// [`Opt`].
@@ -39,6 +32,12 @@ fn options_mwe() -> [UOption; Opt::NumOptionsMwe as usize] {
     [
         uo("help", 'h', UOPT_NO_ARG, "shows this help"),
         uo("?", '?', UOPT_NO_ARG, "shows this help"),
+        uo(
+            "compress",
+            '\0',
+            UOPT_REQUIRES_ARG,
+            "compresses output as gzip, zstd or none",
+        ),
     ]
 }
 
@@ -126,15 +125,29 @@ pub fn main_mwesplit(args: &[String]) -> i32 {
     // The ported driver needs `R: Read + Seek`; stdin is not seekable, so the
     // whole stream is buffered into a Cursor first (faithful for the
     // line-by-line CG state machine the driver runs).
-    let mut input_bytes = Vec::new();
-    let _ = std::io::Read::read_to_end(&mut std::io::stdin(), &mut input_bytes);
+    // ADDED: decompressed on the way in, and compressed on the way out if asked.
+    let compress = occ(Opt::Compress).then(|| options_mwe[Opt::Compress as usize].value.as_str());
+    let compression = match Compression::for_output(compress, None) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("{e}");
+            return EXIT_FAILURE;
+        }
+    };
+    let input_bytes = match compression::read_all(std::io::stdin()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Error: Could not decompress the input: {e}");
+            return EXIT_FAILURE;
+        }
+    };
     let mut cursor = std::io::Cursor::new(input_bytes);
-    let mut stdout = std::io::stdout();
+    let mut stdout = CompressedWriter::new(std::io::stdout(), compression);
     if let Err(e) = applicator.run_grammar_on_text(&mut cursor, &mut stdout) {
         return fail(&e);
     }
 
     // u_cleanup dropped. C++ main falls off the end → returns 0 (status unused
     // by the return; kept for parity with the initialised value).
-    status
+    finish_output(stdout, status)
 }
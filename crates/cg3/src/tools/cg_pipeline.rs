@@ -0,0 +1,643 @@
+//! ADDED — no C++ analog. `cg-pipeline`: a whole CG pipeline described in one
+//! JSON file and run in one process.
+//!
+//! Upstream a pipeline is a shell line —
+//! `cg-conv | cg-mwesplit | vislcg3 -g a.cg3 | vislcg3 -g b.cg3 --trace | cg-conv`
+//! — with a `cg-relabel` step run beforehand on whichever grammar needs it.
+//! The spec names the same pieces, and the runner composes the same library
+//! stages: a [`FormatConverter`] per grammar run (it reads and prints every
+//! stream format, exactly as in `vislcg3`), [`MweSplitApplicator`] for MWE
+//! splitting, and [`Relabeller`](crate::relabeller::Relabeller) on the compiled
+//! grammar, as `cg-relabel` runs it, before the grammar is loaded.
+//!
+//! ```json
+//! {
+//!   "input": "apertium",
+//!   "output": "cg",
+//!   "stages": [
+//!     { "mwesplit": true },
+//!     { "grammar": "disambiguator.cg3", "sections": "1-3" },
+//!     { "grammar": "functions.cg3b", "relabel": "relabel.cg3r", "prefix": "§" },
+//!     { "grammar": "dependency.cg3", "trace": true, "options": "--dep-delimit" }
+//!   ]
+//! }
+//! ```
+//!
+//! * `input` / `output` — a stream format name (`cg`, `niceline`, `apertium`,
+//!   `fst`, `plain`, `jsonl`, `binary`, `matxin`; `input` also takes `auto`,
+//!   the `cg-conv` sniffer, and `output` also `errors`, the grammar-checker
+//!   errors). Both default to `cg`. With no stages the pipeline is a plain
+//!   conversion.
+//! * A grammar stage — `grammar` (a textual or binary grammar), and optionally
+//!   `relabel` (a relabelling file, as for `cg-relabel`), `sections`, `trace`,
+//!   `prefix` (the mapping prefix) and `options` (any further `vislcg3` flags,
+//!   in the `CMDARGS` syntax). `CG3_DEFAULT` / `CG3_OVERRIDE` apply to every
+//!   grammar, as they would to each `vislcg3` of the shell line.
+//! * `{ "mwesplit": true }` — split multi-word cohorts, as `cg-mwesplit`.
+//!
+//! Relative paths are taken from the spec file's directory. Unknown keys are
+//! errors, so a misspelt option cannot be silently dropped.
+//!
+//! Between stages the stream travels as CG text through pipes, as it would in
+//! the shell line: each step runs on its own thread and reads the previous
+//! one's output as it is printed, so no step waits for the whole stream. The
+//! exception is a run of consecutive untraced grammars, which
+//! become one [`FormatConverter`] with the later grammars added through
+//! [`add_pipeline_stage`](crate::grammar_applicator::GrammarApplicator::add_pipeline_stage),
+//! so the stream is parsed once for all of them (see that module for what
+//! follows the first grammar of such a run). A traced grammar always runs on
+//! its own: its trace tags name rules by number.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::binary_grammar::BinaryGrammar;
+use crate::error::{Cg3Error, RunError};
+use crate::format_converter::{BUF_SIZE, FormatConverter, detect_format};
+use crate::grammar::Grammar;
+use crate::grammar_applicator::{GrammarApplicator, StreamFormatKind};
+use crate::inlines::is_cg3b;
+use crate::mwesplit_applicator::MweSplitApplicator;
+use crate::options::{Opt, OptionsTable, options, options_default, options_override};
+use crate::options_parser::{parse_opts, parse_opts_env};
+use crate::relabeller::Relabeller;
+
+use super::vislcg3::ArrivingInput;
+use super::{EXIT_FAILURE, basename, fail, merge_options, print_divvun_version_line};
+
+/// Why a pipeline spec could not be read.
+#[derive(Debug, thiserror::Error)]
+pub enum SpecError {
+    #[error("Error: Error opening {path} for reading!")]
+    Open {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Error: {path} is not valid JSON")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Error: {path}: {at} must be {expected}")]
+    WrongType {
+        path: String,
+        at: String,
+        expected: &'static str,
+    },
+    #[error("Error: {path}: unknown key `{key}` in {at}")]
+    UnknownKey {
+        path: String,
+        at: String,
+        key: String,
+    },
+    #[error("Error: {path}: {at} is neither a `grammar` nor a `mwesplit` stage")]
+    UnknownStage { path: String, at: String },
+    #[error("Error: {path}: unknown stream format `{name}` for {at}")]
+    UnknownFormat {
+        path: String,
+        at: &'static str,
+        name: String,
+    },
+}
+
+/// A parsed pipeline spec: the formats at either end and the stages between.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelineSpec {
+    /// [`StreamFormatKind::Invalid`] stands for `auto`.
+    pub input: StreamFormatKind,
+    pub output: StreamFormatKind,
+    pub stages: Vec<StageSpec>,
+}
+
+/// One stage of a [`PipelineSpec`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum StageSpec {
+    Grammar(GrammarStageSpec),
+    MweSplit,
+}
+
+/// A grammar stage: the grammar, an optional relabelling, and the `vislcg3`
+/// options it runs with.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrammarStageSpec {
+    pub grammar: PathBuf,
+    pub relabel: Option<PathBuf>,
+    /// `--sections`.
+    pub sections: Option<String>,
+    /// `--trace`.
+    pub trace: bool,
+    /// `--prefix`.
+    pub prefix: Option<String>,
+    /// Further `vislcg3` flags, in the `CMDARGS` syntax.
+    pub options: String,
+}
+
+/// The stream format a spec names, by its `cg-conv` option stem.
+pub(crate) fn format_by_name(name: &str) -> Option<StreamFormatKind> {
+    Some(match name {
+        "cg" => StreamFormatKind::Cg,
+        "niceline" => StreamFormatKind::Niceline,
+        "apertium" => StreamFormatKind::Apertium,
+        "fst" => StreamFormatKind::Fst,
+        "plain" => StreamFormatKind::Plain,
+        "jsonl" => StreamFormatKind::Jsonl,
+        "binary" => StreamFormatKind::Binary,
+        "matxin" => StreamFormatKind::Matxin,
+        "errors" => StreamFormatKind::Errors,
+        _ => return None,
+    })
+}
+
+impl PipelineSpec {
+    /// Read and parse the spec at `path`; relative paths in it are resolved
+    /// against its directory.
+    pub fn from_file(path: &Path) -> Result<Self, SpecError> {
+        let text = std::fs::read_to_string(path).map_err(|source| SpecError::Open {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&text, &path.display().to_string(), base)
+    }
+
+    /// Parse spec `text`. `name` is what errors call it; relative paths are
+    /// resolved against `base`.
+    pub fn parse(text: &str, name: &str, base: &Path) -> Result<Self, SpecError> {
+        let doc: Value = serde_json::from_str(text).map_err(|source| SpecError::Json {
+            path: name.to_string(),
+            source,
+        })?;
+        let p = SpecParser { name, base };
+        let top = p.object(&doc, "the spec")?;
+        p.known_keys(top, "the spec", &["input", "output", "stages"])?;
+
+        let format = |at: &'static str, auto: bool| -> Result<StreamFormatKind, SpecError> {
+            let Some(name) = p.string(top, at, at)? else {
+                return Ok(StreamFormatKind::Cg);
+            };
+            match format_by_name(&name) {
+                // The errors format is output only.
+                Some(fmt) if !(auto && fmt == StreamFormatKind::Errors) => Ok(fmt),
+                None if auto && name == "auto" => Ok(StreamFormatKind::Invalid),
+                _ => Err(SpecError::UnknownFormat {
+                    path: p.name.to_string(),
+                    at,
+                    name,
+                }),
+            }
+        };
+        let input = format("input", true)?;
+        let output = format("output", false)?;
+
+        let mut stages = Vec::new();
+        match top.get("stages") {
+            None => {}
+            Some(Value::Array(list)) => {
+                for (i, stage) in list.iter().enumerate() {
+                    stages.push(p.stage(stage, &format!("stages[{i}]"))?);
+                }
+            }
+            Some(_) => return Err(p.wrong_type("stages", "an array")),
+        }
+        Ok(PipelineSpec {
+            input,
+            output,
+            stages,
+        })
+    }
+}
+
+/// The JSON walk behind [`PipelineSpec::parse`]: the spec's name for errors
+/// and the directory its paths are relative to.
+struct SpecParser<'a> {
+    name: &'a str,
+    base: &'a Path,
+}
+
+impl SpecParser<'_> {
+    fn wrong_type(&self, at: &str, expected: &'static str) -> SpecError {
+        SpecError::WrongType {
+            path: self.name.to_string(),
+            at: at.to_string(),
+            expected,
+        }
+    }
+
+    fn object<'v>(&self, v: &'v Value, at: &str) -> Result<&'v Map<String, Value>, SpecError> {
+        v.as_object()
+            .ok_or_else(|| self.wrong_type(at, "an object"))
+    }
+
+    fn known_keys(
+        &self,
+        obj: &Map<String, Value>,
+        at: &str,
+        known: &[&str],
+    ) -> Result<(), SpecError> {
+        match obj.keys().find(|k| !known.contains(&k.as_str())) {
+            Some(key) => Err(SpecError::UnknownKey {
+                path: self.name.to_string(),
+                at: at.to_string(),
+                key: key.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn string(
+        &self,
+        obj: &Map<String, Value>,
+        key: &str,
+        at: &str,
+    ) -> Result<Option<String>, SpecError> {
+        match obj.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(self.wrong_type(at, "a string")),
+        }
+    }
+
+    fn flag(&self, obj: &Map<String, Value>, key: &str, at: &str) -> Result<bool, SpecError> {
+        match obj.get(key) {
+            None => Ok(false),
+            Some(Value::Bool(b)) => Ok(*b),
+            Some(_) => Err(self.wrong_type(at, "true or false")),
+        }
+    }
+
+    fn path(
+        &self,
+        obj: &Map<String, Value>,
+        key: &str,
+        at: &str,
+    ) -> Result<Option<PathBuf>, SpecError> {
+        Ok(self.string(obj, key, at)?.map(|p| self.base.join(p)))
+    }
+
+    fn stage(&self, v: &Value, at: &str) -> Result<StageSpec, SpecError> {
+        let obj = self.object(v, at)?;
+        let field = |key: &str| format!("{at}.{key}");
+        if obj.contains_key("mwesplit") {
+            self.known_keys(obj, at, &["mwesplit"])?;
+            if !self.flag(obj, "mwesplit", &field("mwesplit"))? {
+                return Err(self.wrong_type(&field("mwesplit"), "true"));
+            }
+            return Ok(StageSpec::MweSplit);
+        }
+        let Some(grammar) = self.path(obj, "grammar", &field("grammar"))? else {
+            return Err(SpecError::UnknownStage {
+                path: self.name.to_string(),
+                at: at.to_string(),
+            });
+        };
+        self.known_keys(
+            obj,
+            at,
+            &[
+                "grammar", "relabel", "sections", "trace", "prefix", "options",
+            ],
+        )?;
+        Ok(StageSpec::Grammar(GrammarStageSpec {
+            grammar,
+            relabel: self.path(obj, "relabel", &field("relabel"))?,
+            sections: self.string(obj, "sections", &field("sections"))?,
+            trace: self.flag(obj, "trace", &field("trace"))?,
+            prefix: self.string(obj, "prefix", &field("prefix"))?,
+            options: self
+                .string(obj, "options", &field("options"))?
+                .unwrap_or_default(),
+        }))
+    }
+}
+
+/// One in-process step of a loaded [`Pipeline`].
+enum Segment {
+    /// A conversion, or one or more grammars run on a shared stream.
+    Conv(Box<FormatConverter>),
+    MweSplit(Box<MweSplitApplicator>),
+}
+
+/// A [`PipelineSpec`] with every grammar loaded, ready to run over one stream.
+pub struct Pipeline {
+    segments: Vec<Segment>,
+}
+
+/// Mark `opt` as given with `value`, as if it had been on the command line.
+pub(crate) fn set_opt(options: &mut OptionsTable, opt: Opt, value: &str) {
+    let o = &mut options[opt as usize];
+    o.does_occur = true;
+    o.value = value.to_string();
+    o.values.push(value.to_string());
+}
+
+/// A bare converter, set up the way `cg-conv` sets one up.
+fn converter(
+    input: StreamFormatKind,
+    output: StreamFormatKind,
+) -> Result<FormatConverter, Cg3Error> {
+    let mut conv = FormatConverter::new(GrammarApplicator::new(Grammar::default()))?;
+    let cfg = &mut conv.base_mut().cfg;
+    cfg.fmt_input = input;
+    cfg.fmt_output = output;
+    cfg.unicode_tags = output == StreamFormatKind::Apertium;
+    cfg.is_conv = true;
+    cfg.trace = true;
+    cfg.verbosity_level = 0;
+    Ok(conv)
+}
+
+/// A converter running `grammar` with `options`, set up the way `vislcg3`
+/// sets up its applicator. The stream formats are left to the caller.
+pub(crate) fn grammar_converter(
+    grammar: Grammar,
+    options: &OptionsTable,
+) -> Result<FormatConverter, Cg3Error> {
+    let mut conv = FormatConverter::new(GrammarApplicator::new(Grammar::default()))?;
+    let base = conv.base_mut();
+    base.grammar = grammar;
+    base.set_grammar()?;
+    base.set_options(options)?;
+    Ok(conv)
+}
+
+impl Pipeline {
+    /// Load every grammar `spec` names. Errors are reported here; the exit code
+    /// is returned.
+    pub fn load(spec: &PipelineSpec) -> Result<Self, i32> {
+        let mut env_default = options_default();
+        let mut env_override = options_override();
+        parse_opts_env("CG3_DEFAULT", &mut env_default);
+        parse_opts_env("CG3_OVERRIDE", &mut env_override);
+
+        let mut segments = Vec::new();
+        // The stream is CG text everywhere except at the two ends.
+        let mut input = spec.input;
+        let mut open: Option<(FormatConverter, bool)> = None;
+        for (i, stage) in spec.stages.iter().enumerate() {
+            let output = if i + 1 == spec.stages.len() {
+                spec.output
+            } else {
+                StreamFormatKind::Cg
+            };
+            match stage {
+                StageSpec::MweSplit => {
+                    segments.extend(open.take().map(|(conv, _)| Segment::Conv(Box::new(conv))));
+                    if input != StreamFormatKind::Cg {
+                        let conv = converter(input, StreamFormatKind::Cg).map_err(|e| fail(&e))?;
+                        segments.push(Segment::Conv(Box::new(conv)));
+                    }
+                    let base = GrammarApplicator::new(Grammar::default());
+                    let mut mwe = MweSplitApplicator::new(base).map_err(|e| fail(&e))?;
+                    mwe.base.cfg.verbosity_level = 0;
+                    segments.push(Segment::MweSplit(Box::new(mwe)));
+                    if output != StreamFormatKind::Cg {
+                        let conv = converter(StreamFormatKind::Cg, output).map_err(|e| fail(&e))?;
+                        segments.push(Segment::Conv(Box::new(conv)));
+                    }
+                }
+                StageSpec::Grammar(g) => {
+                    let (grammar, options) = load_grammar(g, &env_default, &env_override)?;
+                    match open.as_mut() {
+                        Some((conv, false)) if !g.trace => {
+                            let mut stage = GrammarApplicator::new(grammar);
+                            stage.set_grammar().map_err(|e| fail(&e))?;
+                            stage.set_options(&options).map_err(|e| fail(&e))?;
+                            conv.base_mut().add_pipeline_stage(stage);
+                            conv.base_mut().cfg.fmt_output = output;
+                            conv.base_mut().cfg.unicode_tags = output == StreamFormatKind::Apertium;
+                        }
+                        _ => {
+                            segments
+                                .extend(open.take().map(|(conv, _)| Segment::Conv(Box::new(conv))));
+                            let mut conv =
+                                grammar_converter(grammar, &options).map_err(|e| fail(&e))?;
+                            let base = conv.base_mut();
+                            base.cfg.fmt_input = input;
+                            base.cfg.fmt_output = output;
+                            base.cfg.unicode_tags = output == StreamFormatKind::Apertium;
+                            open = Some((conv, g.trace));
+                        }
+                    }
+                }
+            }
+            input = StreamFormatKind::Cg;
+        }
+        segments.extend(open.map(|(conv, _)| Segment::Conv(Box::new(conv))));
+        if segments.is_empty() {
+            let conv = converter(spec.input, spec.output).map_err(|e| fail(&e))?;
+            segments.push(Segment::Conv(Box::new(conv)));
+        }
+        Ok(Pipeline { segments })
+    }
+
+    /// Run the pipeline over `input`, printing to `output`. A pipeline runs
+    /// one stream; load it again for the next.
+    ///
+    /// Every step but the last runs on a thread of its own, printing into a
+    /// pipe the next step reads from; the last runs on the calling thread.
+    pub fn run<R, W>(self, input: R, output: &mut W) -> Result<(), Cg3Error>
+    where
+        R: Read + Send,
+        W: Write,
+    {
+        let mut segments = self.segments;
+        let last = segments.pop().expect("a loaded pipeline has a segment");
+        let mut input = ArrivingInput::new(input);
+        crate::uextras::ux_strip_bom(&mut input);
+        std::thread::scope(|scope| {
+            let mut stream: Box<dyn Read + Send + '_> = Box::new(input);
+            let mut steps = Vec::new();
+            for segment in segments {
+                let (reader, writer) = std::io::pipe().map_err(RunError::from)?;
+                let from = std::mem::replace(&mut stream, Box::new(reader));
+                steps.push(scope.spawn(move || {
+                    let mut writer = std::io::BufWriter::new(writer);
+                    segment.run(from, &mut writer)?;
+                    writer.flush().map_err(RunError::from)?;
+                    Ok(())
+                }));
+            }
+            let rv = last.run(stream, output);
+            let mut results: Vec<Result<(), Cg3Error>> = steps
+                .into_iter()
+                .map(|step| step.join().unwrap_or_else(|p| std::panic::resume_unwind(p)))
+                .collect();
+            results.push(rv);
+            first_failure(results)
+        })
+    }
+}
+
+/// The failure to report from a pipeline's steps, in order. A step that fails
+/// closes both its pipes early, so the step after it sees the stream end and
+/// the step before it a broken pipe; the failure is the first that is not one
+/// of those.
+fn first_failure(results: Vec<Result<(), Cg3Error>>) -> Result<(), Cg3Error> {
+    let broken_pipe = |e: &Cg3Error| matches!(e, Cg3Error::Run(RunError::Io(io)) if io.kind() == std::io::ErrorKind::BrokenPipe);
+    let mut errors: Vec<Cg3Error> = results.into_iter().filter_map(Result::err).collect();
+    match errors.iter().position(|e| !broken_pipe(e)) {
+        Some(i) => Err(errors.swap_remove(i)),
+        None if errors.is_empty() => Ok(()),
+        None => Err(errors.swap_remove(0)),
+    }
+}
+
+impl Segment {
+    fn run<R: Read, W: Write>(self, input: R, output: &mut W) -> Result<(), Cg3Error> {
+        let mut input = ArrivingInput::new(input);
+        match self {
+            Segment::Conv(mut conv) => {
+                if conv.base().cfg.fmt_input == StreamFormatKind::Invalid {
+                    // `auto`: sniff, then read the sniffed bytes again ahead of
+                    // the rest.
+                    let head = crate::uextras::read_utf8(&mut input, BUF_SIZE);
+                    conv.base_mut().cfg.fmt_input = detect_format(&String::from_utf8_lossy(&head));
+                    let mut input = ArrivingInput::new(std::io::Cursor::new(head).chain(input));
+                    return conv.run_grammar_on_text(&mut input, output);
+                }
+                conv.run_grammar_on_text(&mut input, output)
+            }
+            Segment::MweSplit(mut mwe) => mwe.run_grammar_on_text(&mut input, output),
+        }
+    }
+}
+
+/// Load a grammar stage: its options (the stage's own over the environment
+/// tables), the relabelling if any, then the grammar the way `vislcg3` loads
+/// each `-g`.
+fn load_grammar(
+    stage: &GrammarStageSpec,
+    env_default: &OptionsTable,
+    env_override: &OptionsTable,
+) -> Result<(Grammar, OptionsTable), i32> {
+    let mut options = options();
+    parse_opts(&stage.options, &mut options);
+    if let Some(sections) = &stage.sections {
+        set_opt(&mut options, Opt::Sections, sections);
+    }
+    if stage.trace {
+        set_opt(&mut options, Opt::Trace, "");
+    }
+    if let Some(prefix) = &stage.prefix {
+        set_opt(&mut options, Opt::MappingPrefix, prefix);
+    }
+    merge_options(&mut options, env_default, env_override, None);
+
+    let path = stage.grammar.display().to_string();
+    let blob = match &stage.relabel {
+        Some(relabel) => Some(relabelled(&path, &relabel.display().to_string())?),
+        None => None,
+    };
+    super::vislcg3::load_stage_grammar(&path, blob, &options, env_override, 0)
+}
+
+/// `cg-comp` into memory: the binary form of the grammar at `path`, or its
+/// own bytes when it already is one.
+pub(crate) fn compiled(path: &str) -> Result<Vec<u8>, i32> {
+    use super::cg_relabel::{cg3_grammar_load, report_load};
+    let Ok(bytes) = std::fs::read(path) else {
+        tracing::error!("Error: Error opening {} for reading!", path);
+        return Err(EXIT_FAILURE);
+    };
+    if bytes.first_chunk::<4>().is_some_and(|&head| is_cg3b(head)) {
+        return Ok(bytes);
+    }
+    let grammar = cg3_grammar_load(path, false).map_err(|e| report_load(&e))?;
+    let mut blob = Vec::new();
+    BinaryGrammar::new(grammar)
+        .write_binary_grammar(&mut blob)
+        .map_err(|e| fail(&e))?;
+    Ok(blob)
+}
+
+/// `cg-comp` (for a textual grammar) then `cg-relabel`, into memory: the
+/// relabelled binary grammar `vislcg3` would be given.
+fn relabelled(path: &str, relabel: &str) -> Result<Vec<u8>, i32> {
+    use super::cg_relabel::{cg3_grammar_load, report_load};
+    // The relabeller works on the grammar as a binary load leaves it.
+    let mut parser = BinaryGrammar::new(Grammar::default());
+    parser
+        .parse_grammar_buffer(&compiled(path)?)
+        .map_err(|e| fail(&e))?;
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).map_err(|e| fail(&e))?;
+    let relabels = cg3_grammar_load(relabel, false).map_err(|e| report_load(&e))?;
+    Relabeller::new(&mut grammar, &relabels, ())
+        .relabel()
+        .map_err(|e| fail(&e))?;
+    let mut blob = Vec::new();
+    BinaryGrammar::new(grammar)
+        .write_binary_grammar(&mut blob)
+        .map_err(|e| fail(&e))?;
+    Ok(blob)
+}
+
+/// Print the usage banner and return `EXIT_FAILURE`, in the manner of the
+/// other tools' `endProgram`.
+fn end_program(name: &str) -> i32 {
+    print_divvun_version_line("Pipeline");
+    println!(
+        "{}: run a CG pipeline described in a JSON file",
+        basename(name)
+    );
+    println!(
+        "USAGE: {} pipeline_file [input_file [output_file]]",
+        basename(name)
+    );
+    EXIT_FAILURE
+}
+
+/// `cg-pipeline pipeline_file [input_file [output_file]]`; the streams default
+/// to stdin and stdout.
+pub fn main_pipeline(args: &[String]) -> i32 {
+    if !(2..=4).contains(&args.len()) {
+        return end_program(args.first().map_or("cg-pipeline", |s| s.as_str()));
+    }
+    let spec = match PipelineSpec::from_file(Path::new(&args[1])) {
+        Ok(spec) => spec,
+        Err(e) => {
+            tracing::error!("{e}");
+            if let Some(cause) = std::error::Error::source(&e) {
+                tracing::error!("{cause}");
+            }
+            return EXIT_FAILURE;
+        }
+    };
+    let pipeline = match Pipeline::load(&spec) {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+
+    let input: Box<dyn Read + Send> = match args.get(2) {
+        Some(path) => match std::fs::File::open(path) {
+            Ok(f) => Box::new(f),
+            Err(_) => {
+                tracing::error!("Error: Error opening {} for reading!", path);
+                return EXIT_FAILURE;
+            }
+        },
+        None => Box::new(std::io::stdin()),
+    };
+    let mut output: Box<dyn Write> = match args.get(3) {
+        Some(path) => match std::fs::File::create(path) {
+            Ok(f) => Box::new(std::io::BufWriter::new(f)),
+            Err(_) => {
+                tracing::error!("Error: Error opening {} for writing!", path);
+                return EXIT_FAILURE;
+            }
+        },
+        None => Box::new(std::io::stdout()),
+    };
+    if let Err(e) = pipeline.run(input, &mut output) {
+        return fail(&e);
+    }
+    if output.flush().is_err() {
+        tracing::error!("Error: Error writing output!");
+        return EXIT_FAILURE;
+    }
+    0
+}
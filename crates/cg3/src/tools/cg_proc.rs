@@ -18,12 +18,17 @@
 //! All four stream formats run LIVE: 0 (base `GrammarApplicator`), 1
 //! (Apertium), 2 (Matxin), and 3 (`BinaryApplicator`), each via its ported
 //! `run_grammar_on_text` driver.
+//!
+//! ADDED: compressed input and grammars are read as they come, and output is
+//! compressed by `--compress gzip|zstd|none` or the output file's extension
+//! (see [`crate::compression`]).
 
 use std::io::Read;
 
 use crate::apertium_applicator::ApertiumApplicator;
 use crate::binary_applicator::BinaryApplicator;
 use crate::binary_grammar::BinaryGrammar;
+use crate::compression::{self, CompressedWriter, Compression};
 use crate::grammar::Grammar;
 use crate::grammar_applicator::GrammarApplicator;
 use crate::inlines::is_cg3b;
@@ -35,7 +40,8 @@ use crate::options_parser::{parse_opts, parse_opts_env};
 use crate::textual_parser::TextualParser;
 
 use super::{
-    EXIT_FAILURE, basename, fail, merge_options, print_divvun_version, print_divvun_version_line,
+    EXIT_FAILURE, basename, fail, finish_output, merge_options, print_divvun_version,
+    print_divvun_version_line,
 };
 
 // [spec:cg3:def:cg-proc.end-program-fn+3]
@@ -68,6 +74,8 @@ fn end_program(name: &str) -> i32 {
     println!("\t-g, --generation:\t do not surround lexical units in ^$");
     println!("\t-1, --first:\t \t only output the first analysis if ambiguity remains");
     println!("\t-z, --null-flush:\tflush output on the null character");
+    println!("\t--compress=FORMAT:\t compress output as gzip, zstd or none");
+    println!("\t\t\t\t   (default: by the output file's extension)");
     println!("\t-v, --version:\t \t version");
     println!("\t-h, --help:\t\t show this help");
     EXIT_FAILURE
@@ -104,6 +112,8 @@ fn long_option(name: &str) -> Option<(char, bool)> {
         "first" => Some(('1', false)),
         "help" => Some(('h', false)),
         "null-flush" => Some(('z', false)),
+        // ADDED: long-only, so its letter is not in the optstring.
+        "compress" => Some(('c', true)),
         _ => None,
     }
 }
@@ -264,6 +274,7 @@ pub fn main_proc(args: &[String]) -> i32 {
     let mut sections: i32 = 0;
     let mut stream_format: i32 = 1;
     let mut single_rule = String::new();
+    let mut compress: Option<String> = None;
 
     // UErrorCode status = U_ZERO_ERROR; (dropped ICU init below)
 
@@ -275,13 +286,8 @@ pub fn main_proc(args: &[String]) -> i32 {
     }
     for (c, optarg) in &getopt.events {
         match c {
-            'd' => {
-                if cmd == '\0' {
-                    cmd = 'd';
-                } else {
-                    return end_program(prog);
-                }
-            }
+            'd' if cmd == '\0' => cmd = 'd',
+            'd' => return end_program(prog),
             'f' => {
                 stream_format = atoi(optarg.as_deref());
             }
@@ -310,6 +316,7 @@ pub fn main_proc(args: &[String]) -> i32 {
                 return 0;
             }
             'z' => { /* Null-flush is default */ }
+            'c' => compress = optarg.clone(),
             _ => return end_program(prog), // 'h' and default
         }
     }
@@ -341,7 +348,10 @@ pub fn main_proc(args: &[String]) -> i32 {
             Ok(f) => f,
             Err(_) => return end_program(prog),
         };
-        if in_.read_exact(&mut head).is_err() {
+        if compression::decompressed(&mut in_)
+            .and_then(|mut grammar| grammar.read_exact(&mut head))
+            .is_err()
+        {
             tracing::error!("Error: Error reading first 4 bytes from grammar!");
             return EXIT_FAILURE;
         }
@@ -367,7 +377,7 @@ pub fn main_proc(args: &[String]) -> i32 {
             "Warning: Text grammar detected - to better process textual\ngrammars, use `vislcg3'; to compile this grammar, use `cg-comp'"
         );
         let mut parser = TextualParser::new(Grammar::default(), false);
-        let buffer = match std::fs::read(grammar_path) {
+        let buffer = match compression::read_file(grammar_path) {
             Ok(b) => b,
             Err(_) => {
                 tracing::error!("Error: Error opening {} for reading!", grammar_path);
@@ -480,21 +490,37 @@ pub fn main_proc(args: &[String]) -> i32 {
     // a response per '\0' while the pipe is still open), so it is wrapped in an
     // adapter supporting the only Seek the drivers perform: the ≤3-byte
     // `ux_strip_bom` rewind (SeekFrom::Current with a small negative offset).
+    // ADDED: either is decompressed on the way in.
     let mut cursor: Box<dyn ReadSeek> = match input_path {
         Some(path) => Box::new(std::io::Cursor::new(
-            std::fs::read(path).unwrap_or_default(),
+            compression::read_file(path).unwrap_or_default(),
         )),
-        None => Box::new(StreamingStdin::new()),
+        None => match compression::decompressed(std::io::stdin()) {
+            Ok(stdin) => Box::new(StreamingStdin::new(stdin)),
+            Err(e) => {
+                tracing::error!("Error: Could not decompress the input: {e}");
+                return EXIT_FAILURE;
+            }
+        },
     };
     // ux_stdout: argv[optind+2] if given (create failure → silent sink, per the
     // C++ bad()-never-fires NOTE above), else stdout.
-    let mut out: Box<dyn std::io::Write> = match output_path {
+    let compression =
+        match Compression::for_output(compress.as_deref(), output_path.map(std::path::Path::new)) {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("{e}");
+                return EXIT_FAILURE;
+            }
+        };
+    let out: Box<dyn std::io::Write> = match output_path {
         Some(path) => match std::fs::File::create(path) {
             Ok(f) => Box::new(f),
             Err(_) => Box::new(std::io::sink()),
         },
         None => Box::new(std::io::stdout()),
     };
+    let mut out = CompressedWriter::new(out, compression);
 
     // try { switch (cmd) { case 'd': default: runGrammarOnText(...); } }
     let run_result = match app {
@@ -512,7 +538,7 @@ pub fn main_proc(args: &[String]) -> i32 {
     }
 
     // u_cleanup dropped. C++ main falls off the end (implicit 0).
-    0
+    finish_output(out, 0)
 }
 
 /// Object-safe `Read + Seek` so file (Cursor) and stdin (streaming) inputs share
@@ -521,13 +547,13 @@ pub fn main_proc(args: &[String]) -> i32 {
 trait ReadSeek: Read + std::io::Seek {}
 impl<T: Read + std::io::Seek> ReadSeek for T {}
 
-/// Streaming stdin with tiny pushback, standing in for the C++ `std::cin`
+/// Streaming stdin (decompressed, if it is compressed) with tiny pushback, standing in for the C++ `std::cin`
 /// istream. `Read` pulls straight from `Stdin` (internally buffered, returns as
 /// soon as bytes are available on the pipe — no read-to-EOF). `Seek` supports
 /// only what `ux_strip_bom` does: `SeekFrom::Current(-n)` for the last few bytes
 /// read (istream `putback`); everything else is unsupported.
 struct StreamingStdin {
-    inner: std::io::Stdin,
+    inner: Box<dyn std::io::BufRead>,
     /// Most recent bytes read (bounded), so small rewinds can be replayed.
     history: Vec<u8>,
     /// How many history bytes have been "put back" and must be re-served.
@@ -537,9 +563,9 @@ struct StreamingStdin {
 const STDIN_HISTORY: usize = 8;
 
 impl StreamingStdin {
-    fn new() -> Self {
+    fn new(inner: Box<dyn std::io::BufRead>) -> Self {
         StreamingStdin {
-            inner: std::io::stdin(),
+            inner,
             history: Vec::new(),
             pushback: 0,
         }
@@ -39,10 +39,10 @@ fn end_program(name: Option<&str>) -> i32 {
 /// The C++ answers "which step failed?" with a null `Grammar*` for three of
 /// these and by terminating the process for the other two, so no caller can
 /// tell them apart or choose what to do — the shape
-/// `[spec:cg3:req:errors.context]` bans. Local to this tool because this
-/// boundary is the only place that can produce them.
+/// `[spec:cg3:req:errors.context]` bans. Crate-private: only the relabelling
+/// tool boundaries (this one and `cg-pipeline`) can produce them.
 #[derive(Debug, thiserror::Error)]
-enum GrammarLoadError {
+pub(crate) enum GrammarLoadError {
     #[error("Error: Error opening {path} for reading!")]
     Open {
         path: String,
@@ -86,7 +86,10 @@ enum GrammarLoadError {
 /// `new Grammar` is never `delete`d on the error-return paths (a memory leak);
 /// the Rust port owns the `Grammar` by value, so those paths simply drop it —
 /// memory-safe, so the leak cannot be reproduced (noted).
-fn cg3_grammar_load(filename: &str, require_binary: bool) -> Result<Grammar, GrammarLoadError> {
+pub(crate) fn cg3_grammar_load(
+    filename: &str,
+    require_binary: bool,
+) -> Result<Grammar, GrammarLoadError> {
     // std::ifstream input(filename, std::ios::binary); if (!input) return 0;
     let mut input = File::open(filename).map_err(|source| GrammarLoadError::Open {
         path: filename.to_string(),
@@ -147,14 +150,14 @@ fn cg3_grammar_load(filename: &str, require_binary: bool) -> Result<Grammar, Gra
     Ok(parsed)
 }
 
-/// Report a load failure on the way out of [`main_relabel`], and derive the exit
-/// code it maps to.
+/// Report a load failure on the way out of [`main_relabel`] (or a `cg-pipeline`
+/// relabel stage), and derive the exit code it maps to.
 ///
 /// The headline is the C++-parity line; the causes underneath are where the
 /// detail lives — an OS error, or the tag-regex diagnostics a `Cg3Error` carries
 /// — so converting the loader does not cost the reader what `report_cli` used
 /// to print.
-fn report_load(e: &GrammarLoadError) -> i32 {
+pub(crate) fn report_load(e: &GrammarLoadError) -> i32 {
     tracing::error!("{e}");
     let mut cause = std::error::Error::source(e);
     while let Some(c) = cause {
@@ -0,0 +1,823 @@
+//! ADDED — no C++ analog. `cg-serve`: grammars loaded once, applied to any
+//! number of requests.
+//!
+//! A checker calling `vislcg3` per sentence pays for parsing the grammar every
+//! time; for a production grammar that dwarfs the run. `cg-serve` loads each
+//! `-g ID=PATH` at startup and answers HTTP/1.1 requests on a localhost port
+//! (`--port`) or a Unix socket (`--socket`):
+//!
+//! * `GET /health` — `200` with `{"status":"ok","grammars":[…]}`.
+//! * `POST /run/ID` — the body is the input stream; the response body is the
+//!   output. The query string takes `in` (a stream format name as in
+//!   `cg-pipeline`, or `auto`; default `cg`), `out` (default `cg`), `trace`
+//!   (`1`/`true`) and `sections` (as `--sections`).
+//!
+//! Errors come back as `text/plain`: `400` for a malformed request (including
+//! `sections` that are not numbers and ranges of the grammar's sections), `404`
+//! for an unknown grammar or path, `413` for a body over [`MAX_BODY`], `500`
+//! when the run fails, `503` when [`QUEUED_PER_THREAD`] runs per worker are
+//! already waiting and `504` when it outlives `--timeout`. One request per
+//! connection.
+//!
+//! Connections are read by `--threads` workers, and the runs they ask for are
+//! done by as many run workers, so no more than `--threads` runs use the CPU
+//! at once. A run needs an applicator of its own — the engine interns input
+//! tags into its grammar and keeps per-stream state in its [`Document`] — so
+//! each grammar keeps a pool of idle applicators per option set (`trace`, the
+//! expanded `sections`), at most `--threads` of them in all. A run takes one,
+//! or builds one from the grammar's compiled form kept in memory (the cost of
+//! a binary grammar load, not a parse); afterwards the applicator gets a fresh
+//! document and goes back to the pool, unless the input tags it interned have
+//! grown its grammar by more than [`MAX_INTERNED_TAGS`]. The stream formats
+//! are per request and need no pool of their own.
+//!
+//! A run that outlives the timeout is answered with `504` but not stopped: the
+//! engine has no cancellation point, so it finishes on its run worker, which
+//! takes no other run meanwhile, and its applicator is discarded. A run still
+//! waiting for a worker when its request times out is dropped unstarted.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::format_converter::FormatConverter;
+use crate::grammar_applicator::{Document, StreamFormatKind};
+use crate::icu_uoptions::u_parse_args;
+use crate::inlines::g_app_set_opts_ranged;
+use crate::options::{
+    OptionsTable, UOPT_NO_ARG, UOPT_REQUIRES_ARG, UOption, options, options_default,
+    options_override,
+};
+use crate::options_parser::parse_opts_env;
+
+use super::cg_pipeline::{compiled, format_by_name, grammar_converter, set_opt};
+use super::{EXIT_FAILURE, U_ILLEGAL_ARGUMENT_ERROR, merge_options, to_uargv, uoption as uo};
+
+/// The largest request body accepted.
+pub const MAX_BODY: usize = 64 << 20;
+
+/// How many tags input may add to an applicator's grammar before the
+/// applicator is retired rather than kept warm.
+pub const MAX_INTERNED_TAGS: usize = 1 << 16;
+
+/// How many runs may wait for each run worker before requests are answered
+/// with `503`.
+pub const QUEUED_PER_THREAD: usize = 4;
+
+/// The most passes a `sections` value may expand to.
+pub const MAX_SECTION_PASSES: usize = 256;
+
+/// The request options that need an applicator of their own.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RunOptions {
+    /// `--trace`.
+    pub trace: bool,
+    /// `--sections`, expanded as the engine reads it: the section each pass
+    /// adds, in order.
+    pub sections: Option<Vec<u32>>,
+}
+
+impl RunOptions {
+    /// `sections` as the engine reads it, or why it is refused: only numbers
+    /// and ranges of the grammar's `count` sections, in at most
+    /// [`MAX_SECTION_PASSES`] passes.
+    pub fn sections(sections: &str, count: usize) -> Result<Vec<u32>, String> {
+        let parts: Vec<&str> = sections.split(',').collect();
+        let well_formed = parts.iter().all(|part| {
+            part.splitn(2, '-')
+                .all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        });
+        if !well_formed || parts.len() > MAX_SECTION_PASSES {
+            return Err(format!("malformed sections `{sections}`"));
+        }
+        // Bounded before the ranges are expanded.
+        let out_of_range = sections
+            .split([',', '-'])
+            .any(|n| n.parse::<usize>().map_or(true, |n| n == 0 || n > count));
+        if out_of_range {
+            return Err(format!("the grammar has sections 1 to {count}"));
+        }
+        let mut passes = Vec::new();
+        g_app_set_opts_ranged(sections, &mut passes, true);
+        if passes.len() > MAX_SECTION_PASSES {
+            return Err(format!("sections `{sections}` ask for too many passes"));
+        }
+        Ok(passes)
+    }
+}
+
+/// `passes` in the `--sections` syntax. A lone number would be read as all
+/// sections up to it, so it is written as a range.
+fn sections_value(passes: &[u32]) -> String {
+    match passes {
+        [one] => format!("{one}-{one}"),
+        _ => passes
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+/// An idle applicator, its `unicode_tags` before any request set it, and how
+/// many tags its grammar had when it was built.
+struct Warm {
+    conv: Box<FormatConverter>,
+    unicode_tags: bool,
+    tags: usize,
+}
+
+impl Warm {
+    fn run(
+        &mut self,
+        input: StreamFormatKind,
+        output: StreamFormatKind,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>, crate::error::Cg3Error> {
+        let mut stream = std::io::Cursor::new(body);
+        crate::uextras::ux_strip_bom(&mut stream);
+        if input == StreamFormatKind::Invalid {
+            let pos = stream.position();
+            drop(self.conv.detect_format(&mut stream));
+            stream.set_position(pos);
+        } else {
+            self.conv.base_mut().cfg.fmt_input = input;
+        }
+        let cfg = &mut self.conv.base_mut().cfg;
+        cfg.fmt_output = output;
+        cfg.unicode_tags = self.unicode_tags || output == StreamFormatKind::Apertium;
+        let mut out = Vec::new();
+        self.conv.run_grammar_on_text(&mut stream, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// One served grammar: what it takes to build an applicator for it, and the
+/// idle ones.
+struct Served {
+    path: String,
+    blob: Vec<u8>,
+    options: OptionsTable,
+    /// How many sections the grammar has.
+    sections: usize,
+    idle: Mutex<HashMap<RunOptions, Vec<Warm>>>,
+    /// The most idle applicators kept, over all option sets.
+    idle_max: usize,
+}
+
+impl Served {
+    fn checkout(&self, opts: &RunOptions, env_override: &OptionsTable) -> Result<Warm, i32> {
+        let idle = self
+            .idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(opts)
+            .and_then(Vec::pop);
+        if let Some(warm) = idle {
+            return Ok(warm);
+        }
+        let mut options = self.options.clone();
+        if opts.trace {
+            set_opt(&mut options, crate::options::Opt::Trace, "");
+        }
+        if let Some(passes) = &opts.sections {
+            set_opt(
+                &mut options,
+                crate::options::Opt::Sections,
+                &sections_value(passes),
+            );
+        }
+        let (grammar, options) = super::vislcg3::load_stage_grammar(
+            &self.path,
+            Some(self.blob.clone()),
+            &options,
+            env_override,
+            0,
+        )?;
+        let conv = grammar_converter(grammar, &options).map_err(|e| super::fail(&e))?;
+        let unicode_tags = conv.base().cfg.unicode_tags;
+        let tags = conv.base().grammar.single_tags.size();
+        Ok(Warm {
+            conv: Box::new(conv),
+            unicode_tags,
+            tags,
+        })
+    }
+
+    /// Keep `warm` for the next run with `opts`, unless input has grown its
+    /// grammar too much. A full pool makes room by dropping an applicator of
+    /// another option set, or else drops `warm`.
+    fn checkin(&self, opts: RunOptions, mut warm: Warm) {
+        if warm.conv.base().grammar.single_tags.size() > warm.tags + MAX_INTERNED_TAGS {
+            return;
+        }
+        warm.conv.base_mut().doc = Document::new();
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.values().map(Vec::len).sum::<usize>() >= self.idle_max {
+            let other = idle
+                .iter_mut()
+                .find(|(key, pool)| **key != opts && !pool.is_empty());
+            let Some((_, pool)) = other else {
+                return;
+            };
+            pool.pop();
+        }
+        idle.entry(opts).or_default().push(warm);
+        idle.retain(|_, pool| !pool.is_empty());
+    }
+}
+
+/// A run waiting for a run worker.
+struct Job {
+    id: String,
+    opts: RunOptions,
+    input: StreamFormatKind,
+    output: StreamFormatKind,
+    body: Vec<u8>,
+    /// Set by whichever comes first: the run worker finishing the run, or the
+    /// request giving up on it.
+    claimed: Arc<AtomicBool>,
+    reply: mpsc::Sender<Reply>,
+}
+
+/// The grammars a server answers for, loaded.
+pub struct Server {
+    grammars: Arc<HashMap<String, Served>>,
+    jobs: mpsc::SyncSender<Job>,
+    timeout: Duration,
+}
+
+/// An HTTP reply: status, content type, body.
+struct Reply(u16, &'static str, Vec<u8>);
+
+impl Reply {
+    fn text(status: u16, message: impl Into<String>) -> Self {
+        let mut body: Vec<u8> = message.into().into_bytes();
+        body.push(b'\n');
+        Reply(status, "text/plain; charset=utf-8", body)
+    }
+}
+
+/// A parsed request: method, path, decoded query pairs, body.
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Server {
+    /// Load every `(id, path)` grammar, textual or binary, with the
+    /// `CG3_DEFAULT` / `CG3_OVERRIDE` options, and start `threads` run
+    /// workers. Errors are reported here; the exit code is returned.
+    pub fn load(
+        grammars: &[(String, String)],
+        timeout: Duration,
+        threads: usize,
+    ) -> Result<Self, i32> {
+        let threads = threads.max(1);
+        let mut env_default = options_default();
+        let mut env_override = options_override();
+        parse_opts_env("CG3_DEFAULT", &mut env_default);
+        parse_opts_env("CG3_OVERRIDE", &mut env_override);
+        let mut base = options();
+        merge_options(&mut base, &env_default, &env_override, None);
+
+        let mut served_all = HashMap::new();
+        for (id, path) in grammars {
+            let mut served = Served {
+                path: path.clone(),
+                blob: compiled(path)?,
+                options: base.clone(),
+                sections: 0,
+                idle: Mutex::new(HashMap::new()),
+                idle_max: threads,
+            };
+            // Fails now rather than on the first request, and leaves one
+            // applicator warm for the plain case.
+            let warm = served.checkout(&RunOptions::default(), &env_override)?;
+            served.sections = warm.conv.base().grammar.sections.len();
+            served.checkin(RunOptions::default(), warm);
+            served_all.insert(id.clone(), served);
+        }
+
+        let grammars = Arc::new(served_all);
+        let env_override = Arc::new(env_override);
+        let (jobs, queue) = mpsc::sync_channel::<Job>(threads * QUEUED_PER_THREAD);
+        let queue = Arc::new(Mutex::new(queue));
+        for _ in 0..threads {
+            let grammars = Arc::clone(&grammars);
+            let env_override = Arc::clone(&env_override);
+            let queue = Arc::clone(&queue);
+            std::thread::spawn(move || {
+                loop {
+                    let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    let Ok(job) = job else { break };
+                    run_job(&grammars, &env_override, job);
+                }
+            });
+        }
+        Ok(Server {
+            grammars,
+            jobs,
+            timeout,
+        })
+    }
+
+    /// Apply grammar `id` to `body`: `404` when there is no such grammar,
+    /// `400` for `sections` it does not have; a run that does not finish
+    /// within the timeout is answered with `504`.
+    fn run(
+        &self,
+        id: &str,
+        trace: bool,
+        sections: Option<&str>,
+        input: StreamFormatKind,
+        output: StreamFormatKind,
+        body: Vec<u8>,
+    ) -> Reply {
+        let Some(served) = self.grammars.get(id) else {
+            return Reply::text(404, format!("no grammar `{id}`"));
+        };
+        let sections = match sections.map(|s| RunOptions::sections(s, served.sections)) {
+            None => None,
+            Some(Ok(passes)) => Some(passes),
+            Some(Err(why)) => return Reply::text(400, why),
+        };
+        let claimed = Arc::new(AtomicBool::new(false));
+        let (reply, answer) = mpsc::channel();
+        let job = Job {
+            id: id.to_string(),
+            opts: RunOptions { trace, sections },
+            input,
+            output,
+            body,
+            claimed: Arc::clone(&claimed),
+            reply,
+        };
+        match self.jobs.try_send(job) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => {
+                return Reply::text(503, "too many runs are waiting; try again later");
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                return Reply::text(500, "no run worker is left");
+            }
+        }
+        match answer.recv_timeout(self.timeout) {
+            Ok(reply) => reply,
+            // The run finished just as the request gave up; its reply is on
+            // the way.
+            Err(_) if claimed.swap(true, Ordering::SeqCst) => answer
+                .recv()
+                .unwrap_or_else(|_| Reply::text(500, "the run was lost")),
+            Err(_) => Reply::text(504, "the run did not finish in time"),
+        }
+    }
+
+    fn route(&self, req: Request) -> Reply {
+        match (req.method.as_str(), req.path.as_str()) {
+            ("GET", "/health") => {
+                let mut ids: Vec<&String> = self.grammars.keys().collect();
+                ids.sort();
+                let body = json!({ "status": "ok", "grammars": ids }).to_string();
+                Reply(200, "application/json", body.into_bytes())
+            }
+            ("POST", path) if path.starts_with("/run/") => {
+                let id = &path["/run/".len()..];
+                let mut trace = false;
+                let mut sections = None;
+                let mut input = StreamFormatKind::Cg;
+                let mut output = StreamFormatKind::Cg;
+                for (key, value) in &req.query {
+                    match key.as_str() {
+                        "in" if value == "auto" => input = StreamFormatKind::Invalid,
+                        "in" | "out" => {
+                            let Some(fmt) = format_by_name(value) else {
+                                return Reply::text(
+                                    400,
+                                    format!("unknown stream format `{value}`"),
+                                );
+                            };
+                            if key == "in" {
+                                input = fmt;
+                            } else {
+                                output = fmt;
+                            }
+                        }
+                        "trace" => trace = value == "1" || value == "true",
+                        "sections" => sections = Some(value.as_str()),
+                        _ => return Reply::text(400, format!("unknown parameter `{key}`")),
+                    }
+                }
+                self.run(id, trace, sections, input, output, req.body)
+            }
+            (_, "/health") => Reply::text(405, "use GET"),
+            (_, path) if path.starts_with("/run/") => Reply::text(405, "use POST"),
+            _ => Reply::text(404, format!("no such path `{}`", req.path)),
+        }
+    }
+
+    /// Answer the one request on `conn`.
+    fn serve<C: Read + Write>(self: &Arc<Self>, conn: C) {
+        let mut reader = BufReader::new(conn);
+        let reply = match read_request(&mut reader) {
+            Ok(req) => self.route(req),
+            Err(reply) => reply,
+        };
+        let Reply(status, content_type, body) = reply;
+        let conn = reader.get_mut();
+        let head = format!(
+            "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            reason(status),
+            body.len()
+        );
+        let sent = conn
+            .write_all(head.as_bytes())
+            .and_then(|()| conn.write_all(&body))
+            .and_then(|()| conn.flush());
+        if let Err(e) = sent {
+            tracing::debug!("cg-serve: could not send the reply: {e}");
+        }
+    }
+}
+
+/// Do `job` on a run worker. A job its request has given up on is dropped
+/// unstarted; a run its request gave up on meanwhile is answered to no one,
+/// and its applicator is not kept.
+fn run_job(grammars: &HashMap<String, Served>, env_override: &OptionsTable, job: Job) {
+    if job.claimed.load(Ordering::SeqCst) {
+        return;
+    }
+    let served = &grammars[&job.id];
+    let (reply, warm) = match served.checkout(&job.opts, env_override) {
+        Ok(mut warm) => match warm.run(job.input, job.output, job.body) {
+            Ok(out) => (Reply(200, content_type(job.output), out), Some(warm)),
+            // A failed run may have left the document half-built.
+            Err(e) => (Reply::text(500, e.to_string()), None),
+        },
+        Err(_) => (
+            Reply::text(500, format!("grammar `{}` could not be set up", job.id)),
+            None,
+        ),
+    };
+    if job.claimed.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(warm) = warm {
+        served.checkin(job.opts, warm);
+    }
+    let _ = job.reply.send(reply);
+}
+
+/// The MIME type a stream format is served as.
+fn content_type(fmt: StreamFormatKind) -> &'static str {
+    match fmt {
+        StreamFormatKind::Jsonl | StreamFormatKind::Errors => "application/jsonl",
+        StreamFormatKind::Binary => "application/octet-stream",
+        StreamFormatKind::Matxin => "application/xml",
+        _ => "text/plain; charset=utf-8",
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
+}
+
+/// `application/x-www-form-urlencoded` decoding of one query component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if bytes
+                .get(i + 1..i + 3)
+                .is_some_and(|h| h.iter().all(u8::is_ascii_hexdigit)) =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("0");
+                out.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Read one request: the request line, the headers (only `Content-Length`
+/// matters) and the body.
+fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, Reply> {
+    let bad = |what: &str| Reply::text(400, what.to_string());
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| bad("unreadable request line"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad("malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    let mut length = 0usize;
+    loop {
+        let mut header = String::new();
+        let n = reader
+            .read_line(&mut header)
+            .map_err(|_| bad("unreadable header"))?;
+        let header = header.trim_end();
+        if n == 0 || header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value
+                .trim()
+                .parse()
+                .map_err(|_| bad("malformed Content-Length"))?;
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Reply::text(
+            413,
+            format!("bodies are limited to {MAX_BODY} bytes"),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad("body shorter than Content-Length"))?;
+    Ok(Request {
+        method: method.to_string(),
+        path: percent_decode(path),
+        query,
+        body,
+    })
+}
+
+/// Hand connections from `incoming` to `threads` workers, forever.
+fn serve_all<C, I>(server: Arc<Server>, incoming: I, threads: usize)
+where
+    C: Read + Write + Send + 'static,
+    I: Iterator<Item = std::io::Result<C>>,
+{
+    let (tx, rx) = mpsc::channel::<C>();
+    let rx = Arc::new(Mutex::new(rx));
+    for _ in 0..threads.max(1) {
+        let rx = Arc::clone(&rx);
+        let server = Arc::clone(&server);
+        std::thread::spawn(move || {
+            loop {
+                let conn = rx.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                let Ok(conn) = conn else { break };
+                server.serve(conn);
+            }
+        });
+    }
+    for conn in incoming {
+        match conn {
+            Ok(conn) => {
+                if tx.send(conn).is_err() {
+                    break;
+                }
+            }
+            Err(e) => tracing::warn!("cg-serve: could not accept a connection: {e}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Opt {
+    Help1,
+    Help2,
+    Grammar,
+    Socket,
+    Port,
+    Threads,
+    Timeout,
+    NumOptionsServe,
+}
+
+fn options_serve() -> [UOption; Opt::NumOptionsServe as usize] {
+    [
+        uo("help", 'h', UOPT_NO_ARG, "shows this help"),
+        uo("?", '?', UOPT_NO_ARG, "shows this help"),
+        uo(
+            "grammar",
+            'g',
+            UOPT_REQUIRES_ARG,
+            "serves grammar file PATH as ID=PATH; repeatable",
+        ),
+        uo(
+            "socket",
+            's',
+            UOPT_REQUIRES_ARG,
+            "listens on this Unix socket",
+        ),
+        uo(
+            "port",
+            'p',
+            UOPT_REQUIRES_ARG,
+            "listens on this port of 127.0.0.1",
+        ),
+        uo(
+            "threads",
+            'j',
+            UOPT_REQUIRES_ARG,
+            "serves this many requests at once; defaults to 4",
+        ),
+        uo(
+            "timeout",
+            't',
+            UOPT_REQUIRES_ARG,
+            "answers 504 after this many seconds, at least 1; defaults to 30",
+        ),
+    ]
+}
+
+fn usage(options: &[UOption], to_stderr: bool) -> i32 {
+    let mut out = String::from(
+        "Usage: cg-serve -g ID=PATH [-g ID=PATH ...] (--socket PATH | --port N) [OPTIONS]\n\nOptions:\n",
+    );
+    let longest = options
+        .iter()
+        .map(|o| o.long_name.map_or(0, str::len))
+        .max()
+        .unwrap_or(0);
+    for o in options {
+        let short = if o.short_name == '\0' {
+            "   ".to_string()
+        } else {
+            format!("-{},", o.short_name)
+        };
+        let long = o.long_name.unwrap_or("");
+        out.push_str(&format!(" {short} --{long:longest$}  {}\n", o.description));
+    }
+    if to_stderr {
+        eprint!("{out}");
+        U_ILLEGAL_ARGUMENT_ERROR
+    } else {
+        print!("{out}");
+        0
+    }
+}
+
+/// `cg-serve` entry point.
+pub fn main_serve(args: &[String]) -> i32 {
+    let mut opts = options_serve();
+    let mut argv = to_uargv(args);
+    let argc = u_parse_args(
+        argv.len() as i32,
+        &mut argv,
+        Opt::NumOptionsServe as i32,
+        &mut opts,
+    );
+    let occ = |o: Opt| opts[o as usize].does_occur;
+    if argc < 0 || occ(Opt::Help1) || occ(Opt::Help2) {
+        return usage(&opts, argc < 0);
+    }
+    if argc > 1 || !occ(Opt::Grammar) || occ(Opt::Socket) == occ(Opt::Port) {
+        return usage(&opts, true);
+    }
+
+    let mut grammars = Vec::new();
+    for spec in &opts[Opt::Grammar as usize].values {
+        let Some((id, path)) = spec.split_once('=').filter(|(id, _)| !id.is_empty()) else {
+            tracing::error!("Error: --grammar expects ID=PATH, got `{spec}`.");
+            return EXIT_FAILURE;
+        };
+        if grammars.iter().any(|(seen, _)| seen == id) {
+            tracing::error!("Error: grammar id `{id}` given twice.");
+            return EXIT_FAILURE;
+        }
+        grammars.push((id.to_string(), path.to_string()));
+    }
+    // A number within `range`: a zero timeout would be refused by the
+    // sockets, no threads would answer nothing, and a port past 65535 would
+    // wrap.
+    let number = |o: Opt, default: u64, range: std::ops::RangeInclusive<u64>| {
+        if !occ(o) {
+            return Ok(default);
+        }
+        let value = &opts[o as usize].value;
+        match value.parse() {
+            Ok(n) if range.contains(&n) => Ok(n),
+            _ => {
+                let name = opts[o as usize].long_name.unwrap_or("");
+                let (lo, hi) = (range.start(), range.end());
+                if *hi == u64::MAX {
+                    tracing::error!(
+                        "Error: --{name} expects a number of at least {lo}, got `{value}`."
+                    );
+                } else {
+                    tracing::error!(
+                        "Error: --{name} expects a number from {lo} to {hi}, got `{value}`."
+                    );
+                }
+                Err(EXIT_FAILURE)
+            }
+        }
+    };
+    let (threads, timeout) = match (
+        number(Opt::Threads, 4, 1..=u64::MAX),
+        number(Opt::Timeout, 30, 1..=u64::MAX),
+    ) {
+        (Ok(threads), Ok(timeout)) => (threads as usize, Duration::from_secs(timeout)),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    let port = match occ(Opt::Port).then(|| number(Opt::Port, 0, 1..=u16::MAX as u64)) {
+        None => None,
+        Some(Ok(port)) => Some(port as u16),
+        Some(Err(code)) => return code,
+    };
+
+    let server = match Server::load(&grammars, timeout, threads) {
+        Ok(server) => Arc::new(server),
+        Err(code) => return code,
+    };
+
+    if let Some(port) = port {
+        let listener = match std::net::TcpListener::bind(("127.0.0.1", port)) {
+            Ok(l) => l,
+            Err(e) => {
+                tracing::error!("Error: cannot listen on 127.0.0.1:{port}: {e}");
+                return EXIT_FAILURE;
+            }
+        };
+        if let Ok(addr) = listener.local_addr() {
+            tracing::info!("cg-serve: listening on http://{addr}");
+        }
+        let incoming = listener.incoming().map(|conn| {
+            conn.and_then(|c| {
+                c.set_read_timeout(Some(timeout))?;
+                c.set_write_timeout(Some(timeout))?;
+                Ok(c)
+            })
+        });
+        serve_all(server, incoming, threads);
+        return 0;
+    }
+
+    let path = &opts[Opt::Socket as usize].value;
+    serve_unix(server, path, threads, timeout)
+}
+
+#[cfg(unix)]
+fn serve_unix(server: Arc<Server>, path: &str, threads: usize, timeout: Duration) -> i32 {
+    use std::os::unix::net::UnixListener;
+    // A socket file left behind by an earlier server would make bind fail.
+    if std::fs::symlink_metadata(path).is_ok_and(|m| {
+        use std::os::unix::fs::FileTypeExt;
+        m.file_type().is_socket()
+    }) {
+        let _ = std::fs::remove_file(path);
+    }
+    let listener = match UnixListener::bind(path) {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Error: cannot listen on {path}: {e}");
+            return EXIT_FAILURE;
+        }
+    };
+    tracing::info!("cg-serve: listening on {path}");
+    let incoming = listener.incoming().map(|conn| {
+        conn.and_then(|c| {
+            c.set_read_timeout(Some(timeout))?;
+            c.set_write_timeout(Some(timeout))?;
+            Ok(c)
+        })
+    });
+    serve_all(server, incoming, threads);
+    0
+}
+
+#[cfg(not(unix))]
+fn serve_unix(_: Arc<Server>, _: &str, _: usize, _: Duration) -> i32 {
+    tracing::error!("Error: --socket needs a Unix platform; use --port.");
+    EXIT_FAILURE
+}
@@ -47,8 +47,10 @@ pub mod cg_conv;
 #[cfg(feature = "profiler")]
 pub mod cg_merge_annotations;
 pub mod cg_mwesplit;
+pub mod cg_pipeline;
 pub mod cg_proc;
 pub mod cg_relabel;
+pub mod cg_serve;
 pub mod vislcg3;
 
 // --- Diagnostics ----------------------------------------------------------------
@@ -105,6 +107,95 @@ pub fn enable_debug_logging(enabled: bool) {
     }
 }
 
+// --- Machine-readable diagnostics ---------------------------------------------
+
+/// ADDED — no C++ analog. What `--diagnostics-format json|sarif` has gathered
+/// for the document the tool writes on its way out; `None` without the flag.
+///
+/// Process-wide like [`LEVEL_HANDLE`], so every exit path — [`fail`] and
+/// [`finish_output`] — writes it without each failure site threading it.
+static MACHINE_DIAGNOSTICS: std::sync::Mutex<Option<MachineDiagnostics>> =
+    std::sync::Mutex::new(None);
+
+struct MachineDiagnostics {
+    format: crate::diagnostics_export::DiagnosticsFormat,
+    tool: &'static str,
+    records: Vec<crate::diagnostics_export::Record>,
+}
+
+/// Report diagnostics as a `format` document from `tool` rather than as text.
+/// The log is cut to errors so stderr holds little besides the document.
+pub(crate) fn set_diagnostics_format(
+    format: crate::diagnostics_export::DiagnosticsFormat,
+    tool: &'static str,
+) {
+    use crate::diagnostics_export::DiagnosticsFormat;
+    if format == DiagnosticsFormat::Human {
+        return;
+    }
+    *MACHINE_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(MachineDiagnostics {
+        format,
+        tool,
+        records: Vec::new(),
+    });
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        let _ = handle.modify(|filter| *filter = tracing_subscriber::filter::LevelFilter::ERROR);
+    }
+}
+
+/// Whether a document is being gathered.
+pub(crate) fn machine_diagnostics() -> bool {
+    MACHINE_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some()
+}
+
+/// Add `records` to the document, if one is being gathered.
+pub(crate) fn add_diagnostics(records: Vec<crate::diagnostics_export::Record>) {
+    if let Some(m) = MACHINE_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+    {
+        m.records.extend(records);
+    }
+}
+
+/// Add a run's diagnostics, placed in `grammar` where they name a rule.
+pub(crate) fn add_runtime_diagnostics(
+    diags: &[crate::runtime_diagnostics::RuntimeDiagnostic],
+    grammar: &crate::grammar::Grammar,
+    input: &str,
+) {
+    if diags.is_empty() || !machine_diagnostics() {
+        return;
+    }
+    let sources = crate::grammar_sources::resolve(grammar);
+    add_diagnostics(crate::diagnostics_export::runtime_records(
+        diags,
+        sources.as_ref(),
+        input,
+    ));
+}
+
+/// Write the document to stderr, once.
+pub(crate) fn finish_diagnostics() {
+    let taken = MACHINE_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(m) = taken {
+        use std::io::Write as _;
+        let mut stderr = std::io::stderr().lock();
+        let _ =
+            crate::diagnostics_export::write_document(m.format, m.tool, &m.records, &mut stderr);
+        let _ = stderr.flush();
+    }
+}
+
 // --- CLI failure mapping ---------------------------------------------------------
 
 /// The C `EXIT_FAILURE` every `endProgram` / `CG3Quit(1)` in the C++ tools
@@ -120,10 +211,66 @@ pub(crate) const EXIT_FAILURE: i32 = 1;
 /// embedder handling the same value cares about the variant and wants nothing to
 /// do with an exit status.
 pub(crate) fn fail(e: &crate::error::Cg3Error) -> i32 {
-    crate::error::report_cli(e);
+    // ADDED: under `--diagnostics-format`, the failure is the document's.
+    if machine_diagnostics() {
+        add_diagnostics(crate::diagnostics_export::failure_records(e));
+        finish_diagnostics();
+    } else {
+        crate::error::report_cli(e);
+    }
     EXIT_FAILURE
 }
 
+/// ADDED — no C++ analog. End a tool's (possibly compressed) output stream,
+/// then exit with `status`. A reader that has gone away (`| head`) is not a
+/// failure, as it is not for the C++ tools, which die of SIGPIPE quietly.
+pub(crate) fn finish_output<W: std::io::Write>(
+    out: crate::compression::CompressedWriter<W>,
+    status: i32,
+) -> i32 {
+    finish_diagnostics();
+    match out.finish() {
+        Ok(_) => status,
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => status,
+        Err(e) => {
+            tracing::error!("Error: Could not write the output: {e}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// ADDED — no C++ analog. Create `path` for a graph export
+/// (`--dep-json`, `--relations-dot`, ...); a file that cannot be created is
+/// logged and fails the run.
+pub(crate) fn export_file(path: &str) -> Result<Box<dyn std::io::Write + Send>, i32> {
+    match std::fs::File::create(path) {
+        Ok(f) => Ok(Box::new(std::io::BufWriter::new(f))),
+        Err(e) => {
+            tracing::error!("Error: Could not create {path}: {e}");
+            Err(EXIT_FAILURE)
+        }
+    }
+}
+
+/// ADDED — no C++ analog. Take the graph exports back out of the engine after
+/// a run and flush them.
+pub(crate) fn finish_graph_exports(
+    applicator: &mut crate::grammar_applicator::GrammarApplicator,
+) -> Result<(), i32> {
+    let flushed = match applicator.diag.dep_export.take() {
+        Some(mut export) => export.flush(),
+        None => Ok(()),
+    }
+    .and_then(|()| match applicator.diag.relation_export.take() {
+        Some(mut export) => export.flush(),
+        None => Ok(()),
+    });
+    flushed.map_err(|e| {
+        tracing::error!("Error: Could not write the graph exports: {e}");
+        EXIT_FAILURE
+    })
+}
+
 // --- Option-table merging --------------------------------------------------------
 
 /// Merge one pair of option tables onto `options`: `defaults` fill only what is
@@ -205,6 +352,25 @@ pub(crate) fn to_uargv(args: &[String]) -> Vec<Vec<char>> {
     args.iter().map(|s| s.chars().collect()).collect()
 }
 
+/// `UOption` aggregate init `{long, short, hasArg, desc}` for the option tables
+/// the tools declare locally (`UOption::new` is private to [`crate::options`]).
+pub(crate) fn uoption(
+    long: &'static str,
+    short: char,
+    has_arg: u8,
+    desc: &'static str,
+) -> crate::options::UOption {
+    crate::options::UOption {
+        long_name: Some(long),
+        short_name: short,
+        has_arg,
+        description: desc.to_string(),
+        does_occur: false,
+        value: String::new(),
+        values: Vec::new(),
+    }
+}
+
 /// C++ `basename(argv[0])` — the trailing path component, used in the various
 /// `endProgram` usage banners. A faithful stand-in for POSIX `basename(3)`
 /// (splits on `/`; returns the whole string when there is no separator).
@@ -8,6 +8,32 @@
 //! ([`crate::grammar_writer::GrammarWriter`]) and/or binary
 //! ([`crate::binary_grammar::BinaryGrammar`]) form.
 //!
+//! `--watch` (ADDED — no C++ analog) keeps the run going on input as it
+//! arrives and swaps in the grammar again whenever it or a file it includes
+//! changes, through [`crate::grammar_watch`].
+//!
+//! `--max-weight` and `--n-best` (ADDED) prune readings by weight once the
+//! grammar is done with each window (see [`crate::grammar_applicator::weights`]).
+//!
+//! Compressed input and grammars are read as they come (ADDED); output is
+//! compressed by `--compress` or the `--stdout` file's extension (see
+//! [`crate::compression`]).
+//!
+//! `--jsonl-strict` (ADDED) stops the run at the first `--in-jsonl` line that
+//! breaks the schema, and `--jsonl-header` (ADDED) starts `--out-jsonl` output
+//! with the schema version marker (see [`crate::jsonl_schema`]).
+//!
+//! `--strict-input` (ADDED) stops the run at the first input line any reader
+//! finds malformed, quoting it, where the default notes it and reads on.
+//!
+//! `--normalize nfc|nfd` (ADDED) reads a textual grammar and the input in that
+//! Unicode form (see [`crate::normalization`]); a binary grammar keeps the form
+//! it was compiled with, which the option must then agree with.
+//!
+//! `--diagnostics-format json|sarif` (ADDED) writes the parse errors, the run's
+//! diagnostics and any fatal failure to stderr as one document (see
+//! [`crate::diagnostics_export`]).
+//!
 //! Remaining NOTEd elision: `--stderr` creates the redirect file (same
 //! truncation side effect as the C++) but diagnostics still go to process
 //! stderr — the engine has no wired `ux_stderr` sink.
@@ -15,10 +41,17 @@
 use std::io::{Read, Write};
 
 use crate::binary_grammar::BinaryGrammar;
+use crate::compression::{self, CompressedWriter, Compression};
 use crate::grammar::{Grammar, Reindexed};
+use crate::grammar_applicator::dep_tree::DepTreeExport;
+use crate::grammar_applicator::relation_graph::RelationExport;
+use crate::grammar_applicator::reload::GrammarSwap;
+use crate::grammar_applicator::weights::WeightPruning;
+use crate::grammar_watch::{GrammarWatch, POLL_INTERVAL, watch_grammar};
 use crate::grammar_writer::GrammarWriter;
 use crate::icu_uoptions::u_parse_args;
 use crate::inlines::is_cg3b;
+use crate::normalization::Normalization;
 use crate::options::{
     Opt, grammar_options_default, grammar_options_override, options, options_default,
     options_override,
@@ -27,11 +60,12 @@ use crate::options_parser::{parse_opts, parse_opts_env};
 use crate::profiler::Profiler;
 use crate::tag_regex::{TagRegex, TagRegexError, compile_tag_regex};
 use crate::textual_parser::TextualParser;
+use crate::tokenizer::RuleTokenizer;
 
 use super::{
     CG3_COPYRIGHT_STRING, CG3_TOO_OLD, DIVVUN_COPYRIGHT_STRING, DIVVUN_REPOSITORY, EXIT_FAILURE,
-    U_ILLEGAL_ARGUMENT_ERROR, U_ZERO_ERROR, fail, merge_options, print_divvun_version_line,
-    to_uargv,
+    U_ILLEGAL_ARGUMENT_ERROR, U_ZERO_ERROR, export_file, fail, finish_graph_exports, finish_output,
+    merge_options, print_divvun_version_line, to_uargv,
 };
 
 /// A `--nrules` / `--nrules-v` pattern that would not compile.
@@ -119,6 +153,16 @@ pub fn main_run(args: &[String]) -> i32 {
     // --debug: the C++ `debug_level` flag only ever gated verbose diagnostics, so
     // the port collapses it to "raise the tracing level to DEBUG" (no engine state).
     super::enable_debug_logging(occ(&options, Opt::Dodebug));
+    // ADDED: JSON or SARIF diagnostics for CI.
+    if occ(&options, Opt::DiagnosticsFormat) {
+        match options[Opt::DiagnosticsFormat as usize].value.parse() {
+            Ok(format) => super::set_diagnostics_format(format, "vislcg3"),
+            Err(e) => {
+                tracing::error!("{e}");
+                return EXIT_FAILURE;
+            }
+        }
+    }
 
     // --min-binary-revision
     if occ(&options, Opt::VersionTooOld) {
@@ -197,7 +241,19 @@ pub fn main_run(args: &[String]) -> i32 {
     // is false and those checks never fire; the C++ proceeds with a dead stream
     // (output silently discarded / input reads as empty). Mirrored here with
     // sink()/empty-input fallbacks. The `--stdin` stat() failure DOES exit.
-    let mut ux_stdout: Box<dyn Write> = if occ(&options, Opt::Stdout) {
+    // ADDED: compressed by --compress, else by the --stdout file's extension.
+    let compression = match Compression::for_output(
+        occ(&options, Opt::Compress).then(|| options[Opt::Compress as usize].value.as_str()),
+        occ(&options, Opt::Stdout)
+            .then(|| std::path::Path::new(&options[Opt::Stdout as usize].value)),
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("{e}");
+            return EXIT_FAILURE;
+        }
+    };
+    let ux_stdout: Box<dyn Write> = if occ(&options, Opt::Stdout) {
         match std::fs::File::create(&options[Opt::Stdout as usize].value) {
             Ok(f) => Box::new(f),
             Err(_) => Box::new(std::io::sink()), // dead ofstream — see NOTE.
@@ -205,6 +261,7 @@ pub fn main_run(args: &[String]) -> i32 {
     } else {
         Box::new(std::io::stdout())
     };
+    let mut ux_stdout = CompressedWriter::new(ux_stdout, compression);
     if occ(&options, Opt::Stderr) {
         // std::ofstream(options[STDERR].value) — created (same truncation side
         // effect as C++), but NOTE: the engine's `ux_stderr` is an elided
@@ -227,8 +284,53 @@ pub fn main_run(args: &[String]) -> i32 {
 
     let verbose = occ(&options, Opt::Verbose);
 
+    // Several `-g` make an in-process pipeline: the first grammar reads and
+    // prints the stream, the rest run after it on every window.
+    let grammar_values = &options[Opt::Grammar as usize].values;
+    let (grammar_path, stage_paths) = match grammar_values.split_first() {
+        Some((first, rest)) if !rest.is_empty() => (first.clone(), rest.to_vec()),
+        _ => (options[Opt::Grammar as usize].value.clone(), Vec::new()),
+    };
+    if !stage_paths.is_empty() {
+        for (opt, flag) in [
+            (Opt::Trace, "--trace"),
+            (Opt::TraceNameOnly, "--trace-name-only"),
+            (Opt::TraceNoRemoved, "--trace-no-removed"),
+            (Opt::TraceEncl, "--trace-encl"),
+            (Opt::Profiling, "--profile"),
+            (Opt::GrammarOut, "--grammar-out"),
+            (Opt::GrammarBin, "--grammar-bin"),
+            (Opt::DumpAst, "--dump-ast"),
+        ] {
+            if occ(&options, opt) {
+                tracing::error!("Error: {flag} cannot be used with more than one grammar!");
+                return EXIT_FAILURE;
+            }
+        }
+    }
+
+    // --watch swaps the one grammar that runs; the grammar-only modes and the
+    // writers would only ever see the first load.
+    let watching = occ(&options, Opt::Watch);
+    if watching {
+        if !stage_paths.is_empty() {
+            tracing::error!("Error: --watch cannot be used with more than one grammar!");
+            return EXIT_FAILURE;
+        }
+        for (opt, flag) in [
+            (Opt::GrammarOnly, "--grammar-only"),
+            (Opt::Profiling, "--profile"),
+            (Opt::GrammarOut, "--grammar-out"),
+            (Opt::GrammarBin, "--grammar-bin"),
+        ] {
+            if occ(&options, opt) {
+                tracing::error!("Error: {flag} cannot be used with --watch!");
+                return EXIT_FAILURE;
+            }
+        }
+    }
+
     // Read the grammar's first 4 bytes to detect binary vs text.
-    let grammar_path = options[Opt::Grammar as usize].value.clone();
     let mut head = [0u8; 4];
     {
         let mut input = match std::fs::File::open(&grammar_path) {
@@ -238,7 +340,10 @@ pub fn main_run(args: &[String]) -> i32 {
                 return EXIT_FAILURE;
             }
         };
-        if input.read_exact(&mut head).is_err() {
+        if compression::decompressed(&mut input)
+            .and_then(|mut grammar| grammar.read_exact(&mut head))
+            .is_err()
+        {
             tracing::error!("Error: Error reading first 4 bytes from grammar!");
             return EXIT_FAILURE;
         }
@@ -311,6 +416,13 @@ pub fn main_run(args: &[String]) -> i32 {
     // the whole run of every other invocation is exactly what
     // `[spec:cg3:req:diagnostics.source-lazy]` forbids.
     let mut grammar_sources: Vec<crate::error::ParseSource> = Vec::new();
+    let mut watch = GrammarWatch::new([std::path::PathBuf::from(&grammar_path)]);
+
+    // ADDED: --normalize, which the grammar is read under.
+    let normalization = match normalization(&options) {
+        Ok(n) => n,
+        Err(code) => return code,
+    };
 
     let mut grammar: Grammar = if is_binary {
         let mut parser = BinaryGrammar::new(Grammar::default());
@@ -324,11 +436,17 @@ pub fn main_run(args: &[String]) -> i32 {
         if let Err(e) = parser.parse_grammar_filename(&grammar_path) {
             return fail(&e);
         }
+        if let Err(code) = check_binary_normalization(&parser.grammar, normalization) {
+            return code;
+        }
         let mut g = parser.grammar;
         g.verbosity_level = verbosity_level;
         g
     } else {
-        let mut parser = TextualParser::new(Grammar::default(), occ(&options, Opt::DumpAst));
+        let mut parser = TextualParser::new(
+            normalized_grammar(normalization),
+            occ(&options, Opt::DumpAst),
+        );
         if verbose {
             parser.set_verbosity(verbosity_level);
         }
@@ -339,7 +457,7 @@ pub fn main_run(args: &[String]) -> i32 {
         // C++: `parser->profiler = profiler.get();` — move the profiler into
         // the parser for the duration of the parse (taken back below).
         parser.profiler = profiler.take();
-        let buffer = match std::fs::read(&grammar_path) {
+        let buffer = match compression::read_file(&grammar_path) {
             Ok(b) => b,
             Err(_) => {
                 tracing::error!("Error: Error opening {} for reading!", grammar_path);
@@ -367,12 +485,19 @@ pub fn main_run(args: &[String]) -> i32 {
         if occ(&options, Opt::GrammarBin) {
             grammar_sources = parser.sources();
         }
+        if watching {
+            watch = GrammarWatch::of_sources(&parser.sources());
+        }
 
         let mut g = parser.grammar;
         g.verbosity_level = verbosity_level;
         g
     };
 
+    // What a reloaded grammar's own CMDARGS merge over: this grammar's are
+    // replaced by the reload's, not kept.
+    let watch_options = watching.then(|| options.clone());
+
     // Grammar cmdargs → parse_opts into grammar_options_{default,override}, merge.
     if !grammar.cmdargs.is_empty() {
         parse_opts(&grammar.cmdargs, &mut grammar_options_default);
@@ -467,7 +592,29 @@ pub fn main_run(args: &[String]) -> i32 {
             applicator.base_mut().cfg.fmt_input = StreamFormatKind::Jsonl;
         } else if occ(&options, Opt::InBinary) {
             applicator.base_mut().cfg.fmt_input = StreamFormatKind::Binary;
+        } else if occ(&options, Opt::InMatxin) {
+            applicator.base_mut().cfg.fmt_input = StreamFormatKind::Matxin;
+        }
+        // ADDED: plaintext tokenizer rules and sentence windows.
+        if occ(&options, Opt::Tokenizer) {
+            let path = &options[Opt::Tokenizer as usize].value;
+            match RuleTokenizer::from_file(std::path::Path::new(path)) {
+                Ok(rules) => applicator.set_plaintext_tokenizer(std::sync::Arc::new(rules)),
+                Err(e) => {
+                    tracing::error!("{e}");
+                    if let Some(cause) = std::error::Error::source(&e) {
+                        tracing::error!("{cause}");
+                    }
+                    return EXIT_FAILURE;
+                }
+            }
         }
+        applicator.set_plaintext_split_sentences(occ(&options, Opt::SplitSentences));
+        // ADDED: strict JSONL reading.
+        applicator.set_jsonl_strict(occ(&options, Opt::JsonlStrict));
+        applicator.set_jsonl_header(occ(&options, Opt::JsonlHeader));
+        // ADDED: stop at malformed input rather than skip it.
+        applicator.base_mut().cfg.strict_input = occ(&options, Opt::StrictInput);
 
         // applicator.setGrammar(&grammar); — the ported base OWNS its grammar,
         // so "point the applicator at the externally-held grammar" becomes:
@@ -482,8 +629,54 @@ pub fn main_run(args: &[String]) -> i32 {
         if let Err(e) = applicator.base_mut().set_options(&options) {
             return fail(&e);
         }
+        // ADDED: prune readings by weight once the grammar is done with a window.
+        let max_weight =
+            occ(&options, Opt::MaxWeight).then(|| options[Opt::MaxWeight as usize].value.as_str());
+        let n_best = occ(&options, Opt::NBest).then(|| options[Opt::NBest as usize].value.as_str());
+        match WeightPruning::from_options(max_weight, n_best) {
+            Ok(pruning) => applicator.base_mut().cfg.weight_pruning = pruning,
+            Err(e) => {
+                tracing::error!("{e}");
+                return EXIT_FAILURE;
+            }
+        }
+        // ADDED: check and export each window's dependency tree and relations.
+        applicator.base_mut().cfg.dep_validate = occ(&options, Opt::DepValidate);
+        if let Err(code) = set_graph_exports(applicator.base_mut(), &options) {
+            return code;
+        }
+        if let Some(watch_options) = watch_options {
+            let swap = GrammarSwap::new();
+            applicator.base_mut().attach_grammar_swap(swap.clone());
+            let path = grammar_path.clone();
+            let options_override = options_override.clone();
+            watch_grammar(watch, swap, POLL_INTERVAL, move || {
+                let mut sources = Vec::new();
+                let stage = load_stage(
+                    &path,
+                    &watch_options,
+                    &options_override,
+                    0,
+                    Some(&mut sources),
+                )
+                .ok()?;
+                let files = if sources.is_empty() {
+                    GrammarWatch::new([std::path::PathBuf::from(&path)])
+                } else {
+                    GrammarWatch::of_sources(&sources)
+                };
+                Some((stage, files))
+            });
+        }
+        for path in &stage_paths {
+            match load_stage(path, &options, &options_override, verbosity_level, None) {
+                Ok(stage) => applicator.base_mut().add_pipeline_stage(stage),
+                Err(code) => return code,
+            }
+        }
         // [spec:cg3:req:diagnostics.runtime-input-named]
         applicator.base_mut().cfg.input_name = input_name(&options);
+        applicator.base_mut().diag.runtime.quiet = super::machine_diagnostics();
 
         applicator.base_mut().cfg.fmt_output = StreamFormatKind::Cg;
         if occ(&options, Opt::OutApertium) {
@@ -499,6 +692,10 @@ pub fn main_run(args: &[String]) -> i32 {
             applicator.base_mut().cfg.fmt_output = StreamFormatKind::Jsonl;
         } else if occ(&options, Opt::OutBinary) {
             applicator.base_mut().cfg.fmt_output = StreamFormatKind::Binary;
+        } else if occ(&options, Opt::OutMatxin) {
+            applicator.base_mut().cfg.fmt_output = StreamFormatKind::Matxin;
+        } else if occ(&options, Opt::OutErrors) {
+            applicator.base_mut().cfg.fmt_output = StreamFormatKind::Errors;
         }
 
         // C++: `applicator.profiler = profiler.get();` — move the profiler into
@@ -507,21 +704,56 @@ pub fn main_run(args: &[String]) -> i32 {
             applicator.base_mut().diag.profiler = profiler.take();
         }
 
-        // applicator.runGrammarOnText(*ux_stdin, *ux_stdout); — the ported
-        // driver needs `R: Read + Seek`; buffer the input stream into a Cursor.
-        let mut input_bytes = Vec::new();
-        match ux_stdin_file {
-            Some(mut f) => {
-                let _ = f.read_to_end(&mut input_bytes);
+        // --watch runs for as long as input keeps coming, so the input is read
+        // as it arrives rather than to the end first.
+        if watching {
+            let source: Box<dyn Read> = match ux_stdin_file {
+                Some(f) => Box::new(f),
+                None => Box::new(std::io::stdin()),
+            };
+            // ADDED: decompressed as it arrives.
+            let source = match compression::decompressed(source) {
+                Ok(source) => source,
+                Err(e) => {
+                    tracing::error!("Error: Could not decompress the input: {e}");
+                    return EXIT_FAILURE;
+                }
+            };
+            let mut input = ArrivingInput::new(source);
+            let result = applicator.run_grammar_on_text(&mut input, &mut ux_stdout);
+            add_runtime_diagnostics(applicator.base_mut());
+            if let Err(e) = result {
+                return fail(&e);
             }
-            None => {
-                let _ = std::io::stdin().read_to_end(&mut input_bytes);
+            if let Err(code) = finish_graph_exports(applicator.base_mut()) {
+                return code;
             }
+            return finish_output(ux_stdout, status);
         }
+
+        // applicator.runGrammarOnText(*ux_stdin, *ux_stdout); — the ported
+        // driver needs `R: Read + Seek`; buffer the input stream into a Cursor.
+        // ADDED: decompressed on the way in.
+        let source: Box<dyn Read> = match ux_stdin_file {
+            Some(f) => Box::new(f),
+            None => Box::new(std::io::stdin()),
+        };
+        let input_bytes = match compression::read_all(source) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::error!("Error: Could not decompress the input: {e}");
+                return EXIT_FAILURE;
+            }
+        };
         let mut cursor = std::io::Cursor::new(input_bytes);
-        if let Err(e) = applicator.run_grammar_on_text(&mut cursor, &mut ux_stdout) {
+        let result = applicator.run_grammar_on_text(&mut cursor, &mut ux_stdout);
+        add_runtime_diagnostics(applicator.base_mut());
+        if let Err(e) = result {
             return fail(&e);
         }
+        if let Err(code) = finish_graph_exports(applicator.base_mut()) {
+            return code;
+        }
 
         // Move the grammar back out (C++ `grammar` lives in main throughout),
         // and the profiler (for the final `Profiler::write`).
@@ -564,7 +796,258 @@ pub fn main_run(args: &[String]) -> i32 {
     }
 
     // u_cleanup dropped.
-    status
+    finish_output(ux_stdout, status)
+}
+
+/// Load a later grammar of a `-g` pipeline into an applicator ready for
+/// [`add_pipeline_stage`](crate::grammar_applicator::GrammarApplicator::add_pipeline_stage).
+/// Errors are reported here; the exit code is returned.
+///
+/// `sources`, when given, receives the sources of a textual grammar's parse.
+fn load_stage(
+    path: &str,
+    options: &crate::options::OptionsTable,
+    options_override: &crate::options::OptionsTable,
+    verbosity_level: u32,
+    sources: Option<&mut Vec<crate::error::ParseSource>>,
+) -> Result<crate::grammar_applicator::GrammarApplicator, i32> {
+    let (grammar, stage_options) = load_grammar(
+        path,
+        None,
+        options,
+        options_override,
+        verbosity_level,
+        sources,
+    )?;
+    let mut stage = crate::grammar_applicator::GrammarApplicator::new(grammar);
+    stage.set_grammar().map_err(|e| fail(&e))?;
+    stage.set_options(&stage_options).map_err(|e| fail(&e))?;
+    Ok(stage)
+}
+
+/// Load one grammar of a pipeline, and the options it runs with.
+///
+/// The same steps as the first grammar minus everything that only one grammar
+/// can have (profiling, the AST dump, the grammar writers): binary or textual
+/// parse, the `--nrules` filters, the grammar's own `CMDARGS` merged over a
+/// copy of `options` (so they apply to this grammar alone), `--prefix`, and
+/// reindexing. Errors are reported here; the exit code is returned.
+///
+/// `blob`, when given, is the grammar to load in place of the file at `path`
+/// (a relabelled grammar, serialised in memory); `path` then only names it.
+pub(crate) fn load_stage_grammar(
+    path: &str,
+    blob: Option<Vec<u8>>,
+    options: &crate::options::OptionsTable,
+    options_override: &crate::options::OptionsTable,
+    verbosity_level: u32,
+) -> Result<(Grammar, crate::options::OptionsTable), i32> {
+    load_grammar(path, blob, options, options_override, verbosity_level, None)
+}
+
+/// [`load_stage_grammar`], handing a textual parse's sources to `sources`.
+fn load_grammar(
+    path: &str,
+    blob: Option<Vec<u8>>,
+    options: &crate::options::OptionsTable,
+    options_override: &crate::options::OptionsTable,
+    verbosity_level: u32,
+    sources: Option<&mut Vec<crate::error::ParseSource>>,
+) -> Result<(Grammar, crate::options::OptionsTable), i32> {
+    let occ = |o: Opt| options[o as usize].does_occur;
+    let in_memory = blob.is_some();
+    let buffer = match blob.map_or_else(|| compression::read_file(path), Ok) {
+        Ok(b) => b,
+        Err(_) => {
+            tracing::error!("Error: Error opening {} for reading!", path);
+            return Err(EXIT_FAILURE);
+        }
+    };
+    let Some(&head) = buffer.first_chunk::<4>() else {
+        tracing::error!("Error: Error reading first 4 bytes from grammar!");
+        return Err(EXIT_FAILURE);
+    };
+    let nrules = nrules_pattern(options, Opt::Nrules, "--nrules")
+        .and_then(|re| Ok((re, nrules_pattern(options, Opt::NrulesInv, "--nrules-v")?)));
+    let (nrules, nrules_inv) = match nrules {
+        Ok(pair) => pair,
+        Err(e) => {
+            tracing::error!("{e}");
+            return Err(EXIT_FAILURE);
+        }
+    };
+
+    let normalization = normalization(options)?;
+
+    let mut grammar = if is_cg3b(head) {
+        let mut parser = BinaryGrammar::new(Grammar::default());
+        parser.set_verbosity(verbosity_level);
+        parser.set_compatible(occ(Opt::Vislcgcompat));
+        parser.nrules = nrules;
+        parser.nrules_inv = nrules_inv;
+        if in_memory {
+            parser.parse_grammar_buffer(&buffer)
+        } else {
+            parser.parse_grammar_filename(path)
+        }
+        .map_err(|e| fail(&e))?;
+        check_binary_normalization(&parser.grammar, normalization)?;
+        parser.grammar
+    } else {
+        let mut parser = TextualParser::new(normalized_grammar(normalization), false);
+        parser.set_verbosity(verbosity_level);
+        parser.set_compatible(occ(Opt::Vislcgcompat));
+        parser.nrules = nrules;
+        parser.nrules_inv = nrules_inv;
+        parser
+            .parse_grammar_named(&buffer, path)
+            .map_err(|e| fail(&e))?;
+        if let Some(sources) = sources {
+            *sources = parser.sources();
+        }
+        parser.grammar
+    };
+    grammar.verbosity_level = verbosity_level;
+
+    let mut stage_options = options.clone();
+    let mut grammar_options_default = grammar_options_default();
+    let mut grammar_options_override = grammar_options_override();
+    if !grammar.cmdargs.is_empty() {
+        parse_opts(&grammar.cmdargs, &mut grammar_options_default);
+    }
+    if !grammar.cmdargs_override.is_empty() {
+        parse_opts(&grammar.cmdargs_override, &mut grammar_options_override);
+    }
+    merge_options(
+        &mut stage_options,
+        &grammar_options_default,
+        &grammar_options_override,
+        Some(options_override),
+    );
+
+    if stage_options[Opt::MappingPrefix as usize].does_occur {
+        let mp = stage_options[Opt::MappingPrefix as usize]
+            .value
+            .chars()
+            .next()
+            .unwrap_or('@');
+        if grammar.is_binary && grammar.mapping_prefix != mp {
+            tracing::error!(
+                "Error: Mapping prefix must match the one used for compiling the binary grammar!"
+            );
+            return Err(EXIT_FAILURE);
+        }
+        grammar.mapping_prefix = mp;
+    }
+    // `--show-unused-sets` / `--show-tags` describe the first grammar only.
+    let _ = grammar.reindex(false, false).map_err(|e| fail(&e))?;
+    Ok((grammar, stage_options))
+}
+
+/// Input read as it arrives, for `--watch` and `cg-pipeline`. The drivers need `Seek` only to put
+/// back the few bytes they just read (`ux_strip_bom`, the Matxin reader), so
+/// the last [`ArrivingInput::KEPT`] bytes are kept for that.
+pub(super) struct ArrivingInput<R> {
+    inner: std::io::BufReader<R>,
+    /// The bytes most recently read, oldest first.
+    recent: std::collections::VecDeque<u8>,
+    /// Bytes put back, to be read again before `inner`.
+    put_back: Vec<u8>,
+    pos: u64,
+}
+
+impl<R: Read> ArrivingInput<R> {
+    const KEPT: usize = 8;
+
+    pub(super) fn new(inner: R) -> Self {
+        ArrivingInput {
+            inner: std::io::BufReader::new(inner),
+            recent: std::collections::VecDeque::with_capacity(Self::KEPT),
+            put_back: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ArrivingInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = if self.put_back.is_empty() {
+            self.inner.read(buf)?
+        } else {
+            let n = buf.len().min(self.put_back.len());
+            buf[..n].copy_from_slice(&self.put_back[..n]);
+            self.put_back.drain(..n);
+            n
+        };
+        for &b in &buf[..n] {
+            if self.recent.len() == Self::KEPT {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(b);
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read> std::io::Seek for ArrivingInput<R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        match pos {
+            std::io::SeekFrom::Current(back)
+                if back <= 0 && back.unsigned_abs() as usize <= self.recent.len() =>
+            {
+                for _ in 0..back.unsigned_abs() {
+                    if let Some(b) = self.recent.pop_back() {
+                        self.put_back.insert(0, b);
+                    }
+                }
+                self.pos -= back.unsigned_abs();
+                Ok(self.pos)
+            }
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "input read as it arrives can only put back what it just read",
+            )),
+        }
+    }
+}
+
+/// ADDED: hand a run's diagnostics to the `--diagnostics-format` document.
+fn add_runtime_diagnostics(applicator: &mut crate::grammar_applicator::GrammarApplicator) {
+    let report = applicator.take_runtime_diagnostics();
+    super::add_runtime_diagnostics(
+        &report.diagnostics,
+        &applicator.grammar,
+        &applicator.cfg.input_name,
+    );
+}
+
+/// ADDED — no C++ analog. Set up the `--dep-dot` / `--dep-json` and
+/// `--relations-dot` / `--relations-json` exports.
+fn set_graph_exports(
+    applicator: &mut crate::grammar_applicator::GrammarApplicator,
+    options: &crate::options::OptionsTable,
+) -> Result<(), i32> {
+    let file = |opt: Opt| {
+        let opt = &options[opt as usize];
+        opt.does_occur.then(|| export_file(&opt.value)).transpose()
+    };
+    let deps = DepTreeExport {
+        dot: file(Opt::DepDot)?,
+        json: file(Opt::DepJson)?,
+    };
+    if deps.dot.is_some() || deps.json.is_some() {
+        applicator.diag.dep_export = Some(deps);
+    }
+    let relations = RelationExport {
+        dot: file(Opt::RelationsDot)?,
+        json: file(Opt::RelationsJson)?,
+        ..Default::default()
+    };
+    if relations.dot.is_some() || relations.json.is_some() {
+        applicator.diag.relation_export = Some(relations);
+    }
+    Ok(())
 }
 
 // [spec:cg3:req:diagnostics.runtime-input-named]
@@ -581,6 +1064,45 @@ fn input_name(options: &crate::options::OptionsTable) -> String {
     crate::grammar_applicator::STDIN_SOURCE_NAME.to_string()
 }
 
+/// ADDED — no C++ analog. The `--normalize` form, if one was asked for; a bad
+/// value is logged and fails the run.
+fn normalization(options: &crate::options::OptionsTable) -> Result<Option<Normalization>, i32> {
+    let opt = &options[Opt::Normalize as usize];
+    if !opt.does_occur {
+        return Ok(None);
+    }
+    opt.value.parse().map(Some).map_err(|e| {
+        tracing::error!("{e}");
+        EXIT_FAILURE
+    })
+}
+
+/// ADDED — no C++ analog. The empty grammar a textual parse starts from, in
+/// the `--normalize` form; the grammar's own `OPTIONS` may still change it.
+fn normalized_grammar(normalization: Option<Normalization>) -> Grammar {
+    let mut grammar = Grammar::default();
+    grammar.normalization = normalization.unwrap_or_default();
+    grammar
+}
+
+/// ADDED — no C++ analog. A binary grammar's tags were normalised when it was
+/// compiled, so `--normalize` can only agree with it, not change it.
+fn check_binary_normalization(
+    grammar: &Grammar,
+    normalization: Option<Normalization>,
+) -> Result<(), i32> {
+    match normalization {
+        Some(n) if n != grammar.normalization => {
+            tracing::error!(
+                "Error: --normalize {n} does not match the binary grammar's {}; recompile it with that option instead!",
+                grammar.normalization
+            );
+            Err(EXIT_FAILURE)
+        }
+        _ => Ok(()),
+    }
+}
+
 // [spec:cg3:req:diagnostics.sidecar]
 /// `--grammar-bin`: write the grammar in binary form, and its sources beside it.
 ///
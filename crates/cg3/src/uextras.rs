@@ -605,6 +605,32 @@ pub fn get_line_clean_chars<R: Read>(
     packoff
 }
 
+/// ADDED — no C++ analog. The 1-based column in the raw `line` of the char at
+/// `cleaned_at` in the buffer [`get_line_clean`] made of it, undoing the
+/// collapse of whitespace runs, so a diagnostic about a cleaned buffer can
+/// point into the line as it was read.
+pub fn raw_column(line: impl IntoIterator<Item = char>, cleaned_at: usize) -> u32 {
+    let mut it = line.into_iter().peekable();
+    let mut raw = 0usize;
+    let mut cleaned = 0usize;
+    while let Some(&c) = it.peek() {
+        if cleaned == cleaned_at || isnl(c) || c == '\0' {
+            break;
+        }
+        if isspace(c) {
+            while it.peek().is_some_and(|&d| isspace(d) && !isnl(d)) {
+                it.next();
+                raw += 1;
+            }
+        } else {
+            it.next();
+            raw += 1;
+        }
+        cleaned += 1;
+    }
+    raw as u32 + 1
+}
+
 // [spec:cg3:def:uextras.cg3.ux-is-set-op-fn]
 // [spec:cg3:sem:uextras.cg3.ux-is-set-op-fn]
 //
@@ -711,16 +737,14 @@ fn u_get_combining_class(_c: UChar) -> u8 {
 // [spec:cg3:sem:uextras.cg3.ux-str-case-compare-fn]
 //
 // Proper full-Unicode case-insensitive equality. ICU
-// `u_strCaseCompare(U_FOLD_CASE_DEFAULT)` is approximated with Rust's
-// Unicode-aware lowercase folding (parity risk: ICU `foldCase` and Rust
-// `to_lowercase` tables differ for some scripts). BUG note: the C++ error path
+// `u_strCaseCompare(U_FOLD_CASE_DEFAULT)` is full default case folding, as
+// `CaseLocale::Root` (see `crate::case_folding`). BUG note: the C++ error path
 // `throw new std::runtime_error(...)` (a raw POINTER, uncatchable by
 // `catch(const std::exception&)`) has no analog — the std folding path has no
 // `UErrorCode`, so it is simply unreachable here.
 /// C++ `ux_strCaseCompare`.
 pub fn ux_str_case_compare(a: &UString, b: &UString) -> bool {
-    let fold = |s: &str| -> String { s.chars().flat_map(|c| c.to_lowercase()).collect() };
-    fold(a) == fold(b)
+    crate::case_folding::CaseLocale::Root.caseless_eq(a, b)
 }
 
 // [spec:cg3:def:uextras.cg3.substr-t.value-type]
@@ -1088,4 +1112,16 @@ mod tests {
         ux_bufcpy(&mut dst_none, None, 4);
         assert_eq!(dst_none[0], '\0');
     }
+
+    #[test]
+    fn raw_column_undoes_the_whitespace_collapse() {
+        let raw = "a  \t b\tc\n";
+        let mut line = vec!['\0'; 32];
+        let mut cleaned = vec!['\0'; 33];
+        get_line_clean_chars(&mut line, &mut cleaned, &mut Cursor::new(raw), true);
+        let at = cleaned.iter().position(|&c| c == 'c').unwrap();
+        assert_eq!(raw_column(raw.chars(), at), 8);
+        assert_eq!(raw_column(raw.chars(), 0), 1);
+        assert_eq!(raw_column(raw.chars(), 99), 9);
+    }
 }
@@ -340,8 +340,10 @@ impl WindowStream {
 
         let mut prev: Option<CohortId> = None;
         while let Some(sw) = s_window {
-            let cohorts = store.single_windows.get(sw.0).cohorts.clone();
-            for citer in cohorts {
+            // Indexed so the window's cohort list is not copied per rebuild.
+            let n = store.single_windows.get(sw.0).cohorts.len();
+            for i in 0..n {
+                let citer = store.single_windows.get(sw.0).cohorts[i];
                 {
                     let c = store.cohorts.get_mut(citer.0);
                     c.prev = prev;
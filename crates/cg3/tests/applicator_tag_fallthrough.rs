@@ -69,8 +69,8 @@ fn paren_leading_varstring_tag_is_not_interned() {
 /// Construction now stops instead.
 #[test]
 fn uncompilable_regex_varstring_tag_is_not_interned() {
-    let e = observe("\"[:script=Greek:]\"r").expect_err("no tag built from a bad pattern");
-    assert!(e.contains("[:script=Greek:]"), "{e}");
+    let e = observe("\"a(b\"r").expect_err("no tag built from a bad pattern");
+    assert!(e.contains("a(b"), "{e}");
 }
 
 /// With no rule in flight the failure belongs to the INPUT, and the line it
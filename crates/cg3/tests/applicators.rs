@@ -1,12 +1,12 @@
 //! Stream-format applicator integration tests — Apertium, Matxin, Binary
 //! stream (.cg3bsf), FST, JSONL, FormatConverter, Niceline, Plaintext, and
-//! MweSplit applicators.
+//! MweSplit applicators, the grammar-checker errors output, cohort source
+//! offsets, and reading weights.
 //!
 //! Drives are real end-to-end runs of the ported binaries (`cg-proc`,
 //! `cg-conv`, `cg-comp`, `cg-mwesplit`) over the `test/` fixture corpus (the
 //! same protocol as the fixtures' `run.pl` scripts), plus in-process library
-//! calls for lower-level serializer/parser details and for Matxin output
-//! (which follows the upstream converter's missing switch arm), plus
+//! calls for lower-level serializer/parser details, plus
 //! `ApertiumApplicator::testPR` which is a
 //! commented-out debug block in the C++ `cg-proc.cpp`).
 
@@ -147,6 +147,54 @@ fn apertium_cg_proc_fixtures() {
     }
 }
 
+// ADDED: `OPTIONS += casefold-tr ;` folds `"…"i` tags and raises `-w` wordform
+// case by the Turkish rules (`KIZ` is `kız`, `izmir` titles to `İzmir`), and
+// every locale folds fully (`"<straße>"i` matches `STRASSE`).
+#[test]
+fn apertium_case_locale() {
+    let dir = std::env::temp_dir();
+    let run = |name: &str, grammar: &str, input: &str| -> String {
+        let src = tmp(&format!("{name}.cg3"));
+        let bin = tmp(&format!("{name}.bin"));
+        std::fs::write(&src, grammar).unwrap();
+        let st = Command::new(env!("CARGO_BIN_EXE_cg-comp"))
+            .arg(&src)
+            .arg(&bin)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("spawn cg-comp");
+        assert!(st.success(), "cg-comp failed for {name}");
+        let out = run_with_stdin(
+            env!("CARGO_BIN_EXE_cg-proc"),
+            &["-w", bin.to_str().unwrap()],
+            &dir,
+            input.as_bytes(),
+        );
+        let _ = std::fs::remove_file(&src);
+        let _ = std::fs::remove_file(&bin);
+        String::from_utf8(out).unwrap()
+    };
+    let rules = "DELIMITERS = \"<.>\" ;\nLIST Kiz = \"<kız>\"i ;\nLIST Strasse = \"<straße>\"i ;\n\
+                 ADD (@kiz) Kiz ;\nADD (@strasse) Strasse ;\n";
+    let input = "^KIZ/kız<n>$ ^İzmir/izmir<np>$ ^STRASSE/straße<n>$\n";
+
+    let root = run("case-root", rules, input);
+    assert_eq!(
+        root.trim_end(),
+        "^KIZ/KIZ<n>$ ^İzmir/Izmir<np>$ ^STRASSE/STRASSE<n><@strasse>$"
+    );
+    let turkish = run(
+        "case-tr",
+        &format!("OPTIONS += casefold-tr ;\n{rules}"),
+        input,
+    );
+    assert_eq!(
+        turkish.trim_end(),
+        "^KIZ/KIZ<n><@kiz>$ ^İzmir/İzmir<np>$ ^STRASSE/STRASSE<n><@strasse>$"
+    );
+}
+
 // ApertiumApplicator::parseStreamVar — driven by a real cg-proc run over an
 // Apertium stream whose superblank carries a `[<STREAMCMD:SETVAR:...>]`
 // command (the only input shape that reaches parseStreamVar: a blank longer
@@ -404,6 +452,174 @@ fn binary_stream_roundtrip() {
     );
 }
 
+// Binary stream inspector — ADDED, no C++ analog. `cg-conv --inspect-binary`
+// lists each packet and cohort at its byte offset, and a cut stream is
+// reported at the offset where it ends, with a failing exit status.
+#[test]
+fn binary_stream_inspector() {
+    let root = repo_root();
+    let input = std::fs::read(root.join("test/T_InputCommands/input.txt")).unwrap();
+    let stream = run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["--in-cg", "--out-binary"],
+        &root,
+        &input,
+    );
+    let report = String::from_utf8(run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["--inspect-binary"],
+        &root,
+        &stream,
+    ))
+    .unwrap();
+    assert!(
+        report.starts_with("00000000  header   CGBF, version 1\n"),
+        "{report}"
+    );
+    assert!(report.contains("  command  FLUSH\n"), "{report}");
+    assert!(report.contains("    cohort \"<word>\""), "{report}");
+    assert!(report.contains("  text     \"test2"), "{report}");
+    assert!(!report.contains("problem"), "{report}");
+
+    let cut = stream.len() - 3;
+    let out = Command::new(env!("CARGO_BIN_EXE_cg-conv"))
+        .arg("--inspect-binary")
+        .current_dir(&root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            child.stdin.take().unwrap().write_all(&stream[..cut])?;
+            child.wait_with_output()
+        })
+        .unwrap();
+    assert!(!out.status.success());
+    let report = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        report.contains(&format!("{cut:08x}  problem: stream is truncated")),
+        "{report}"
+    );
+}
+
+// ADDED: cg-conv tag filters apply in the shared print path, so CG and JSONL
+// output see the same thinned readings.
+#[test]
+fn conv_tag_filters() {
+    let root = repo_root();
+    let input = "\"<a>\"\n\t\"a\" n <W:1> §x @SUBJ SELECT:12\n\t\t\"b\" pfx\n\t\t\t\"c\" deep\n;\t\"a\" v REMOVE:3\n\n";
+    let run = |args: &[&str]| {
+        let mut all = vec!["--in-cg", "--deleted"];
+        all.extend_from_slice(args);
+        String::from_utf8(run_with_stdin(
+            env!("CARGO_BIN_EXE_cg-conv"),
+            &all,
+            &root,
+            input.as_bytes(),
+        ))
+        .unwrap()
+    };
+
+    let cg = run(&[
+        "--drop-tag-types",
+        "mapping,trace",
+        "--drop-tags",
+        "^<W",
+        "--rename-prefix",
+        "§=@",
+        "--max-subreading-depth",
+        "1",
+    ]);
+    assert_eq!(cg, "\"<a>\"\n\t\"a\" n @x\n\t\t\"b\" pfx\n;\t\"a\" v\n\n");
+
+    let jsonl = run(&["--out-jsonl", "--strip-deleted", "--keep-tags", "^n$"]);
+    let cohort = jsonl.lines().next().unwrap();
+    assert!(cohort.contains(r#""ts":["n"]"#), "{jsonl}");
+    assert!(
+        !cohort.contains("drs") && !cohort.contains("pfx"),
+        "{jsonl}"
+    );
+
+    let out = Command::new(env!("CARGO_BIN_EXE_cg-conv"))
+        .args(["--rename-prefix", "oops"])
+        .current_dir(&root)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+// ADDED: SETVAR lists and GETVARS read alike in every text format, and an
+// embedder sees and changes the same variables between runs.
+#[test]
+fn stream_variables() {
+    let root = repo_root();
+    let run = |args: &[&str], input: &str| {
+        String::from_utf8(run_with_stdin(
+            env!("CARGO_BIN_EXE_cg-conv"),
+            args,
+            &root,
+            input.as_bytes(),
+        ))
+        .unwrap()
+    };
+
+    let cg = run(
+        &["--in-cg"],
+        "<STREAMCMD:SETVAR:a=1,b,c=x=y>\n\"<w>\"\n\t\"w\" n\n<STREAMCMD:GETVARS>\n\
+         <STREAMCMD:REMVAR:b>\n<STREAMCMD:GETVARS>\n",
+    );
+    assert!(
+        cg.contains("\t\"w\" n\n<STREAMCMD:VARS:a=1,b,c=x=y>\n"),
+        "{cg}"
+    );
+    assert!(cg.contains("<STREAMCMD:VARS:a=1,c=x=y>\n"), "{cg}");
+
+    let jsonl = run(
+        &["--in-jsonl", "--out-jsonl"],
+        "{\"cmd\":\"<STREAMCMD:SETVAR:a=1,b>\"}\n{\"cmd\":\"<STREAMCMD:GETVARS>\"}\n",
+    );
+    assert!(
+        jsonl.contains(r#"{"cmd":"<STREAMCMD:VARS:a=1,b>"}"#),
+        "{jsonl}"
+    );
+
+    let apertium = run(
+        &["--in-apertium", "--out-apertium"],
+        "[<STREAMCMD:SETVAR:a=1>]^w/w<n>$ [<STREAMCMD:GETVARS>]^v/v<n>$\n",
+    );
+    assert!(
+        apertium.contains("^w/w<n>$ [<STREAMCMD:VARS:a=1>]^v/v<n>$"),
+        "{apertium}"
+    );
+
+    let mut base = conv_base();
+    base.cfg.is_conv = true;
+    base.cfg.verbosity_level = 0;
+    base.set_variable("lang", Some("sme")).unwrap();
+    base.set_variable("flag", None).unwrap();
+    assert_eq!(base.variable("lang"), Some(Some("sme".to_string())));
+    assert_eq!(base.variable("flag"), Some(None));
+    let input = "<STREAMCMD:GETVARS>\n<STREAMCMD:REMVAR:flag>\n<STREAMCMD:SETVAR:n=2>\n";
+    let mut cursor = std::io::Cursor::new(input.as_bytes().to_vec());
+    let mut out: Vec<u8> = Vec::new();
+    base.run_grammar_on_text(&mut cursor, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(
+        text.starts_with("<STREAMCMD:VARS:flag,lang=sme>\n"),
+        "{text}"
+    );
+    assert_eq!(
+        base.variables(),
+        [
+            ("lang".to_string(), Some("sme".to_string())),
+            ("n".to_string(), Some("2".to_string())),
+        ]
+    );
+    assert!(base.remove_variable("lang"));
+    assert!(!base.remove_variable("lang"));
+    assert_eq!(base.variable("lang"), None);
+}
+
 // ===========================================================================
 // FSTApplicator — supplement the converter/CLI coverage below with a direct
 // wrapper run: conv grammar + is_conv + trace, then
@@ -529,6 +745,459 @@ fn jsonl_conv_roundtrip() {
     );
 }
 
+// Versioned JSONL schema — ADDED, no C++ analog. `--jsonl-header` opens the
+// output with the version marker; `--jsonl-strict` stops at the first line breaking the
+// schema, naming its line and the JSON pointer of the value, where the
+// default reading skips what it cannot use.
+#[test]
+fn jsonl_schema_marker_and_strict_mode() {
+    let root = repo_root();
+    let conv = |args: &[&str], input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cg-conv"))
+            .args(args)
+            .current_dir(&root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn cg-conv");
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let good = "{\"cg3jsonl\":1}\n{\"w\":\"a\",\"rs\":[{\"l\":\"a\",\"ts\":[\"n\"]}]}\n";
+    let out = conv(&["--jsonl-strict", "-J"], good);
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(!text.contains("cg3jsonl"), "{text}");
+    let out = conv(&["--jsonl-strict", "--jsonl-header", "-J"], good);
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "{\"cg3jsonl\":1}");
+    assert_eq!(
+        lines[1..].iter().filter(|l| l.contains("cg3jsonl")).count(),
+        0
+    );
+    assert!(lines[1].contains("\"w\":\"a\""), "{text}");
+
+    for (bad, pointer) in [
+        (
+            "{\"w\":\"b\",\"rss\":[]}",
+            "\"/rss\": unknown key \"rss\"; did you mean \"rs\"?",
+        ),
+        (
+            "{\"w\":\"b\",\"rs\":[{\"l\":\"b\",\"ts\":[\"n\",3]}]}",
+            "\"/rs/0/ts/1\": expected a string",
+        ),
+        ("{\"cg3jsonl\":9}", "\"/cg3jsonl\": schema version 9"),
+        ("[1]", "\"\": expected an object"),
+    ] {
+        let input = format!("{good}{bad}\n");
+        let out = conv(&["--jsonl-strict"], &input);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(!out.status.success(), "{bad} was accepted");
+        assert!(
+            stderr.contains(&format!("invalid JSONL on line 3 at {pointer}")),
+            "{bad}: {stderr}"
+        );
+
+        // Without the flag, the same stream still converts.
+        let out = conv(&["-j"], &input);
+        assert!(out.status.success(), "{bad}");
+        assert!(String::from_utf8_lossy(&out.stdout).contains("\"<a>\""));
+    }
+}
+
+// ===========================================================================
+// Matxin XML as a FormatConverter format — ADDED, no C++ analog. A CG stream
+// with dependencies goes out through `cg-conv --out-matxin` as a nested NODE
+// tree (with XML escaping applied exactly once) and comes back through
+// `--in-matxin` with the `#x->y` relations intact.
+#[test]
+fn matxin_conv_roundtrip() {
+    let root = repo_root();
+    let input = concat!(
+        "\"<the>\"\n\t\"the\" det #1->2\n",
+        "\"<dog&co>\"\n\t\"dog&co\" n @SUBJ #2->0\n",
+    );
+    let out = run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["--in-cg", "--parse-dep", "--out-matxin"],
+        &root,
+        input.as_bytes(),
+    );
+    let xml = String::from_utf8(out).unwrap();
+    assert!(xml.starts_with("<corpus>\n"), "corpus not opened:\n{xml}");
+    assert!(xml.ends_with("</corpus>\n"), "corpus not closed:\n{xml}");
+    assert!(
+        xml.contains("form=\"dog&amp;co\" lem=\"dog&amp;co\" mi=\"n\" si=\"SUBJ\">"),
+        "head node wrong:\n{xml}"
+    );
+    assert!(!xml.contains("&amp;amp;"), "double escape:\n{xml}");
+    let head = xml.find("<NODE ord=\"2\"").unwrap();
+    let dependent = xml.find("<NODE ord=\"1\"").unwrap();
+    assert!(
+        head < dependent,
+        "dependent not nested under its head:\n{xml}"
+    );
+
+    // Explicit and auto-detected Matxin input agree, and restore the CG.
+    let back = run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["--in-matxin"],
+        &root,
+        xml.as_bytes(),
+    );
+    let auto = run_with_stdin(env!("CARGO_BIN_EXE_cg-conv"), &[], &root, xml.as_bytes());
+    assert_eq!(back, auto, "auto-detect (matxin) diverged from --in-matxin");
+    assert_eq!(String::from_utf8(back).unwrap(), input);
+
+    // A self-closing `<SENTENCE/>` is an empty sentence, as
+    // `<SENTENCE></SENTENCE>` is.
+    for empty in [
+        "<SENTENCE/>",
+        "<SENTENCE ord=\"9\"/>",
+        "<SENTENCE></SENTENCE>",
+    ] {
+        let doc = xml.replacen("<corpus>\n", &format!("<corpus>\n{empty}\n"), 1);
+        let back = run_with_stdin(
+            env!("CARGO_BIN_EXE_cg-conv"),
+            &["--in-matxin"],
+            &root,
+            doc.as_bytes(),
+        );
+        assert_eq!(String::from_utf8(back).unwrap(), input, "{empty}");
+    }
+}
+
+// Every window becomes its own SENTENCE with ords restarting at 1, and no
+// node of an earlier window leaks into a later one.
+#[test]
+fn matxin_vislcg3_windows() {
+    let root = repo_root();
+    let grammar = tmp("matxin-windows.cg3");
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nLIST N = n ;\nLIST D = det ;\nSETPARENT D TO (1 N) ;\n",
+    )
+    .unwrap();
+    let input = concat!(
+        "\"<the>\"\n\t\"the\" det\n\"<dog>\"\n\t\"dog\" n\n\"<.>\"\n\t\".\" pun\n",
+        "\"<a>\"\n\t\"a\" det\n\"<cat>\"\n\t\"cat\" n\n",
+    );
+    let out = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", grammar.to_str().unwrap(), "--out-matxin"],
+        &root,
+        input.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&grammar);
+    let xml = String::from_utf8(out).unwrap();
+    let sentences: Vec<&str> = xml.split("<SENTENCE ").skip(1).collect();
+    assert_eq!(sentences.len(), 2, "expected two sentences:\n{xml}");
+    assert!(sentences[0].contains("form=\"dog\""), "{xml}");
+    assert!(
+        !sentences[1].contains("form=\"dog\""),
+        "window leaked:\n{xml}"
+    );
+    assert!(
+        sentences[1].contains("<NODE ord=\"1\" alloc=\"0\" form=\"a\""),
+        "{xml}"
+    );
+    assert!(
+        sentences[1].contains("<NODE ord=\"2\" alloc=\"0\" form=\"cat\""),
+        "{xml}"
+    );
+    assert!(
+        !sentences[1].contains("ord=\"3\" alloc"),
+        "ords not restarted:\n{xml}"
+    );
+}
+
+// Malformed Matxin input and sub-readings on Matxin output are reported as
+// errors (non-zero exit) instead of aborting, and the document written so far
+// is still a closed `<corpus>`.
+#[test]
+fn matxin_errors_exit_cleanly() {
+    let root = repo_root();
+    for (args, input) in [
+        (
+            &["--in-matxin"][..],
+            "<corpus><SENTENCE ord=\"1\"><NODE ord=\"1\" form=\"x\"></SENTENCE></corpus>\n",
+        ),
+        (
+            &["--in-cg", "--out-matxin"][..],
+            "\"<a>\"\n\t\"a\" x\n\t\t\"b\" y\n",
+        ),
+    ] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cg-conv"))
+            .args(args)
+            .current_dir(&root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn cg-conv");
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(!out.status.success(), "{args:?} should fail");
+        assert!(!out.stderr.is_empty(), "{args:?} gave no diagnostic");
+        if args.contains(&"--out-matxin") {
+            assert_eq!(
+                String::from_utf8_lossy(&out.stdout),
+                "<corpus>\n</corpus>\n"
+            );
+        }
+    }
+}
+
+// Grammar-checker errors as JSON — ADDED, no C++ analog. A checker-style
+// grammar marks an agreement error, a suggestion reading, and a LEFT relation
+// widening the span; `vislcg3 --out-errors` reports it per window with
+// character offsets into the rebuilt surface text, and `cg-conv -E` reads the
+// same out of the CG stream that grammar prints.
+#[test]
+fn errors_output_reports_checker_marks() {
+    let root = repo_root();
+    let grammar = tmp("errors.cg3");
+    std::fs::write(
+        &grammar,
+        concat!(
+            "DELIMITERS = \"<.>\" ;\n",
+            "LIST Sg = sg ;\nLIST Pl = pl ;\nLIST V = v ;\nLIST N = n ;\n",
+            "ADD (&agr) TARGET V IF (0 Sg) (-1 N + Pl) ;\n",
+            "COPY (&SUGGEST \"<bark>\") TARGET V + (&agr) ;\n",
+            "ADDRELATION (LEFT) (&agr) TO (-1 N) ;\n",
+        ),
+    )
+    .unwrap();
+    let input = concat!(
+        "\"<The>\"\n\t\"the\" det\n: \n",
+        "\"<dogs>\"\n\t\"dog\" n pl\n: \n",
+        "\"<barks>\"\n\t\"bark\" v sg\n",
+        "\"<.>\"\n\t\".\" pun\n:\\n\n",
+        "\"<Hi>\"\n\t\"hi\" interj\n",
+    );
+    let g = grammar.to_str().unwrap();
+    let errors = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", g, "--out-errors"],
+        &root,
+        input.as_bytes(),
+    );
+    let cg = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", g],
+        &root,
+        input.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&grammar);
+    let converted = run_with_stdin(env!("CARGO_BIN_EXE_cg-conv"), &["-E"], &root, &cg);
+
+    let windows: Vec<serde_json::Value> = String::from_utf8(errors)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(windows.len(), 2, "one object per window: {windows:?}");
+    let expected = serde_json::json!({
+        "tag": "agr",
+        "cohort": 3,
+        "span": [2, 3],
+        "beg": 4,
+        "end": 14,
+        "form": "dogs barks",
+        "suggestions": ["dogs bark"],
+    });
+    assert_eq!(windows[0]["text"], "The dogs barks.\n");
+    assert_eq!(windows[0]["errors"], serde_json::json!([expected.clone()]));
+    assert_eq!(windows[1]["text"], "Hi");
+    assert_eq!(windows[1]["offset"], 16);
+    assert_eq!(windows[1]["errors"], serde_json::json!([]));
+
+    // cg-conv has one window for the whole stream; the error is the same.
+    let converted: serde_json::Value =
+        serde_json::from_str(String::from_utf8(converted).unwrap().trim_end()).unwrap();
+    assert_eq!(converted["text"], "The dogs barks.\nHi");
+    assert_eq!(converted["errors"], serde_json::json!([expected]));
+}
+
+// An error's span follows relations through the cohorts they reach, and its
+// offsets count the text of the windows before it.
+#[test]
+fn errors_output_follows_chained_relations() {
+    let root = repo_root();
+    let grammar = tmp("errors-chained.cg3");
+    std::fs::write(
+        &grammar,
+        concat!(
+            "DELIMITERS = \"<.>\" ;\n",
+            "LIST Sg = sg ;\nLIST Pl = pl ;\nLIST V = v ;\nLIST N = n ;\nLIST A = adj ;\n",
+            "ADD (&agr) TARGET V IF (0 Sg) (-1 N + Pl) ;\n",
+            "ADDRELATION (LEFT) (&agr) TO (-1 N) ;\n",
+            "ADDRELATION (MOD) N TO (-1 A) ;\n",
+        ),
+    )
+    .unwrap();
+    let input = concat!(
+        "\"<Hi>\"\n\t\"hi\" interj\n",
+        "\"<.>\"\n\t\".\" pun\n: \n",
+        "\"<big>\"\n\t\"big\" adj\n: \n",
+        "\"<dogs>\"\n\t\"dog\" n pl\n: \n",
+        "\"<barks>\"\n\t\"bark\" v sg\n",
+    );
+    let errors = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", grammar.to_str().unwrap(), "--out-errors"],
+        &root,
+        input.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&grammar);
+    let windows: Vec<serde_json::Value> = String::from_utf8(errors)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(windows.len(), 2, "{windows:?}");
+    assert_eq!(windows[1]["offset"], 4);
+    assert_eq!(windows[1]["text"], "big dogs barks");
+    let error = &windows[1]["errors"][0];
+    assert_eq!(error["span"], serde_json::json!([1, 3]));
+    assert_eq!((&error["beg"], &error["end"]), (&4.into(), &18.into()));
+    assert_eq!(error["form"], "big dogs barks");
+}
+
+/// `(w, ob, ou)` per cohort of a JSONL stream.
+fn jsonl_offsets(jsonl: &[u8]) -> Vec<(String, serde_json::Value, serde_json::Value)> {
+    String::from_utf8(jsonl.to_vec())
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .filter(|v| v.get("w").is_some())
+        .map(|v| {
+            (
+                v["w"].as_str().unwrap().to_string(),
+                v["ob"].clone(),
+                v["ou"].clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn cohort_offsets_follow_the_input_text() {
+    use serde_json::json;
+    let root = repo_root();
+    let conv = env!("CARGO_BIN_EXE_cg-conv");
+    let cohort = |w: &str, ob: [usize; 2], ou: [usize; 2]| (w.to_string(), json!(ob), json!(ou));
+
+    // Plaintext: tokens and peeled punctuation, whitespace runs, later lines.
+    let plain = run_with_stdin(
+        conv,
+        &["-x", "-J"],
+        &root,
+        "Ça va, «𝒳»\nNext  one!\n".as_bytes(),
+    );
+    assert_eq!(
+        jsonl_offsets(&plain),
+        vec![
+            cohort("Ça", [0, 3], [0, 2]),
+            cohort("va", [4, 6], [3, 5]),
+            cohort(",", [6, 7], [5, 6]),
+            cohort("«", [8, 10], [7, 8]),
+            cohort("𝒳", [10, 14], [8, 10]),
+            cohort("»", [14, 16], [10, 11]),
+            cohort("Next", [17, 21], [12, 16]),
+            cohort("one", [23, 26], [18, 21]),
+            cohort("!", [26, 27], [21, 22]),
+        ]
+    );
+
+    // Apertium: superblank contents count, their brackets and word-bound
+    // blanks do not; escapes count once.
+    let apertium = run_with_stdin(
+        conv,
+        &["-a", "-J"],
+        &root,
+        "[<p>]^Ça/ça<n>$ [[t:b:1]]^𝒳/x<n>$^\\$/dollar<sent>$\n".as_bytes(),
+    );
+    assert_eq!(
+        jsonl_offsets(&apertium),
+        vec![
+            cohort("Ça", [3, 6], [3, 5]),
+            cohort("𝒳", [7, 11], [6, 8]),
+            cohort("$", [11, 12], [8, 9]),
+        ]
+    );
+
+    // CG: `:` lines stand for their unescaped text; the offsets survive a
+    // JSONL round trip.
+    let cg = "\"<Ça>\"\n\t\"ça\" n\n: \n\"<𝒳>\"\n\t\"x\" n\n:\\n\n\"<.>\"\n\t\".\" sent\n";
+    let jsonl = run_with_stdin(conv, &["-c", "-J"], &root, cg.as_bytes());
+    let expected = vec![
+        cohort("Ça", [0, 3], [0, 2]),
+        cohort("𝒳", [4, 8], [3, 5]),
+        cohort(".", [9, 10], [6, 7]),
+    ];
+    assert_eq!(jsonl_offsets(&jsonl), expected);
+    let again = run_with_stdin(conv, &["-j", "-J"], &root, &jsonl);
+    assert_eq!(jsonl_offsets(&again), expected);
+}
+
+#[test]
+fn cohort_offsets_across_restructuring_rules() {
+    use serde_json::json;
+    let root = repo_root();
+    let grammar = tmp("offsets.cg3");
+    std::fs::write(
+        &grammar,
+        concat!(
+            "DELIMITERS = \"<.>\" ;\n",
+            "SPLITCOHORT (\"<Né>\" \"né\" adj \"<York>\" \"york\" n) TARGET (\"né-york\") ;\n",
+            "MERGECOHORTS (\"<a lot>\" \"a lot\" adv) TARGET (\"a\") IF (1 (\"lot\")) WITH (1 (\"lot\")) ;\n",
+            "ADDCOHORT (\"<x>\" \"x\" x) BEFORE (\"é\") ;\n",
+        ),
+    )
+    .unwrap();
+    let input = concat!(
+        "\"<Né-York>\"\n\t\"né-york\" n\n: \n",
+        "\"<a>\"\n\t\"a\" det\n: \n",
+        "\"<lot>\"\n\t\"lot\" n\n: \n",
+        "\"<é>\"\n\t\"é\" n\n",
+        "\"<.>\"\n\t\".\" sent\n",
+    );
+    let out = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", grammar.to_str().unwrap(), "--out-jsonl"],
+        &root,
+        input.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&grammar);
+    let cohort = |w: &str, ob: [usize; 2], ou: [usize; 2]| (w.to_string(), json!(ob), json!(ou));
+    assert_eq!(
+        jsonl_offsets(&out),
+        vec![
+            cohort("Né", [0, 3], [0, 2]),
+            cohort("York", [4, 8], [3, 7]),
+            cohort("a lot", [9, 14], [8, 13]),
+            cohort("x", [15, 15], [14, 14]),
+            cohort("é", [15, 17], [14, 15]),
+            cohort(".", [17, 18], [15, 16]),
+        ]
+    );
+}
+
 // ===========================================================================
 // FormatConverter (cg-conv side) — real cg-conv runs. The constructor builds
 // the minimal conv grammar on every invocation; runGrammarOnText dispatches
@@ -861,7 +1530,7 @@ fn niceline_conv() {
 // ===========================================================================
 // PlaintextApplicator — supplement the converter/CLI coverage with a direct
 // wrapper run: conv grammar + is_conv, then runGrammarOnText over raw
-// plaintext. The driver tokenizes lines into cohorts (peeling ASCII
+// plaintext. The driver tokenizes lines into cohorts (peeling Unicode
 // punctuation) and the print side re-emits space-separated wordforms
 // (printSingleWindow → printCohort).
 // [spec:cg3:sem:plaintext-applicator.cg3.plaintext-applicator.plaintext-applicator-fn/test]
@@ -893,6 +1562,177 @@ fn plaintext_applicator_in_process() {
     assert!(text.contains("."), "punctuation cohort lost:\n{text:?}");
 }
 
+#[test]
+fn plaintext_tokenizer_rules_and_sentence_windows() {
+    let root = repo_root();
+    let conv = env!("CARGO_BIN_EXE_cg-conv");
+    let input = "«¡Hola!» dijo Dr. Smith's. Ver http://a.b/c, ¿vale?\n";
+    let words = |jsonl: &[u8]| -> Vec<String> {
+        jsonl_offsets(jsonl)
+            .into_iter()
+            .map(|(w, _, _)| w)
+            .collect()
+    };
+
+    // The default peels Unicode punctuation, in the C++ order.
+    let plain = run_with_stdin(conv, &["-x", "-J"], &root, input.as_bytes());
+    assert_eq!(
+        words(&plain),
+        [
+            "«",
+            "¡",
+            "Hola",
+            "»",
+            "!",
+            "dijo",
+            "Dr",
+            ".",
+            "Smith's",
+            ".",
+            "Ver",
+            "http://a.b/c",
+            ",",
+            "¿",
+            "vale",
+            "?"
+        ]
+    );
+
+    // Rules keep abbreviations and URLs whole and split clitics off.
+    let rules = tmp("tokenizer.rules");
+    std::fs::write(
+        &rules,
+        "# test rules\nabbrev Dr.\ntoken https?://[^\\s,)]+\nsplit (\\w+)('s)\n",
+    )
+    .unwrap();
+    let r = rules.to_str().unwrap();
+    let ruled = run_with_stdin(conv, &["--tokenizer", r, "-J"], &root, input.as_bytes());
+    assert_eq!(
+        words(&ruled),
+        [
+            "«",
+            "¡",
+            "Hola",
+            "!",
+            "»",
+            "dijo",
+            "Dr.",
+            "Smith",
+            "'s",
+            ".",
+            "Ver",
+            "http://a.b/c",
+            ",",
+            "¿",
+            "vale",
+            "?"
+        ]
+    );
+
+    // Sentence windows: one per sentence-final token.
+    let grammar = tmp("sentences.cg3");
+    std::fs::write(&grammar, "DELIMITERS = \"<$.>\" ;\n").unwrap();
+    let windows = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &[
+            "-g",
+            grammar.to_str().unwrap(),
+            "--in-plain",
+            "--tokenizer",
+            r,
+            "--split-sentences",
+            "--out-errors",
+        ],
+        &root,
+        input.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&rules);
+    let _ = std::fs::remove_file(&grammar);
+    let texts: Vec<String> = String::from_utf8(windows)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["text"].to_string())
+        .collect();
+    assert_eq!(
+        texts,
+        [
+            "\"«¡Hola!\"",
+            "\"»dijoDr.Smith's.\"",
+            "\"Verhttp://a.b/c,¿vale?\""
+        ]
+    );
+}
+
+// ADDED: reading weights — `<W:w>` tags through the FST, Apertium and JSONL
+// formats, and `--n-best` / `--max-weight` pruning after the grammar.
+#[test]
+fn reading_weights_survive_formats_and_prune() {
+    let root = repo_root();
+    let text = |out: Vec<u8>| String::from_utf8(out).unwrap();
+
+    // FST weights become weight tags; cg-conv prints cohorts straight through.
+    let fst = "a\ta+n\t1.5\na\ta+v\t0.5\n\nb\tb+n\n\n";
+    let best = run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["-f", "--n-best", "1"],
+        &root,
+        fst.as_bytes(),
+    );
+    assert_eq!(
+        text(best),
+        "\"<a>\"\n\t\"a\" v <W:0.500000>\n;\t\"a\" n <W:1.500000>\n\"<b>\"\n\t\"b\" n\n"
+    );
+
+    // Apertium weights are numeric, and pruning follows the rules.
+    let grammar = tmp("weights.cg3");
+    std::fs::write(&grammar, "DELIMITERS = \"<.>\" ;\nREMOVE (adj) ;\n").unwrap();
+    let g = grammar.to_str().unwrap();
+    let apertium = "^a/a<adj><W:0.1>/a<n><W:1.5>/a<v><W:0.5>$ ^b/b<n>/b<v><W:4>$\n";
+    let pruned = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", g, "--in-apertium", "--out-apertium", "--n-best", "1"],
+        &root,
+        apertium.as_bytes(),
+    );
+    assert_eq!(text(pruned), "^a/a<v><W:0.5>$ ^b/b<n>$\n");
+
+    // Above the threshold everywhere: the lightest reading stays.
+    let cg =
+        "\"<a>\"\n\t\"a\" n <W:3>\n\t\"a\" v <W:2>\n\"<b>\"\n\t\"b\" n <W:1>\n\t\"b\" v <W:2.5>\n";
+    let pruned = run_with_stdin(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &["-g", g, "--max-weight", "1.5"],
+        &root,
+        cg.as_bytes(),
+    );
+    let _ = std::fs::remove_file(&grammar);
+    assert_eq!(
+        text(pruned),
+        "\"<a>\"\n\t\"a\" v <W:2>\n\"<b>\"\n\t\"b\" n <W:1>\n"
+    );
+
+    // JSONL gives each reading its "wt", and reads one back as a weight tag.
+    let jsonl = text(run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["-J"],
+        &root,
+        cg.as_bytes(),
+    ));
+    let first: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+    assert_eq!(first["rs"][0]["wt"], 3.0);
+    assert_eq!(first["rs"][1]["wt"], 2.0);
+    let back = run_with_stdin(
+        env!("CARGO_BIN_EXE_cg-conv"),
+        &["-j", "--n-best", "1"],
+        &root,
+        br#"{"w":"a","rs":[{"l":"a","ts":["n"],"wt":2.5},{"l":"a","ts":["v"],"wt":0.25}]}"#,
+    );
+    assert_eq!(
+        text(back),
+        "\"<a>\"\n\t\"a\" v <W:0.25>\n;\t\"a\" n <W:2.5>\n"
+    );
+}
+
 // ===========================================================================
 // MweSplitApplicator — the exact test/T_MweSplit/run.pl protocol:
 // `cg-mwesplit < input.txt`, diffed (-B) against expected.txt. cg-mwesplit
@@ -16,7 +16,9 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use cg3::binary_grammar::BinaryGrammar;
+use cg3::case_folding::CaseLocale;
 use cg3::grammar::Grammar;
+use cg3::normalization::Normalization;
 use cg3::textual_parser::TextualParser;
 
 fn repo_root() -> PathBuf {
@@ -313,3 +315,56 @@ fn provenance_never_reaches_the_wire() {
         "the .cg3b must not depend on rule provenance"
     );
 }
+
+// ADDED: `OPTIONS += normalize-nfd ;` survives the binary form and the textual
+// writer, and the grammar's tags are stored decomposed. The `.cg3b` carries a
+// revision the C++ refuses, since it would run the grammar without normalising.
+#[test]
+fn normalization_survives_both_forms() {
+    let src = "OPTIONS += normalize-nfd ;\nDELIMITERS = \"<.>\" ;\nLIST A = \"<caf\u{e9}>\" ;\nSELECT A ;\n";
+    let mut parser = TextualParser::new(Grammar::default(), false);
+    parser.parse_grammar_utf8(src.as_bytes()).unwrap();
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).unwrap();
+    assert_eq!(grammar.normalization, Normalization::Nfd);
+    assert!(
+        (0..grammar.single_tags_list.capacity())
+            .filter_map(|i| grammar.single_tags_list.try_get(i))
+            .any(|t| t.tag == "\"<cafe\u{301}>\"")
+    );
+
+    let mut text: Vec<u8> = Vec::new();
+    cg3::grammar_writer::GrammarWriter::new(&grammar).write_grammar(&mut grammar, &mut text);
+    assert!(String::from_utf8_lossy(&text).contains("OPTIONS += normalize-nfd ;"));
+
+    let mut writer = BinaryGrammar::new(grammar);
+    let mut blob: Vec<u8> = Vec::new();
+    writer.write_binary_grammar(&mut blob).unwrap();
+    let rev = u32::from_be_bytes(blob[4..8].try_into().unwrap());
+    assert_eq!(rev, 13898 | 1 << 31, "a revision past any the C++ reads");
+    let mut reader = BinaryGrammar::new(Grammar::default());
+    reader.parse_grammar_buffer(&blob).unwrap();
+    assert_eq!(reader.grammar.normalization, Normalization::Nfd);
+}
+
+// ADDED: `OPTIONS += casefold-tr ;` survives the binary form, which carries a
+// revision the C++ refuses, since it would fold with the root locale.
+#[test]
+fn case_locale_survives_the_binary_form() {
+    let src =
+        "OPTIONS += casefold-tr ;\nDELIMITERS = \"<.>\" ;\nLIST A = \"<kız>\"i ;\nSELECT A ;\n";
+    let mut parser = TextualParser::new(Grammar::default(), false);
+    parser.parse_grammar_utf8(src.as_bytes()).unwrap();
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).unwrap();
+    assert_eq!(grammar.case_locale, CaseLocale::Turkic);
+
+    let mut writer = BinaryGrammar::new(grammar);
+    let mut blob: Vec<u8> = Vec::new();
+    writer.write_binary_grammar(&mut blob).unwrap();
+    let rev = u32::from_be_bytes(blob[4..8].try_into().unwrap());
+    assert_eq!(rev, 13898 | 1 << 31, "a revision past any the C++ reads");
+    let mut reader = BinaryGrammar::new(Grammar::default());
+    reader.parse_grammar_buffer(&blob).unwrap();
+    assert_eq!(reader.grammar.case_locale, CaseLocale::Turkic);
+}
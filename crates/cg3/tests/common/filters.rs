@@ -80,10 +80,12 @@ pub fn stabilize_relations(s: &str) -> String {
         .into_owned()
 }
 
-/// Port of `scripts/cg-sort -m <prefix>`: within each cohort, unique the
-/// readings, sort each reading's mapping tags (those beginning with `prefix`),
-/// then sort the readings. (Only the `--mapping` mode `runall.pl` uses is
-/// implemented — no weight/reverse/first.)
+/// Port of `scripts/cg-sort -m <prefix>`: within each cohort, sort each
+/// reading's mapping tags (those beginning with `prefix`), then sort and
+/// unique the readings. A reading's sub-readings (the more deeply indented
+/// lines under it) move with it, and the text between `<STREAMCMD:IGNORE>`
+/// and `<STREAMCMD:RESUME>` is passed through as it is. (Only the `--mapping`
+/// mode `runall.pl` uses is implemented — no weight/reverse/first.)
 pub fn cg_sort(s: &str, mapping_prefix: &str) -> String {
     let esc = regex::escape(mapping_prefix);
     let map_tag = Regex::new(&format!(r" ({esc}\S+)")).unwrap();
@@ -91,10 +93,11 @@ pub fn cg_sort(s: &str, mapping_prefix: &str) -> String {
 
     let mut out = String::new();
     let mut in_cohort = false;
+    let mut ignoring = false;
+    // Each reading with its sub-reading lines, and the reading line's indent.
     let mut readings: Vec<String> = Vec::new();
-    let mut seen_readings: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut indent = 0;
     let mut deleted: Vec<String> = Vec::new();
-    let mut seen_deleted: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut trail = String::new();
 
     let flush = |out: &mut String,
@@ -115,8 +118,13 @@ pub fn cg_sort(s: &str, mapping_prefix: &str) -> String {
     };
 
     for line in s.split_inclusive('\n') {
+        if ignoring {
+            ignoring = !line.starts_with("<STREAMCMD:RESUME>");
+            out.push_str(line);
+            continue;
+        }
         // Cohort header: `"<...>"`.
-        if line.starts_with("\"<") {
+        if line.starts_with("\"<") || line.starts_with("<STREAMCMD:IGNORE>") {
             flush(
                 &mut out,
                 &mut readings,
@@ -124,21 +132,30 @@ pub fn cg_sort(s: &str, mapping_prefix: &str) -> String {
                 &mut trail,
                 &mut in_cohort,
             );
-            in_cohort = true;
-            seen_readings.clear();
-            seen_deleted.clear();
+            out.push_str(line);
+            in_cohort = line.starts_with('"');
+            ignoring = !in_cohort;
             continue;
         }
         if in_cohort {
             let t = line.trim_start_matches([' ', '\t']);
-            if t.len() != line.len() && t.starts_with('"') {
-                if seen_readings.insert(line.to_string()) {
-                    readings.push(line.to_string());
-                }
-            } else if line.starts_with(';') && t.starts_with('"') {
-                if seen_deleted.insert(line.to_string()) {
-                    deleted.push(line.to_string());
+            let depth = line.len() - t.len();
+            // A reading line opens its baseform's quotes and closes them.
+            if depth != 0 && t.starts_with('"') && t[1..].contains('"') {
+                match readings.last_mut() {
+                    Some(reading) if depth > indent && trail.is_empty() => {
+                        reading.push_str(line);
+                    }
+                    _ => {
+                        readings.push(line.to_string());
+                        indent = depth;
+                    }
                 }
+            } else if line
+                .strip_prefix(';')
+                .is_some_and(|d| d.trim_start_matches([' ', '\t']).starts_with('"'))
+            {
+                deleted.push(line.to_string());
             } else {
                 trail.push_str(line);
             }
@@ -156,17 +173,25 @@ pub fn cg_sort(s: &str, mapping_prefix: &str) -> String {
     out
 }
 
-fn push_sorted(out: &mut String, lines: &mut [String], map_tag: &Regex, map_run: &Regex) {
-    for l in lines.iter_mut() {
-        let mut tags: Vec<String> = map_tag.captures_iter(l).map(|c| c[1].to_string()).collect();
-        if !tags.is_empty() {
-            tags.sort();
-            let joined = format!(" {}", tags.join(" "));
-            *l = map_run.replace(l, joined.as_str()).into_owned();
+fn push_sorted(out: &mut String, readings: &mut Vec<String>, map_tag: &Regex, map_run: &Regex) {
+    for reading in readings.iter_mut() {
+        let mut sorted = String::new();
+        for l in reading.split_inclusive('\n') {
+            let mut tags: Vec<String> =
+                map_tag.captures_iter(l).map(|c| c[1].to_string()).collect();
+            if tags.is_empty() {
+                sorted.push_str(l);
+            } else {
+                tags.sort();
+                let joined = format!(" {}", tags.join(" "));
+                sorted.push_str(&map_run.replace(l, joined.as_str()));
+            }
         }
+        *reading = sorted;
     }
-    lines.sort();
-    for l in lines.iter() {
-        out.push_str(l);
+    readings.sort();
+    readings.dedup();
+    for reading in readings.iter() {
+        out.push_str(reading);
     }
 }
@@ -11,8 +11,8 @@
 /// Two bad regex tags plus the rule that references the first set, so the
 /// parser recovers more than once.
 const BAD_GRAMMAR: &str = "DELIMITERS = \"<.>\" ;\n\
-                           LIST a = \"[:script=Greek:]\"r ;\n\
-                           LIST b = \"[:script=Latin:]\"r ;\n\
+                           LIST a = \"[:script=Nopes:]\"r ;\n\
+                           LIST b = \"[:script=Nopex:]\"r ;\n\
                            SELECT a ;\n";
 
 /// Errors are layered by boundary: a grammar that will not load surfaces as a
@@ -50,11 +50,11 @@ fn tag_regex_failures_name_the_tag() {
 
     let mut applicator = cg3::grammar_applicator::GrammarApplicator::new(grammar);
     let err = applicator
-        .set_text_delimiter("[:script=Greek:]".to_string())
-        .expect_err("an ICU in-set property must be rejected");
+        .set_text_delimiter("[:script=Nopes:]".to_string())
+        .expect_err("an unknown ICU in-set property must be rejected");
 
     let rendered = err.to_string();
-    assert!(rendered.contains("in-set property"), "{rendered}");
+    assert!(rendered.contains("unknown Unicode property"), "{rendered}");
     assert!(
         !err.tag_regex_errors().is_empty(),
         "the diagnostic must be reachable structurally, not only as text"
@@ -71,8 +71,8 @@ fn tag_regex_failures_name_the_tag() {
 fn every_recoverable_error_is_reported() {
     // Three independent failures on three separate lines.
     let src = "DELIMITERS = \"<.>\" ;\n\
-               LIST a = \"[:script=Greek:]\"r ;\n\
-               LIST b = \"[:script=Latin:]\"r ;\n\
+               LIST a = \"[:script=Nopes:]\"r ;\n\
+               LIST b = \"[:script=Nopex:]\"r ;\n\
                SELECT a ;\n";
     let mut parser =
         cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
@@ -102,7 +102,7 @@ fn every_recoverable_error_is_reported() {
 // [spec:cg3:req:diagnostics.source-retained/test]
 #[test]
 fn spans_select_the_offending_text() {
-    let src = "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Greek:]\"r ;\nSELECT a ;\n";
+    let src = "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Nopes:]\"r ;\nSELECT a ;\n";
     let mut parser =
         cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
     let err = parser
@@ -117,7 +117,7 @@ fn spans_select_the_offending_text() {
     let text: Vec<char> = sources[span.source].text.chars().collect();
     let quoted: String = text[span.range.clone()].iter().collect();
     assert!(
-        quoted.starts_with("\"[:script=Greek:]\""),
+        quoted.starts_with("\"[:script=Nopes:]\""),
         "the span must cover the tag that failed, got {quoted:?}"
     );
     assert!(
@@ -134,7 +134,7 @@ fn included_files_are_separate_sources() {
     let dir = std::env::temp_dir().join(format!("cg3-diag-{}", std::process::id()));
     std::fs::create_dir_all(&dir).expect("temp dir");
     let included = dir.join("lists.cg3");
-    std::fs::write(&included, "LIST a = \"[:script=Greek:]\"r ;\n").expect("write include");
+    std::fs::write(&included, "LIST a = \"[:script=Nopes:]\"r ;\n").expect("write include");
     let top = format!(
         "DELIMITERS = \"<.>\" ;\nINCLUDE {} ;\nSELECT nosuch ;\n",
         included.display()
@@ -223,7 +223,7 @@ fn rules_know_where_they_were_written() {
 // [spec:cg3:req:diagnostics.source-named/test]
 #[test]
 fn a_named_parse_reports_its_file_name() {
-    let src = "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Greek:]\"r ;\n";
+    let src = "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Nopes:]\"r ;\n";
     let mut parser =
         cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
     let err = parser
@@ -237,3 +237,211 @@ fn a_named_parse_reports_its_file_name() {
     assert_eq!(sources[0].name, "grammars/nb.cg3");
     assert_eq!(errors[0].file, "nb.cg3");
 }
+
+/// A run hands its warnings back as values: the malformed input line and the
+/// cohort it concerns, and for a warning raised under a rule, that rule — which
+/// the renderer quotes from the grammar's sources.
+#[test]
+fn runs_return_their_diagnostics() {
+    let dir = std::env::temp_dir().join(format!("cg3-runtime-diag-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("temp dir");
+    let path = dir.join("loop.cg3");
+    std::fs::write(
+        &path,
+        "DELIMITERS = \"<.>\" ;\nSECTION\nSETPARENT (x) TO (1 (y)) ;\nSETPARENT (y) TO (-1 (x)) ;\n",
+    )
+    .expect("write grammar");
+
+    let mut parser =
+        cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
+    let bytes = std::fs::read(&path).expect("read grammar");
+    parser
+        .parse_grammar_named(&bytes, &path.to_string_lossy())
+        .expect("parses");
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).unwrap();
+    let mut applicator = cg3::grammar_applicator::GrammarApplicator::new(grammar);
+    applicator.set_grammar().unwrap();
+
+    let input = "\"<w>\"\n\t\"w\" x\n\"<v>\"\n\t\"v\" y\n\"<.>\"\n\t\".\" z\n";
+    let mut out = Vec::new();
+    let diags = applicator
+        .run_grammar_on_text_diagnosed(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect("the run succeeds")
+        .diagnostics;
+
+    use cg3::runtime_diagnostics::RuntimeDiagnosticKind as K;
+    let looped = diags
+        .iter()
+        .find(|d| matches!(d.kind, K::DependencyLoop { .. }))
+        .unwrap_or_else(|| panic!("no loop warning in {diags:?}"));
+    assert_eq!(
+        looped.kind,
+        K::DependencyLoop {
+            parent: 1,
+            child: 2
+        }
+    );
+    assert_eq!(looped.rule_line, Some(4), "{looped:?}");
+    assert_eq!((looped.window, looped.cohort), (Some(1), Some(2)));
+
+    let sources = cg3::grammar_sources::resolve(&applicator.grammar);
+    let mut rendered = Vec::new();
+    cg3::diagnostics::render_runtime_diagnostics(
+        std::slice::from_ref(looped),
+        sources.as_ref(),
+        "<stdin>",
+        &mut rendered,
+        false,
+    )
+    .expect("a Vec cannot fail");
+    let rendered = String::from_utf8(rendered).unwrap();
+    assert!(rendered.contains("SETPARENT (y) TO (-1 (x))"), "{rendered}");
+    assert!(rendered.contains("loop.cg3"), "{rendered}");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+/// A window forced shut by the hard limit names the cohort it was cut at.
+#[test]
+fn hard_limit_names_the_cohort() {
+    let mut parser =
+        cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
+    parser
+        .parse_grammar_utf8(b"DELIMITERS = \"<.>\" ;\n")
+        .expect("parses");
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).unwrap();
+    let mut applicator = cg3::grammar_applicator::GrammarApplicator::new(grammar);
+    applicator.set_grammar().unwrap();
+    applicator.cfg.hard_limit = 2;
+
+    let input = "\"<a>\"\n\t\"a\" x\n\"<b>\"\n\t\"b\" x\n\"<c>\"\n\t\"c\" x\n";
+    let mut out = Vec::new();
+    let diags = applicator
+        .run_grammar_on_text_diagnosed(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect("the run succeeds")
+        .diagnostics;
+    use cg3::runtime_diagnostics::RuntimeDiagnosticKind as K;
+    let forced = diags
+        .iter()
+        .find(|d| matches!(d.kind, K::HardLimit { .. }))
+        .unwrap_or_else(|| panic!("no hard-limit warning in {diags:?}"));
+    let message = forced.to_string();
+    assert!(
+        message.starts_with("Warning: Hard limit of 2 cohorts reached at cohort \"<"),
+        "{message}"
+    );
+    assert!(message.contains(") on line "), "{message}");
+    assert!(message.ends_with(" - forcing break."), "{message}");
+}
+
+/// A line a reader cannot use comes back quoted, with its line and column; with
+/// `strict_input` the run stops on it instead.
+#[test]
+fn malformed_input_is_quoted() {
+    let applicator = || {
+        let mut parser =
+            cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
+        parser
+            .parse_grammar_utf8(b"DELIMITERS = \"<.>\" ;\n")
+            .expect("parses");
+        let mut grammar = parser.grammar;
+        let _ = grammar.reindex(false, false).unwrap();
+        let mut applicator = cg3::grammar_applicator::GrammarApplicator::new(grammar);
+        applicator.set_grammar().unwrap();
+        applicator
+    };
+
+    let input = "\"<w>\"\n\t\"w\" x\n\"<v y\n\"<.>\"\n\t\".\" z\n";
+    let mut out = Vec::new();
+    let diags = applicator()
+        .run_grammar_on_text_diagnosed(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect("the line is skipped")
+        .diagnostics;
+    use cg3::runtime_diagnostics::RuntimeDiagnosticKind as K;
+    let bad = diags
+        .iter()
+        .find(|d| matches!(d.kind, K::NotACohort { .. }))
+        .unwrap_or_else(|| panic!("no warning in {diags:?}"));
+    let excerpt = bad.excerpt.as_ref().expect("quoted");
+    assert_eq!((excerpt.line, excerpt.column), (3, 1));
+    assert_eq!(excerpt.text, "\"<v y");
+    assert_eq!(bad.line, 3);
+
+    let mut rendered = Vec::new();
+    cg3::diagnostics::render_runtime_diagnostics(
+        std::slice::from_ref(bad),
+        None,
+        "in.cg",
+        &mut rendered,
+        false,
+    )
+    .expect("a Vec cannot fail");
+    let rendered = String::from_utf8(rendered).unwrap();
+    assert!(rendered.contains("--> in.cg:3:1"), "{rendered}");
+    assert!(rendered.contains("3 | \"<v y\n  | ^"), "{rendered}");
+
+    let mut strict = applicator();
+    strict.cfg.strict_input = true;
+    strict.cfg.input_name = "in.cg".to_string();
+    let err = strict
+        .run_grammar_on_text(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect_err("strict input stops");
+    assert!(
+        matches!(
+            &err,
+            cg3::error::Cg3Error::Run(cg3::error::RunError::MalformedInput { excerpt, .. })
+                if excerpt.line == 3 && excerpt.column == 1
+        ),
+        "{err:?}"
+    );
+    assert!(
+        err.to_string().starts_with("in.cg:3:1: malformed input"),
+        "{err}"
+    );
+}
+
+/// A report says how many diagnostics it only counted, and a failed run leaves
+/// what it raised before failing for `take_runtime_diagnostics`.
+#[test]
+fn reports_count_the_dropped_and_survive_failure() {
+    use cg3::runtime_diagnostics::{MAX_KEPT, RuntimeDiagnosticKind as K};
+    // No DELIMITERS: the run opens with a warning about the limits.
+    let applicator = || {
+        let mut parser =
+            cg3::textual_parser::TextualParser::new(cg3::grammar::Grammar::default(), false);
+        parser
+            .parse_grammar_utf8(b"LIST x = x ;\n")
+            .expect("parses");
+        let mut grammar = parser.grammar;
+        let _ = grammar.reindex(false, false).unwrap();
+        let mut applicator = cg3::grammar_applicator::GrammarApplicator::new(grammar);
+        applicator.set_grammar().unwrap();
+        applicator
+    };
+
+    let input = "\"<v y\n".repeat(MAX_KEPT + 5);
+    let mut out = Vec::new();
+    let mut lenient = applicator();
+    let report = lenient
+        .run_grammar_on_text_diagnosed(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect("the lines are skipped");
+    assert_eq!(report.diagnostics.len(), MAX_KEPT);
+    assert_eq!(report.dropped, 6);
+    assert_eq!(lenient.take_runtime_diagnostics().dropped, 0);
+
+    let mut strict = applicator();
+    strict.cfg.strict_input = true;
+    strict
+        .run_grammar_on_text_diagnosed(&mut std::io::Cursor::new(input.as_bytes()), &mut out)
+        .expect_err("strict input stops");
+    let report = strict.take_runtime_diagnostics();
+    assert!(
+        matches!(
+            report.diagnostics.as_slice(),
+            [d] if matches!(d.kind, K::NoDelimiters { .. })
+        ),
+        "{report:?}"
+    );
+}
@@ -15,6 +15,9 @@
 //!    format (`--in-cg|--out-binary` → `--in-binary|--out-binary` →
 //!    `--in-binary|--out-cg`), compare untraced/sorted/stabilised output.
 //!
+//! [`filter_tools_match_harness_filters`] checks the shipped `cg-untrace`,
+//! `cg-sort` and `cg-stabilize-relations` against the harness's own filters.
+//!
 //! Directories needing a custom protocol (external process, relabel,
 //! sub-readings) are in [`CUSTOM`] and covered by dedicated tests
 //! (`tools_cli.rs`, `profiler_relabeller.rs`, `engine.rs`); the Apertium
@@ -261,6 +264,69 @@ fn golden_binary_stream() {
     });
 }
 
+// ---------------------------------------------------------------------------
+// The shipped filter tools against the harness's own text filters.
+// ---------------------------------------------------------------------------
+
+/// `cg-untrace`, `cg-sort` and `cg-stabilize-relations` agree with the
+/// harness filters above, which stay independent of the code under test, on
+/// every `expected.txt`. The tools print through the window model, so the
+/// harness filters see the fixture as a plain `cg-conv` prints it (mapping
+/// tags last, `<<<` read as a window end). `cg-sort` is given the untraced
+/// text, so its output is compared as it is; `--deleted` moves a removed
+/// cohort's lines about, so relations are compared once `untrace` drops them.
+#[test]
+fn filter_tools_match_harness_filters() {
+    run_all("filter_tools_match_harness_filters", |dir| {
+        let fixture = expected(dir);
+        let prefix = mapping_prefix(dir);
+        let pipe_in = |exe: &str, args: &[&str], input: &str| -> Result<String, String> {
+            let (out, ok) = run_capture(exe, args, dir, input.as_bytes());
+            if !ok {
+                return Err(format!("{exe} exited non-zero"));
+            }
+            Ok(String::from_utf8_lossy(&out).into_owned())
+        };
+        let pipe = |exe: &str, args: &[&str]| pipe_in(exe, args, &fixture);
+        let plain = pipe(
+            env!("CARGO_BIN_EXE_cg-conv"),
+            &["--in-cg", "--out-cg", "--prefix", &prefix],
+        )?;
+        let plain_deleted = pipe(
+            env!("CARGO_BIN_EXE_cg-conv"),
+            &["--in-cg", "--out-cg", "--deleted", "--prefix", &prefix],
+        )?;
+        let untraced = pipe(
+            env!("CARGO_BIN_EXE_cg-untrace"),
+            &["--in-cg", "--prefix", &prefix],
+        )?;
+        let sorted = pipe_in(
+            env!("CARGO_BIN_EXE_cg-sort"),
+            &["--in-cg", "--prefix", &prefix],
+            &untrace(&plain),
+        )?;
+        let stabilized = pipe(
+            env!("CARGO_BIN_EXE_cg-stabilize-relations"),
+            &["--in-cg", "--deleted", "--prefix", &prefix],
+        )?;
+        let checks = [
+            ("cg-untrace", untrace(&plain), untraced),
+            ("cg-sort", cg_sort(&untrace(&plain), &prefix), sorted),
+            (
+                "cg-stabilize-relations",
+                untrace(&stabilize_relations(&plain_deleted)),
+                untrace(&stabilized),
+            ),
+        ];
+        for (name, want, got) in checks {
+            if !diff_b_equal(&want, &got) {
+                return Err(format!("{name} differs from the harness filter"));
+            }
+        }
+        Ok(())
+    });
+}
+
 // ---------------------------------------------------------------------------
 
 /// Run `f` over every golden dir, aggregating failures into one assertion.
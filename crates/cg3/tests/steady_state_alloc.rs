@@ -0,0 +1,125 @@
+//! Steady-state heap traffic of the engine: once the store's arenas, spare
+//! lists and scratch stacks have warmed up, a window of the common rule types
+//! (SELECT/REMOVE/MAP/ADD, sections, barriers, careful and negated contexts)
+//! must be processed without touching the heap.
+//!
+//! Each fixture's input is run in-process through `FormatConverter` twice on
+//! fresh applicators — a few copies, then many more — and the allocation counts
+//! of the two runs must be equal: every extra window was free. Counting uses
+//! `stats_alloc`'s instrumented system allocator, so this file holds a single
+//! test (the counters are process-wide).
+
+use std::alloc::System;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use cg3::format_converter::FormatConverter;
+use cg3::grammar::Grammar;
+use cg3::grammar_applicator::{GrammarApplicator, StreamFormatKind};
+use cg3::options::{Opt, options};
+use cg3::textual_parser::TextualParser;
+use stats_alloc::{INSTRUMENTED_SYSTEM, Region, StatsAlloc};
+
+#[global_allocator]
+static GLOBAL: &StatsAlloc<System> = &INSTRUMENTED_SYSTEM;
+
+fn repo_root() -> PathBuf {
+    // crates/cg3 -> repo root
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .canonicalize()
+        .unwrap()
+}
+
+/// Run `test/<name>` over `reps` concatenated copies of its input (CG in, CG
+/// out to a sink) and return the allocations made while processing, along
+/// with the number of windows seen.
+fn allocations_for(name: &str, reps: usize) -> (u32, usize) {
+    let dir = repo_root().join("test").join(name);
+    let grammar_path = dir.join("grammar.cg3");
+    let args: Vec<String> = std::fs::read_to_string(dir.join("args.txt"))
+        .map(|s| s.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    let mut argv: Vec<Vec<char>> = std::iter::once("vislcg3")
+        .chain(args.iter().map(|s| s.as_str()))
+        .map(|s| s.chars().collect())
+        .collect();
+    let mut opts = options();
+    cg3::icu_uoptions::u_parse_args(
+        argv.len() as i32,
+        &mut argv,
+        Opt::NumOptions as i32,
+        &mut opts,
+    );
+
+    let mut parser = TextualParser::new(Grammar::default(), false);
+    parser
+        .parse_grammar_named(
+            &std::fs::read(&grammar_path).unwrap(),
+            &grammar_path.to_string_lossy(),
+        )
+        .unwrap();
+    let mut grammar = parser.grammar;
+    let _ = grammar.reindex(false, false).unwrap();
+
+    let mut conv = FormatConverter::new(GrammarApplicator::new(Grammar::default())).unwrap();
+    let base = conv.base_mut();
+    base.cfg.fmt_input = StreamFormatKind::Cg;
+    base.cfg.fmt_output = StreamFormatKind::Cg;
+    base.grammar = grammar;
+    base.set_grammar().unwrap();
+    base.set_options(&opts).unwrap();
+
+    let one = std::fs::read(dir.join("input.txt")).unwrap();
+    let mut input = Vec::new();
+    for _ in 0..reps {
+        input.extend_from_slice(&one);
+        if !one.ends_with(b"\n") {
+            input.push(b'\n');
+        }
+        // Keeps fixtures without DELIMITERS from fusing into one window.
+        input.extend_from_slice(b"<STREAMCMD:FLUSH>\n");
+    }
+    let mut cursor = Cursor::new(input);
+    let mut sink = std::io::sink();
+
+    let region = Region::new(GLOBAL);
+    conv.run_grammar_on_text(&mut cursor, &mut sink).unwrap();
+    let stats = region.change();
+    (
+        conv.base().doc.num_windows,
+        stats.allocations + stats.reallocations,
+    )
+}
+
+#[test]
+fn common_rule_windows_do_not_allocate_after_warm_up() {
+    let fixtures = [
+        "T_Barrier",
+        "T_CarefulBarrier",
+        "T_Iff",
+        "T_MapThenRemove",
+        "T_MapThenSelect",
+        "T_MultipleSections",
+        "T_NRules",
+        "T_NegatedContextTest",
+        "T_NotContextTest",
+        "T_OmniWithBarrier",
+        "T_SectionRanges",
+        "T_Sections",
+    ];
+    let mut failures = Vec::new();
+    for name in fixtures {
+        let (w_short, a_short) = allocations_for(name, 3);
+        let (w_long, a_long) = allocations_for(name, 13);
+        assert!(w_long > w_short, "{name}: no extra windows");
+        if a_long != a_short {
+            failures.push(format!(
+                "{name}: {} allocations over {} extra windows",
+                a_long as isize - a_short as isize,
+                w_long - w_short
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
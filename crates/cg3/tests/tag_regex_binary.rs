@@ -100,6 +100,16 @@ fn icu_literal_quoting_loads() {
     load(&blob).expect("a \\Q...\\E grammar must load");
 }
 
+/// ICU's in-set properties, named characters and octal escapes are translated,
+/// so a `.cg3b` that uses them loads and keeps its ICU spelling.
+#[test]
+fn translated_icu_constructs_load() {
+    for pattern in [r"[:script=Greek:]", r"\N{SNOWMAN}", r"\0101"] {
+        let blob = cg3b_with_pattern("abc", pattern);
+        load(&blob).unwrap_or_else(|e| panic!("{pattern} must load: {e}"));
+    }
+}
+
 /// Backtracking constructs are the reason for the engine choice: a `.cg3b`
 /// written by C++ `vislcg3` may use any of them, and all must load.
 #[test]
@@ -117,13 +127,12 @@ fn backtracking_constructs_load() {
     }
 }
 
-/// A construct this engine would MISREAD must name both the tag and the
-/// construct, rather than surfacing as a bare exit code — or, worse, than
-/// compiling to something that silently matches the wrong thing.
+/// A construct ICU rejects too must name both the tag and the construct,
+/// rather than surfacing as a bare exit code.
 #[test]
-fn unsupported_construct_names_tag_and_construct() {
-    let blob = cg3b_with_pattern("abc", r"[:script=Greek:]");
-    let err = load(&blob).expect_err("in-set property must be rejected");
+fn invalid_construct_names_tag_and_construct() {
+    let blob = cg3b_with_pattern("abc", r"\N{NO SUCH CHARACTER}");
+    let err = load(&blob).expect_err("an unknown character name must be rejected");
 
     assert!(
         matches!(err, Cg3Error::Grammar(_)),
@@ -133,12 +142,12 @@ fn unsupported_construct_names_tag_and_construct() {
     assert_eq!(reported.len(), 1, "one bad tag");
     assert_eq!(reported[0].tag.as_deref(), Some("\"abc\""), "tag text");
     assert_eq!(
-        reported[0].pattern, r"[:script=Greek:]",
+        reported[0].pattern, r"\N{NO SUCH CHARACTER}",
         "offending pattern"
     );
 
     let rendered = err.to_string();
-    assert!(rendered.contains("in-set property"), "{rendered}");
+    assert!(rendered.contains("unknown character name"), "{rendered}");
     assert!(rendered.contains("abc"), "{rendered}");
 }
 
@@ -146,7 +155,7 @@ fn unsupported_construct_names_tag_and_construct() {
 /// them in one pass instead of recompiling per tag.
 #[test]
 fn all_bad_tags_are_reported_together() {
-    // Two regex tags, both patched to distinct unsupported constructs.
+    // Two regex tags, both patched to distinct invalid constructs.
     let src = "DELIMITERS = \"<.>\" ;\nLIST a = \"one\"r ;\nLIST b = \"two\"r ;\n";
     let mut parser = TextualParser::new(Grammar::default(), false);
     parser.parse_grammar_utf8(src.as_bytes()).unwrap();
@@ -156,7 +165,7 @@ fn all_bad_tags_are_reported_together() {
     let mut blob: Vec<u8> = Vec::new();
     writer.write_binary_grammar(&mut blob).unwrap();
 
-    for (marker, pattern) in [("one", r"[:script=Greek:]"), ("two", r"\0101")] {
+    for (marker, pattern) in [("one", r"\N{NO SUCH CHARACTER}"), ("two", r"\08")] {
         let stored = format!("^\"{marker}\"$");
         let needle: Vec<u8> = (stored.len() as u32)
             .to_be_bytes()
@@ -185,6 +194,6 @@ fn all_bad_tags_are_reported_together() {
     assert_eq!(reported.len(), 2, "both bad tags reported in one pass");
 
     let rendered = err.to_string();
-    assert!(rendered.contains("in-set property"), "{rendered}");
-    assert!(rendered.contains("octal escape"), "{rendered}");
+    assert!(rendered.contains("unknown character name"), "{rendered}");
+    assert!(rendered.contains("no octal digit"), "{rendered}");
 }
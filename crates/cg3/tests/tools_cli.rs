@@ -5,6 +5,7 @@
 //! All outputs go to `std::env::temp_dir()`; tests run dir-local (cwd = the
 //! fixture dir) where relative paths matter. Nothing under `test/` is written.
 
+use std::ffi::OsStr;
 use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -128,6 +129,18 @@ fn all_tools_versions_identify_divvun_builds() {
         "MWE Splitter",
         &["--version"],
     );
+    assert_divvun_version(
+        "cg-pipeline",
+        env!("CARGO_BIN_EXE_cg-pipeline"),
+        "Pipeline",
+        &["--version"],
+    );
+    assert_divvun_version(
+        "cg-serve",
+        env!("CARGO_BIN_EXE_cg-serve"),
+        "Server",
+        &["--version"],
+    );
     assert_divvun_version(
         "cg-proc",
         env!("CARGO_BIN_EXE_cg-proc"),
@@ -140,6 +153,24 @@ fn all_tools_versions_identify_divvun_builds() {
         "Relabeller",
         &["--version"],
     );
+    assert_divvun_version(
+        "cg-untrace",
+        env!("CARGO_BIN_EXE_cg-untrace"),
+        "Untrace",
+        &["--version"],
+    );
+    assert_divvun_version(
+        "cg-sort",
+        env!("CARGO_BIN_EXE_cg-sort"),
+        "Sort",
+        &["--version"],
+    );
+    assert_divvun_version(
+        "cg-stabilize-relations",
+        env!("CARGO_BIN_EXE_cg-stabilize-relations"),
+        "Relation Stabilizer",
+        &["--version"],
+    );
 
     #[cfg(feature = "profiler")]
     {
@@ -188,12 +219,430 @@ fn vislcg3_main_runs_t_select() {
     run_vislcg3_expect(&dir, Path::new("grammar.cg3"), "vislcg3-select.txt");
 }
 
+/// Several `-g` run in one process, each grammar on every window in turn.
+/// The result must be what piping one vislcg3 into the next prints, including
+/// where the second grammar matches tags only the first one knew (`seen`,
+/// `<n:7>` compared numerically, the `@x` mapping) and wordforms it never
+/// interned itself.
+#[test]
+fn vislcg3_runs_several_grammars_in_sequence() {
+    let dir = temp_path("pipeline");
+    std::fs::create_dir_all(&dir).expect("fixture dir");
+    std::fs::write(
+        dir.join("first.cg3"),
+        "DELIMITERS = \"<.>\" ;\nLIST N = n ; LIST V = v ;\n\
+         SELECT N IF (-1 (det)) ;\nADD (seen <n:7>) N ;\nMAP (@x) V ;\n",
+    )
+    .expect("write first grammar");
+    std::fs::write(
+        dir.join("second.cg3"),
+        "DELIMITERS = \"<.>\" ;\n\
+         ADD (big) (<n>5>) ;\nADD (after-x) (*) IF (-1 (@x)) ;\n\
+         SELECT (seen) IF (0 (\"<dog>\")) ;\nREMOVE (v) IF (1 (big)) ;\n",
+    )
+    .expect("write second grammar");
+    let input = "\"<the>\"\n\t\"the\" det\n\"<dog>\"\n\t\"dog\" n\n\t\"dog\" v\n\
+                 \"<runs>\"\n\t\"run\" v\n\"<.>\"\n\t\".\" clb\n\
+                 \"<cats>\"\n\t\"cat\" v\n\t\"cat\" n\n\"<.>\"\n\t\".\" clb\n";
+
+    let vislcg3 = |args: &[&str], stdin: &[u8]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+            .current_dir(&dir)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn vislcg3");
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+        child.wait_with_output().expect("wait for vislcg3")
+    };
+
+    let first = vislcg3(&["-g", "first.cg3"], input.as_bytes());
+    assert!(first.status.success());
+    let piped = vislcg3(&["-g", "second.cg3"], &first.stdout);
+    assert!(piped.status.success());
+    let chained = vislcg3(&["-g", "first.cg3", "-g", "second.cg3"], input.as_bytes());
+    assert!(
+        chained.status.success(),
+        "{}",
+        String::from_utf8_lossy(&chained.stderr)
+    );
+    let piped = String::from_utf8(piped.stdout).unwrap();
+    let chained = String::from_utf8(chained.stdout).unwrap();
+    assert!(
+        piped.contains("big") && piped.contains("after-x"),
+        "{piped}"
+    );
+    assert_eq!(chained, piped);
+
+    // A grammar whose tests span windows sees the earlier window in its own
+    // terms too, though only the current one is handed to it otherwise.
+    std::fs::write(
+        dir.join("spanning.cg3"),
+        "DELIMITERS = \"<.>\" ;\n\
+         ADD (after-dog) (*) IF (-1*W (\"<dog>\")) ;\nREMOVE (v) IF (-1W (<n>5>)) ;\n",
+    )
+    .expect("write spanning grammar");
+    let piped = vislcg3(&["-g", "spanning.cg3"], &first.stdout);
+    assert!(piped.status.success());
+    let chained = vislcg3(&["-g", "first.cg3", "-g", "spanning.cg3"], input.as_bytes());
+    assert!(chained.status.success());
+    let piped = String::from_utf8(piped.stdout).unwrap();
+    assert!(piped.contains("\"cat\" n seen <n:7> after-dog"), "{piped}");
+    assert_eq!(String::from_utf8(chained.stdout).unwrap(), piped);
+
+    // Trace output names rules by number, which only means something for one
+    // grammar.
+    let traced = vislcg3(
+        &["--trace", "-g", "first.cg3", "-g", "second.cg3"],
+        input.as_bytes(),
+    );
+    assert!(!traced.status.success());
+    assert!(String::from_utf8_lossy(&traced.stderr).contains("--trace"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// `vislcg3 --watch` runs on input as it arrives, picks up an edit to an
+/// included file for the windows after it, and keeps the running grammar when
+/// an edit does not parse.
+#[test]
+fn vislcg3_watch_swaps_in_edited_includes() {
+    use std::io::BufRead as _;
+    let dir = temp_path("watch");
+    std::fs::create_dir_all(&dir).expect("fixture dir");
+    std::fs::write(
+        dir.join("grammar.cg3"),
+        "DELIMITERS = \"<.>\" ;\nINCLUDE rules.cg3 ;\n",
+    )
+    .expect("write grammar");
+    std::fs::write(dir.join("rules.cg3"), "ADD (first) (*) ;\n").expect("write include");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .current_dir(&dir)
+        .args(["--watch", "-g", "grammar.cg3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn vislcg3 --watch");
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    // One window, flushed, and what came back for it.
+    let mut window = || {
+        stdin
+            .write_all(b"\"<a>\"\n\t\"a\" n\n\"<.>\"\n\t\".\" CLB\n<STREAMCMD:FLUSH>\n")
+            .unwrap();
+        stdin.flush().unwrap();
+        let mut got = String::new();
+        while !got.contains("<STREAMCMD:FLUSH>") {
+            assert_ne!(stdout.read_line(&mut got).unwrap(), 0, "{got}");
+        }
+        got
+    };
+    // Longer than the poll interval, and past the file system's mtime grain.
+    let settle = || std::thread::sleep(std::time::Duration::from_millis(1500));
+
+    assert!(window().contains("\"a\" n first"));
+    settle();
+    std::fs::write(dir.join("rules.cg3"), "ADD (second) (*) ;\n").expect("edit include");
+    settle();
+    assert!(window().contains("\"a\" n second"));
+    std::fs::write(dir.join("rules.cg3"), "ADD (third (*) ;\n").expect("break include");
+    settle();
+    assert!(window().contains("\"a\" n second"));
+
+    drop(stdin);
+    let out = child.wait_with_output().expect("wait for vislcg3");
+    assert!(out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("rules.cg3"), "{stderr}");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A `cg-pipeline` spec runs what the equivalent shell line of tools prints:
+/// a conversion from JSONL, MWE split, two grammars sharing one stream, and a
+/// traced grammar on its own.
+#[test]
+fn cg_pipeline_matches_the_tool_chain() {
+    let dir = temp_path("cg-pipeline");
+    std::fs::create_dir_all(&dir).expect("fixture dir");
+    let mwe = repo_root().join("test/T_MweSplit");
+    std::fs::write(
+        dir.join("first.cg3"),
+        "DELIMITERS = \"<.>\" ;\nADD (seen) (*) ;\n",
+    )
+    .expect("write first grammar");
+    std::fs::write(
+        dir.join("second.cg3"),
+        "DELIMITERS = \"<.>\" ;\nADD (after-seen) (*) IF (-1 (seen)) ;\n",
+    )
+    .expect("write second grammar");
+    std::fs::write(
+        dir.join("third.cg3"),
+        "DELIMITERS = \"<.>\" ;\nSECTION\nADD (one) (seen) ;\nSECTION\nADD (two) (seen) ;\n",
+    )
+    .expect("write third grammar");
+    std::fs::write(
+        dir.join("pipeline.json"),
+        r#"{
+            "input": "jsonl",
+            "stages": [
+                { "mwesplit": true },
+                { "grammar": "first.cg3" },
+                { "grammar": "second.cg3" },
+                { "grammar": "third.cg3", "sections": "1", "trace": true }
+            ]
+        }"#,
+    )
+    .expect("write spec");
+
+    let run = |bin: &str, args: &[&str], stdin: &[u8]| {
+        let mut child = Command::new(bin)
+            .current_dir(&dir)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn tool");
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+        let out = child.wait_with_output().expect("wait for tool");
+        assert!(
+            out.status.success(),
+            "{bin}: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        out.stdout
+    };
+    let cg_conv = env!("CARGO_BIN_EXE_cg-conv");
+    let vislcg3 = env!("CARGO_BIN_EXE_vislcg3");
+    let input = run(
+        cg_conv,
+        &["-c", "-J"],
+        &std::fs::read(mwe.join("input.txt")).unwrap(),
+    );
+    let mut piped = run(cg_conv, &["-j"], &input);
+    piped = run(env!("CARGO_BIN_EXE_cg-mwesplit"), &[], &piped);
+    piped = run(vislcg3, &["-g", "first.cg3"], &piped);
+    piped = run(vislcg3, &["-g", "second.cg3"], &piped);
+    piped = run(
+        vislcg3,
+        &["-g", "third.cg3", "--sections", "1", "-t"],
+        &piped,
+    );
+
+    let pipeline = run(
+        env!("CARGO_BIN_EXE_cg-pipeline"),
+        &["pipeline.json"],
+        &input,
+    );
+    let pipeline = String::from_utf8(pipeline).unwrap();
+    assert!(
+        pipeline.contains("after-seen") && pipeline.contains("ADD:") && !pipeline.contains("two"),
+        "{pipeline}"
+    );
+    assert_eq!(pipeline, String::from_utf8(piped).unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A relabel stage does in memory what `cg-comp` + `cg-relabel` do on disk.
+#[test]
+fn cg_pipeline_relabels_t_relabel_list() {
+    let dir = repo_root().join("test/T_RelabelList");
+    let spec = temp_path("relabel-pipeline.json");
+    std::fs::write(
+        &spec,
+        format!(
+            r#"{{ "stages": [ {{ "grammar": "{0}/grammar.cg3", "relabel": "{0}/relabel.cg3r" }} ] }}"#,
+            dir.display()
+        ),
+    )
+    .expect("write spec");
+    let out = temp_path("relabel-pipeline.txt");
+    let status = Command::new(env!("CARGO_BIN_EXE_cg-pipeline"))
+        .arg(&spec)
+        .arg(dir.join("input.txt"))
+        .arg(&out)
+        .status()
+        .expect("spawn cg-pipeline");
+    assert!(status.success(), "cg-pipeline exited with {status}");
+    let got = std::fs::read_to_string(&out).expect("read cg-pipeline output");
+    let want = std::fs::read_to_string(dir.join("expected.txt")).unwrap();
+    let _ = std::fs::remove_file(&spec);
+    let _ = std::fs::remove_file(&out);
+    assert!(diff_b_equal(&want, &got), "cg-pipeline output:\n{got}");
+}
+
+/// A misspelt key is an error naming it, not an option silently dropped.
+#[test]
+fn cg_pipeline_rejects_unknown_keys() {
+    let spec = temp_path("typo-pipeline.json");
+    std::fs::write(
+        &spec,
+        r#"{ "stages": [ { "grammar": "a.cg3", "sectoins": "1" } ] }"#,
+    )
+    .expect("write spec");
+    let out = Command::new(env!("CARGO_BIN_EXE_cg-pipeline"))
+        .arg(&spec)
+        .stdin(Stdio::null())
+        .output()
+        .expect("spawn cg-pipeline");
+    let _ = std::fs::remove_file(&spec);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("unknown key `sectoins` in stages[0]"),
+        "{stderr}"
+    );
+}
+
+/// One HTTP/1.1 exchange with `cg-serve` over its Unix socket: status line
+/// and body.
+#[cfg(unix)]
+fn cg_serve_request(socket: &Path, head: &str, body: &[u8]) -> (String, Vec<u8>) {
+    use std::io::Read as _;
+    let mut conn = std::os::unix::net::UnixStream::connect(socket).expect("connect to cg-serve");
+    write!(
+        conn,
+        "{head} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    )
+    .unwrap();
+    conn.write_all(body).unwrap();
+    let mut reply = Vec::new();
+    conn.read_to_end(&mut reply).expect("read reply");
+    let split = reply
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .expect("reply head");
+    let status = String::from_utf8_lossy(&reply[..split])
+        .lines()
+        .next()
+        .unwrap_or("")
+        .to_string();
+    (status, reply[split + 4..].to_vec())
+}
+
+/// `cg-serve` answers what `vislcg3` prints for the same grammar and options,
+/// reports its grammars on `/health`, and refuses unknown grammars.
+#[cfg(unix)]
+#[test]
+fn cg_serve_matches_vislcg3() {
+    let dir = temp_path("cg-serve");
+    std::fs::create_dir_all(&dir).expect("fixture dir");
+    let grammar = dir.join("sections.cg3");
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nSECTION\nADD (one) (*) ;\nSECTION\nADD (two) (*) ;\n",
+    )
+    .expect("write grammar");
+    let socket = dir.join("serve.sock");
+    let mut server = Command::new(env!("CARGO_BIN_EXE_cg-serve"))
+        .arg("-g")
+        .arg(format!("sections={}", grammar.display()))
+        .arg("--socket")
+        .arg(&socket)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spawn cg-serve");
+    // Ready once it accepts: the socket file appears before the listen.
+    for _ in 0..200 {
+        if std::os::unix::net::UnixStream::connect(&socket).is_ok() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(25));
+    }
+
+    let input = std::fs::read(repo_root().join("test/T_MweSplit/input.txt")).unwrap();
+    let (status, health) = cg_serve_request(&socket, "GET /health", b"");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(health, br#"{"grammars":["sections"],"status":"ok"}"#);
+
+    let want = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .arg("-g")
+        .arg(&grammar)
+        .args(["--sections", "1", "-t"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            child.stdin.take().unwrap().write_all(&input)?;
+            child.wait_with_output()
+        })
+        .expect("run vislcg3")
+        .stdout;
+    // Twice: the second run reuses the applicator the first one returned.
+    for _ in 0..2 {
+        let (status, got) =
+            cg_serve_request(&socket, "POST /run/sections?trace=1&sections=1", &input);
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(
+            String::from_utf8(got).unwrap(),
+            String::from_utf8_lossy(&want)
+        );
+    }
+    let (status, got) = cg_serve_request(&socket, "POST /run/sections?out=jsonl", &input);
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    let got = String::from_utf8(got).unwrap();
+    assert!(got.starts_with('{') && got.contains("two"), "{got}");
+
+    let (status, _) = cg_serve_request(&socket, "POST /run/missing", &input);
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+    let (status, _) = cg_serve_request(&socket, "POST /run/sections?out=xml", &input);
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    // `sections` must name the grammar's own sections.
+    let (status, _) = cg_serve_request(&socket, "POST /run/sections?sections=2-2", &input);
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    for bad in ["3", "1-x", "1,,2"] {
+        let (status, _) = cg_serve_request(
+            &socket,
+            &format!("POST /run/sections?sections={bad}"),
+            &input,
+        );
+        assert_eq!(status, "HTTP/1.1 400 Bad Request", "{bad}");
+    }
+
+    let _ = server.kill();
+    let _ = server.wait();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// `cg-serve` refuses a zero timeout, no threads and a port outside 1..=65535
+/// before it loads anything, rather than failing every connection or binding
+/// a port the number wrapped to.
+#[test]
+fn cg_serve_refuses_out_of_range_numbers() {
+    for (flag, value) in [
+        ("--timeout", "0"),
+        ("--threads", "0"),
+        ("--port", "0"),
+        ("--port", "70000"),
+    ] {
+        let mut args = vec!["-g", "g=missing.cg3", flag, value];
+        if flag != "--port" {
+            args.extend(["--port", "1"]);
+        }
+        let out = Command::new(env!("CARGO_BIN_EXE_cg-serve"))
+            .args(&args)
+            .stdin(Stdio::null())
+            .output()
+            .expect("spawn cg-serve");
+        assert!(!out.status.success(), "{flag} {value}");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(
+            stderr.contains(&format!("{flag} expects a number")),
+            "{flag} {value}: {stderr}"
+        );
+    }
+}
+
 // The `--nrules` / `--nrules-v` filters are compiled through the ICU seam, so an
 // ICU-spelled filter means on the command line what the same spelling means in a
 // grammar. `\Q...\E` exists only in ICU — the `regex` crate rejects it outright —
-// and `[:script=Greek:]` is the reverse hazard: every Rust engine ACCEPTS it as a
-// literal character set and silently matches the wrong thing, so the seam names
-// it instead.
+// and a pattern ICU rejects, such as an unknown `\N{NAME}`, is refused on the
+// command line as it is in a grammar.
 // [spec:cg3:req:tag-regex.single-seam+1/test]
 #[test]
 fn nrules_filters_speak_icu() {
@@ -219,10 +668,10 @@ fn nrules_filters_speak_icu() {
     let _ = std::fs::remove_file(&out);
     assert!(diff_b_equal(&want, &got), "ICU-spelled --nrules diverged");
 
-    // A construct this engine would misread is refused, not quietly obeyed.
+    // A pattern ICU rejects is refused, not quietly obeyed.
     let refused = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
         .current_dir(&dir)
-        .args(["--nrules", "[:script=Greek:]"])
+        .args(["--nrules", r"\N{NO SUCH CHARACTER}"])
         .arg("-g")
         .arg("grammar.cg3")
         .arg("-I")
@@ -231,10 +680,13 @@ fn nrules_filters_speak_icu() {
         .arg(temp_path("nrules-bad.txt"))
         .output()
         .expect("spawn vislcg3");
-    assert!(!refused.status.success(), "a misread pattern must not pass");
+    assert!(
+        !refused.status.success(),
+        "an invalid pattern must not pass"
+    );
     let stderr = String::from_utf8_lossy(&refused.stderr);
     assert!(stderr.contains("--nrules"), "{stderr}");
-    assert!(stderr.contains("in-set property"), "{stderr}");
+    assert!(stderr.contains("unknown character name"), "{stderr}");
 }
 
 // [spec:cg3:sem:cg-comp.main-fn/test]
@@ -343,7 +795,7 @@ fn a_bad_grammar_is_reported_against_its_source() {
     let grammar = temp_path("diagnostics.cg3");
     std::fs::write(
         &grammar,
-        "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Greek:]\"r ;\nSELECT nosuch ;\n",
+        "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Nopes:]\"r ;\nSELECT nosuch ;\n",
     )
     .expect("write grammar");
     let out = Command::new(env!("CARGO_BIN_EXE_cg-comp"))
@@ -359,7 +811,7 @@ fn a_bad_grammar_is_reported_against_its_source() {
         "the report must name the file and the place: {stderr}"
     );
     assert!(
-        stderr.contains("LIST a = \"[:script=Greek:]\"r ;"),
+        stderr.contains("LIST a = \"[:script=Nopes:]\"r ;"),
         "the report must quote the offending line: {stderr}"
     );
     assert!(
@@ -487,6 +939,169 @@ fn a_runtime_failure_falls_back_unresolved() {
     let _ = std::fs::remove_dir_all(&dir);
 }
 
+/// `--diagnostics-format` puts every parse error on stderr as one JSON or SARIF
+/// document, each with its file, line, column and span, and a run's warnings
+/// too, placed on the rule that raised them.
+#[test]
+fn diagnostics_format_writes_json_and_sarif() {
+    let grammar = temp_path("diag-bad.cg3");
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nLIST a = \"[:script=Nopes:]\"r ;\nSELECT (x) IF (-1 ;\n",
+    )
+    .unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_cg-comp"))
+        .arg("--diagnostics-format=json")
+        .arg(&grammar)
+        .arg(temp_path("diag-bad.cg3b"))
+        .output()
+        .expect("spawn cg-comp");
+    assert!(!out.status.success());
+    let doc: serde_json::Value =
+        serde_json::from_slice(&out.stderr).expect("stderr is the document");
+    let diags = doc["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 2, "{doc}");
+    assert_eq!(diags[0]["kind"], "tag-regex");
+    assert_eq!(
+        (diags[0]["line"].as_u64(), diags[0]["column"].as_u64()),
+        (Some(2), Some(10))
+    );
+    assert!(diags[0]["span"]["start"].is_u64());
+    assert_eq!(diags[1]["kind"], "syntax");
+    assert!(diags[1]["file"].as_str().unwrap().ends_with("diag-bad.cg3"));
+
+    let looping = temp_path("diag-loop.cg3");
+    std::fs::write(
+        &looping,
+        "DELIMITERS = \"<.>\" ;\nSECTION\nSETPARENT (x) TO (1 (y)) ;\nSETPARENT (y) TO (-1 (x)) ;\n",
+    )
+    .unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .arg("-g")
+        .arg(&looping)
+        .args(["--diagnostics-format", "sarif"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn vislcg3");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"\"<w>\"\n\t\"w\" x\n\"<v>\"\n\t\"v\" y\n\"<.>\"\n\t\".\" z\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success());
+    let doc: serde_json::Value =
+        serde_json::from_slice(&out.stderr).expect("stderr is the document");
+    assert_eq!(doc["version"], "2.1.0");
+    let result = &doc["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "dependency-loop", "{doc}");
+    assert_eq!(result["level"], "warning");
+    let region = &result["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(
+        (region["startLine"].as_u64(), region["startColumn"].as_u64()),
+        (Some(4), Some(1))
+    );
+
+    for path in [grammar, looping] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Malformed input is quoted with its line and column: as a record by
+/// `--diagnostics-format`, and as the failure `--strict-input` stops on.
+#[test]
+fn strict_input_stops_at_the_quoted_line() {
+    let grammar = temp_path("strict-input.cg3");
+    std::fs::write(&grammar, "DELIMITERS = \"<.>\" ;\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+            .arg("-g")
+            .arg(&grammar)
+            .arg("--in-apertium")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn vislcg3");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"^a/a<n>$\n^a/a<n>$ ^b/b<n$ ^c/c<n>$\n")
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let out = run(&["--diagnostics-format", "json"]);
+    assert!(out.status.success());
+    let doc: serde_json::Value =
+        serde_json::from_slice(&out.stderr).expect("stderr is the document");
+    let unclosed = &doc["diagnostics"][0];
+    assert_eq!(unclosed["kind"], "unclosed-tag", "{doc}");
+    assert_eq!(
+        (unclosed["line"].as_u64(), unclosed["column"].as_u64()),
+        (Some(2), Some(10))
+    );
+    assert_eq!(unclosed["excerpt"], "^a/a<n>$ ^b/b<n$");
+
+    let out = run(&["--strict-input"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--> <stdin>:2:10"), "{stderr}");
+    assert!(
+        stderr.contains("2 | ^a/a<n>$ ^b/b<n$\n  |          ^"),
+        "{stderr}"
+    );
+
+    let _ = std::fs::remove_file(grammar);
+}
+
+/// A reading with no cohort above it, and a JSONL stream marked with a schema
+/// version this build does not read, are quoted like any other unusable input
+/// and stop a `--strict-input` run.
+#[test]
+fn strict_input_stops_on_orphan_readings_and_newer_jsonl() {
+    let grammar = temp_path("strict-orphan.cg3");
+    std::fs::write(&grammar, "DELIMITERS = \"<.>\" ;\n").unwrap();
+    let run = |args: &[&str], input: &[u8]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+            .arg("-g")
+            .arg(&grammar)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn vislcg3");
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let orphan = b"\t\"a\" n\n\"<b>\"\n\t\"b\" n\n";
+    let out = run(&[], orphan);
+    assert!(out.status.success());
+    let out = run(&["--strict-input"], orphan);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--> <stdin>:1:2"), "{stderr}");
+    assert!(stderr.contains("no containing cohort"), "{stderr}");
+
+    let newer = b"{\"cg3jsonl\":9}\n{\"w\":\"a\",\"rs\":[{\"l\":\"a\",\"ts\":[\"n\"]}]}\n";
+    let out = run(&["--in-jsonl"], newer);
+    assert!(out.status.success());
+    let out = run(&["--in-jsonl", "--strict-input"], newer);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--> <stdin>:1:2"), "{stderr}");
+    assert!(stderr.contains("schema version 9"), "{stderr}");
+
+    let _ = std::fs::remove_file(grammar);
+}
+
 // [spec:cg3:sem:cg-comp.end-program-fn+3/test]
 // cg-comp's endProgram: wrong argc (no args) prints the version + usage banner
 // to stdout and exits EXIT_FAILURE.
@@ -538,6 +1153,55 @@ fn cg_conv_main_converts_niceline_stream() {
     );
 }
 
+// ADDED: the stream filter tools work on the window model, so JSONL goes
+// through them as well as CG, and comes out as JSONL.
+#[test]
+fn stream_filter_tools() {
+    let run = |exe: &str, args: &[&str], input: &str| {
+        let mut child = Command::new(exe)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("spawn filter");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let out = child.wait_with_output().expect("wait filter");
+        assert!(out.status.success(), "{exe} exited with {}", out.status);
+        String::from_utf8(out.stdout).unwrap()
+    };
+    let cg = "\"<a>\"\n\t\"z\" n SELECT:12 ID:7 R:obj:9 @OBJ\n\t\"a\" v MAP:3\n\t\"z\" n @OBJ\n;\t\"a\" adv REMOVE:4\n\"<b>\"\n\t\"b\" n ID:9 R:x:7\n";
+
+    assert_eq!(
+        run(env!("CARGO_BIN_EXE_cg-untrace"), &[], cg),
+        "\"<a>\"\n\t\"z\" n ID:7 R:obj:9 @OBJ\n\t\"a\" v\n\t\"z\" n @OBJ\n\"<b>\"\n\t\"b\" n ID:9 R:x:7\n"
+    );
+    assert_eq!(
+        run(env!("CARGO_BIN_EXE_cg-sort"), &[], cg),
+        "\"<a>\"\n\t\"a\" v MAP:3\n\t\"z\" n @OBJ\n\t\"z\" n SELECT:12 ID:7 R:obj:9 @OBJ\n;\t\"a\" adv REMOVE:4\n\"<b>\"\n\t\"b\" n ID:9 R:x:7\n"
+    );
+    assert_eq!(
+        run(env!("CARGO_BIN_EXE_cg-stabilize-relations"), &[], cg),
+        cg.replace("ID:7", "ID:1")
+            .replace("R:obj:9", "R:obj:2")
+            .replace("ID:9", "ID:2")
+            .replace("R:x:7", "R:x:1")
+    );
+
+    let jsonl = run(env!("CARGO_BIN_EXE_cg-conv"), &["--out-jsonl"], cg);
+    let untraced = run(env!("CARGO_BIN_EXE_cg-untrace"), &["--sort"], &jsonl);
+    let cohort = untraced.lines().next().unwrap();
+    assert!(
+        cohort.contains(r#""rs":[{"l":"a","ts":["v"]},{"l":"z","ts":["n","@OBJ"]},{"l":"z","ts":["n","ID:7","R:obj:9","@OBJ"]}]"#),
+        "{untraced}"
+    );
+}
+
 // [spec:cg3:sem:inlines.cg3.is-cg3bsf-fn+1/test]
 // The format sniff hands the stream magic detector whatever the first read
 // returned, so an empty stream used to index past the end of a zero-length
@@ -604,6 +1268,147 @@ fn cg_proc_main_runs_apertium_t_select() {
     );
 }
 
+/// Every tool reads gzip and zstd input by its magic bytes, grammars
+/// included, and compresses its output by the file's extension or by
+/// `--compress`; what comes out decompresses to the plain run's output.
+#[test]
+fn tools_read_and_write_compressed_streams() {
+    use cg3::compression::{Compression, compress, read_all};
+
+    let pipe = |bin: &str, dir: &Path, args: &[&OsStr], stdin: &[u8]| {
+        let mut child = Command::new(bin)
+            .current_dir(dir)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("spawn tool");
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+        let out = child.wait_with_output().expect("wait for tool");
+        assert!(out.status.success(), "{bin} exited with {}", out.status);
+        out.stdout
+    };
+    let p = OsStr::new;
+
+    let dir = repo_root().join("test/T_Select");
+    let input = std::fs::read(dir.join("input.txt")).unwrap();
+    let want = std::fs::read_to_string(dir.join("expected.txt")).unwrap();
+    let grammar = temp_path("compressed-grammar.cg3.gz");
+    let packed = compress(
+        &std::fs::read(dir.join("grammar.cg3")).unwrap(),
+        Compression::Gzip,
+    )
+    .unwrap();
+    std::fs::write(&grammar, packed).unwrap();
+    let input_zst = temp_path("compressed-input.txt.zst");
+    std::fs::write(&input_zst, compress(&input, Compression::Zstd).unwrap()).unwrap();
+
+    // vislcg3: compressed grammar and --stdin, .gz --stdout.
+    let out = temp_path("compressed-out.txt.gz");
+    let status = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .current_dir(&dir)
+        .args(read_args(&dir))
+        .arg("-g")
+        .arg(&grammar)
+        .arg("-I")
+        .arg(&input_zst)
+        .arg("-O")
+        .arg(&out)
+        .status()
+        .expect("spawn vislcg3");
+    assert!(status.success());
+    let written = std::fs::read(&out).unwrap();
+    assert_eq!(Compression::sniff(&written), Compression::Gzip);
+    let got = String::from_utf8(read_all(&written[..]).unwrap()).unwrap();
+    assert!(diff_b_equal(&want, &got), "vislcg3 .gz output differs");
+
+    // cg-comp: compressed grammar in, .zst binary out, which loads again.
+    let bin = temp_path("compressed-grammar.cg3b.zst");
+    let status = Command::new(env!("CARGO_BIN_EXE_cg-comp"))
+        .arg(&grammar)
+        .arg(&bin)
+        .status()
+        .expect("spawn cg-comp");
+    assert!(status.success());
+    assert_eq!(
+        Compression::sniff(&std::fs::read(&bin).unwrap()),
+        Compression::Zstd
+    );
+    let args = read_args(&dir);
+    let mut args: Vec<&OsStr> = args.iter().map(OsStr::new).collect();
+    args.extend([p("-g"), bin.as_os_str(), p("--compress"), p("zstd")]);
+    let stdout = pipe(
+        env!("CARGO_BIN_EXE_vislcg3"),
+        &dir,
+        &args,
+        &compress(&input, Compression::Gzip).unwrap(),
+    );
+    assert_eq!(Compression::sniff(&stdout), Compression::Zstd);
+    let got = String::from_utf8(read_all(&stdout[..]).unwrap()).unwrap();
+    assert!(diff_b_equal(&want, &got), "vislcg3 zstd stdout differs");
+
+    // cg-conv and cg-mwesplit: as for plain input, and --compress.
+    for bin in [
+        env!("CARGO_BIN_EXE_cg-conv"),
+        env!("CARGO_BIN_EXE_cg-mwesplit"),
+    ] {
+        let plain = pipe(bin, &dir, &[], &input);
+        let packed = pipe(
+            bin,
+            &dir,
+            &[p("--compress"), p("gzip")],
+            &compress(&input, Compression::Zstd).unwrap(),
+        );
+        assert_eq!(Compression::sniff(&packed), Compression::Gzip);
+        assert_eq!(read_all(&packed[..]).unwrap(), plain, "{bin}");
+    }
+
+    // cg-proc: compressed binary grammar and input file, .zst output file.
+    let dir = repo_root().join("test/Apertium/T_Select");
+    let want = std::fs::read_to_string(dir.join("expected.txt")).unwrap();
+    let bin = temp_path("compressed-apertium.cg3b.gz");
+    let status = Command::new(env!("CARGO_BIN_EXE_cg-comp"))
+        .current_dir(&dir)
+        .arg("grammar.cg3")
+        .arg(&bin)
+        .status()
+        .expect("spawn cg-comp");
+    assert!(status.success());
+    let input_gz = temp_path("compressed-apertium.txt.gz");
+    let packed = compress(
+        &std::fs::read(dir.join("input.txt")).unwrap(),
+        Compression::Gzip,
+    )
+    .unwrap();
+    std::fs::write(&input_gz, packed).unwrap();
+    let proc_out = temp_path("compressed-apertium-out.txt.zst");
+    let status = Command::new(env!("CARGO_BIN_EXE_cg-proc"))
+        .current_dir(&dir)
+        .arg("-d")
+        .arg(&bin)
+        .arg(&input_gz)
+        .arg(&proc_out)
+        .status()
+        .expect("spawn cg-proc");
+    assert!(status.success());
+    let written = std::fs::read(&proc_out).unwrap();
+    assert_eq!(Compression::sniff(&written), Compression::Zstd);
+    let got = String::from_utf8(read_all(&written[..]).unwrap()).unwrap();
+    assert!(diff_b_equal(&want, &got), "cg-proc .zst output differs");
+
+    for path in [grammar, input_zst, out, input_gz, proc_out] {
+        let _ = std::fs::remove_file(path);
+    }
+    for path in [
+        temp_path("compressed-grammar.cg3b.zst"),
+        temp_path("compressed-apertium.cg3b.gz"),
+    ] {
+        let _ = std::fs::remove_file(cg3::grammar_sources::sidecar_path(&path));
+        let _ = std::fs::remove_file(path);
+    }
+}
+
 // [spec:cg3:sem:cg-proc.end-program-fn+3/test]
 // cg-proc's endProgram: with no grammar argument main falls through to the
 // usage path — version + option summary on stdout, exit EXIT_FAILURE.
@@ -896,3 +1701,361 @@ fn cg_merge_annotations_main_sums_counts() {
     let _ = std::fs::remove_file(&in_db);
     let _ = std::fs::remove_file(&merged_db);
 }
+
+#[test]
+fn normalize_meets_input_in_the_other_form() {
+    let grammar = temp_path("normalize.cg3");
+    let run = |options: &str, lists: &str, input: &str, args: &[&str]| {
+        std::fs::write(
+            &grammar,
+            format!(
+                "{options}DELIMITERS = \"<.>\" ;\n{lists}\
+                 ADD (@plain) Plain ;\nADD (@regex) Regex ;\nADD (@icase) Icase ;\n"
+            ),
+        )
+        .unwrap();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+            .arg("-g")
+            .arg(&grammar)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn vislcg3");
+        // A bad option fails before the input is read; the pipe may be gone.
+        let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+        child.wait_with_output().unwrap()
+    };
+    // The grammar in NFC, the input in NFD, and the reverse.
+    let nfc_lists = "LIST Plain = \"<caf\u{e9}>\" ;\nLIST Regex = \"<.*\u{e9}>\"r ;\n\
+                     LIST Icase = \"<CAF\u{c9}>\"i ;\n";
+    let nfd_lists = "LIST Plain = \"<cafe\u{301}>\" ;\nLIST Regex = \"<.*e\u{301}>\"r ;\n\
+                     LIST Icase = \"<CAFE\u{301}>\"i ;\n";
+    let nfc_input = "\"<caf\u{e9}>\"\n\t\"caf\u{e9}\" n\n";
+    let nfd_input = "\"<cafe\u{301}>\"\n\t\"cafe\u{301}\" n\n";
+    let mapped = |out: &std::process::Output| {
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let text = String::from_utf8_lossy(&out.stdout).into_owned();
+        ["@plain", "@regex", "@icase"]
+            .iter()
+            .filter(|t| text.contains(*t))
+            .count()
+    };
+
+    assert_eq!(mapped(&run("", nfc_lists, nfd_input, &[])), 0);
+    let options = "OPTIONS += normalize-nfc ;\n";
+    let out = run(options, nfc_lists, nfd_input, &[]);
+    assert_eq!(mapped(&out), 3);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("\"<caf\u{e9}>\""));
+    assert_eq!(
+        mapped(&run("", nfc_lists, nfd_input, &["--normalize", "nfd"])),
+        3
+    );
+    assert_eq!(
+        mapped(&run("", nfd_lists, nfc_input, &["--normalize", "nfc"])),
+        3
+    );
+
+    let out = run("", nfc_lists, nfc_input, &["--normalize", "nfkc"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("must be nfc, nfd or none"));
+
+    let _ = std::fs::remove_file(grammar);
+}
+
+// ADDED: --dep-validate notes what keeps a window's dependencies from being
+// one projective tree; --dep-dot / --dep-json write each window's tree.
+#[test]
+fn dependency_trees_are_checked_and_exported() {
+    let grammar = temp_path("dep_tree.cg3");
+    let dot_path = temp_path("dep_tree.dot");
+    let json_path = temp_path("dep_tree.json");
+    // The rule closes a loop the input did not have, in the second window.
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nSETPARENT (loop) TO (1 (n)) ;\n",
+    )
+    .unwrap();
+    let input = "\"<a>\"\n\t\"a\" n #1->2\n\"<b>\"\n\t\"b\" v #2->0\n\"<c>\"\n\t\"c\" n #3->1\n\
+                 \"<d>\"\n\t\"d\" n #4->2\n\"<.>\"\n\t\".\" clb #5->2\n\
+                 \"<e>\"\n\t\"e\" loop #1->0\n\"<f>\"\n\t\"f\" n #2->1\n";
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .arg("-g")
+        .arg(&grammar)
+        .args(["--dep-allow-loops", "--dep-validate", "--dep-dot"])
+        .arg(&dot_path)
+        .arg("--dep-json")
+        .arg(&json_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn vislcg3");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(out.status.success(), "{stderr}");
+    let dot = std::fs::read_to_string(&dot_path).unwrap();
+    let json = std::fs::read_to_string(&json_path).unwrap();
+    for path in [&grammar, &dot_path, &json_path] {
+        let _ = std::fs::remove_file(path);
+    }
+
+    let warnings: Vec<&str> = stderr
+        .lines()
+        .filter(|l| l.contains("Dependency") || l.contains("dependency"))
+        .collect();
+    assert_eq!(warnings.len(), 5, "{stderr}");
+    assert!(!stderr.contains("rule on grammar line"), "{stderr}");
+    for expected in [
+        "Dependency 2->0 crosses 3->1, in window 1",
+        "Dependency 3->1 crosses 4->2, in window 1",
+        "Dependency 3->1 crosses 5->2, in window 1",
+        "Dependency tree should have one root, has none, in window 2",
+        "Dependency cycle 1->2->1, in window 2",
+    ] {
+        assert!(stderr.contains(expected), "{expected}: {stderr}");
+    }
+
+    assert_eq!(dot.matches("digraph").count(), 2);
+    assert!(dot.contains("\tn0 -> n2 [color=red];\n"), "{dot}");
+    assert!(dot.contains("\tn2 -> n1;\n"), "{dot}");
+    assert!(dot.contains("\tn1 -> n2 [color=red];\n"), "{dot}");
+
+    let trees: Vec<serde_json::Value> = json
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(trees.len(), 2);
+    let root = &trees[0]["tree"][0];
+    assert_eq!(
+        (root["id"].as_u64(), root["form"].as_str()),
+        (Some(2), Some("b"))
+    );
+    let children: Vec<u64> = root["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(children, [1, 4, 5]);
+    assert_eq!(root["children"][0]["children"][0]["id"], 3);
+    assert_eq!(trees[1]["tree"], serde_json::json!([]));
+    assert_eq!(trees[1]["detached"][0]["id"], 1);
+}
+
+#[test]
+fn relations_are_exported_per_window() {
+    let grammar = temp_path("relations.cg3");
+    let dot_path = temp_path("relations.dot");
+    let json_path = temp_path("relations.json");
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nADDRELATIONS (coref) (ref) (ref) TO (*-1W (n)) ;\n",
+    )
+    .unwrap();
+    let input = "\"<Kim>\"\n\t\"Kim\" n\n\"<left>\"\n\t\"leave\" v\n\"<.>\"\n\t\".\" clb\n\
+                 \"<she>\"\n\t\"she\" prn ref\n\"<sat>\"\n\t\"sit\" v\n";
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vislcg3"))
+        .arg("-g")
+        .arg(&grammar)
+        .arg("--relations-dot")
+        .arg(&dot_path)
+        .arg("--relations-json")
+        .arg(&json_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn vislcg3");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let dot = std::fs::read_to_string(&dot_path).unwrap();
+    let json = std::fs::read_to_string(&json_path).unwrap();
+    for path in [&grammar, &dot_path, &json_path] {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // The relation back to Kim is added from the second window, before the
+    // first is printed.
+    assert_eq!(dot.matches("digraph").count(), 2);
+    assert!(dot.contains("\tc1 -> c5 [label=\"ref\"];\n"), "{dot}");
+    assert!(dot.contains("\tc5 -> c1 [label=\"coref\"];\n"), "{dot}");
+    assert!(
+        dot.contains("\tc1 [label=\"1\" shape=plaintext];\n"),
+        "{dot}"
+    );
+
+    let graphs: Vec<serde_json::Value> = json
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(graphs.len(), 2);
+    assert_eq!(
+        graphs[0]["relations"],
+        serde_json::json!([{"name": "ref", "source": 1, "target": 5}])
+    );
+    assert_eq!(
+        graphs[1]["relations"],
+        serde_json::json!([{"name": "coref", "source": 5, "target": 1}])
+    );
+    assert_eq!(graphs[1]["cohorts"][0]["form"], "she");
+}
+
+/// cg-conv exports the input's `ID:`/`R:` relations as they stand and prints
+/// the stream exactly as it would without the export.
+#[test]
+fn relation_export_leaves_the_stream_alone() {
+    let json = temp_path("conv-relations.json");
+    let input = b"\"<a>\"\n\t\"a\" n ID:7 R:ref:9\n\"<b>\"\n\t\"b\" v ID:9\n";
+    let conv = |args: &[&std::ffi::OsStr]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cg-conv"))
+            .args(["--in-cg", "--out-cg"])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn cg-conv");
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        out.stdout
+    };
+
+    let plain = conv(&[]);
+    let exported = conv(&["--relations-json".as_ref(), json.as_os_str()]);
+    let graphs = std::fs::read_to_string(&json).unwrap();
+    let _ = std::fs::remove_file(&json);
+
+    assert_eq!(
+        String::from_utf8_lossy(&exported),
+        String::from_utf8_lossy(&plain)
+    );
+    assert!(
+        String::from_utf8_lossy(&plain).contains("ID:7 R:ref:9"),
+        "{}",
+        String::from_utf8_lossy(&plain)
+    );
+    let graph: serde_json::Value = serde_json::from_str(graphs.trim()).unwrap();
+    assert_eq!(
+        graph["relations"],
+        serde_json::json!([{"name": "ref", "source": 7, "target": 9}])
+    );
+    assert_eq!(graph["cohorts"][1]["id"], 9);
+}
+
+#[test]
+fn cg_comp_writes_the_grammar_graph() {
+    let grammar = temp_path("graph.cg3");
+    let binary = temp_path("graph.cg3b");
+    let sidecar = temp_path("graph.cg3b.cg3src");
+    let dot_path = temp_path("graph.dot");
+    let json = temp_path("graph.json");
+    std::fs::write(
+        &grammar,
+        "DELIMITERS = \"<.>\" ;\nLIST N = n ;\nLIST V = v ;\nLIST Det = det ;\n\
+         SECTION\nSELECT:pick Det (1 N) ;\nREMOVE V (-1 Det) ;\nJUMP (pick) (*) (0 V) ;\n",
+    )
+    .unwrap();
+    let cg_comp = env!("CARGO_BIN_EXE_cg-comp");
+
+    let out = Command::new(cg_comp)
+        .arg(format!("--graph={}", dot_path.display()))
+        .arg(&grammar)
+        .arg(&binary)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(binary.exists());
+    let dot = std::fs::read_to_string(&dot_path).unwrap();
+    assert!(dot.starts_with("digraph grammar {\n"), "{dot}");
+    assert!(
+        dot.contains("[label=\"SELECT:pick, line 6\" shape=box];"),
+        "{dot}"
+    );
+    assert!(dot.contains("\tr2 -> r0 [label=\"jump\"];\n"), "{dot}");
+
+    // Without an output file only the graph is written, here cut down to N.
+    let out = Command::new(cg_comp)
+        .arg(&grammar)
+        .args(["--graph-root", "N", "--graph"])
+        .arg(&json)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let graph: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    let labels: Vec<&str> = graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "SECTION 1",
+            "SELECT:pick, line 6",
+            "JUMP, line 8",
+            "START",
+            "END",
+            "N"
+        ]
+    );
+    assert!(
+        graph["edges"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!({"from": "r0", "to": "s1", "label": "context"})),
+        "{graph}"
+    );
+
+    let out = Command::new(cg_comp)
+        .arg(&grammar)
+        .args(["--graph-root=Nope", "--graph"])
+        .arg(&json)
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("no set, rule, template or anchor Nope"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    for path in [&grammar, &binary, &sidecar, &dot_path, &json] {
+        let _ = std::fs::remove_file(path);
+    }
+}